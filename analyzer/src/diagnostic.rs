@@ -91,6 +91,100 @@ pub enum DiagnosticID {
     /// A field access was done on a value that is not a structure
     #[assoc(code = 19)]
     InvalidFieldAccess,
+
+    /// An `impl` block targets a type that cannot declare methods.
+    #[assoc(code = 20)]
+    InvalidImpl,
+
+    /// A `self` parameter is declared outside of a method.
+    #[assoc(code = 21)]
+    InvalidSelfParameter,
+
+    /// A `match` over a bounded type does not cover all of its values.
+    #[assoc(code = 22)]
+    NonExhaustiveMatch,
+
+    /// A symbol is declared with an identifier reserved by the host.
+    #[assoc(code = 23)]
+    ReservedIdentifier,
+
+    /// A variable is declared but never used.
+    #[assoc(code = 24)]
+    #[assoc(critical = false)]
+    UnusedSymbol,
+
+    /// A statement follows an expression that never completes, such as a `return`.
+    #[assoc(code = 25)]
+    #[assoc(critical = false)]
+    UnreachableCode,
+
+    /// A type alias refers to itself.
+    #[assoc(code = 26)]
+    RecursiveTypeAlias,
+
+    /// A variadic parameter is followed by other parameters.
+    #[assoc(code = 27)]
+    MisplacedVariadic,
+
+    /// A named argument does not match a parameter of the called function.
+    #[assoc(code = 28)]
+    InvalidNamedArgument,
+
+    /// A construct is used while the targeted language version does not support it yet.
+    #[assoc(code = 29)]
+    UnavailableFeature,
+
+    /// A variable is declared again in the same scope, making the previous one unreachable.
+    #[assoc(code = 30)]
+    #[assoc(critical = false)]
+    ShadowedVariable,
+
+    /// An external command has the same name as a reachable function, which is likely a mistake.
+    #[assoc(code = 31)]
+    #[assoc(critical = false)]
+    FunctionCalledAsCommand,
+
+    /// A field access refers to a field that the structure does not declare.
+    #[assoc(code = 32)]
+    UnknownField,
+
+    /// A range is stepped by zero, which would never reach its end.
+    #[assoc(code = 33)]
+    InvalidRangeStep,
+
+    /// A glob import excludes a symbol that the imported module does not export.
+    #[assoc(code = 34)]
+    #[assoc(critical = false)]
+    UnknownExclusion,
+
+    /// An expression that runs no command is sent to the background.
+    #[assoc(code = 35)]
+    DetachedValue,
+
+    /// A symbol is imported by name but never used.
+    #[assoc(code = 36)]
+    #[assoc(critical = false)]
+    UnusedImport,
+
+    /// A `break` or a `continue` targets a label that no enclosing loop has.
+    #[assoc(code = 37)]
+    UnknownLoopLabel,
+
+    /// An arithmetic operation between constants produces an integer that does not fit in an `Int`.
+    #[assoc(code = 38)]
+    IntegerOverflow,
+
+    /// An expression is nested deeper than the analyzer is allowed to recurse.
+    #[assoc(code = 39)]
+    RecursionLimit,
+
+    /// An `if` is used as a value, but has no `else` branch to provide a value when its condition is false.
+    #[assoc(code = 40)]
+    MissingElse,
+
+    /// A type alias refers to a type that is defined after it.
+    #[assoc(code = 41)]
+    UndefinedAliasedType,
 }
 
 /// Observations are labels in a code snippet that are used to explain a [`Diagnostic`].
@@ -102,6 +196,17 @@ pub struct Observation {
     pub location: SourceLocation,
     /// An optional help string to complete the observation
     pub message: Option<String>,
+    /// Secondary ranges of the same source, highlighted alongside the main location
+    pub highlights: Vec<Highlight>,
+}
+
+/// A secondary range of an [`Observation`], such as a sub-expression that explains it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Highlight {
+    /// The highlighted range, in the source of its observation
+    pub segment: SourceSegment,
+    /// An optional label for this range
+    pub message: Option<String>,
 }
 
 impl Observation {
@@ -112,6 +217,7 @@ impl Observation {
         Self {
             location,
             message: None,
+            highlights: Vec::new(),
         }
     }
 
@@ -125,6 +231,7 @@ impl Observation {
         Self {
             location: SourceLocation::new(source, reef, segment),
             message: Some(message.into()),
+            highlights: Vec::new(),
         }
     }
 
@@ -138,8 +245,18 @@ impl Observation {
         Self {
             location: SourceLocation::new(source, reef, segment),
             message: Some(message.into()),
+            highlights: Vec::new(),
         }
     }
+
+    /// Highlights a secondary range of the observed source.
+    pub fn with_highlight(mut self, segment: SourceSegment, message: impl Into<String>) -> Self {
+        self.highlights.push(Highlight {
+            segment,
+            message: Some(message.into()),
+        });
+        self
+    }
 }
 
 /// A location in a source code.
@@ -184,6 +301,11 @@ impl Diagnostic {
         }
     }
 
+    /// Tests if this diagnostic is an error rather than a warning.
+    pub fn is_critical(&self) -> bool {
+        self.identifier.critical()
+    }
+
     pub fn with_observation(mut self, o: Observation) -> Self {
         self.observations.push(o);
         self
@@ -208,3 +330,32 @@ impl From<(SourceId, ReefId, SourceSegment)> for Observation {
         Self::new(SourceLocation::new(source, reef, segment))
     }
 }
+
+/// Finds the candidate that is the closest to a misspelled name, if it is close enough.
+///
+/// The distance between two names is the number of character edits needed to transform one into
+/// the other. Only candidates that differ by at most a third of the name length are accepted.
+pub fn closest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
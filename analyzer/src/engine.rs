@@ -1,3 +1,4 @@
+use ast::function::FunctionDeclaration;
 use ast::Expr;
 use context::source::ContentId;
 
@@ -5,6 +6,13 @@ use crate::environment::Environment;
 use crate::name::Name;
 use crate::relations::SourceId;
 
+/// A recorded state of an [`Engine`], to which it can be rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineCheckpoint {
+    /// The number of tracked origins.
+    pub(crate) origins: usize,
+}
+
 /// Owns references to the global AST and its environments.
 #[derive(Debug, Default)]
 pub struct Engine<'a> {
@@ -94,8 +102,46 @@ impl<'a> Engine<'a> {
         self.origins.is_empty()
     }
 
+    /// Records the current state of the engine, to which it can be rolled back.
+    pub fn checkpoint(&self) -> EngineCheckpoint {
+        EngineCheckpoint {
+            origins: self.origins.len(),
+        }
+    }
+
+    /// Removes all the origins that were tracked after the given checkpoint,
+    /// and returns their environments.
+    ///
+    /// The expressions taken by the engine are kept alive, as references to them may still exist.
+    pub fn rollback(&mut self, checkpoint: EngineCheckpoint) -> Vec<Environment> {
+        self.origins
+            .drain(checkpoint.origins..)
+            .filter_map(|(_, _, env)| env)
+            .collect()
+    }
+
     /// Gets the id of the AST tree that was used to create the given origin.
     pub fn get_original_content(&self, id: SourceId) -> Option<ContentId> {
         self.origins.get(id.0).map(|(content_id, _, _)| *content_id)
     }
+
+    /// Lists the functions directly declared in an environment that are marked with an attribute,
+    /// in their declaration order.
+    pub fn find_attributed_functions<'s>(
+        &'s self,
+        parent: SourceId,
+        attribute: &'s str,
+    ) -> impl Iterator<Item = (SourceId, &'a FunctionDeclaration)> + 's {
+        self.origins
+            .iter()
+            .enumerate()
+            .filter_map(move |(id, (_, expr, env))| match (*expr, env) {
+                (Expr::FunctionDeclaration(function), Some(env))
+                    if env.parent == Some(parent) && function.has_attribute(attribute) =>
+                {
+                    Some((SourceId(id), function))
+                }
+                _ => None,
+            })
+    }
 }
@@ -26,7 +26,7 @@ use context::source::{SourceSegment, SourceSegmentHolder};
 use symbols::Symbols;
 
 use crate::name::Name;
-use crate::relations::{SourceId, SymbolRef};
+use crate::relations::{LocalId, SourceId, SymbolRef};
 
 pub mod symbols;
 
@@ -52,6 +52,9 @@ pub struct Environment {
 
     /// A mapping of expression segments to their declaring environment.
     pub declarations: HashMap<SourceSegment, SourceId>,
+
+    /// The variables declared with `var` or `val`, with the segment of their declaration.
+    pub declared_variables: Vec<(LocalId, SourceSegment)>,
 }
 
 impl Environment {
@@ -63,6 +66,7 @@ impl Environment {
             symbols: Symbols::default(),
             definitions: HashMap::new(),
             declarations: HashMap::new(),
+            declared_variables: Vec::new(),
         }
     }
 
@@ -76,6 +80,7 @@ impl Environment {
             symbols: Symbols::default(),
             definitions: HashMap::new(),
             declarations: HashMap::new(),
+            declared_variables: Vec::new(),
         }
     }
 
@@ -191,7 +191,7 @@ impl Symbols {
     }
 
     /// Returns an entry for the given external symbol name relation
-    pub fn external(&mut self, loc: SymbolLocation) -> Entry<SymbolLocation, RelationId> {
+    pub fn external(&mut self, loc: SymbolLocation) -> Entry<'_, SymbolLocation, RelationId> {
         self.externals.entry(loc)
     }
 
@@ -204,6 +204,13 @@ impl Symbols {
         self.locals.position_reachable_local(name, registry)
     }
 
+    /// Finds a variable with the given name that was declared in the current scope.
+    ///
+    /// Variables of the parent scopes are not considered.
+    pub fn find_in_current_scope(&self, name: &str) -> Option<LocalId> {
+        self.locals.position_current_scope_variable(name)
+    }
+
     /// Finds the local exported symbol associated with an already known name.
     ///
     /// Exported symbols are always declared in the outermost scope, and should be checked only
@@ -365,6 +372,19 @@ impl Locals {
             .position(|var| var.depth >= 0 && var.name == name && registry.accepts(var.ty))
             .map(|idx| LocalId(self.vars.len() - 1 - idx))
     }
+
+    /// Gets the id of a variable declared in the current scope.
+    fn position_current_scope_variable(&self, name: &str) -> Option<LocalId> {
+        self.vars
+            .iter()
+            .rev()
+            .position(|var| {
+                var.depth == self.current_depth as isize
+                    && var.name == name
+                    && var.ty == SymbolInfo::Variable
+            })
+            .map(|idx| LocalId(self.vars.len() - 1 - idx))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
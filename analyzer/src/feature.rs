@@ -0,0 +1,26 @@
+use enum_assoc::Assoc;
+
+/// The most recent version of the language, that is targeted by default.
+pub const LATEST_VERSION: u32 = 2;
+
+/// A language construct that is only available from a given language version.
+#[derive(PartialEq, Debug, Assoc, Clone, Copy)]
+#[func(pub fn name(&self) -> &'static str)]
+#[func(pub fn since(&self) -> u32)]
+pub enum Feature {
+    /// Markers placed before declarations, such as `@test`.
+    #[assoc(name = "attributes", since = 2)]
+    Attributes,
+
+    /// The `type Alias = Type` declarations.
+    #[assoc(name = "type aliases", since = 2)]
+    TypeAliases,
+
+    /// Arguments that are matched to parameters by name.
+    #[assoc(name = "named arguments", since = 2)]
+    NamedArguments,
+
+    /// The `in` membership operator.
+    #[assoc(name = "in operator", since = 2)]
+    InOperator,
+}
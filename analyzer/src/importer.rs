@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use ast::Expr;
 use context::source::ContentId;
@@ -78,6 +79,48 @@ where
     }
 }
 
+/// An importer that splits the time of a resolution between the imports and the rest of it.
+pub(crate) struct TimedImporter<'i, I> {
+    importer: &'i mut I,
+
+    /// The instant at which the resolution was resumed after the last import.
+    resumed: Instant,
+
+    /// The time spent importing the sources.
+    import: Duration,
+
+    /// The time spent resolving the sources, outside of the imports.
+    resolution: Duration,
+}
+
+impl<'i, I: ASTImporter> TimedImporter<'i, I> {
+    /// Starts measuring a resolution that imports sources from the given importer.
+    pub(crate) fn new(importer: &'i mut I) -> Self {
+        Self {
+            importer,
+            resumed: Instant::now(),
+            import: Duration::ZERO,
+            resolution: Duration::ZERO,
+        }
+    }
+
+    /// Ends the resolution, and returns the time spent importing and resolving the sources.
+    pub(crate) fn finish(self) -> (Duration, Duration) {
+        (self.import, self.resolution + self.resumed.elapsed())
+    }
+}
+
+impl<I: ASTImporter> ASTImporter for TimedImporter<'_, I> {
+    fn import(&mut self, name: &Name) -> ImportResult {
+        let start = Instant::now();
+        self.resolution += start - self.resumed;
+        let result = self.importer.import(name);
+        self.resumed = Instant::now();
+        self.import += self.resumed - start;
+        result
+    }
+}
+
 impl From<Option<Imported>> for ImportResult {
     fn from(opt: Option<Imported>) -> Self {
         match opt {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 
 use indexmap::IndexMap;
@@ -41,6 +41,11 @@ impl Imports {
         self.imports.get_mut(&source)
     }
 
+    /// Iterates over the imports of each source that declares some.
+    pub fn iter(&self) -> impl Iterator<Item = (SourceId, &SourceImports)> {
+        self.imports.iter().map(|(id, imports)| (*id, imports))
+    }
+
     /// Removes all the imports that were declared at or after the given source.
     pub fn retain_before(&mut self, source: SourceId) {
         self.imports.retain(|id, _| id.0 < source.0);
@@ -56,6 +61,9 @@ pub struct SourceImports {
 
     /// Binds a symbol name to its resolved import, with the source segment where the import is declared.
     imported_symbols: HashMap<String, (ResolvedImport, SourceSegment)>,
+
+    /// The names of the imported symbols that were brought by a glob import.
+    glob_imports: HashSet<String>,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash)]
@@ -66,7 +74,11 @@ pub enum UnresolvedImport {
         loc: SymbolLocation,
     },
     /// Variant to target all the exported symbols of a symbol
-    AllIn(SymbolLocation),
+    AllIn {
+        loc: SymbolLocation,
+        /// The names of the symbols that are not imported, with the segment that excludes them.
+        exclusions: Vec<(String, SourceSegment)>,
+    },
 }
 
 /// A resolved symbol import
@@ -90,8 +102,11 @@ impl Debug for SourceImports {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut imported_symbols: Vec<_> = self.imported_symbols.iter().collect();
         imported_symbols.sort_by_key(|(k, _)| *k);
+        let mut glob_imports: Vec<_> = self.glob_imports.iter().collect();
+        glob_imports.sort();
         f.debug_struct("Imports")
             .field("imported_symbols", &imported_symbols)
+            .field("glob_imports", &glob_imports)
             .field("unresolved_imports", &self.unresolved_imports)
             .finish()
     }
@@ -102,6 +117,7 @@ impl SourceImports {
         Self {
             unresolved_imports,
             imported_symbols: HashMap::new(),
+            glob_imports: HashSet::new(),
         }
     }
 
@@ -113,9 +129,16 @@ impl SourceImports {
         Self {
             unresolved_imports,
             imported_symbols,
+            glob_imports: HashSet::new(),
         }
     }
 
+    #[cfg(test)]
+    pub fn with_glob_imports<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.glob_imports = names.into_iter().map(str::to_owned).collect();
+        self
+    }
+
     ///Adds an unresolved import, placing the given `import_expr` as the dependent .
     pub fn add_unresolved_import(
         &mut self,
@@ -125,6 +148,18 @@ impl SourceImports {
         self.unresolved_imports.insert(import, segment)
     }
 
+    /// Lists the aliases of the imports that still need to be resolved, with the segment of their import.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &SourceSegment)> {
+        self.unresolved_imports
+            .iter()
+            .filter_map(|(import, segment)| match import {
+                UnresolvedImport::Symbol {
+                    alias: Some(alias), ..
+                } => Some((alias.as_str(), segment)),
+                _ => None,
+            })
+    }
+
     pub fn take_unresolved_imports(&mut self) -> IndexMap<UnresolvedImport, SourceSegment> {
         std::mem::take(&mut self.unresolved_imports)
     }
@@ -135,9 +170,31 @@ impl SourceImports {
         resolved: ResolvedImport,
         segment: SourceSegment,
     ) {
+        self.glob_imports.remove(&name);
         self.imported_symbols.insert(name, (resolved, segment));
     }
 
+    /// Binds a symbol name that is imported by a glob import.
+    pub fn set_glob_import(
+        &mut self,
+        name: String,
+        resolved: ResolvedImport,
+        segment: SourceSegment,
+    ) {
+        self.glob_imports.insert(name.clone());
+        self.imported_symbols.insert(name, (resolved, segment));
+    }
+
+    /// Lists the symbols that were imported by name, with the segment of their import.
+    pub fn explicit_imports(
+        &self,
+    ) -> impl Iterator<Item = (&str, &ResolvedImport, &SourceSegment)> {
+        self.imported_symbols
+            .iter()
+            .filter(|(name, _)| !self.glob_imports.contains(*name))
+            .map(|(name, (resolved, segment))| (name.as_str(), resolved, segment))
+    }
+
     pub fn get_import(&self, name: &str) -> Option<&ResolvedImport> {
         self.imported_symbols.get(name).map(|(i, _)| i)
     }
@@ -17,23 +17,26 @@
 #![allow(dead_code)]
 
 use crate::diagnostic::Diagnostic;
-use crate::engine::Engine;
-use crate::importer::{ASTImporter, Imported};
+use crate::engine::{Engine, EngineCheckpoint};
+use crate::importer::{ASTImporter, Imported, TimedImporter};
 use crate::imports::Imports;
 use crate::name::Name;
 use crate::reef::Externals;
-use crate::relations::{Relations, SourceId};
+use crate::relations::{Relations, RelationsCheckpoint, SourceId};
 use crate::steps::collect::SymbolCollector;
 use crate::steps::resolve_sources;
 use crate::steps::typing::apply_types;
+use crate::steps::unused::{diagnose_unused_imports, diagnose_unused_variables};
 use crate::types::ctx::TypeContext;
 use crate::types::engine::TypedEngine;
 use crate::types::Typing;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 pub mod diagnostic;
 pub mod engine;
 pub mod environment;
+pub mod feature;
 pub mod importer;
 pub mod name;
 pub mod relations;
@@ -72,10 +75,26 @@ pub struct Analyzer<'a> {
     /// The applied types over the [`Engine`].
     pub engine: TypedEngine,
 
+    /// The time spent in the steps of the last analysis.
+    pub durations: AnalysisDurations,
+
     /// The diagnostics that were generated during the analysis.
     diagnostics: Vec<Diagnostic>,
 }
 
+/// The wall-clock time spent in each step of an analysis.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AnalysisDurations {
+    /// The time spent importing the sources, that includes reading and parsing them.
+    pub import: Duration,
+
+    /// The time spent collecting and resolving the symbols, without the imports.
+    pub resolution: Duration,
+
+    /// The time spent ascribing and checking the types.
+    pub typing: Duration,
+}
+
 impl<'a> Analyzer<'a> {
     /// Creates a new empty analyzer.
     pub fn new() -> Self {
@@ -89,28 +108,35 @@ impl<'a> Analyzer<'a> {
         importer: &mut impl ASTImporter,
         externals: &Externals,
     ) -> Analysis<'a, '_> {
+        let checkpoint = self.checkpoint();
         let last_next_source_id = SourceId(self.resolution.engine.len());
+        let mut importer = TimedImporter::new(importer);
         resolve_sources(
             vec![entry_point],
             &mut self.resolution,
-            importer,
+            &mut importer,
             externals,
             &mut self.diagnostics,
         );
-        if self.diagnostics.is_empty() {
-            let (engine, context, typing) = apply_types(
-                &self.resolution.engine,
-                &self.resolution.relations,
-                externals,
-                &mut self.diagnostics,
-            );
-            self.engine = engine;
-            self.type_context = context;
-            self.typing = typing;
-        }
+        self.diagnostics.extend(diagnose_unused_variables(
+            &self.resolution.engine,
+            &self.resolution.relations,
+            externals.current,
+            last_next_source_id,
+        ));
+        self.diagnostics.extend(diagnose_unused_imports(
+            &self.resolution.engine,
+            &self.resolution.relations,
+            &self.resolution.imports,
+            externals.current,
+            last_next_source_id,
+        ));
+        (self.durations.import, self.durations.resolution) = importer.finish();
+        let replaced = self.type_sources(externals);
         Analysis {
             analyzer: self,
-            last_next_source_id,
+            checkpoint,
+            replaced,
         }
     }
 
@@ -124,9 +150,10 @@ impl<'a> Analyzer<'a> {
         importer: &mut impl ASTImporter,
         externals: &Externals,
     ) -> Analysis<'a, '_> {
-        let last_next_source_id = SourceId(self.resolution.engine.len());
+        let checkpoint = self.checkpoint();
         let name = inject.name.clone();
         let mut visit = vec![name.clone()];
+        let mut importer = TimedImporter::new(importer);
 
         self.diagnostics.extend(SymbolCollector::inject(
             inject,
@@ -141,31 +168,84 @@ impl<'a> Analyzer<'a> {
         resolve_sources(
             visit,
             &mut self.resolution,
-            importer,
+            &mut importer,
             externals,
             &mut self.diagnostics,
         );
-        if self.diagnostics.is_empty() {
-            let (engine, context, typing) = apply_types(
-                &self.resolution.engine,
-                &self.resolution.relations,
-                externals,
-                &mut self.diagnostics,
-            );
-            self.engine = engine;
-            self.type_context = context;
-            self.typing = typing;
-        }
+        (self.durations.import, self.durations.resolution) = importer.finish();
+        let replaced = self.type_sources(externals);
         Analysis {
             analyzer: self,
-            last_next_source_id,
+            checkpoint,
+            replaced,
+        }
+    }
+
+    /// Applies the types over the resolved sources, unless the resolution failed.
+    ///
+    /// Returns the types that were replaced, if any.
+    fn type_sources(&mut self, externals: &Externals) -> Option<AppliedTypes> {
+        if self.diagnostics.iter().any(Diagnostic::is_critical) {
+            self.durations.typing = Duration::ZERO;
+            return None;
         }
+        let start = Instant::now();
+        let (engine, context, typing) = apply_types(
+            &self.resolution.engine,
+            &self.resolution.relations,
+            externals,
+            &mut self.diagnostics,
+        );
+        let replaced = AppliedTypes {
+            engine: std::mem::replace(&mut self.engine, engine),
+            context: std::mem::replace(&mut self.type_context, context),
+            typing: std::mem::replace(&mut self.typing, typing),
+        };
+        self.durations.typing = start.elapsed();
+        Some(replaced)
     }
 
     /// Takes the diagnostics that were generated during the analysis
     pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.diagnostics)
     }
+
+    /// Records the current state of the resolution, to which the analyzer can be rolled back.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            engine: self.resolution.engine.checkpoint(),
+            relations: self.resolution.relations.checkpoint(),
+        }
+    }
+
+    /// Removes all the sources, objects and imports that were added after the given checkpoint.
+    ///
+    /// The types are not restored, as they are entirely replaced by each analysis.
+    fn rollback(&mut self, checkpoint: Checkpoint) {
+        let resolution = &mut self.resolution;
+        for env in resolution.engine.rollback(checkpoint.engine) {
+            resolution.visited.remove(&env.fqn);
+        }
+        resolution.relations.rollback(checkpoint.relations);
+        resolution
+            .imports
+            .retain_before(SourceId(checkpoint.engine.origins));
+    }
+}
+
+/// A recorded state of an [`Analyzer`] resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    engine: EngineCheckpoint,
+    relations: RelationsCheckpoint,
+}
+
+/// The type knowledge of an analyzer, as produced by a typing step.
+#[derive(Debug)]
+struct AppliedTypes {
+    engine: TypedEngine,
+    context: TypeContext,
+    typing: Typing,
 }
 
 /// An analysis result that can be observed and reverted.
@@ -176,7 +256,10 @@ pub struct Analysis<'a, 'revert> {
 
     /// Reverting the operation means internally removing all the sources that were added
     /// after the last stable state.
-    last_next_source_id: SourceId,
+    checkpoint: Checkpoint,
+
+    /// The types that the analysis replaced, restored when it is reverted.
+    replaced: Option<AppliedTypes>,
 }
 
 impl Analysis<'_, '_> {
@@ -190,7 +273,7 @@ impl Analysis<'_, '_> {
 
     /// Returns the source id of the injected source.
     pub fn attributed_id(&self) -> SourceId {
-        SourceId(self.last_next_source_id.0)
+        SourceId(self.checkpoint.engine.origins)
     }
 
     #[must_use = "This method does not revert the analysis, `Analysis::revert` must be called"]
@@ -202,15 +285,12 @@ impl Analysis<'_, '_> {
     ///
     /// This drops the [`Analyzer`] unique ownership.
     pub fn revert(self) {
-        let id = self.last_next_source_id;
-        let resolution = &mut self.analyzer.resolution;
-        for (_, _, env) in resolution.engine.origins.drain(id.0..) {
-            if let Some(env) = env {
-                resolution.visited.remove(&env.fqn);
-            }
+        self.analyzer.rollback(self.checkpoint);
+        if let Some(replaced) = self.replaced {
+            self.analyzer.engine = replaced.engine;
+            self.analyzer.type_context = replaced.context;
+            self.analyzer.typing = replaced.typing;
         }
-        resolution.relations.retain_before(id);
-        resolution.imports.retain_before(id);
     }
 }
 
@@ -255,3 +335,152 @@ pub struct ResolutionResult<'e> {
     imports: Imports,
     visited: HashSet<Name>,
 }
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_trusted;
+    use pretty_assertions::assert_eq;
+
+    use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+    use crate::importer::{ASTImporter, ImportResult, StaticImporter};
+    use crate::name::Name;
+    use crate::reef::{Externals, ReefId, DEFAULT_RECURSION_LIMIT};
+    use crate::relations::SourceId;
+    use crate::{analyze, Analyzer, Inject};
+
+    /// Injects a line as the REPL would, reverting it if it has errors.
+    fn inject_line(
+        analyzer: &mut Analyzer,
+        importer: &mut impl ASTImporter,
+        externals: &Externals,
+        line: &str,
+        attached: Option<SourceId>,
+    ) -> Option<SourceId> {
+        let ImportResult::Success(imported) = importer.import(&Name::new(line)) else {
+            unreachable!()
+        };
+        let inject = Inject {
+            name: Name::new("stdin"),
+            imported,
+            attached,
+        };
+        let mut analysis = analyzer.inject(inject, importer, externals);
+        let id = analysis.attributed_id();
+        if analysis
+            .take_diagnostics()
+            .iter()
+            .any(Diagnostic::is_critical)
+        {
+            analysis.revert();
+            None
+        } else {
+            Some(id)
+        }
+    }
+
+    #[test]
+    fn failed_injection_leaves_no_residue() {
+        let externals = Externals::default();
+        let mut importer = StaticImporter::new(
+            [
+                (Name::new("first"), "val a = 1"),
+                (Name::new("bad"), "fun f() = $a + $c\nval b = f()"),
+                (Name::new("last"), "echo $a"),
+            ],
+            parse_trusted,
+        );
+
+        let mut recovered = Analyzer::new();
+        let first = inject_line(&mut recovered, &mut importer, &externals, "first", None);
+        let checkpoint = recovered.checkpoint();
+        assert_eq!(
+            inject_line(&mut recovered, &mut importer, &externals, "bad", first),
+            None
+        );
+        assert_eq!(recovered.checkpoint(), checkpoint);
+        let last = inject_line(&mut recovered, &mut importer, &externals, "last", first);
+
+        let mut expected = Analyzer::new();
+        let expected_first = inject_line(&mut expected, &mut importer, &externals, "first", None);
+        let expected_last = inject_line(
+            &mut expected,
+            &mut importer,
+            &externals,
+            "last",
+            expected_first,
+        );
+
+        assert_eq!(last, expected_last);
+        assert_eq!(recovered.checkpoint(), expected.checkpoint());
+        assert_eq!(
+            recovered.resolution.relations.iter().collect::<Vec<_>>(),
+            expected.resolution.relations.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(recovered.resolution.visited, expected.resolution.visited);
+    }
+
+    #[test]
+    fn failed_typing_restores_types() {
+        let externals = Externals::default();
+        let mut importer = StaticImporter::new(
+            [
+                (Name::new("first"), "val a = 1"),
+                (Name::new("bad"), "val b: Int = 'a'"),
+            ],
+            parse_trusted,
+        );
+
+        let mut analyzer = Analyzer::new();
+        let first = inject_line(&mut analyzer, &mut importer, &externals, "first", None)
+            .expect("the first line should be valid");
+        assert_eq!(
+            inject_line(&mut analyzer, &mut importer, &externals, "bad", Some(first)),
+            None
+        );
+        assert_eq!(analyzer.engine.len(), first.0 + 1);
+        assert_eq!(
+            analyzer
+                .engine
+                .iter_chunks()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            vec![first]
+        );
+    }
+
+    #[test]
+    fn deep_nesting_is_diagnosed() {
+        // Run with the stack size of a main thread, as the analysis is by the CLI
+        let diagnostics = std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(|| {
+                let source = ["1"; 2000].join(" + ");
+                let externals = Externals::default();
+                let mut importer =
+                    StaticImporter::new([(Name::new("main"), source.as_str())], parse_trusted);
+                let mut analyzer = analyze(Name::new("main"), &mut importer, &externals);
+                analyzer.take_diagnostics()
+            })
+            .unwrap()
+            .join()
+            .expect("the analysis should not overflow the stack");
+
+        // The statement is walked one level below the root block, and each level drops the last
+        // operand of the additions
+        let reported_terms = 2000 - (DEFAULT_RECURSION_LIMIT - 1);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(
+                DiagnosticID::RecursionLimit,
+                "Expression is nested too deeply"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                0..4 * (reported_terms - 1) + 1,
+                format!("This expression exceeds the nesting limit of {DEFAULT_RECURSION_LIMIT}"),
+            ))
+            .with_help("Extract the inner expressions into variables or functions")]
+        );
+    }
+}
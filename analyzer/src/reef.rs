@@ -1,8 +1,11 @@
 use crate::Analyzer;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::engine::Engine;
-use crate::relations::{ObjectId, Relations};
+use crate::environment::symbols::Symbols;
+use crate::environment::Environment;
+use crate::feature::{Feature, LATEST_VERSION};
+use crate::relations::{ObjectId, Relations, SourceId};
 use crate::types::builtin::lang_reef;
 use crate::types::ctx::TypeContext;
 use crate::types::engine::TypedEngine;
@@ -42,6 +45,21 @@ impl<'e> Reef<'e> {
             type_context: TypeContext::default(),
         }
     }
+
+    /// Iterates over the environments of this reef, with the source they are attached to.
+    pub fn environments(&self) -> impl Iterator<Item = (SourceId, &Environment)> {
+        self.engine.environments()
+    }
+
+    /// Gets the environment attached to a source of this reef.
+    pub fn get_environment(&self, id: SourceId) -> Option<&Environment> {
+        self.engine.get_environment(id)
+    }
+
+    /// Gets the symbols declared in the environment attached to a source of this reef.
+    pub fn get_symbols(&self, id: SourceId) -> Option<&Symbols> {
+        self.get_environment(id).map(|env| &env.symbols)
+    }
 }
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
@@ -52,10 +70,22 @@ pub struct Externals<'a> {
     pub current: ReefId,
     names: HashMap<String, ReefId>,
     reefs: Vec<Reef<'a>>,
+
+    /// Additional identifiers that the analyzed sources cannot declare.
+    reserved_words: HashSet<String>,
+
+    /// The language version that the analyzed sources must conform to.
+    target_version: u32,
+
+    /// The maximum nesting depth of the expressions that the analyzer explores.
+    recursion_limit: usize,
 }
 
 pub const LANG_REEF: ReefId = ReefId(0);
 
+/// The default maximum nesting depth of an expression.
+pub const DEFAULT_RECURSION_LIMIT: usize = 256;
+
 impl Default for Externals<'_> {
     /// Creates a Reefs set with the required `lang` reef with id 0
     fn default() -> Self {
@@ -63,6 +93,9 @@ impl Default for Externals<'_> {
             current: ReefId(1),
             names: HashMap::from([("lang".to_string(), LANG_REEF)]),
             reefs: vec![lang_reef()],
+            reserved_words: HashSet::new(),
+            target_version: LATEST_VERSION,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
         }
     }
 }
@@ -77,6 +110,14 @@ impl<'e> Externals<'e> {
         self.reefs.get(id.0)
     }
 
+    /// Iterates over all the registered reefs, starting with the `lang` reef.
+    pub fn iter_reefs(&self) -> impl Iterator<Item = (ReefId, &Reef<'e>)> {
+        self.reefs
+            .iter()
+            .enumerate()
+            .map(|(id, reef)| (ReefId(id), reef))
+    }
+
     pub fn get_reef_by_name(&self, name: &str) -> Option<(&Reef<'e>, ReefId)> {
         self.names
             .get(name)
@@ -87,6 +128,43 @@ impl<'e> Externals<'e> {
         self.reefs.get_mut(id.0)
     }
 
+    /// Reserves an identifier, so that declaring a symbol with this name is an error.
+    ///
+    /// This is useful for hosts that embed the language and give a special meaning to some words.
+    pub fn reserve_word(&mut self, word: impl Into<String>) {
+        self.reserved_words.insert(word.into());
+    }
+
+    /// Tests if an identifier has been reserved by the host.
+    pub fn is_reserved(&self, name: &str) -> bool {
+        self.reserved_words.contains(name)
+    }
+
+    /// Restricts the analyzed sources to the features available in the given language version.
+    pub fn set_target_version(&mut self, version: u32) {
+        self.target_version = version;
+    }
+
+    /// Gets the language version that the analyzed sources must conform to.
+    pub fn target_version(&self) -> u32 {
+        self.target_version
+    }
+
+    /// Tests if a feature can be used with the targeted language version.
+    pub fn is_available(&self, feature: Feature) -> bool {
+        feature.since() <= self.target_version
+    }
+
+    /// Sets the maximum nesting depth of the expressions, past which an expression is rejected.
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+
+    /// Gets the maximum nesting depth of the expressions.
+    pub fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+
     pub fn register(&mut self, reef: Reef<'e>) -> ReefId {
         let id = ReefId(self.reefs.len());
         if self.names.insert(reef.name.clone(), id).is_some() {
@@ -97,3 +175,35 @@ impl<'e> Externals<'e> {
         id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_trusted;
+    use pretty_assertions::assert_eq;
+
+    use crate::analyze;
+    use crate::importer::StaticImporter;
+    use crate::name::Name;
+    use crate::reef::{Externals, Reef, LANG_REEF};
+    use crate::relations::SourceId;
+
+    #[test]
+    fn iter_reefs_after_analysis() {
+        let name = Name::new("main");
+        let mut externals = Externals::default();
+        let mut importer =
+            StaticImporter::new([(name.clone(), "val x = 1\nfun foo() = $x")], parse_trusted);
+        let analyzer = analyze(name, &mut importer, &externals);
+        let user = externals.register(Reef::new("main".to_string(), analyzer));
+
+        let reefs = externals
+            .iter_reefs()
+            .map(|(id, reef)| (id, reef.name.as_str(), reef.environments().count()))
+            .collect::<Vec<_>>();
+        assert_eq!(reefs, vec![(LANG_REEF, "lang", 0), (user, "main", 2)]);
+
+        let reef = externals.get_reef(user).unwrap();
+        let symbols = reef.get_symbols(SourceId(0)).unwrap();
+        assert_eq!(symbols.len(), 2);
+    }
+}
@@ -21,7 +21,7 @@ use crate::reef::{ReefId, LANG_REEF};
 ///
 /// Some main structures are based on object identifiers
 /// - [`ResolvedSymbol`] contains a [`SourceId`] and a [`LocalId`] which globally targets a symbol inside a given source environment,
-///                      this structure is emitted by the resolution phase.
+///   this structure is emitted by the resolution phase.
 /// - [`SymbolRef`] refers to a symbol, which is either local (to an unbound environment) or external, where the relation is held by the [`Relations`]
 pub type ObjectId = usize;
 
@@ -146,6 +146,13 @@ impl Relation {
     }
 }
 
+/// A recorded state of the [`Relations`], to which they can be rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelationsCheckpoint {
+    /// The number of tracked objects.
+    relations: usize,
+}
+
 /// A collection of objects that are tracked globally and may link to each other.
 #[derive(Debug, Default)]
 pub struct Relations {
@@ -204,9 +211,16 @@ impl Relations {
         Some(self.relations.get(id.0)?.state)
     }
 
-    /// Removes all the objects that have been created at or after the given id.
-    pub fn retain_before(&mut self, id: SourceId) {
-        self.relations.retain(|relation| relation.origin.0 < id.0);
+    /// Records the current state of the relations, to which they can be rolled back.
+    pub fn checkpoint(&self) -> RelationsCheckpoint {
+        RelationsCheckpoint {
+            relations: self.relations.len(),
+        }
+    }
+
+    /// Removes all the objects that have been tracked after the given checkpoint.
+    pub fn rollback(&mut self, checkpoint: RelationsCheckpoint) {
+        self.relations.truncate(checkpoint.relations);
     }
 
     /// Creates a dependency graph for the given engine.
@@ -10,6 +10,7 @@ pub mod collect;
 pub mod resolve;
 mod shared_diagnostics;
 pub mod typing;
+pub(crate) mod unused;
 
 pub(super) fn resolve_sources(
     mut to_visit: Vec<Name>,
@@ -3,12 +3,13 @@ use std::collections::HashSet;
 use ast::call::Call;
 use ast::control_flow::ForKind;
 use ast::function::FunctionParameter;
+use ast::operation::BinaryOperator;
 use ast::r#match::MatchPattern;
 use ast::r#type::Type;
 use ast::r#use::{Import as ImportExpr, InclusionPathItem};
 use ast::range;
 use ast::value::LiteralValue;
-use ast::variable::{Tilde, VarName};
+use ast::variable::{Identifier, Tilde, VarName};
 use ast::Expr;
 use context::source::{ContentId, SourceSegment, SourceSegmentHolder};
 use range::Iterable;
@@ -17,13 +18,14 @@ use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
 use crate::engine::Engine;
 use crate::environment::symbols::{MagicSymbolKind, SymbolInfo, SymbolLocation, SymbolRegistry};
 use crate::environment::Environment;
+use crate::feature::Feature;
 use crate::importer::{ASTImporter, ImportResult, Imported};
 use crate::imports::{Imports, UnresolvedImport};
 use crate::name::Name;
 use crate::reef::{Externals, ReefId};
 use crate::relations::{RelationState, Relations, SourceId, SymbolRef};
 use crate::steps::resolve::SymbolResolver;
-use crate::steps::shared_diagnostics::diagnose_invalid_symbol;
+use crate::steps::shared_diagnostics::{diagnose_invalid_symbol, diagnose_recursion_limit};
 use crate::steps::typing::magic::is_magic_variable_name;
 use crate::Inject;
 
@@ -67,6 +69,9 @@ pub struct SymbolCollector<'a, 'b, 'e> {
 
     /// The stack of environments currently being collected.
     stack: Vec<SourceId>,
+
+    /// The number of expressions currently being walked.
+    depth: usize,
 }
 
 impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
@@ -133,6 +138,7 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
             externals,
             diagnostics: Vec::new(),
             stack: Vec::new(),
+            depth: 0,
         }
     }
 
@@ -252,9 +258,43 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
         self.stack.push(state.module);
 
         self.tree_walk(&mut state, root_block, to_visit);
+        self.check_shadowed_aliases(state.module);
         self.stack.pop();
     }
 
+    /// Reports the import aliases that are also declared as symbols of the module.
+    ///
+    /// The local symbol takes precedence when referenced by its simple name, and the alias is only
+    /// followed by the qualified paths.
+    fn check_shadowed_aliases(&mut self, mod_id: SourceId) {
+        let Some(imports) = self.imports.get_imports(mod_id) else {
+            return;
+        };
+        let env = self.engine.get_environment(mod_id).unwrap();
+        for (alias, segment) in imports.aliases() {
+            let is_declared = [SymbolRegistry::Objects, SymbolRegistry::Types]
+                .into_iter()
+                .any(|registry| env.symbols.find_exported(alias, registry).is_some());
+            if !is_declared {
+                continue;
+            }
+            let diagnostic = Diagnostic::new(
+                DiagnosticID::ShadowedImport,
+                format!("{alias} is both an import alias and a local symbol."),
+            )
+            .with_observation(Observation::here(
+                mod_id,
+                self.externals.current,
+                segment.clone(),
+                "this alias is shadowed by the local symbol",
+            ))
+            .with_help(format!(
+                "`{alias}` only refers to the import in qualified paths, such as `{alias}::name`"
+            ));
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
     fn add_checked_import(
         &mut self,
         mod_id: SourceId,
@@ -314,13 +354,17 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                         .push(make_invalid_path_diagnostic(mod_id, reef, segments)),
                 }
             }
-            ImportExpr::AllIn(items, _) => {
+            ImportExpr::AllIn(items, exclusions, _) => {
                 relative_path.extend(items.iter().cloned());
                 match SymbolLocation::compute(&relative_path) {
                     Ok(loc) => {
                         let name = loc.name.clone();
                         to_visit.push(name.clone());
-                        let unresolved = UnresolvedImport::AllIn(loc);
+                        let exclusions = exclusions
+                            .iter()
+                            .map(|symbol| (symbol.value.to_string(), symbol.segment()))
+                            .collect();
+                        let unresolved = UnresolvedImport::AllIn { loc, exclusions };
                         self.add_checked_import(mod_id, unresolved, import, name)
                     }
                     Err(segments) => self
@@ -329,14 +373,13 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 }
             }
 
-            ImportExpr::Environment(_) => {
-                let diagnostic = Diagnostic::new(
-                    DiagnosticID::UnsupportedFeature,
-                    "import of environment variables and commands are not yet supported.",
-                )
-                .with_observation((mod_id, reef, import.segment()).into());
-
-                self.diagnostics.push(diagnostic);
+            ImportExpr::Environment(ident) => {
+                // environment variables are always available, and are bound as local variables
+                let env = self.current_env();
+                let symbol = env
+                    .symbols
+                    .declare_local(ident.value.to_string(), SymbolInfo::Variable);
+                env.annotate(ident, SymbolRef::Local(symbol));
             }
             ImportExpr::List(list) => {
                 relative_path.extend(list.root.iter().cloned());
@@ -361,6 +404,27 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
     }
 
     fn tree_walk(&mut self, state: &mut ResolutionState, expr: &'e Expr, to_visit: &mut Vec<Name>) {
+        let limit = self.externals.recursion_limit();
+        if self.depth >= limit {
+            self.diagnostics.push(diagnose_recursion_limit(
+                state.module,
+                self.externals.current,
+                expr.segment(),
+                limit,
+            ));
+            return;
+        }
+        self.depth += 1;
+        self.collect_expr(state, expr, to_visit);
+        self.depth -= 1;
+    }
+
+    fn collect_expr(
+        &mut self,
+        state: &mut ResolutionState,
+        expr: &'e Expr,
+        to_visit: &mut Vec<Name>,
+    ) {
         match expr {
             Expr::Use(import) => {
                 if !state.accept_imports {
@@ -379,6 +443,9 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 self.tree_walk(state, &assign.value, to_visit);
             }
             Expr::Binary(binary) => {
+                if binary.op == BinaryOperator::In {
+                    self.check_feature(state, Feature::InOperator, binary.segment());
+                }
                 self.tree_walk(state, &binary.left, to_visit);
                 self.tree_walk(state, &binary.right, to_visit);
             }
@@ -414,9 +481,11 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                     }
                     self.current_env().begin_scope();
                     if let Some(name) = &arm.val_name {
-                        self.current_env()
+                        let env = self.current_env();
+                        let symbol = env
                             .symbols
                             .declare_local(name.to_string(), SymbolInfo::Variable);
+                        env.annotate(name, SymbolRef::Local(symbol));
                     }
                     self.tree_walk(state, &arm.body, to_visit);
                     self.current_env().end_scope();
@@ -428,6 +497,10 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                     self.tree_walk(state, arg, to_visit);
                 }
             }
+            Expr::NamedArgument(named) => {
+                self.check_feature(state, Feature::NamedArguments, named.segment());
+                self.tree_walk(state, &named.value, to_visit);
+            }
             Expr::ProgrammaticCall(call) => {
                 match SymbolLocation::compute(&call.path) {
                     Ok(loc) => {
@@ -500,18 +573,52 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 if let Some(initializer) = &var.initializer {
                     self.tree_walk(state, initializer, to_visit);
                 }
+                self.check_reserved(state, &var.var.name);
                 if let Some(ty) = &var.var.ty {
                     self.collect_type(*self.stack.last().unwrap(), ty)
                 }
+                // the `_` pattern does not bind anything
+                if !var.is_ignored() {
+                    self.check_shadowed(state, &var.var.name, var.segment());
+                    let env = self.current_env();
+                    let symbol = env
+                        .symbols
+                        .declare_local(var.var.name.to_string(), SymbolInfo::Variable);
+                    env.annotate(var, SymbolRef::Local(symbol));
+                    env.declared_variables.push((symbol, var.segment()));
+                }
+            }
+            Expr::TupleDeclaration(decl) => {
+                self.tree_walk(state, &decl.initializer, to_visit);
+                // the destructured tuple is held in a hidden variable, bound to the declaration
                 let env = self.current_env();
-                let symbol = env
+                let tuple = env
                     .symbols
-                    .declare_local(var.var.name.to_string(), SymbolInfo::Variable);
-                env.annotate(var, SymbolRef::Local(symbol));
+                    .declare_local(String::new(), SymbolInfo::Variable);
+                env.annotate(decl, SymbolRef::Local(tuple));
+                for name in &decl.names {
+                    self.check_reserved(state, name);
+                    if name.value == "_" {
+                        continue;
+                    }
+                    self.check_shadowed(state, name, name.segment());
+                    let env = self.current_env();
+                    let symbol = env
+                        .symbols
+                        .declare_local(name.to_string(), SymbolInfo::Variable);
+                    env.annotate(name, SymbolRef::Local(symbol));
+                    env.declared_variables.push((symbol, name.segment()));
+                }
             }
             Expr::VarReference(var) => {
                 if let VarName::User(name) = &var.name {
-                    if is_magic_variable_name(name) {
+                    // variadic parameters are already declared as `@` in the function
+                    let is_parameter = self
+                        .current_env()
+                        .symbols
+                        .find_reachable(name, SymbolRegistry::Objects)
+                        .is_some();
+                    if is_magic_variable_name(name) && !is_parameter {
                         let script_env = self
                             .engine
                             .get_environment_mut(*self.stack.first().unwrap())
@@ -538,6 +645,15 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                         SymbolRegistry::Objects,
                     );
                     self.current_env().annotate(var, symbol);
+                } else if var.name == VarName::Slf {
+                    let symbol = self.identify_symbol(
+                        *self.stack.last().unwrap(),
+                        state.module,
+                        SymbolLocation::unspecified(Name::new("self")),
+                        var.segment(),
+                        SymbolRegistry::Objects,
+                    );
+                    self.current_env().annotate(var, symbol);
                 }
             }
             Expr::FieldAccess(access) => {
@@ -623,6 +739,11 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
             Expr::Parenthesis(paren) => {
                 self.tree_walk(state, &paren.expression, to_visit);
             }
+            Expr::TupleLiteral(tuple) => {
+                for element in &tuple.elements {
+                    self.tree_walk(state, element, to_visit);
+                }
+            }
             Expr::Subshell(subshell) => {
                 self.current_env().begin_scope();
                 for expr in &subshell.expressions {
@@ -667,6 +788,7 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 self.current_env().begin_scope();
                 match fr.kind.as_ref() {
                     ForKind::Range(range) => {
+                        self.check_reserved(state, &range.receiver);
                         let env = self.current_env();
                         let symbol = env
                             .symbols
@@ -689,72 +811,20 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 }
             }
             Expr::FunctionDeclaration(func) => {
+                self.check_reserved(state, &func.name);
+                for attribute in &func.attributes {
+                    self.check_feature(state, Feature::Attributes, attribute.segment());
+                }
                 let symbol = self
                     .current_env()
                     .symbols
                     .declare_local(func.name.value.to_string(), SymbolInfo::Function);
                 self.current_env().annotate(func, SymbolRef::Local(symbol));
-
-                let func_id = self.engine().track(state.content, expr);
-                self.current_env().bind_source(func, func_id);
-                let func_env = self
-                    .current_env()
-                    .fork(state.module, func.name.value.as_ref());
-
-                self.stack.push(func_id);
-
-                let func_env = self.engine().attach(func_id, func_env);
-
-                for type_param in &func.type_parameters {
-                    func_env
-                        .symbols
-                        .declare_local(type_param.name.to_string(), SymbolInfo::Type);
-                    if !type_param.params.is_empty() {
-                        unimplemented!("Parametrized type parameters are not yet supported");
-                    }
-                }
-
-                for param in &func.parameters {
-                    let param_name = match param {
-                        FunctionParameter::Named(named) => {
-                            if let Some(ty) = &named.ty {
-                                self.collect_type(func_id, ty);
-                            }
-                            named.name.to_string()
-                        }
-                        FunctionParameter::Variadic(_, _) => "@".to_owned(),
-                        FunctionParameter::Slf(_) => continue,
-                    };
-                    let func_env = self.engine().get_environment_mut(func_id).unwrap();
-
-                    let symbol = func_env
-                        .symbols
-                        .declare_local(param_name, SymbolInfo::Variable);
-
-                    // Only named parameters can be annotated for now
-                    if let FunctionParameter::Named(named) = param {
-                        func_env.annotate(named, SymbolRef::Local(symbol));
-                    }
-                }
-                if let Some(ty) = &func.return_type {
-                    self.collect_type(func_id, ty)
-                }
-
-                if let Some(body) = &func.body {
-                    self.tree_walk(&mut state.fork(func_id), body, to_visit);
-                }
-
-                Self::resolve_captures(
-                    &self.stack,
-                    self.engine,
-                    self.relations,
-                    self.externals.current,
-                    &mut self.diagnostics,
-                );
-                self.stack.pop();
+                self.collect_function(state, expr, None, to_visit);
             }
             Expr::LambdaDef(lambda) => {
                 let func_id = self.engine().track(state.content, expr);
+                self.current_env().bind_source(lambda, func_id);
 
                 let func_env = self
                     .current_env()
@@ -764,6 +834,7 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 self.engine().attach(func_id, func_env);
 
                 for param in &lambda.args {
+                    self.check_reserved(state, &param.name);
                     let func_env = self.engine().get_environment_mut(func_id).unwrap();
                     let symbol = func_env
                         .symbols
@@ -785,6 +856,7 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                 self.stack.pop();
             }
             Expr::StructDeclaration(decl) => {
+                self.check_reserved(state, &decl.name);
                 let struct_env_id = self.engine().track(state.content, expr);
 
                 let struct_env = self
@@ -817,11 +889,257 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                     .annotate(decl, SymbolRef::Local(local_id));
             }
             Expr::Literal(_) | Expr::Continue(_) | Expr::Break(_) => {}
-            Expr::Impl(_) => todo!(),
+            Expr::Impl(imp) => {
+                if let (Some(first), Some(last)) =
+                    (imp.type_parameters.first(), imp.type_parameters.last())
+                {
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticID::UnsupportedFeature,
+                            "Parametrized impls are not supported yet",
+                        )
+                        .with_observation(Observation::here(
+                            *self.stack.last().unwrap(),
+                            self.externals.current,
+                            first.segment().start..last.segment().end,
+                            "Remove the type parameters",
+                        )),
+                    );
+                } else {
+                    self.collect_type(*self.stack.last().unwrap(), &imp.impl_type);
+                    for method in &imp.functions {
+                        let method = self
+                            .engine()
+                            .take(Expr::FunctionDeclaration(method.clone()));
+                        self.collect_function(state, method, Some(&imp.impl_type), to_visit);
+                    }
+                }
+            }
+            Expr::TypeAlias(alias) => {
+                self.check_feature(state, Feature::TypeAliases, alias.segment());
+                self.check_reserved(state, &alias.name);
+                let env_id = *self.stack.last().unwrap();
+                if !alias.parameters.is_empty() {
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticID::UnsupportedFeature,
+                            "Parametrized type aliases are not supported yet",
+                        )
+                        .with_observation(Observation::here(
+                            env_id,
+                            self.externals.current,
+                            alias.segment(),
+                            "Remove the type parameters",
+                        )),
+                    );
+                }
+                // declared before the aliased type is collected, so that recursive aliases can be reported
+                let local_id = self
+                    .current_env()
+                    .symbols
+                    .declare_local(alias.name.to_string(), SymbolInfo::Type);
+                self.current_env()
+                    .annotate(alias, SymbolRef::Local(local_id));
+                self.collect_type(env_id, &alias.aliased);
+            }
         }
         state.accept_imports = false;
     }
 
+    /// Reports the declarations of identifiers that are reserved by the host.
+    fn check_reserved(&mut self, state: &ResolutionState, name: &Identifier) {
+        if self.externals.is_reserved(&name.value) {
+            self.diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::ReservedIdentifier,
+                    format!("`{}` is a reserved identifier", name.value),
+                )
+                .with_observation(Observation::here(
+                    state.module,
+                    self.externals.current,
+                    name.segment(),
+                    "Cannot be used as a name",
+                )),
+            );
+        }
+    }
+
+    /// Reports a variable declaration that shadows a variable of the same scope.
+    ///
+    /// Shadowing a variable of a parent scope is intentional, and is not reported.
+    fn check_shadowed(
+        &mut self,
+        state: &ResolutionState,
+        name: &Identifier,
+        declaration: SourceSegment,
+    ) {
+        let env = self.current_env();
+        let Some(shadowed) = env.symbols.find_in_current_scope(&name.value) else {
+            return;
+        };
+        let Some(previous) = env
+            .declared_variables
+            .iter()
+            .find_map(|(local, segment)| (*local == shadowed).then(|| segment.clone()))
+        else {
+            return;
+        };
+        let reef = self.externals.current;
+        self.diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::ShadowedVariable,
+                format!("Variable `{name}` shadows a variable of the same scope"),
+            )
+            .with_observation(Observation::here(
+                state.module,
+                reef,
+                declaration,
+                "This declaration shadows the previous one",
+            ))
+            .with_observation(Observation::context(
+                state.module,
+                reef,
+                previous,
+                "Previously declared here",
+            )),
+        );
+    }
+
+    /// Reports the constructs that are not available in the targeted language version.
+    fn check_feature(&mut self, state: &ResolutionState, feature: Feature, segment: SourceSegment) {
+        if !self.externals.is_available(feature) {
+            self.diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::UnavailableFeature,
+                    format!(
+                        "Feature `{}` requires version ≥ {}",
+                        feature.name(),
+                        feature.since()
+                    ),
+                )
+                .with_observation(Observation::here(
+                    state.module,
+                    self.externals.current,
+                    segment,
+                    format!(
+                        "Not available in version {}",
+                        self.externals.target_version()
+                    ),
+                )),
+            );
+        }
+    }
+
+    /// Collects a function declaration expression, that may be a method of an `impl` block.
+    fn collect_function(
+        &mut self,
+        state: &mut ResolutionState,
+        expr: &'e Expr,
+        impl_type: Option<&'e Type>,
+        to_visit: &mut Vec<Name>,
+    ) {
+        let Expr::FunctionDeclaration(func) = expr else {
+            unreachable!("expression should be a function declaration")
+        };
+
+        let func_id = self.engine().track(state.content, expr);
+        self.current_env().bind_source(func, func_id);
+        // methods of different types may share the same name
+        let func_name = match impl_type {
+            Some(impl_type) => format!("{impl_type}::{}", func.name),
+            None => func.name.to_string(),
+        };
+        let func_env = self.current_env().fork(state.module, &func_name);
+
+        self.stack.push(func_id);
+
+        let func_env = self.engine().attach(func_id, func_env);
+
+        for type_param in &func.type_parameters {
+            func_env
+                .symbols
+                .declare_local(type_param.name.to_string(), SymbolInfo::Type);
+            if !type_param.params.is_empty() {
+                unimplemented!("Parametrized type parameters are not yet supported");
+            }
+        }
+
+        // the receiver type of a method is needed to type its `self` parameter
+        if let Some(impl_type) = impl_type {
+            self.collect_type(func_id, impl_type);
+        }
+
+        for param in &func.parameters {
+            let param_name = match param {
+                FunctionParameter::Named(named) => {
+                    self.check_reserved(state, &named.name);
+                    if let Some(ty) = &named.ty {
+                        self.collect_type(func_id, ty);
+                    }
+                    named.name.to_string()
+                }
+                FunctionParameter::Variadic(name, ty, _) => {
+                    if let Some(ty) = ty {
+                        self.collect_type(func_id, ty);
+                    }
+                    match name {
+                        Some(name) => {
+                            self.check_reserved(state, name);
+                            name.to_string()
+                        }
+                        None => "@".to_owned(),
+                    }
+                }
+                FunctionParameter::Slf(_) => "self".to_owned(),
+            };
+            let func_env = self.engine().get_environment_mut(func_id).unwrap();
+
+            let symbol = func_env
+                .symbols
+                .declare_local(param_name, SymbolInfo::Variable);
+
+            // Only named parameters can be annotated for now
+            match param {
+                FunctionParameter::Named(named) => {
+                    func_env.annotate(named, SymbolRef::Local(symbol));
+                }
+                FunctionParameter::Variadic(Some(_), _, _) => {
+                    func_env.annotate(param, SymbolRef::Local(symbol));
+                }
+                _ => {}
+            }
+        }
+        let has_self_parameter = func
+            .parameters
+            .iter()
+            .any(|param| matches!(param, FunctionParameter::Slf(_)));
+        if impl_type.is_some() && !has_self_parameter {
+            // methods always have access to their receiver
+            self.engine()
+                .get_environment_mut(func_id)
+                .unwrap()
+                .symbols
+                .declare_local("self".to_owned(), SymbolInfo::Variable);
+        }
+
+        if let Some(ty) = &func.return_type {
+            self.collect_type(func_id, ty)
+        }
+
+        if let Some(body) = &func.body {
+            self.tree_walk(&mut state.fork(func_id), body, to_visit);
+        }
+
+        Self::resolve_captures(
+            &self.stack,
+            self.engine,
+            self.relations,
+            self.externals.current,
+            &mut self.diagnostics,
+        );
+        self.stack.pop();
+    }
+
     fn resolve_captures(
         stack: &[SourceId],
         engine: &Engine,
@@ -859,8 +1177,17 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
                     origin_env.annotate(p, symref)
                 }
             },
-            Type::Callable(_) | Type::ByName(_) => {
-                panic!("Callable and By Name types are not yet supported.")
+            Type::Callable(callable) => {
+                for param in &callable.params {
+                    self.collect_type(origin, param)
+                }
+                self.collect_type(origin, &callable.output)
+            }
+            Type::ByName(by_name) => self.collect_type(origin, &by_name.name),
+            Type::Tuple(tuple) => {
+                for element in &tuple.elements {
+                    self.collect_type(origin, element)
+                }
             }
         }
     }
@@ -949,6 +1276,15 @@ impl<'a, 'b, 'e> SymbolCollector<'a, 'b, 'e> {
         match local_symbol {
             None => SymbolRef::External(track_global!()),
             Some(id) if location.name.is_qualified() => {
+                // A local symbol cannot be qualified, so the path may only go through an import alias
+                let is_aliased = self.imports.get_imports(source).is_some_and(|imports| {
+                    imports
+                        .aliases()
+                        .any(|(alias, _)| alias == location.name.root())
+                });
+                if is_aliased {
+                    return SymbolRef::External(track_global!());
+                }
                 let var = symbols.get(id).unwrap();
                 self.diagnostics.push(diagnose_invalid_symbol(
                     var.ty,
@@ -1034,6 +1370,7 @@ mod tests {
     use context::str_find::{find_in, find_in_nth};
     use parser::parse_trusted;
 
+    use crate::feature::LATEST_VERSION;
     use crate::importer::StaticImporter;
     use crate::relations::{LocalId, RelationId};
 
@@ -1086,6 +1423,172 @@ mod tests {
         )
     }
 
+    fn collect_reserving(content: &str, reserved: &[&str]) -> Vec<Diagnostic> {
+        let mut externals = Externals::default();
+        for word in reserved {
+            externals.reserve_word(*word);
+        }
+        collect_with(content, &externals)
+    }
+
+    fn collect_targeting(content: &str, version: u32) -> Vec<Diagnostic> {
+        let mut externals = Externals::default();
+        externals.set_target_version(version);
+        collect_with(content, &externals)
+    }
+
+    /// Collects the symbols of a single module against the given externals.
+    fn collect_with(content: &str, externals: &Externals) -> Vec<Diagnostic> {
+        let mut engine = Engine::default();
+        let mut relations = Relations::default();
+        let mut imports = Imports::default();
+        let mut importer = StaticImporter::new([(Name::new("test"), content)], parse_trusted);
+        SymbolCollector::collect_symbols(
+            &mut engine,
+            &mut relations,
+            &mut imports,
+            externals,
+            &mut vec![Name::new("test")],
+            &mut HashSet::new(),
+            &mut importer,
+        )
+    }
+
+    #[test]
+    fn collect_by_name_parameter() {
+        assert_eq!(
+            collect_reserving("fun lazy(value: => Int) -> Int = 1", &[]),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn declare_reserved_identifier() {
+        let content = "var report = 1";
+        assert_eq!(
+            collect_reserving(content, &["report"]),
+            vec![Diagnostic::new(
+                DiagnosticID::ReservedIdentifier,
+                "`report` is a reserved identifier"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "report"),
+                "Cannot be used as a name"
+            ))]
+        );
+    }
+
+    #[test]
+    fn declare_unreserved_identifier() {
+        assert_eq!(collect_reserving("var report = 1", &[]), vec![]);
+    }
+
+    #[test]
+    fn shadow_in_same_scope() {
+        let content = "var x = 1\nvar x = 2";
+        assert_eq!(
+            collect_reserving(content, &[]),
+            vec![Diagnostic::new(
+                DiagnosticID::ShadowedVariable,
+                "Variable `x` shadows a variable of the same scope"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "var x = 2"),
+                "This declaration shadows the previous one"
+            ))
+            .with_observation(Observation::context(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "var x = 1"),
+                "Previously declared here"
+            ))]
+        );
+    }
+
+    #[test]
+    fn shadow_with_destructuring() {
+        let content = "val lo = 1\nval (lo, hi) = (2, 3)";
+        assert_eq!(
+            collect_reserving(content, &[]),
+            vec![Diagnostic::new(
+                DiagnosticID::ShadowedVariable,
+                "Variable `lo` shadows a variable of the same scope"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in_nth(content, "lo", 1),
+                "This declaration shadows the previous one"
+            ))
+            .with_observation(Observation::context(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "val lo = 1"),
+                "Previously declared here"
+            ))]
+        );
+    }
+
+    #[test]
+    fn shadow_in_nested_scope() {
+        let content = "var x = 1\nif true { var x = 2 }\nvar y = { var x = 3; $x }";
+        assert_eq!(collect_reserving(content, &[]), vec![]);
+    }
+
+    #[test]
+    fn feature_unavailable_in_target_version() {
+        let content = "type Str = String";
+        assert_eq!(
+            collect_targeting(content, 1),
+            vec![Diagnostic::new(
+                DiagnosticID::UnavailableFeature,
+                "Feature `type aliases` requires version ≥ 2"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, content),
+                "Not available in version 1"
+            ))]
+        );
+    }
+
+    #[test]
+    fn feature_available_in_latest_version() {
+        assert_eq!(
+            collect_targeting("type Str = String", LATEST_VERSION),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn find_test_functions() {
+        let content = "@test fun first() = {}\nfun helper() = {}\n@test\nfun second() = helper()";
+        let mut engine = Engine::default();
+        let mut relations = Relations::default();
+        let mut imports = Imports::default();
+        let mut importer = StaticImporter::new([(Name::new("test"), content)], parse_trusted);
+        let diagnostics = SymbolCollector::collect_symbols(
+            &mut engine,
+            &mut relations,
+            &mut imports,
+            &Externals::default(),
+            &mut vec![Name::new("test")],
+            &mut HashSet::new(),
+            &mut importer,
+        );
+        assert_eq!(diagnostics, vec![]);
+        let tests: Vec<_> = engine
+            .find_attributed_functions(SourceId(0), "test")
+            .map(|(_, function)| function.name.value.as_str())
+            .collect();
+        assert_eq!(tests, vec!["first", "second"]);
+    }
+
     #[test]
     fn bind_local_variables() {
         let expr = parse_trusted("var bar = 4; $bar");
@@ -1096,6 +1599,25 @@ mod tests {
         assert_eq!(relations.iter().collect::<Vec<_>>(), vec![]);
     }
 
+    #[test]
+    fn bind_environment_variables() {
+        let content = "use @HOME; echo $HOME";
+        let expr = parse_trusted(content);
+        let mut engine = Engine::default();
+        let mut relations = Relations::default();
+        let (diagnostics, env) = tree_walk(&expr, &mut engine, &mut relations);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(relations.iter().collect::<Vec<_>>(), vec![]);
+        assert_eq!(
+            env.get_raw_symbol(find_in(content, "HOME")),
+            Some(SymbolRef::Local(LocalId(0)))
+        );
+        assert_eq!(
+            env.get_raw_symbol(find_in(content, "$HOME")),
+            Some(SymbolRef::Local(LocalId(0)))
+        );
+    }
+
     #[test]
     fn test_symbol_clashes_with_module() {
         let math_source = "use math::{add, multiply, divide}; fun multiply(a: Int, b: Int) = a * b";
@@ -215,16 +215,23 @@ impl<'a, 'e> SymbolResolver<'a, 'e> {
                         );
                     }
 
-                    if result == SymbolResolutionResult::NotFound {
+                    // A qualified name cannot refer to a local symbol, but may refer to an aliased import
+                    if result == SymbolResolutionResult::NotFound
+                        || (symbol_name.is_qualified()
+                            && matches!(result, SymbolResolutionResult::Invalid(_)))
+                    {
                         if let Some(imports) = &self.imports.get_imports(env_id) {
                             // If the symbol wasn't found from the environment locals, try to resolve using its imports
-                            result = resolve_symbol_from_imports(
+                            let imported = resolve_symbol_from_imports(
                                 self.engine,
                                 imports,
                                 symbol_name,
                                 self.externals,
                                 registry,
-                            )
+                            );
+                            if imported != SymbolResolutionResult::NotFound {
+                                result = imported;
+                            }
                         }
                     }
 
@@ -535,6 +542,94 @@ mod tests {
         )
     }
 
+    #[test]
+    fn aliased_module_reference() {
+        let mut importer = StaticImporter::new(
+            [
+                (
+                    Name::new("main"),
+                    "use reef::math as M\nM::id(9)\nfun f() = M::id(1)",
+                ),
+                (Name::new("math"), "fun id(n: Int) -> Int = $n"),
+            ],
+            parse_trusted,
+        );
+        let externals = Externals::default();
+        let mut diagnostics = Vec::new();
+        let res = resolve_all(
+            Name::new("main"),
+            &externals,
+            &mut importer,
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(
+            res.relations
+                .iter()
+                .map(|(_, r)| r.clone())
+                .filter(|r| r.registry == SymbolRegistry::Objects)
+                .collect::<Vec<_>>(),
+            vec![
+                Relation::resolved(
+                    SourceId(0),
+                    ResolvedSymbol::new(ReefId(1), SourceId(2), LocalId(0)),
+                    SymbolRegistry::Objects,
+                ),
+                Relation::resolved(
+                    SourceId(1),
+                    ResolvedSymbol::new(ReefId(1), SourceId(2), LocalId(0)),
+                    SymbolRegistry::Objects,
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn module_alias_clashing_with_local() {
+        let src = "use reef::math as M\nval M = 5\necho $M\nM::id($M)";
+        let mut importer = StaticImporter::new(
+            [
+                (Name::new("main"), src),
+                (Name::new("math"), "fun id(n: Int) -> Int = $n"),
+            ],
+            parse_trusted,
+        );
+        let externals = Externals::default();
+        let mut diagnostics = Vec::new();
+        let res = resolve_all(
+            Name::new("main"),
+            &externals,
+            &mut importer,
+            &mut diagnostics,
+        );
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(
+                DiagnosticID::ShadowedImport,
+                "M is both an import alias and a local symbol.",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(src, "reef::math as M"),
+                "this alias is shadowed by the local symbol",
+            ))
+            .with_help("`M` only refers to the import in qualified paths, such as `M::name`")]
+        );
+        assert_eq!(
+            res.relations
+                .iter()
+                .map(|(_, r)| r.clone())
+                .filter(|r| r.registry == SymbolRegistry::Objects)
+                .collect::<Vec<_>>(),
+            vec![Relation::resolved(
+                SourceId(0),
+                ResolvedSymbol::new(ReefId(1), SourceId(1), LocalId(0)),
+                SymbolRegistry::Objects,
+            )]
+        )
+    }
+
     #[test]
     fn report_unknown_imported_symbol() {
         let mut importer = StaticImporter::new(
@@ -686,7 +781,10 @@ mod tests {
                         find_in(test_src, "foo")
                     ),
                     (
-                        UnresolvedImport::AllIn(SymbolLocation::in_current_reef(Name::new("std"))),
+                        UnresolvedImport::AllIn {
+                            loc: SymbolLocation::in_current_reef(Name::new("std")),
+                            exclusions: Vec::new(),
+                        },
                         find_in(test_src, "reef::std::*")
                     ),
                     (
@@ -796,6 +894,7 @@ mod tests {
                     ),
                 ])
             )
+            .with_glob_imports(["Bar", "Foo"])
         );
     }
 
@@ -1127,6 +1226,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn exclude_from_glob_import() {
+        let source = "use reef::std::* except {Bar, Baz}\n$Foo; $Bar";
+        let mut importer = StaticImporter::new(
+            [
+                (Name::new("main"), source),
+                (Name::new("std"), "val Foo = 1; val Bar = 2"),
+            ],
+            parse_trusted,
+        );
+        let externals = Externals::default();
+        let mut diagnostics = Vec::new();
+        let res = resolve_all(
+            Name::new("main"),
+            &externals,
+            &mut importer,
+            &mut diagnostics,
+        );
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    DiagnosticID::UnknownExclusion,
+                    "module `std` does not export `Baz`."
+                )
+                .with_observation(Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    find_in(source, "Baz"),
+                    "this exclusion has no effect",
+                )),
+                Diagnostic::new(
+                    DiagnosticID::UnknownSymbol,
+                    "Could not resolve symbol `Bar`."
+                )
+                .with_observation((SourceId(0), ReefId(1), find_in(source, "$Bar")).into()),
+            ]
+        );
+        assert_eq!(
+            res.relations.iter().map(|(_, r)| r.clone()).next(),
+            Some(Relation::resolved(
+                SourceId(0),
+                ResolvedSymbol::new(ReefId(1), SourceId(1), LocalId(0)),
+                SymbolRegistry::Objects,
+            ))
+        );
+    }
+
+    #[test]
+    fn import_escaped_keyword_module() {
+        let source = "use reef::`match`::value\n$value";
+        let mut importer = StaticImporter::new(
+            [
+                (Name::new("main"), source),
+                (Name::new("match"), "val value = 1"),
+            ],
+            parse_trusted,
+        );
+        let externals = Externals::default();
+        let mut diagnostics = Vec::new();
+        let res = resolve_all(
+            Name::new("main"),
+            &externals,
+            &mut importer,
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(
+            res.relations.iter().map(|(_, r)| r.clone()).next(),
+            Some(Relation::resolved(
+                SourceId(0),
+                ResolvedSymbol::new(ReefId(1), SourceId(1), LocalId(0)),
+                SymbolRegistry::Objects,
+            ))
+        );
+    }
+
     #[test]
     fn test_unknown_symbols() {
         let a_src = "val C = 'A'";
@@ -121,7 +121,7 @@ impl<'a> SymbolResolver<'a, '_> {
                 }
 
                 //if the unresolved import is an 'AllIn' import, meaning that it imports all symbols from given module
-                UnresolvedImport::AllIn(loc) => {
+                UnresolvedImport::AllIn { loc, exclusions } => {
                     match resolve_loc(&loc, engine, externals) {
                         None => diagnostics.push(
                             Diagnostic::new(
@@ -147,13 +147,19 @@ impl<'a> SymbolResolver<'a, '_> {
                                     );
                                     diagnostics.push(diagnostic);
                                 }
-                                Some((env_id, env)) => {
+                                Some((found_env_id, env)) => {
                                     let mut symbols_map: HashMap<
                                         String,
                                         HashMap<SymbolRegistry, ResolvedSymbol>,
                                     > = HashMap::new();
 
                                     for (var_id, var) in env.symbols.exported_symbols() {
+                                        if exclusions
+                                            .iter()
+                                            .any(|(excluded, _)| *excluded == var.name)
+                                        {
+                                            continue;
+                                        }
                                         let symbols =
                                             symbols_map.entry(var.name.clone()).or_default();
 
@@ -163,15 +169,43 @@ impl<'a> SymbolResolver<'a, '_> {
                                             if registry.accepts(var.ty) {
                                                 symbols.insert(
                                                     registry,
-                                                    ResolvedSymbol::new(reef_id, env_id, var_id),
+                                                    ResolvedSymbol::new(
+                                                        reef_id,
+                                                        found_env_id,
+                                                        var_id,
+                                                    ),
                                                 );
                                                 break;
                                             }
                                         }
                                     }
 
+                                    for (excluded, excluded_segment) in exclusions {
+                                        if !env
+                                            .symbols
+                                            .exported_symbols()
+                                            .any(|(_, var)| var.name == excluded)
+                                        {
+                                            diagnostics.push(
+                                                Diagnostic::new(
+                                                    DiagnosticID::UnknownExclusion,
+                                                    format!(
+                                                        "module `{}` does not export `{excluded}`.",
+                                                        env.fqn
+                                                    ),
+                                                )
+                                                .with_observation(Observation::here(
+                                                    env_id,
+                                                    externals.current,
+                                                    excluded_segment,
+                                                    "this exclusion has no effect",
+                                                )),
+                                            );
+                                        }
+                                    }
+
                                     for (var_name, symbols) in symbols_map {
-                                        imports.set_resolved_import(
+                                        imports.set_glob_import(
                                             var_name.clone(),
                                             ResolvedImport::Symbols(symbols),
                                             segment.clone(),
@@ -33,3 +33,23 @@ pub fn diagnose_invalid_symbol(
             Name::from(tail)
         ))
 }
+
+/// Reports an expression that is nested too deeply to be analyzed.
+pub fn diagnose_recursion_limit(
+    env_id: SourceId,
+    reef: ReefId,
+    segment: SourceSegment,
+    limit: usize,
+) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticID::RecursionLimit,
+        "Expression is nested too deeply",
+    )
+    .with_observation(Observation::here(
+        env_id,
+        reef,
+        segment,
+        format!("This expression exceeds the nesting limit of {limit}"),
+    ))
+    .with_help("Extract the inner expressions into variables or functions")
+}
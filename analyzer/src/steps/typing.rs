@@ -4,6 +4,7 @@ use ast::call::{Call, Detached, Pipeline, ProgrammaticCall, RedirOp, Redirected}
 use ast::control_flow::If;
 use ast::function::FunctionDeclaration;
 use ast::group::{Block, Subshell};
+use ast::lambda::LambdaDef;
 use ast::operation::{BinaryOperation, BinaryOperator, UnaryOperation, UnaryOperator};
 use ast::r#type::CastedExpr;
 use ast::r#use::InclusionPathItem;
@@ -19,8 +20,11 @@ use context::source::{SourceSegment, SourceSegmentHolder};
 use crate::dependency::topological_sort;
 use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
 use crate::engine::Engine;
+use crate::environment::symbols::SymbolInfo;
 use crate::reef::{Externals, ReefId};
-use crate::relations::{Relations, SourceId, SymbolRef};
+use crate::relations::{RelationState, Relations, SourceId, SymbolRef};
+use crate::steps::shared_diagnostics::diagnose_recursion_limit;
+use crate::steps::typing::alias::ascribe_type_alias;
 use crate::steps::typing::assign::{
     ascribe_assign_rhs, ascribe_assign_subscript, create_subscript,
 };
@@ -29,32 +33,43 @@ use crate::steps::typing::coercion::{
     check_type_annotation, coerce_condition, convert_description, convert_expression, convert_many,
     is_compatible, resolve_type_annotation,
 };
+use crate::steps::typing::constant::diagnose_overflows;
 use crate::steps::typing::exploration::{Exploration, Links};
 use crate::steps::typing::function::{
-    declare_function, find_operand_implementation, infer_return, type_call, type_method, Return,
+    declare_function, declare_lambda, find_operand_implementation, infer_return, type_call,
+    type_method, Return,
 };
 use crate::steps::typing::iterable::ascribe_for;
-use crate::steps::typing::lower::{convert_into_string, generate_unwrap};
-use crate::steps::typing::magic::{is_magic_variable_name, prepend_implicits};
+use crate::steps::typing::lower::{convert_into_string, generate_unwrap, spread_glob};
+use crate::steps::typing::magic::{
+    ascribe_env_import, imported_env_variables, is_magic_variable_name, prepend_implicits,
+};
+use crate::steps::typing::r#match::ascribe_match;
 use crate::steps::typing::structure::{
-    ascribe_field_access, ascribe_field_assign, ascribe_struct_declaration,
+    ascribe_field_access, ascribe_field_assign, ascribe_impl, ascribe_struct_declaration,
+    ascribe_tuple, ascribe_tuple_declaration,
 };
-use crate::types::builtin::{BOOL_STRUCT, STRING_STRUCT};
+use crate::types::builtin::{BOOL_STRUCT, COMMAND_STRUCT, STRING_STRUCT};
 use crate::types::ctx::{TypeContext, TypedVariable};
 use crate::types::engine::{Chunk, ChunkKind, TypedEngine};
 use crate::types::hir::{
-    Conditional, Convert, Declaration, ExprKind, FunctionCall, LocalAssignment, Loop, MethodCall,
-    Redir, Redirect, Subprocess, Substitute, TypedExpr, Var,
+    self, Conditional, Convert, Declaration, ExprKind, FunctionCall, LocalAssignment, Loop,
+    MethodCall, Redir, Redirect, Subprocess, Substitute, TypedExpr, Var,
 };
 use crate::types::operator::name_operator_method;
 use crate::types::ty::{FunctionDesc, Type, TypeRef};
 use crate::types::{
-    builtin, Typing, BOOL, ERROR, EXITCODE, FLOAT, GLOB, INT, NOTHING, PID, STRING, UNIT,
+    builtin, Typing, BOOL, DURATION, ERROR, EXITCODE, FLOAT, GLOB, INT, NOTHING, PID, STRING, UNIT,
 };
 
+mod alias;
 mod assign;
 mod bounds;
 mod coercion;
+pub mod complete;
+mod constant;
+pub mod dump;
+pub mod explain;
 mod exploration;
 mod function;
 mod lower;
@@ -63,6 +78,7 @@ mod view;
 
 mod iterable;
 pub mod magic;
+mod r#match;
 
 pub fn apply_types(
     engine: &Engine,
@@ -78,13 +94,23 @@ pub fn apply_types(
         typing: Typing::default(),
         ctx: TypeContext::default(),
         returns: Vec::new(),
+        loop_labels: Vec::new(),
         externals,
     };
 
     for env_id in environments {
-        if let Some(entry) =
-            apply_types_to_source(&mut exploration, diagnostics, engine, relations, env_id)
-        {
+        if is_typed(&exploration.type_engine, env_id) {
+            // functions that infer their return type are typed when they are declared
+            continue;
+        }
+        if let Some(entry) = apply_types_to_source(
+            &mut exploration,
+            diagnostics,
+            engine,
+            relations,
+            env_id,
+            false,
+        ) {
             exploration.type_engine.insert(env_id, entry);
         }
     }
@@ -106,8 +132,11 @@ enum ExpressionValue {
 /// checked.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 struct TypingState {
-    // if not in loop, `continue` and `break` will raise a diagnostic
-    in_loop: bool,
+    // the number of enclosing loops, `continue` and `break` will raise a diagnostic outside any loop
+    loop_depth: usize,
+
+    // the number of enclosing expressions, bounded by the recursion limit of the externals
+    depth: usize,
 
     local_value: ExpressionValue,
 }
@@ -126,15 +155,50 @@ impl TypingState {
         }
     }
 
-    /// Returns a new state with `in_loop` set to true
+    /// Returns a new state nested in one more loop
     fn with_in_loop(self) -> Self {
         Self {
-            in_loop: true,
+            loop_depth: self.loop_depth + 1,
+            ..self
+        }
+    }
+
+    /// Returns a new state nested in one more expression
+    fn with_nested(self) -> Self {
+        Self {
+            depth: self.depth + 1,
             ..self
         }
     }
 }
 
+/// Tests if the body of the given function has already been typed.
+fn is_typed(engine: &TypedEngine, source: SourceId) -> bool {
+    matches!(
+        engine.get_user(source),
+        Some(Chunk {
+            kind: ChunkKind::DefinedFunction(Some(_)),
+            ..
+        })
+    )
+}
+
+/// Tests if all the symbols that a function refers to from its enclosing environments are typed.
+fn are_captures_typed(function_source: SourceId, exploration: &Exploration, links: Links) -> bool {
+    let env = links.engine.get_environment(function_source).unwrap();
+    env.symbols
+        .external_symbols()
+        .all(|(_, relation)| match links.relations[relation].state {
+            RelationState::Resolved(symbol) => {
+                symbol.reef != exploration.externals.current
+                    || exploration
+                        .ctx
+                        .is_local_typed(symbol.source, symbol.object_id)
+            }
+            _ => false,
+        })
+}
+
 fn verify_free_function(
     func: &FunctionDeclaration,
     externals: &Externals,
@@ -170,6 +234,7 @@ fn apply_types_to_source(
     engine: &Engine,
     relations: &Relations,
     source_id: SourceId,
+    eager: bool,
 ) -> Option<Chunk> {
     let links = Links {
         source: source_id,
@@ -182,9 +247,10 @@ fn apply_types_to_source(
         Expr::FunctionDeclaration(func) => {
             // Take any previous forward declaration if present.
             let forward_declaration = exploration.type_engine.take_user(source_id);
+            let forward_declaration_absent = forward_declaration.is_none();
 
             let base_chunk = forward_declaration
-                .unwrap_or_else(|| declare_function(func, exploration, links, diagnostics));
+                .unwrap_or_else(|| declare_function(func, None, exploration, links, diagnostics));
 
             let function_id = base_chunk.function_id;
             let function_type = base_chunk.function_type;
@@ -193,19 +259,24 @@ fn apply_types_to_source(
             let expected_return_type = chunk_function.return_type;
 
             let typed_body = func.body.as_ref().map(|body| {
-                ascribe_types(
+                let body = ascribe_types(
                     exploration,
                     links,
                     diagnostics,
                     body,
                     TypingState::default()
                         .with_local_value(ExpressionValue::Expected(expected_return_type)),
-                )
+                );
+                diagnose_overflows(&body, exploration, links, diagnostics);
+                body
             });
 
+            // the calls typed against a forward declaration rely on its declared return type
+            let inferable = eager || forward_declaration_absent;
             let return_type = infer_return(
                 func,
                 expected_return_type,
+                inferable,
                 links,
                 typed_body.as_ref(),
                 diagnostics,
@@ -235,6 +306,33 @@ fn apply_types_to_source(
                 }
             }
         }
+        // lambdas are typed where they are defined, once their captures are typed
+        Expr::LambdaDef(_) if !eager => None,
+        Expr::LambdaDef(lambda) => {
+            let chunk = exploration
+                .type_engine
+                .take_user(source_id)
+                .unwrap_or_else(|| declare_lambda(lambda, exploration, links, diagnostics));
+
+            let body = ascribe_types(
+                exploration,
+                links,
+                diagnostics,
+                &lambda.body,
+                TypingState::default().with_local_value(ExpressionValue::Unspecified),
+            );
+            diagnose_overflows(&body, exploration, links, diagnostics);
+            exploration
+                .type_engine
+                .get_function_mut(chunk.function_id)
+                .unwrap()
+                .return_type = body.ty;
+
+            Some(Chunk {
+                kind: ChunkKind::DefinedFunction(Some(body)),
+                ..chunk
+            })
+        }
         Expr::StructDeclaration(_) => None,
         expr => {
             exploration
@@ -243,6 +341,7 @@ fn apply_types_to_source(
 
             let expression =
                 ascribe_types(exploration, links, diagnostics, expr, TypingState::new());
+            diagnose_overflows(&expression, exploration, links, diagnostics);
 
             let script_fn_id = exploration.type_engine.add_function(FunctionDesc::script());
             let script_fn_name = links.env().fqn.to_string();
@@ -268,6 +367,7 @@ fn ascribe_literal(lit: &Literal) -> TypedExpr {
         LiteralValue::Float(_) => FLOAT,
         LiteralValue::String(_) => STRING,
         LiteralValue::Bool(_) => BOOL,
+        LiteralValue::Duration(_) => DURATION,
     };
     TypedExpr {
         kind: ExprKind::Literal(lit.parsed.clone()),
@@ -299,10 +399,12 @@ fn ascribe_template_string(
             name_operator_method(BinaryOperator::Plus),
             &[STRING],
             STRING,
+            &|ty| exploration.get_type(ty),
         )
         .expect("string type should have a concatenation method");
 
-    let mut it = tpl.parts.iter().map(|part| {
+    let mut parts: Vec<TypedExpr> = Vec::with_capacity(tpl.parts.len());
+    for part in &tpl.parts {
         let typed_part = ascribe_types(
             exploration,
             links,
@@ -310,8 +412,25 @@ fn ascribe_template_string(
             part,
             state.with_local_value(ExpressionValue::Unused),
         );
-        convert_into_string(typed_part, exploration, diagnostics, links.source)
-    });
+        let typed_part = convert_into_string(typed_part, exploration, diagnostics, links.source);
+
+        // Adjacent literals are joined rather than concatenated at runtime
+        if let (
+            Some(TypedExpr {
+                kind: ExprKind::Literal(LiteralValue::String(previous)),
+                segment,
+                ..
+            }),
+            ExprKind::Literal(LiteralValue::String(current)),
+        ) = (parts.last_mut(), &typed_part.kind)
+        {
+            previous.push_str(current);
+            segment.end = typed_part.segment.end;
+            continue;
+        }
+        parts.push(typed_part);
+    }
+    let mut it = parts.into_iter();
     let acc = it.next().unwrap();
     it.fold(acc, |acc, current| {
         let segment = current.segment.clone();
@@ -527,6 +646,22 @@ fn ascribe_var_declaration(
         );
     }
 
+    if decl.is_ignored() {
+        // The value is only evaluated for its side effects
+        return TypedExpr {
+            kind: ExprKind::Block(vec![
+                initializer,
+                TypedExpr {
+                    kind: ExprKind::Noop,
+                    ty: UNIT,
+                    segment: decl.segment(),
+                },
+            ]),
+            ty: UNIT,
+            segment: decl.segment(),
+        };
+    }
+
     let id = links.env().get_raw_symbol(decl.segment()).unwrap();
 
     let SymbolRef::Local(id) = id else {
@@ -573,9 +708,18 @@ fn ascribe_magic_var_reference(
         ),
     };
 
+    // program arguments are of type Vec[String], variadic parameters may hold other types
+    let pargs_type = match pargs_var {
+        Var::Local(id) => exploration
+            .ctx
+            .get_local(links.source, id)
+            .map_or(builtin::STRING_VEC, |var| var.type_ref),
+        Var::External(_) => builtin::STRING_VEC,
+    };
+
     let parg_reference_expression = TypedExpr {
         kind: ExprKind::Reference(pargs_var),
-        ty: builtin::STRING_VEC, //program arguments is of type Vec[String]
+        ty: pargs_type,
         segment: var_ref.segment(),
     };
 
@@ -631,6 +775,7 @@ fn ascribe_var_reference(
     var_ref: &VarReference,
     links: Links,
     exploration: &Exploration,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> TypedExpr {
     if let Some(magic_ref) = ascribe_magic_var_reference(var_ref, exploration, links) {
         return magic_ref;
@@ -660,6 +805,22 @@ fn ascribe_var_reference(
         .unwrap()
         .type_ref;
 
+    if let Some(Type::Function(..)) = exploration.get_type(type_ref) {
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::UnsupportedFeature,
+                "Functions cannot be used as values yet",
+            )
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                var_ref.segment(),
+                "This function is referenced as a value",
+            ))
+            .with_help("Call the function instead"),
+        );
+    }
+
     let var = match symbol {
         SymbolRef::Local(id) => Var::Local(id),
         SymbolRef::External(id) => Var::External(
@@ -676,7 +837,12 @@ fn ascribe_var_reference(
     }
 }
 
-fn ascribe_identifier(ident: &Path, links: Links, exploration: &Exploration) -> TypedExpr {
+fn ascribe_identifier(
+    ident: &Path,
+    links: Links,
+    exploration: &Exploration,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
     ascribe_var_reference(
         &VarReference {
             name: VarName::User(ident.path.last().unwrap().name().into()),
@@ -684,6 +850,7 @@ fn ascribe_identifier(ident: &Path, links: Links, exploration: &Exploration) ->
         },
         links,
         exploration,
+        diagnostics,
     )
 }
 
@@ -698,11 +865,16 @@ fn ascribe_block(
     let mut it = block
         .expressions
         .iter()
-        .filter(|expr| !matches!(expr, Expr::Use(_)))
+        .filter(|expr| match expr {
+            // only the imported environment variables have to be bound
+            Expr::Use(import) => !imported_env_variables(&import.import).is_empty(),
+            _ => true,
+        })
         .peekable();
 
+    let mut reported_unreachable = false;
     while let Some(expr) = it.next() {
-        expressions.push(ascribe_types(
+        let typed_expr = ascribe_types(
             exploration,
             links,
             diagnostics,
@@ -712,7 +884,29 @@ fn ascribe_block(
             } else {
                 state
             },
-        ));
+        );
+        if typed_expr.ty == NOTHING && !reported_unreachable {
+            if let Some(next) = it.peek() {
+                // only the first unreachable statement is reported
+                reported_unreachable = true;
+                diagnostics.push(
+                    Diagnostic::new(DiagnosticID::UnreachableCode, "Unreachable code")
+                        .with_observation(Observation::here(
+                            links.source,
+                            exploration.externals.current,
+                            next.segment(),
+                            "This statement will never be executed",
+                        ))
+                        .with_observation(Observation::context(
+                            links.source,
+                            exploration.externals.current,
+                            typed_expr.segment.clone(),
+                            "Any code following this expression is unreachable",
+                        )),
+                );
+            }
+        }
+        expressions.push(typed_expr);
     }
     let ty = expressions.last().map_or(UNIT, |expr| expr.ty);
     TypedExpr {
@@ -791,7 +985,10 @@ fn ascribe_pipeline(
         ));
     }
     TypedExpr {
-        kind: ExprKind::Pipeline(commands),
+        kind: ExprKind::Pipeline(hir::Pipeline {
+            commands,
+            pipes: pipeline.pipes.clone(),
+        }),
         ty: EXITCODE,
         segment: pipeline.segment(),
     }
@@ -811,17 +1008,30 @@ fn ascribe_substitution(
         .iter()
         .map(|command| ascribe_types(exploration, links, diagnostics, command, state))
         .collect::<Vec<_>>();
+    let (kind, ty) = match substitution.kind {
+        ast::substitution::SubstitutionKind::Capture => (ExprKind::Capture(commands), STRING),
+        ast::substitution::SubstitutionKind::Status => (
+            ExprKind::Subprocess(Subprocess {
+                inner: Box::new(TypedExpr {
+                    kind: ExprKind::Block(commands),
+                    ty: UNIT,
+                    segment: substitution.underlying.segment.clone(),
+                }),
+                awaited: true,
+            }),
+            EXITCODE,
+        ),
+        ast::substitution::SubstitutionKind::Process { direction } => (
+            ExprKind::Substitute(match direction {
+                ast::substitution::Direction::Input => Substitute::In(commands),
+                ast::substitution::Direction::Output => Substitute::Out(commands),
+            }),
+            STRING,
+        ),
+    };
     TypedExpr {
-        kind: match substitution.kind {
-            ast::substitution::SubstitutionKind::Capture => ExprKind::Capture(commands),
-            ast::substitution::SubstitutionKind::Process { direction } => {
-                ExprKind::Substitute(match direction {
-                    ast::substitution::Direction::Input => Substitute::In(commands),
-                    ast::substitution::Direction::Output => Substitute::Out(commands),
-                })
-            }
-        },
-        ty: STRING,
+        kind,
+        ty,
         segment: substitution.segment(),
     }
 }
@@ -840,6 +1050,20 @@ fn ascribe_detached(
         &detached.underlying,
         state.with_local_value(ExpressionValue::Unused),
     );
+    if expr.ty.is_ok() && !runs_command(&expr) {
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::DetachedValue,
+                "Only commands can run in the background",
+            )
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                detached.underlying.segment(),
+                "This expression does not run any command",
+            )),
+        );
+    }
     TypedExpr {
         kind: ExprKind::Subprocess(Subprocess {
             inner: Box::new(expr),
@@ -850,6 +1074,23 @@ fn ascribe_detached(
     }
 }
 
+/// Tests if the expression may spawn a command or call a function that could.
+fn runs_command(expr: &TypedExpr) -> bool {
+    let mut found = false;
+    expr.walk(&mut |expr| {
+        found |= matches!(
+            expr.kind,
+            ExprKind::ProcessCall(_)
+                | ExprKind::Pipeline(_)
+                | ExprKind::Redirect(_)
+                | ExprKind::Capture(_)
+                | ExprKind::Substitute(_)
+                | ExprKind::FunctionCall(_)
+        );
+    });
+    found
+}
+
 fn ascribe_subshell(
     subshell: &Subshell,
     exploration: &mut Exploration,
@@ -919,7 +1160,7 @@ fn ascribe_function_declaration(
         // if not, forward declare it by typing its declared signature
         let declaration_link = links.with_source(function_source);
         let forward_declared_chunk =
-            declare_function(fun, exploration, declaration_link, diagnostics);
+            declare_function(fun, None, exploration, declaration_link, diagnostics);
         exploration
             .type_engine
             .insert(function_source, forward_declared_chunk);
@@ -939,6 +1180,27 @@ fn ascribe_function_declaration(
         .ctx
         .set_local_typed(links.source, local_id, type_ref);
 
+    // The return type of a block function may be inferred from its body, that has to be typed
+    // before the calls that follow the declaration.
+    if fun.return_type.is_none()
+        && matches!(fun.body.as_deref(), Some(Expr::Block(_)))
+        && !is_typed(&exploration.type_engine, function_source)
+        && are_captures_typed(function_source, exploration, links)
+    {
+        let returns = std::mem::take(&mut exploration.returns);
+        if let Some(chunk) = apply_types_to_source(
+            exploration,
+            diagnostics,
+            links.engine,
+            links.relations,
+            function_source,
+            true,
+        ) {
+            exploration.type_engine.insert(function_source, chunk);
+        }
+        exploration.returns = returns;
+    }
+
     TypedExpr {
         kind: ExprKind::Declare(Declaration {
             identifier: local_id,
@@ -949,6 +1211,53 @@ fn ascribe_function_declaration(
     }
 }
 
+fn ascribe_lambda(
+    lambda: &LambdaDef,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let lambda_source = links.env().get_raw_env(lambda.segment()).unwrap();
+
+    // the lambda's return type is inferred from its body, that has to be typed before its calls
+    if !is_typed(&exploration.type_engine, lambda_source) {
+        let returns = std::mem::take(&mut exploration.returns);
+        if let Some(chunk) = apply_types_to_source(
+            exploration,
+            diagnostics,
+            links.engine,
+            links.relations,
+            lambda_source,
+            true,
+        ) {
+            exploration.type_engine.insert(lambda_source, chunk);
+        }
+        exploration.returns = returns;
+    }
+
+    // lambdas are typed, but cannot be compiled as closures yet
+    diagnostics.push(
+        Diagnostic::new(
+            DiagnosticID::UnsupportedFeature,
+            "Lambdas are not supported as values yet",
+        )
+        .with_observation(Observation::here(
+            links.source,
+            exploration.externals.current,
+            lambda.segment(),
+            "This lambda cannot be stored or called",
+        ))
+        .with_help("Declare a named function with `fun` instead"),
+    );
+
+    let chunk = exploration.type_engine.get_user(lambda_source).unwrap();
+    TypedExpr {
+        kind: ExprKind::Noop,
+        ty: TypeRef::new(exploration.externals.current, chunk.function_type),
+        segment: lambda.segment(),
+    }
+}
+
 fn ascribe_binary(
     bin: &BinaryOperation,
     exploration: &mut Exploration,
@@ -958,6 +1267,20 @@ fn ascribe_binary(
 ) -> TypedExpr {
     let left_expr = ascribe_types(exploration, links, diagnostics, &bin.left, state);
     let right_expr = ascribe_types(exploration, links, diagnostics, &bin.right, state);
+    let (left_expr, right_expr) = match bin.op {
+        // The membership is tested by the collection, on the right-hand side
+        BinaryOperator::In => (right_expr, left_expr),
+        // Exit codes are combined as is, any other operand is tested as a condition
+        BinaryOperator::And | BinaryOperator::Or
+            if left_expr.ty != EXITCODE || right_expr.ty != EXITCODE =>
+        {
+            (
+                coerce_condition(left_expr, exploration, links.source, diagnostics),
+                coerce_condition(right_expr, exploration, links.source, diagnostics),
+            )
+        }
+        _ => (left_expr, right_expr),
+    };
     let left_type = left_expr.ty;
     let right_type = right_expr.ty;
     let name = name_operator_method(bin.op);
@@ -967,8 +1290,9 @@ fn ascribe_binary(
         .map(|methods| methods.as_slice())
         .unwrap_or(&[]);
 
+    let left_base_reef = exploration.get_base_type(left_type).reef;
     let method =
-        find_operand_implementation(exploration, left_type.reef, methods, left_expr, right_expr);
+        find_operand_implementation(exploration, left_base_reef, methods, left_expr, right_expr);
     match method {
         Ok(method) => TypedExpr {
             ty: method.return_type,
@@ -976,20 +1300,24 @@ fn ascribe_binary(
             segment: bin.segment(),
         },
         Err(left) => {
-            diagnostics.push(
-                Diagnostic::new(DiagnosticID::UnknownMethod, "Undefined operator")
-                    .with_observation(Observation::here(
-                        links.source,
-                        exploration.externals.current,
-                        bin.segment(),
-                        format!(
-                            "No operator `{}` between type `{}` and `{}`",
-                            name,
-                            exploration.new_type_view(left_type, &TypesBounds::inactive()),
-                            exploration.new_type_view(right_type, &TypesBounds::inactive()),
-                        ),
-                    )),
-            );
+            let mut diagnostic = Diagnostic::new(DiagnosticID::UnknownMethod, "Undefined operator")
+                .with_observation(Observation::here(
+                    links.source,
+                    exploration.externals.current,
+                    bin.segment(),
+                    format!(
+                        "No operator `{}` between type `{}` and `{}`",
+                        name,
+                        exploration.new_type_view(left_type, &TypesBounds::inactive()),
+                        exploration.new_type_view(right_type, &TypesBounds::inactive()),
+                    ),
+                ));
+            if bin.op == BinaryOperator::Modulo && (left_type == FLOAT || right_type == FLOAT) {
+                diagnostic = diagnostic.with_help(
+                    "The modulo is only defined between integers, round `Float` operands with `std::math::round` or `std::math::floor`",
+                );
+            }
+            diagnostics.push(diagnostic);
             left
         }
     }
@@ -1075,6 +1403,18 @@ fn ascribe_range(
                 diagnostics.push(diagnostic);
                 return start.poison();
             }
+            if let ExprKind::Literal(LiteralValue::Int(0)) = step.kind {
+                diagnostics.push(
+                    Diagnostic::new(DiagnosticID::InvalidRangeStep, "Range step cannot be zero")
+                        .with_observation(Observation::here(
+                            links.source,
+                            exploration.externals.current,
+                            step.segment(),
+                            "The range would never reach its end",
+                        )),
+                );
+                return start.poison();
+            }
 
             let symbol = links.env().get_raw_symbol(range.segment()).unwrap();
             let function_type_ref = exploration
@@ -1096,6 +1436,7 @@ fn ascribe_range(
                     arguments: vec![start, end, step],
                     reef: ReefId(1),
                     source_id: *structure_source,
+                    evaluation_order: None,
                 }),
                 ty: function.return_type,
                 segment: range.segment(),
@@ -1235,7 +1576,9 @@ fn ascribe_not(
     let lang = exploration.externals.lang();
     let (not_method, not_method_id) = lang
         .typed_engine
-        .get_method_exact(BOOL_STRUCT, "not", &[], BOOL)
+        .get_method_exact(BOOL_STRUCT, "not", &[], BOOL, &|ty| {
+            exploration.get_type(ty)
+        })
         .expect("A Bool should be invertible");
     match convert_expression(
         not,
@@ -1273,6 +1616,10 @@ fn ascribe_not(
     }
 }
 
+/// Types a conditional expression, including the `else if` chain that follows it.
+///
+/// All the branches of the chain are unified together, so that a mismatch is
+/// reported over every branch rather than only the innermost pair.
 fn ascribe_if(
     block: &If,
     exploration: &mut Exploration,
@@ -1280,41 +1627,53 @@ fn ascribe_if(
     diagnostics: &mut Vec<Diagnostic>,
     state: TypingState,
 ) -> TypedExpr {
-    let condition = ascribe_types(exploration, links, diagnostics, &block.condition, state);
-
     let current_reef = exploration.externals.current;
 
-    let condition = coerce_condition(condition, exploration, links.source, diagnostics);
-    let mut then = ascribe_types(
-        exploration,
-        links,
-        diagnostics,
-        &block.success_branch,
-        state,
-    );
+    let mut arms = Vec::new();
+    let mut current = block;
+    let mut otherwise = loop {
+        let condition = ascribe_types(exploration, links, diagnostics, &current.condition, state);
+        let condition = coerce_condition(condition, exploration, links.source, diagnostics);
+        let then = ascribe_types(
+            exploration,
+            links,
+            diagnostics,
+            &current.success_branch,
+            state,
+        );
+        arms.push((current, condition, then));
 
-    let mut otherwise = block
-        .fail_branch
-        .as_ref()
-        .map(|expr| ascribe_types(exploration, links, diagnostics, expr, state));
+        match current.fail_branch.as_deref() {
+            Some(Expr::If(nested)) => current = nested,
+            Some(expr) => break Some(ascribe_types(exploration, links, diagnostics, expr, state)),
+            None => break None,
+        }
+    };
 
     let ty = if state.local_value != ExpressionValue::Unused {
-        match convert_many(
-            exploration,
-            &mut TypesBounds::inactive(),
-            [then.ty, otherwise.as_ref().map_or(UNIT, |expr| expr.ty)],
-        ) {
+        let types = arms
+            .iter()
+            .map(|(_, _, then)| then.ty)
+            .chain([otherwise.as_ref().map_or(UNIT, |expr| expr.ty)])
+            .collect::<Vec<_>>();
+        match convert_many(exploration, &mut TypesBounds::inactive(), types) {
             Ok(ty) => {
                 // Generate appropriate casts and implicits conversions
-                then = convert_expression(
-                    then,
-                    ty,
-                    &mut TypesBounds::inactive(),
-                    exploration,
-                    links.source,
-                    diagnostics,
-                )
-                .expect("Type mismatch should already have been caught");
+                arms = arms
+                    .into_iter()
+                    .map(|(block, condition, then)| {
+                        let then = convert_expression(
+                            then,
+                            ty,
+                            &mut TypesBounds::inactive(),
+                            exploration,
+                            links.source,
+                            diagnostics,
+                        )
+                        .expect("Type mismatch should already have been caught");
+                        (block, condition, then)
+                    })
+                    .collect();
                 otherwise = otherwise.map(|expr| {
                     convert_expression(
                         expr,
@@ -1328,20 +1687,40 @@ fn ascribe_if(
                 });
                 ty
             }
+            Err(_) if otherwise.is_none() => {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::MissingElse,
+                        "`if` used as a value requires an `else` branch",
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        block.segment(),
+                        "This `if` has no `else` branch",
+                    ))
+                    .with_help(
+                        "Add an `else` branch to provide a value when the condition is false",
+                    ),
+                );
+                ERROR
+            }
             Err(_) => {
                 let mut diagnostic = Diagnostic::new(
                     DiagnosticID::TypeMismatch,
                     "`if` and `else` have incompatible types",
-                )
-                .with_observation(Observation::here(
-                    links.source,
-                    current_reef,
-                    block.success_branch.segment(),
-                    format!(
-                        "Found `{}`",
-                        exploration.new_type_view(then.ty, &TypesBounds::inactive()),
-                    ),
-                ));
+                );
+                for (block, _, then) in &arms {
+                    diagnostic = diagnostic.with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        block.success_branch.segment(),
+                        format!(
+                            "Found `{}`",
+                            exploration.new_type_view(then.ty, &TypesBounds::inactive()),
+                        ),
+                    ));
+                }
                 if let Some(otherwise) = &otherwise {
                     diagnostic = diagnostic.with_observation(Observation::here(
                         links.source,
@@ -1360,15 +1739,20 @@ fn ascribe_if(
     } else {
         UNIT
     };
-    TypedExpr {
-        kind: ExprKind::Conditional(Conditional {
-            condition: Box::new(condition),
-            then: Box::new(then),
-            otherwise: otherwise.map(Box::new),
-        }),
-        ty,
-        segment: block.segment.clone(),
+
+    // Rebuild the chain, starting from the innermost `if`
+    for (block, condition, then) in arms.into_iter().rev() {
+        otherwise = Some(TypedExpr {
+            kind: ExprKind::Conditional(Conditional {
+                condition: Box::new(condition),
+                then: Box::new(then),
+                otherwise: otherwise.map(Box::new),
+            }),
+            ty,
+            segment: block.segment.clone(),
+        });
     }
+    otherwise.expect("a conditional has at least one branch")
 }
 
 fn ascribe_call(
@@ -1395,6 +1779,21 @@ fn ascribe_call(
             };
             return ascribe_pfc(&pfc, exploration, links, diagnostics, state);
         }
+        if is_reachable_function(cmd, links) {
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::FunctionCalledAsCommand,
+                    format!("`{cmd}` is called as an external command"),
+                )
+                .with_observation(Observation::here(
+                    links.source,
+                    exploration.externals.current,
+                    segment.clone(),
+                    format!("`{cmd}` is also a function"),
+                ))
+                .with_help(format!("Use `{cmd}(...)` to call the function")),
+            );
+        }
     }
 
     let args = call
@@ -1403,25 +1802,21 @@ fn ascribe_call(
         .map(|expr| {
             let expr = ascribe_types(exploration, links, diagnostics, expr, state);
             if expr.ty == GLOB {
-                let glob = exploration
-                    .get_method_exact(expr.ty, "spread", &[], builtin::STRING_VEC)
-                    .expect("glob method not found");
-                let segment = expr.segment.clone();
-                TypedExpr {
-                    kind: ExprKind::MethodCall(MethodCall {
-                        callee: Box::new(expr),
-                        arguments: vec![],
-                        function_id: glob.1,
-                    }),
-                    ty: glob.0.return_type,
-                    segment,
-                }
+                spread_glob(expr, exploration)
             } else {
                 convert_into_string(expr, exploration, diagnostics, links.source)
             }
         })
         .collect::<Vec<_>>();
 
+    if let [Expr::Literal(Literal {
+        parsed: LiteralValue::String(cmd),
+        ..
+    }), ..] = call.arguments.as_slice()
+    {
+        check_command_arguments(cmd, call, &args[1..], exploration, links, diagnostics);
+    }
+
     TypedExpr {
         kind: ExprKind::ProcessCall(args),
         ty: EXITCODE,
@@ -1429,29 +1824,93 @@ fn ascribe_call(
     }
 }
 
-fn ascribe_pfc(
-    call: &ProgrammaticCall,
-    exploration: &mut Exploration,
+/// Checks the arguments of a command against its signature, if the language knows about it.
+fn check_command_arguments(
+    cmd: &str,
+    call: &Call,
+    args: &[TypedExpr],
+    exploration: &Exploration,
     links: Links,
     diagnostics: &mut Vec<Diagnostic>,
-    state: TypingState,
-) -> TypedExpr {
-    let function_match = type_call(call, exploration, links, state, diagnostics);
-    TypedExpr {
-        kind: ExprKind::FunctionCall(FunctionCall {
-            arguments: function_match.arguments,
-            function_id: function_match.function_id,
-            source_id: function_match.function_source,
-            reef: function_match.reef,
-        }),
-        ty: function_match.return_type,
-        segment: call.segment.clone(),
+) {
+    let lang = exploration.externals.lang();
+    if lang.typed_engine.get_methods(COMMAND_STRUCT, cmd).is_none() {
+        return;
     }
-}
-
-fn ascribe_method_call(
-    method: &ast::call::MethodCall,
-    exploration: &mut Exploration,
+    // a spread glob is passed as strings
+    let arg_types = args
+        .iter()
+        .map(|arg| {
+            if arg.ty == builtin::STRING_VEC {
+                STRING
+            } else {
+                arg.ty
+            }
+        })
+        .collect::<Vec<_>>();
+    if arg_types.iter().any(|ty| ty.is_err()) {
+        return;
+    }
+    if lang
+        .typed_engine
+        .get_method_exact(COMMAND_STRUCT, cmd, &arg_types, EXITCODE, &|ty| {
+            exploration.get_type(ty)
+        })
+        .is_none()
+    {
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                format!("Missing arguments for `{cmd}`"),
+            )
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                call.segment(),
+                "Command is called here",
+            )),
+        );
+    }
+}
+
+/// Tests if a function with the given name is declared in the environment or in its parents.
+fn is_reachable_function(name: &str, links: Links) -> bool {
+    std::iter::successors(Some(links.env()), |env| {
+        env.parent
+            .and_then(|parent| links.engine.get_environment(parent))
+    })
+    .any(|env| {
+        env.symbols
+            .all()
+            .iter()
+            .any(|symbol| symbol.name == name && symbol.ty == SymbolInfo::Function)
+    })
+}
+
+fn ascribe_pfc(
+    call: &ProgrammaticCall,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+    state: TypingState,
+) -> TypedExpr {
+    let function_match = type_call(call, exploration, links, state, diagnostics);
+    TypedExpr {
+        kind: ExprKind::FunctionCall(FunctionCall {
+            arguments: function_match.arguments,
+            function_id: function_match.function_id,
+            source_id: function_match.function_source,
+            reef: function_match.reef,
+            evaluation_order: function_match.evaluation_order,
+        }),
+        ty: function_match.return_type,
+        segment: call.segment.clone(),
+    }
+}
+
+fn ascribe_method_call(
+    method: &ast::call::MethodCall,
+    exploration: &mut Exploration,
     links: Links,
     diagnostics: &mut Vec<Diagnostic>,
     state: TypingState,
@@ -1524,15 +1983,12 @@ fn ascribe_loop(
         Expr::Loop(l) => (None, &l.body),
         _ => unreachable!("Expression is not a loop"),
     };
-    let body = ascribe_types(
-        exploration,
-        links,
-        diagnostics,
-        body,
-        state
-            .with_in_loop()
-            .with_local_value(ExpressionValue::Unused),
-    );
+    let label = match loo {
+        Expr::While(w) => w.label.as_ref(),
+        Expr::Loop(l) => l.label.as_ref(),
+        _ => None,
+    };
+    let body = ascribe_loop_body(body, label, exploration, links, diagnostics, state);
 
     TypedExpr {
         kind: ExprKind::ConditionalLoop(Loop {
@@ -1544,19 +2000,45 @@ fn ascribe_loop(
     }
 }
 
+/// Ascribes types to the body of a loop, in which jumps can target the loop by its label.
+pub(super) fn ascribe_loop_body(
+    body: &Expr,
+    label: Option<&Identifier>,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+    state: TypingState,
+) -> TypedExpr {
+    exploration
+        .loop_labels
+        .push(label.map(|label| label.value.to_string()));
+    let body = ascribe_types(
+        exploration,
+        links,
+        diagnostics,
+        body,
+        state
+            .with_in_loop()
+            .with_local_value(ExpressionValue::Unused),
+    );
+    exploration.loop_labels.pop();
+    body
+}
+
 fn ascribe_continue_or_break(
     expr: &Expr,
+    exploration: &Exploration,
     diagnostics: &mut Vec<Diagnostic>,
     source: SourceId,
-    current_reef: ReefId,
-    in_loop: bool,
+    state: TypingState,
 ) -> TypedExpr {
-    let (kind, kind_name) = match expr {
-        Expr::Continue(_) => (ExprKind::Continue, "continue"),
-        Expr::Break(_) => (ExprKind::Break, "break"),
+    let (jump, kind_name) = match expr {
+        Expr::Continue(jump) => (jump, "continue"),
+        Expr::Break(jump) => (jump, "break"),
         _ => panic!("e is not a loop"),
     };
-    if !in_loop {
+    let current_reef = exploration.externals.current;
+    let depth = if state.loop_depth == 0 {
         diagnostics.push(
             Diagnostic::new(
                 DiagnosticID::InvalidBreakOrContinue,
@@ -1564,7 +2046,39 @@ fn ascribe_continue_or_break(
             )
             .with_observation((source, current_reef, expr.segment()).into()),
         );
-    }
+        0
+    } else if let Some(label) = &jump.label {
+        // Only the loops of the current function are visible
+        let visible = &exploration.loop_labels[exploration.loop_labels.len() - state.loop_depth..];
+        match visible
+            .iter()
+            .rev()
+            .position(|candidate| candidate.as_deref() == Some(label.value.as_str()))
+        {
+            Some(depth) => depth,
+            None => {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::UnknownLoopLabel,
+                        format!("Use of undeclared loop label `{}`", label.value),
+                    )
+                    .with_observation(Observation::here(
+                        source,
+                        current_reef,
+                        label.segment(),
+                        "No enclosing loop has this label",
+                    )),
+                );
+                0
+            }
+        }
+    } else {
+        0
+    };
+    let kind = match expr {
+        Expr::Continue(_) => ExprKind::Continue(depth),
+        _ => ExprKind::Break(depth),
+    };
     TypedExpr {
         kind,
         ty: NOTHING,
@@ -1582,6 +2096,17 @@ fn ascribe_types(
     expr: &Expr,
     state: TypingState,
 ) -> TypedExpr {
+    let limit = exploration.externals.recursion_limit();
+    if state.depth >= limit {
+        diagnostics.push(diagnose_recursion_limit(
+            links.source,
+            exploration.externals.current,
+            expr.segment(),
+            limit,
+        ));
+        return TypedExpr::error(expr.segment());
+    }
+    let state = state.with_nested();
     match expr {
         Expr::FunctionDeclaration(fd) => {
             ascribe_function_declaration(fd, exploration, links, diagnostics)
@@ -1589,6 +2114,9 @@ fn ascribe_types(
         Expr::StructDeclaration(decl) => {
             ascribe_struct_declaration(decl, exploration, links, diagnostics)
         }
+        Expr::Impl(imp) => ascribe_impl(imp, exploration, links, diagnostics),
+        Expr::TypeAlias(alias) => ascribe_type_alias(alias, exploration, links, diagnostics),
+        Expr::LambdaDef(lambda) => ascribe_lambda(lambda, exploration, links, diagnostics),
         Expr::Literal(lit) => ascribe_literal(lit),
         Expr::TemplateString(tpl) => {
             ascribe_template_string(tpl, exploration, links, diagnostics, state)
@@ -1597,12 +2125,34 @@ fn ascribe_types(
         Expr::VarDeclaration(decl) => {
             ascribe_var_declaration(decl, exploration, links, diagnostics, state)
         }
-        Expr::VarReference(var) => ascribe_var_reference(var, links, exploration),
+        Expr::TupleDeclaration(decl) => {
+            ascribe_tuple_declaration(decl, exploration, links, diagnostics, state)
+        }
+        Expr::VarReference(var) => ascribe_var_reference(var, links, exploration, diagnostics),
+        Expr::Use(import) => ascribe_env_import(import, exploration, links, diagnostics),
         Expr::FieldAccess(fa) => ascribe_field_access(fa, links, exploration, diagnostics, state),
-        Expr::Path(ident) => ascribe_identifier(ident, links, exploration),
+        Expr::Path(ident) => ascribe_identifier(ident, links, exploration, diagnostics),
         Expr::If(block) => ascribe_if(block, exploration, links, diagnostics, state),
+        Expr::Match(match_expr) => {
+            ascribe_match(match_expr, exploration, links, diagnostics, state)
+        }
         Expr::Call(call) => ascribe_call(call, exploration, links, diagnostics, state),
         Expr::ProgrammaticCall(call) => ascribe_pfc(call, exploration, links, diagnostics, state),
+        Expr::NamedArgument(named) => {
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::InvalidNamedArgument,
+                    "Named arguments are only supported in function calls",
+                )
+                .with_observation(Observation::here(
+                    links.source,
+                    exploration.externals.current,
+                    named.segment(),
+                    "Remove the argument name",
+                )),
+            );
+            ascribe_types(exploration, links, diagnostics, &named.value, state)
+        }
         Expr::MethodCall(method) => {
             ascribe_method_call(method, exploration, links, diagnostics, state)
         }
@@ -1626,6 +2176,7 @@ fn ascribe_types(
         Expr::Parenthesis(paren) => {
             ascribe_types(exploration, links, diagnostics, &paren.expression, state)
         }
+        Expr::TupleLiteral(tuple) => ascribe_tuple(tuple, links, exploration, diagnostics, state),
         Expr::Unary(unary) => ascribe_unary(unary, exploration, links, diagnostics, state),
         Expr::Binary(bo) => ascribe_binary(bo, exploration, links, diagnostics, state),
         Expr::Subscript(sub) => ascribe_subscript(sub, exploration, links, diagnostics, state),
@@ -1637,13 +2188,9 @@ fn ascribe_types(
             ascribe_loop(e, exploration, links, diagnostics, state)
         }
         Expr::For(f) => ascribe_for(f, exploration, links, diagnostics, state),
-        e @ (Expr::Continue(_) | Expr::Break(_)) => ascribe_continue_or_break(
-            e,
-            diagnostics,
-            links.source,
-            exploration.externals.current,
-            state.in_loop,
-        ),
+        e @ (Expr::Continue(_) | Expr::Break(_)) => {
+            ascribe_continue_or_break(e, exploration, diagnostics, links.source, state)
+        }
         _ => todo!("{expr:?}"),
     }
 }
@@ -1670,10 +2217,16 @@ mod tests {
         let name = Name::new(source.name);
         let mut externals = Externals::default();
         let mut importer = StaticImporter::new([(name.clone(), source.source)], parse_trusted);
-        let analyzer = analyze(name, &mut importer, &externals);
-
-        if !analyzer.diagnostics.is_empty() {
-            return Err(analyzer.diagnostics);
+        let mut analyzer = analyze(name, &mut importer, &externals);
+
+        // warnings are tested separately from the types
+        let errors: Vec<_> = analyzer
+            .take_diagnostics()
+            .into_iter()
+            .filter(Diagnostic::is_critical)
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         externals.register(Reef::new(source.name.to_string(), analyzer));
@@ -1801,6 +2354,31 @@ mod tests {
         assert_eq!(res, Ok(UNIT));
     }
 
+    #[test]
+    fn var_assign_increment_int() {
+        let res = extract_type(Source::unknown("var n = 1; n += 2"));
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn var_assign_undefined_operator() {
+        let content = "var s = 'abc'; s -= 1";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "Undefined operator",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "s -= 1"),
+                "No operator `sub` between type `String` and `Int`",
+            ))]),
+        );
+    }
+
     #[test]
     fn var_assign_increment() {
         let res = extract_type(Source::unknown("var n = 'Hello, '; n += 'world!'"));
@@ -1826,6 +2404,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ignored_declaration() {
+        let res = extract_type(Source::unknown("val _ = 1; val _ = 'a'"));
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn ignored_declaration_is_not_bound() {
+        let content = "val _ = 1; $_";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownSymbol,
+                "Could not resolve symbol `_`."
+            )
+            .with_observation(
+                (SourceId(0), ReefId(1), find_in(content, "$_")).into()
+            )])
+        );
+    }
+
+    #[test]
+    fn env_variable_without_std() {
+        let content = "use @HOME; $HOME";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Err(vec![missing_std_for_env(content)]));
+    }
+
+    #[test]
+    fn env_variable_cannot_reassign() {
+        let content = "use @HOME; HOME = 'foo'";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![
+                missing_std_for_env(content),
+                Diagnostic::new(
+                    DiagnosticID::CannotReassign,
+                    "Cannot assign twice to immutable variable `HOME`",
+                )
+                .with_observation(Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    find_in(content, "HOME = 'foo'"),
+                    "Assignment happens here",
+                )),
+            ])
+        );
+    }
+
+    /// Reports an environment variable that is imported without the standard library.
+    fn missing_std_for_env(content: &str) -> Diagnostic {
+        Diagnostic::new(
+            DiagnosticID::ImportResolution,
+            "Cannot read environment variable `HOME`",
+        )
+        .with_observation(Observation::here(
+            SourceId(0),
+            ReefId(1),
+            find_in(content, "HOME"),
+            "The standard library is required to read environment variables",
+        ))
+    }
+
     #[test]
     fn val_cannot_reassign() {
         let content = "val l = 1; l = 2";
@@ -1939,6 +2582,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn condition_chain_different_types() {
+        let content = "val n = if true { 1 } else if false { 'a' } else { true }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "`if` and `else` have incompatible types",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "{ 1 }"),
+                "Found `Int`",
+            ))
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "{ 'a' }"),
+                "Found `String`",
+            ))
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "{ true }"),
+                "Found `Bool`",
+            ))])
+        );
+    }
+
+    #[test]
+    fn condition_value_without_else() {
+        let content = "val x: Int = if true; 1";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::MissingElse,
+                "`if` used as a value requires an `else` branch",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "if true; 1"),
+                "This `if` has no `else` branch",
+            ))
+            .with_help(
+                "Add an `else` branch to provide a value when the condition is false"
+            )])
+        );
+    }
+
     #[test]
     fn incompatible_cast() {
         let content = "val n = 'a' as Int";
@@ -1992,7 +2688,8 @@ mod tests {
                 ReefId(1),
                 find_in(content, "square(9, 9)"),
                 "Function is called here",
-            ))])
+            ))
+            .with_help("The function signature is `square(Int) -> Int`")])
         );
     }
 
@@ -2017,32 +2714,29 @@ mod tests {
                 ReefId(1),
                 find_in(content, "str: String"),
                 "Parameter is declared here",
-            ))]),
+            ))
+            .with_help("The function signature is `dup(String) -> String`")]),
         );
     }
 
     #[test]
-    fn cannot_invoke_non_function() {
-        let content = "val test = 1;test()";
+    fn duration_literal() {
+        let res = extract_type(Source::unknown("val timeout = 500ms; $timeout"));
+        assert_eq!(res, Ok(DURATION));
+    }
+
+    #[test]
+    fn timeout_call() {
+        let content = "fun timeout(duration: Duration, command: String) -> Exitcode;\n\
+            timeout(5s, 'sleep 10')";
         let res = extract_type(Source::unknown(content));
-        assert_eq!(
-            res,
-            Err(vec![Diagnostic::new(
-                DiagnosticID::TypeMismatch,
-                "Cannot invoke non function type",
-            )
-            .with_observation(Observation::here(
-                SourceId(0),
-                ReefId(1),
-                find_in(content, "test()"),
-                "Call expression requires function, found `Int`",
-            ))])
-        );
+        assert_eq!(res, Ok(EXITCODE));
     }
 
     #[test]
-    fn type_function_parameters() {
-        let content = "fun test(a: String) = { var b: Int = $a }";
+    fn timeout_without_duration() {
+        let content = "fun timeout(duration: Duration, command: String) -> Exitcode;\n\
+            timeout(5, 'sleep 10')";
         let res = extract_type(Source::unknown(content));
         assert_eq!(
             res,
@@ -2050,29 +2744,346 @@ mod tests {
                 DiagnosticID::TypeMismatch,
                 "Type mismatch",
             )
-            .with_observation(Observation::context(
-                SourceId(1),
+            .with_observation(Observation::here(
+                SourceId(0),
                 ReefId(1),
-                find_in(content, "Int"),
-                "Expected `Int`",
+                find_in(content, "5"),
+                "Expected `Duration`, found `Int`",
             ))
-            .with_observation(Observation::here(
+            .with_observation(Observation::context(
                 SourceId(1),
                 ReefId(1),
-                find_in(content, "$a"),
-                "Found `String`",
-            ))])
+                find_in(content, "duration: Duration"),
+                "Parameter is declared here",
+            ))
+            .with_help(
+                "The function signature is `timeout(Duration, String) -> Exitcode`"
+            )]),
         );
     }
 
+    fn extract_variadic_arguments(content: &str) -> Vec<TypedExpr> {
+        let exprs = extract_expr(Source::unknown(content)).expect("no diagnostics");
+        let ExprKind::FunctionCall(call) = &exprs.last().unwrap().kind else {
+            panic!("expected a function call");
+        };
+        let [_, packed] = call.arguments.as_slice() else {
+            panic!("expected a packed argument");
+        };
+        let ExprKind::Vector(elements) = &packed.kind else {
+            panic!("expected a vector argument");
+        };
+        elements.clone()
+    }
+
     #[test]
-    fn a_calling_b() {
-        let res = extract_type(Source::unknown(
-            "fun a() -> Int = b()\nfun b() -> Int = 1\na()",
+    fn variadic_no_trailing_arguments() {
+        let elements = extract_variadic_arguments(
+            "fun count(prefix: String, Int...) -> Int = $@.len()\ncount('a')",
+        );
+        assert_eq!(elements, vec![]);
+    }
+
+    #[test]
+    fn variadic_one_trailing_argument() {
+        let content = "fun count(prefix: String, Int...) -> Int = $@.len()\ncount('a', 4)";
+        let elements = extract_variadic_arguments(content);
+        assert_eq!(
+            elements,
+            vec![TypedExpr {
+                kind: ExprKind::Literal(4.into()),
+                ty: INT,
+                segment: find_in(content, "4"),
+            }]
+        );
+    }
+
+    #[test]
+    fn variadic_many_trailing_arguments() {
+        let content = "fun count(prefix: String, Int...) -> Int = $@.len()\ncount('a', 4, 5, 6)";
+        let elements = extract_variadic_arguments(content);
+        assert_eq!(
+            elements.iter().map(|expr| expr.ty).collect::<Vec<_>>(),
+            vec![INT, INT, INT]
+        );
+    }
+
+    #[test]
+    fn variadic_missing_required_argument() {
+        let content = "fun count(prefix: String, Int...) -> Int = $@.len()\ncount()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "This function takes at least 1 argument but 0 were supplied",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "count()"),
+                "Function is called here",
+            ))
+            .with_help(
+                "The function signature is `count(String, Int...) -> Int`"
+            )])
+        );
+    }
+
+    #[test]
+    fn variadic_wrong_argument_type() {
+        let content = "fun count(prefix: String, Int...) -> Int = $@.len()\ncount('a', 1, 'b')";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "'b'"),
+                "Expected `Int`, found `String`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "Int..."),
+                "Parameter is declared here",
+            ))
+            .with_help(
+                "The function signature is `count(String, Int...) -> Int`"
+            )]),
+        );
+    }
+
+    #[test]
+    fn named_variadic_distributes_arguments() {
+        let content =
+            "fun greet(first: String, @rest: String) -> Int = $rest.len()\ngreet('a', 'b', 'c')";
+        let exprs = extract_expr(Source::unknown(content)).expect("no diagnostics");
+        let ExprKind::FunctionCall(call) = &exprs.last().unwrap().kind else {
+            panic!("expected a function call");
+        };
+        let [first, packed] = call.arguments.as_slice() else {
+            panic!("expected a packed argument");
+        };
+        assert_eq!(
+            first,
+            &TypedExpr {
+                kind: ExprKind::Literal("a".into()),
+                ty: STRING,
+                segment: find_in(content, "'a'"),
+            }
+        );
+        assert_eq!(
+            packed.kind,
+            ExprKind::Vector(vec![
+                TypedExpr {
+                    kind: ExprKind::Literal("b".into()),
+                    ty: STRING,
+                    segment: find_in(content, "'b'"),
+                },
+                TypedExpr {
+                    kind: ExprKind::Literal("c".into()),
+                    ty: STRING,
+                    segment: find_in(content, "'c'"),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn variadic_before_fixed_parameter() {
+        let content = "fun greet(@rest: String, last: Int) = $last";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::MisplacedVariadic,
+                "A variadic parameter must be the last parameter",
+            )
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "@rest: String"),
+                "Move this parameter to the end of the list",
+            ))]),
+        );
+    }
+
+    #[test]
+    fn named_arguments_reordering() {
+        let content = "fun sub(a: Int, b: Int) -> Int = $a - $b\nsub(b = 1, a = 2)";
+        let exprs = extract_expr(Source::unknown(content)).expect("no diagnostics");
+        let ExprKind::FunctionCall(call) = &exprs.last().unwrap().kind else {
+            panic!("expected a function call");
+        };
+        assert_eq!(
+            call.arguments,
+            vec![
+                TypedExpr {
+                    kind: ExprKind::Literal(2.into()),
+                    ty: INT,
+                    segment: find_in(content, "2"),
+                },
+                TypedExpr {
+                    kind: ExprKind::Literal(1.into()),
+                    ty: INT,
+                    segment: find_in(content, "1"),
+                },
+            ]
+        );
+        assert_eq!(call.evaluation_order, Some(vec![1, 0]));
+    }
+
+    #[test]
+    fn named_constructor_arguments() {
+        let content = "struct Point { x: Int, y: String }\nval p = Point(y = 'a', x = 2); $p.x";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn unknown_argument_name() {
+        let content = "fun sub(a: Int, b: Int) -> Int = $a - $b\nsub(1, c = 2)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::InvalidNamedArgument,
+                "Unknown argument name `c`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "c = 2"),
+                "No parameter is named `c`",
+            ))]),
+        );
+    }
+
+    #[test]
+    fn positional_after_named_argument() {
+        let content = "fun sub(a: Int, b: Int) -> Int = $a - $b\nsub(a = 1, 2)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::InvalidNamedArgument,
+                "Positional argument after named arguments",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "2"),
+                "Name this argument or move it before the named arguments",
+            ))]),
+        );
+    }
+
+    #[test]
+    fn cannot_invoke_non_function() {
+        let content = "val test = 1;test()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Cannot invoke non function type",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "test()"),
+                "Call expression requires function, found `Int`",
+            ))])
+        );
+    }
+
+    #[test]
+    fn type_function_parameters() {
+        let content = "fun test(a: String) = { var b: Int = $a }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch",
+            )
+            .with_observation(Observation::context(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "Int"),
+                "Expected `Int`",
+            ))
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "$a"),
+                "Found `String`",
+            ))])
+        );
+    }
+
+    #[test]
+    fn logical_operators() {
+        let res = extract_type(Source::unknown("true && false || true"));
+        assert_eq!(res, Ok(BOOL));
+    }
+
+    #[test]
+    fn logical_operator_mixing_exitcode() {
+        let res = extract_type(Source::unknown("val code = echo a; true && $code"));
+        assert_eq!(res, Ok(BOOL));
+    }
+
+    #[test]
+    fn logical_operator_non_boolean() {
+        let content = "true || 4";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Condition must be a boolean",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "4"),
+                "Type `Int` cannot be used as a condition",
+            ))])
+        );
+    }
+
+    #[test]
+    fn a_calling_b() {
+        let res = extract_type(Source::unknown(
+            "fun a() -> Int = b()\nfun b() -> Int = 1\na()",
+        ));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn direct_recursion() {
+        let res = extract_type(Source::unknown(
+            "fun fib(n: Int) -> Int = if $n < 2; $n; else fib($n - 1) + fib($n - 2)\nfib(10)",
         ));
         assert_eq!(res, Ok(INT));
     }
 
+    #[test]
+    fn mutual_recursion() {
+        let res = extract_type(Source::unknown(
+            "fun is_even(n: Int) -> Bool = if $n == 0; true; else is_odd($n - 1)
+            fun is_odd(n: Int) -> Bool = if $n == 0; false; else is_even($n - 1)
+            is_even(4)",
+        ));
+        assert_eq!(res, Ok(BOOL));
+    }
+
     #[test]
     fn bidirectional_usage() {
         let res = extract_type(Source::unknown(
@@ -2106,6 +3117,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn by_name_parameter() {
+        let content = "fun lazy(value: => Int) -> Int = $value; lazy(1 + 2)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn by_name_parameter_mismatch() {
+        let content = "fun lazy(value: => Int) -> Int = $value; lazy('a')";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "'a'"),
+                "Expected `Int`, found `String`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "value: => Int"),
+                "Parameter is declared here",
+            ))
+            .with_help("The function signature is `lazy(Int) -> Int`")]),
+        );
+    }
+
+    #[test]
+    fn pass_function_as_callable() {
+        let content = "fun double(n: Int) -> Int = $n * 2
+        fun apply(f: (Int) => Int, x: Int) -> Int = $x
+        apply($double, 4)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnsupportedFeature,
+                "Functions cannot be used as values yet",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$double"),
+                "This function is referenced as a value",
+            ))
+            .with_help("Call the function instead")])
+        );
+    }
+
+    #[test]
+    fn pass_incompatible_function_as_callable() {
+        let content = "fun concat(a: String, b: String) -> String = \"$a$b\"
+        fun apply(f: (Int) => Int, x: Int) -> Int = $x
+        apply($concat, 4)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![
+                Diagnostic::new(
+                    DiagnosticID::UnsupportedFeature,
+                    "Functions cannot be used as values yet",
+                )
+                .with_observation(Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    find_in(content, "$concat"),
+                    "This function is referenced as a value",
+                ))
+                .with_help("Call the function instead"),
+                Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
+                    .with_observation(Observation::here(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in(content, "$concat"),
+                        "Expected `(Int) => Int`, found `concat`",
+                    ))
+                    .with_observation(Observation::context(
+                        SourceId(2),
+                        ReefId(1),
+                        find_in(content, "f: (Int) => Int"),
+                        "Parameter is declared here",
+                    ))
+                    .with_help("The function signature is `apply((Int) => Int, Int) -> Int`"),
+            ])
+        );
+    }
+
+    #[test]
+    fn invoke_callable_parameter() {
+        let content = "fun apply(f: (Int) => Int, x: Int) -> Int = f($x)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnsupportedFeature,
+                "Callable values cannot be invoked yet",
+            )
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "f($x)"),
+                "This call has no function to run",
+            ))])
+        );
+    }
+
     #[test]
     fn explicit_valid_return() {
         let content = "fun some() -> Int = return 20";
@@ -2141,6 +3264,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn labeled_break_and_continue() {
+        let content = "outer@ loop { while true { break@outer } }
+        outer@ for i in 1..3 { loop { continue@outer } }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn break_to_unknown_label() {
+        let content = "loop { break@outer }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownLoopLabel,
+                "Use of undeclared loop label `outer`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "outer"),
+                "No enclosing loop has this label",
+            ))])
+        );
+    }
+
+    #[test]
+    fn constant_addition_overflow() {
+        let content = "val max = 9223372036854775807 + 1";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::IntegerOverflow,
+                "Integer overflow",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "9223372036854775807 + 1"),
+                "This constant expression does not fit between -9223372036854775808 and 9223372036854775807",
+            ))])
+        );
+    }
+
+    #[test]
+    fn constant_negation_overflow() {
+        let content =
+            "val min = -9223372036854775807 - 1; val n = -($min); -(-9223372036854775807 - 1)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::IntegerOverflow,
+                "Integer overflow",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "-(-9223372036854775807 - 1)"),
+                "This constant expression does not fit between -9223372036854775808 and 9223372036854775807",
+            ))])
+        );
+    }
+
+    #[test]
+    fn constant_overflow_in_argument() {
+        let content = "fun f(n: Int) -> Int = $n; f(9223372036854775807 * 2) + 1";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::IntegerOverflow,
+                "Integer overflow",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "9223372036854775807 * 2"),
+                "This constant expression does not fit between -9223372036854775808 and 9223372036854775807",
+            ))])
+        );
+    }
+
     #[test]
     fn explicit_valid_return_mixed() {
         let content = "fun some() -> Int = {\nif true; return 5; 9\n}";
@@ -2206,31 +3414,100 @@ mod tests {
     }
 
     #[test]
-    fn no_infer_block_return_type() {
-        let content = "fun test(n: Float) = {if false; return 0; $n}; test(156.0)";
+    fn no_infer_block_return_type() {
+        let content = "fun test(n: Float) = {if false; return 0; $n}; test(156.0)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::CannotInfer,
+                "Return type is not inferred for block functions",
+            )
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "return 0"),
+                "Returning `Int`",
+            ))
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "$n"),
+                "Returning `Float`",
+            ))
+            .with_help(
+                "Try adding an explicit return type to the function"
+            )])
+        );
+    }
+
+    #[test]
+    fn infer_block_return_type() {
+        let content = "fun f() = { val x = 1; $x }; f()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn infer_recursive_block_return_type() {
+        let content = "fun f() = { f(); 1 }; f()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::CannotInfer,
+                "Recursive functions need an explicit return type",
+            )
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in_nth(content, "f()", 1),
+                "`f` is called recursively here",
+            ))
+            .with_help("Add an explicit return type to the function")])
+        );
+    }
+
+    /// Reports a lambda, that is typed but cannot be used as a value.
+    fn unsupported_lambda(content: &str, lambda: &str) -> Diagnostic {
+        Diagnostic::new(
+            DiagnosticID::UnsupportedFeature,
+            "Lambdas are not supported as values yet",
+        )
+        .with_observation(Observation::here(
+            SourceId(0),
+            ReefId(1),
+            find_in(content, lambda),
+            "This lambda cannot be stored or called",
+        ))
+        .with_help("Declare a named function with `fun` instead")
+    }
+
+    #[test]
+    fn lambda_call() {
+        let content = "val f = (x: Int) => $x + 1; f(2)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![unsupported_lambda(content, "(x: Int) => $x + 1")])
+        );
+    }
+
+    #[test]
+    fn lambda_capture() {
+        let content = "val n = 4.0; val f = (x: Float) => $x * $n; f(2.0)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![unsupported_lambda(content, "(x: Float) => $x * $n")])
+        );
+    }
+
+    #[test]
+    fn lambda_untyped_parameter() {
+        let content = "val f = s => $s; f('a')";
         let res = extract_type(Source::unknown(content));
-        assert_eq!(
-            res,
-            Err(vec![Diagnostic::new(
-                DiagnosticID::CannotInfer,
-                "Return type is not inferred for block functions",
-            )
-            .with_observation(Observation::here(
-                SourceId(1),
-                ReefId(1),
-                find_in(content, "return 0"),
-                "Returning `Int`",
-            ))
-            .with_observation(Observation::here(
-                SourceId(1),
-                ReefId(1),
-                find_in(content, "$n"),
-                "Returning `Float`",
-            ))
-            .with_help(
-                "Try adding an explicit return type to the function"
-            )])
-        );
+        assert_eq!(res, Err(vec![unsupported_lambda(content, "s => $s")]));
     }
 
     #[test]
@@ -2261,6 +3538,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn join_adjacent_literals() {
+        let content = "echo abc'def'";
+        let res = extract_expr(Source::unknown(content));
+        assert_eq!(
+            res,
+            Ok(vec![TypedExpr {
+                kind: ExprKind::ProcessCall(vec![
+                    TypedExpr {
+                        kind: ExprKind::Literal("echo".into()),
+                        ty: STRING,
+                        segment: find_in(content, "echo"),
+                    },
+                    TypedExpr {
+                        kind: ExprKind::Literal("abcdef".into()),
+                        ty: STRING,
+                        segment: find_in(content, "abc'def'"),
+                    },
+                ]),
+                ty: EXITCODE,
+                segment: content.segment(),
+            }])
+        );
+    }
+
+    #[test]
+    fn keep_interpolated_concatenation() {
+        let content = "val x = 'b'; echo a'c'$x";
+        let res = extract_expr(Source::unknown(content)).expect("no diagnostics");
+        let ExprKind::ProcessCall(args) = &res[1].kind else {
+            panic!("expected a process call");
+        };
+        let ExprKind::MethodCall(concatenation) = &args[1].kind else {
+            panic!("expected a concatenation");
+        };
+        assert_eq!(
+            *concatenation.callee,
+            TypedExpr {
+                kind: ExprKind::Literal("ac".into()),
+                ty: STRING,
+                segment: find_in(content, "a'c'"),
+            }
+        );
+        assert_eq!(
+            concatenation.arguments,
+            vec![TypedExpr {
+                kind: ExprKind::Reference(Var::Local(LocalId(0))),
+                ty: STRING,
+                segment: find_in(content, "$x"),
+            }]
+        );
+    }
+
     #[test]
     fn conversions() {
         let content = "val n = 75 + 1;val j = $n as Float\ngrep $n 4.2";
@@ -2390,6 +3720,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn membership_operator() {
+        let content = "val list = 'abc'.bytes(); 1 in $list";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(BOOL));
+    }
+
+    #[test]
+    fn membership_element_mismatch() {
+        let content = "val list = 'abc'.bytes(); 'a' in $list";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "Undefined operator",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "'a' in $list"),
+                "No operator `contains` between type `Vec[Int]` and `String`",
+            ))]),
+        );
+    }
+
     #[test]
     fn valid_operator() {
         let content = "val c = 7.3 - 2.4; $c";
@@ -2404,6 +3760,21 @@ mod tests {
         assert_eq!(res, Ok(FLOAT));
     }
 
+    #[test]
+    fn misspelled_method() {
+        let content = "val n = 'test'.lem()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "No method named `lem` found for type `String`",
+            )
+            .with_observation((SourceId(0), ReefId(1), find_in(content, ".lem()")).into())
+            .with_help("Did you mean `len`?")])
+        );
+    }
+
     #[test]
     fn valid_method_but_invalid_parameter_count() {
         let content = "val n = 'test'.len(5)";
@@ -2502,7 +3873,38 @@ mod tests {
                 ReefId(1),
                 find_in(content, "9.9 % 3.3"),
                 "No operator `mod` between type `Float` and `Float`",
-            ))])
+            ))
+            .with_help(
+                "The modulo is only defined between integers, round `Float` operands with `std::math::round` or `std::math::floor`"
+            )])
+        );
+    }
+
+    #[test]
+    fn int_modulo() {
+        let res = extract_type(Source::unknown("val r = 7 % 3; $r"));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn mixed_modulo() {
+        let content = "val r = 7 % 2.5";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "Undefined operator",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "7 % 2.5"),
+                "No operator `mod` between type `Int` and `Float`",
+            ))
+            .with_help(
+                "The modulo is only defined between integers, round `Float` operands with `std::math::round` or `std::math::floor`"
+            )])
         );
     }
 
@@ -2582,6 +3984,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn capture_substitution() {
+        let content = "val out = $(pwd); $out";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn status_substitution() {
+        let content = "val status = $?(test -f /etc/passwd); $status";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(EXITCODE));
+    }
+
     #[test]
     fn redirect_to_string() {
         let content = "val file = '/tmp/file'; cat /etc/passwd > $file 2>&1";
@@ -2608,6 +4024,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn append_to_string() {
+        let content = "val file = '/tmp/file'; cat /etc/passwd >> $file";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(EXITCODE));
+    }
+
+    #[test]
+    fn append_to_non_string() {
+        let content = "val file = {}; cat /etc/passwd >> $file";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Cannot stringify type `Unit`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$file"),
+                "No method `to_string` on type `Unit`",
+            ))])
+        );
+    }
+
+    #[test]
+    fn here_document() {
+        let content = "cat <<END\nhello\nworld\nEND";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(EXITCODE));
+    }
+
     #[test]
     fn redirect_to_string_fd() {
         let content = "grep 'test' >&matches";
@@ -2649,6 +4098,26 @@ mod tests {
         assert_eq!(res, Ok(UNIT));
     }
 
+    #[test]
+    fn trim_string() {
+        let res = extract_type(Source::unknown("'  padded '.trim()"));
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn replace_in_string() {
+        let res = extract_type(Source::unknown(
+            "val path = '/tmp'; $path.replace('/', '_')",
+        ));
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn string_contains() {
+        let res = extract_type(Source::unknown("'Hello, world'.contains('world')"));
+        assert_eq!(res, Ok(BOOL));
+    }
+
     #[test]
     fn access_method_in_parameter() {
         let res = extract_type(Source::unknown(
@@ -2740,6 +4209,34 @@ mod tests {
         );
     }
 
+    /// Asserts that indexing the `$n` integer variable is reported.
+    fn assert_non_indexable(content: &str) {
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "Value is not indexable",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$n"),
+                "Type `Int` has no index operator",
+            ))])
+        );
+    }
+
+    #[test]
+    fn assign_non_indexable() {
+        assert_non_indexable("val n = 4; $n[0] = 1");
+    }
+
+    #[test]
+    fn subscript_non_indexable() {
+        assert_non_indexable("val n = 1; $n[0]");
+    }
+
     #[test]
     fn incorrect_concretized_type() {
         let content =
@@ -2939,7 +4436,8 @@ mod tests {
                         ReefId(1),
                         find_in(content, "vec: Vec[A]"),
                         "Parameter is declared here",
-                    )),
+                    ))
+                    .with_help("The function signature is `foo(A, Vec[A], B, B) -> B`"),
                 Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
                     .with_observation(Observation::here(
                         SourceId(0),
@@ -2952,7 +4450,8 @@ mod tests {
                         ReefId(1),
                         find_in(content, "c: B"),
                         "Parameter is declared here",
-                    )),
+                    ))
+                    .with_help("The function signature is `foo(A, Vec[A], B, B) -> B`"),
                 Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
                     .with_observation(Observation::here(
                         SourceId(0),
@@ -2977,6 +4476,21 @@ mod tests {
         assert_eq!(res, Ok(EXITCODE));
     }
 
+    #[test]
+    fn command_as_condition() {
+        let source = Source::unknown("if grep -q x file { }");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn compare_exitcode_to_int() {
+        let source =
+            Source::unknown("val code = { grep -q x file }; val failed = $code != 0; $failed");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(BOOL));
+    }
+
     #[test]
     fn background_process() {
         let source = Source::unknown("foo &");
@@ -2984,6 +4498,32 @@ mod tests {
         assert_eq!(res, Ok(PID));
     }
 
+    #[test]
+    fn background_sleep() {
+        let source = Source::unknown("sleep 1 &");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(PID));
+    }
+
+    #[test]
+    fn background_value() {
+        let content = "(1 + 1) &";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::DetachedValue,
+                "Only commands can run in the background",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "(1 + 1)"),
+                "This expression does not run any command",
+            ))])
+        );
+    }
+
     #[test]
     fn subprocess() {
         let source = Source::unknown("(foo)");
@@ -3083,4 +4623,98 @@ mod tests {
             typing.get_type(TypeId(6))
         );
     }
+
+    fn extract_diagnostics(content: &str) -> Vec<Diagnostic> {
+        let name = Name::new("test");
+        let mut importer = StaticImporter::new([(name.clone(), content)], parse_trusted);
+        analyze(name, &mut importer, &Externals::default()).take_diagnostics()
+    }
+
+    #[test]
+    fn function_called_as_command() {
+        let content = "fun square(n: Int) -> Int = $n * $n\nsquare 9";
+        assert_eq!(
+            extract_diagnostics(content),
+            vec![Diagnostic::new(
+                DiagnosticID::FunctionCalledAsCommand,
+                "`square` is called as an external command",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in_nth(content, "square", 1),
+                "`square` is also a function",
+            ))
+            .with_help("Use `square(...)` to call the function")]
+        );
+    }
+
+    #[test]
+    fn printf_without_format() {
+        let content = "printf '%s\\n' *.txt 1\nprintf";
+        assert_eq!(
+            extract_diagnostics(content),
+            vec![
+                Diagnostic::new(DiagnosticID::TypeMismatch, "Missing arguments for `printf`",)
+                    .with_observation(Observation::here(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in_nth(content, "printf", 1),
+                        "Command is called here",
+                    ))
+            ]
+        );
+    }
+
+    #[test]
+    fn unreachable_after_return() {
+        let content = "fun f() -> Int = { return 1; 2 }";
+        assert_eq!(
+            extract_diagnostics(content),
+            vec![
+                Diagnostic::new(DiagnosticID::UnreachableCode, "Unreachable code")
+                    .with_observation(Observation::here(
+                        SourceId(1),
+                        ReefId(1),
+                        find_in(content, "2"),
+                        "This statement will never be executed",
+                    ))
+                    .with_observation(Observation::context(
+                        SourceId(1),
+                        ReefId(1),
+                        find_in(content, "return 1"),
+                        "Any code following this expression is unreachable",
+                    ))
+            ]
+        );
+    }
+
+    #[test]
+    fn unreachable_after_break() {
+        let content = "loop { break; echo a; echo b }";
+        assert_eq!(
+            extract_diagnostics(content),
+            vec![
+                Diagnostic::new(DiagnosticID::UnreachableCode, "Unreachable code")
+                    .with_observation(Observation::here(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in(content, "echo a"),
+                        "This statement will never be executed",
+                    ))
+                    .with_observation(Observation::context(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in(content, "break"),
+                        "Any code following this expression is unreachable",
+                    ))
+            ]
+        );
+    }
+
+    #[test]
+    fn reachable_return() {
+        let content = "fun f(n: Int) -> Int = { if $n > 0 { return 1 }; 2 }";
+        assert_eq!(extract_diagnostics(content), vec![]);
+    }
 }
@@ -0,0 +1,187 @@
+use ast::r#type::{ParametrizedType, Type, TypeAlias};
+use context::source::SourceSegmentHolder;
+
+use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+use crate::relations::SymbolRef;
+use crate::steps::typing::coercion::resolve_type_annotation;
+use crate::steps::typing::exploration::{Exploration, Links};
+use crate::types::hir::{ExprKind, TypedExpr};
+use crate::types::{ERROR, UNIT};
+
+/// Binds the alias name to the aliased type.
+///
+/// Aliases are expanded when they are declared, so that annotations referring to them directly get
+/// the aliased type. An alias can therefore only refer to types that are already defined, which
+/// rules out any recursion.
+pub(super) fn ascribe_type_alias(
+    alias: &TypeAlias,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let SymbolRef::Local(local_id) = links.env().get_raw_symbol(alias.segment()).unwrap() else {
+        unreachable!()
+    };
+
+    let ty = match find_undefined_type(&alias.aliased, exploration, links) {
+        Some(undefined) => {
+            let current_reef = exploration.externals.current;
+            diagnostics.push(
+                if links.env().get_raw_symbol(undefined.segment())
+                    == Some(SymbolRef::Local(local_id))
+                {
+                    Diagnostic::new(
+                        DiagnosticID::RecursiveTypeAlias,
+                        format!("Type alias `{}` is recursive", alias.name),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        undefined.segment(),
+                        "Refers to itself",
+                    ))
+                } else {
+                    Diagnostic::new(
+                        DiagnosticID::UndefinedAliasedType,
+                        format!(
+                            "Type alias `{}` refers to a type that is not yet defined",
+                            alias.name
+                        ),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        undefined.segment(),
+                        format!("`{undefined}` is defined after the alias"),
+                    ))
+                    .with_help("Move the alias after the definition of the types it refers to")
+                },
+            );
+            ERROR
+        }
+        None => resolve_type_annotation(exploration, links, &alias.aliased, diagnostics),
+    };
+    exploration.ctx.set_local_typed(links.source, local_id, ty);
+
+    TypedExpr {
+        kind: ExprKind::Noop,
+        ty: UNIT,
+        segment: alias.segment(),
+    }
+}
+
+/// Finds a type of the annotation whose definition has not been typed yet.
+fn find_undefined_type<'a>(
+    ty: &'a Type,
+    exploration: &Exploration,
+    links: Links,
+) -> Option<&'a ParametrizedType> {
+    let Type::Parametrized(parametrized) = ty else {
+        return None;
+    };
+    let symbol = links.env().get_raw_symbol(parametrized.segment())?;
+    if exploration
+        .get_var(links.source, symbol, links.relations)
+        .is_none()
+    {
+        return Some(parametrized);
+    }
+    parametrized
+        .params
+        .iter()
+        .find_map(|param| find_undefined_type(param, exploration, links))
+}
+
+#[cfg(test)]
+mod tests {
+    use context::source::Source;
+    use context::str_find::{find_in, find_in_nth};
+    use pretty_assertions::assert_eq;
+
+    use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+    use crate::reef::ReefId;
+    use crate::relations::SourceId;
+    use crate::steps::typing::tests::extract_type;
+    use crate::types::{INT, STRING};
+
+    #[test]
+    fn alias_in_parameter() {
+        let content = "type Str = String\nfun dup(str: Str) -> Str = $str\ndup('a')";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn alias_argument_mismatch() {
+        let content = "type Str = String\nfun dup(str: Str) -> Str = $str\ndup(4)";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "4"),
+                "Expected `String`, found `Int`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "str: Str"),
+                "Parameter is declared here",
+            ))
+            .with_help("The function signature is `dup(String) -> String`")]),
+        );
+    }
+
+    #[test]
+    fn parametrized_alias() {
+        let content = "type Ints = Vec[Int]\nval ints: Ints = 'abc'.bytes(); $ints.len()";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn recursive_alias() {
+        let content = "type Ints = Vec[Ints]";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::RecursiveTypeAlias,
+                "Type alias `Ints` is recursive",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in_nth(content, "Ints", 1),
+                "Refers to itself",
+            ))]),
+        );
+    }
+
+    #[test]
+    fn mutually_recursive_aliases() {
+        let content = "type A = B\ntype B = A";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UndefinedAliasedType,
+                "Type alias `A` refers to a type that is not yet defined",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "B"),
+                "`B` is defined after the alias",
+            ))
+            .with_help(
+                "Move the alias after the definition of the types it refers to"
+            )]),
+        );
+    }
+}
@@ -59,7 +59,6 @@ pub(super) fn create_subscript(
         return Err(target);
     }
 
-    let index_ty = index.ty;
     let target_ty = target.ty;
     let methods = exploration
         .get_methods(target_ty, "[]")
@@ -96,23 +95,17 @@ pub(super) fn create_subscript(
                     ),
                 ))
             } else {
-                Diagnostic::new(
-                    DiagnosticID::UnknownMethod,
-                    format!(
-                        "The type `{}` cannot be indexed by `{}`",
-                        exploration.new_type_view(target_ty, &TypesBounds::inactive()),
-                        exploration.new_type_view(index_ty, &TypesBounds::inactive())
-                    ),
-                )
-                .with_observation(Observation::here(
-                    links.source,
-                    exploration.externals.current,
-                    sub.index.segment(),
-                    format!(
-                        "Indexing with `{}` is invalid",
-                        exploration.new_type_view(index_ty, &TypesBounds::inactive())
-                    ),
-                ))
+                // The type does not define any index operator, whatever the index type
+                Diagnostic::new(DiagnosticID::UnknownMethod, "Value is not indexable")
+                    .with_observation(Observation::here(
+                        links.source,
+                        exploration.externals.current,
+                        sub.target.segment(),
+                        format!(
+                            "Type `{}` has no index operator",
+                            exploration.new_type_view(target_ty, &TypesBounds::inactive())
+                        ),
+                    ))
             });
             Err(target)
         }
@@ -6,8 +6,9 @@ use crate::relations::SourceId;
 use crate::steps::typing::bounds::TypesBounds;
 use crate::steps::typing::exploration::{Exploration, Links};
 use crate::steps::typing::lower::call_convert_on;
+use crate::steps::typing::structure::tuple_type;
 use crate::types::hir::TypedExpr;
-use crate::types::ty::{Type, TypeRef};
+use crate::types::ty::{FunctionDesc, Type, TypeRef};
 use crate::types::{UnificationError, BOOL, ERROR, NOTHING};
 
 /// Unifies two type identifiers, returning the type that the right hand side was unified to.
@@ -53,6 +54,17 @@ pub(super) fn convert_description(
         return Ok(assign_to);
     }
 
+    // a function value may be used where a callable with a compatible signature is expected
+    if let (Type::Function(_, expected_id), Type::Function(_, actual_id)) = (lhs, rhs) {
+        let expected = exploration.get_function(assign_to.reef, *expected_id);
+        let actual = exploration.get_function(rvalue.reef, *actual_id);
+        if let (Some(expected), Some(actual)) = (expected, actual) {
+            if is_signature_compatible(exploration, expected, actual, bounds) {
+                return Ok(assign_to);
+            }
+        }
+    }
+
     // apply the `A U Nothing => A` rule only if `A` is a base type
     if is_base_type && *rhs == Type::Nothing {
         return Ok(assign_to);
@@ -103,6 +115,46 @@ pub(super) fn convert_description(
     Err(UnificationError())
 }
 
+/// Tests if a function can be called through a callable of the expected signature.
+///
+/// The function must accept every argument that the callable accepts, and its return value
+/// must fit in the callable's return type.
+fn is_signature_compatible(
+    exploration: &Exploration,
+    expected: &FunctionDesc,
+    actual: &FunctionDesc,
+    bounds: &mut TypesBounds,
+) -> bool {
+    if !actual.type_parameters.is_empty() || expected.parameters.len() != actual.parameters.len() {
+        return false;
+    }
+    let are_parameters_compatible =
+        expected
+            .parameters
+            .iter()
+            .zip(&actual.parameters)
+            .all(|(expected_param, actual_param)| {
+                expected_param.variadic == actual_param.variadic
+                    && convert_description(
+                        exploration,
+                        actual_param.ty,
+                        expected_param.ty,
+                        bounds,
+                        true,
+                    )
+                    .is_ok()
+            });
+    are_parameters_compatible
+        && convert_description(
+            exploration,
+            expected.return_type,
+            actual.return_type,
+            bounds,
+            true,
+        )
+        .is_ok()
+}
+
 /// Unifies multiple type identifiers in any direction.
 pub(super) fn convert_many<I: IntoIterator<Item = TypeRef>>(
     exploration: &mut Exploration,
@@ -140,6 +192,8 @@ pub(super) fn resolve_type_annotation(
             let main_base_type = exploration.get_type(main_base_ty).unwrap();
 
             let generics = match main_base_type {
+                // an alias of an instantiated type already has its generics
+                _ if main_base_ty != main_type => &[],
                 Type::Function(_, function_id) => exploration
                     .get_function(main_base_ty.reef, *function_id)
                     .map(|s| s.type_parameters.as_slice())
@@ -194,11 +248,51 @@ pub(super) fn resolve_type_annotation(
                 .add_type(Type::Instantiated(main_type, params), None);
             TypeRef::new(exploration.externals.current, instantiated_id)
         }
-        ast::r#type::Type::Callable(_) => unimplemented!(),
-        ast::r#type::Type::ByName(_) => unimplemented!(),
+        ast::r#type::Type::Callable(callable) => {
+            let parameters = callable
+                .params
+                .iter()
+                .map(|param| resolve_type_annotation(exploration, links, param, diagnostics))
+                .collect();
+            let output = resolve_type_annotation(exploration, links, &callable.output, diagnostics);
+            add_function_type(exploration, parameters, output)
+        }
+        // By-name values cannot be delayed yet, so they are evaluated by the caller as regular
+        // arguments
+        ast::r#type::Type::ByName(by_name) => {
+            resolve_type_annotation(exploration, links, &by_name.name, diagnostics)
+        }
+        ast::r#type::Type::Tuple(tuple) => {
+            let elements: Vec<_> = tuple
+                .elements
+                .iter()
+                .map(|element| resolve_type_annotation(exploration, links, element, diagnostics))
+                .collect();
+            if elements.iter().any(|element| element.is_err()) {
+                return ERROR;
+            }
+            tuple_type(elements, exploration)
+        }
     }
 }
 
+/// Registers the type of a function that is not bound to any source.
+fn add_function_type(
+    exploration: &mut Exploration,
+    parameters: Vec<TypeRef>,
+    return_type: TypeRef,
+) -> TypeRef {
+    let function_id = exploration.type_engine.add_function(FunctionDesc::function(
+        Vec::new(),
+        parameters,
+        return_type,
+    ));
+    let function_type = exploration
+        .typing
+        .add_type(Type::Function(None, function_id), None);
+    TypeRef::new(exploration.externals.current, function_type)
+}
+
 pub(super) fn is_compatible(
     exploration: &Exploration,
     assign_to: TypeRef,
@@ -0,0 +1,121 @@
+use crate::reef::{Externals, ReefId};
+use crate::steps::typing::explain::AnalyzedTypes;
+use crate::steps::typing::view::TypeLookup;
+use crate::types::engine::TypedEngine;
+use crate::types::ty::{FunctionDesc, Type, TypeRef};
+use crate::types::Typing;
+
+/// A method that can be called on a value, with its rendered signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodSignature {
+    pub name: String,
+    pub signature: String,
+}
+
+/// Lists the methods that can be called with the `.` syntax on a value of the given type.
+///
+/// The `typing` and `engine` hold the types and methods of the `reef` being analyzed, other
+/// reefs are looked up in the externals. The methods that implement operators are listed with
+/// their name, such as `add`, but the methods whose name is not an identifier are excluded. The
+/// methods are sorted by name, then by signature.
+pub fn list_methods(
+    ty: TypeRef,
+    reef: ReefId,
+    typing: &Typing,
+    engine: &TypedEngine,
+    externals: &Externals,
+) -> Vec<MethodSignature> {
+    let types = AnalyzedTypes::new(reef, typing, engine, externals);
+    let definition = match types.get_type(ty) {
+        Some(Type::Instantiated(definition, _)) => *definition,
+        _ => ty,
+    };
+    let Some(&Type::Structure(_, structure_id)) = types.get_type(definition) else {
+        return Vec::new();
+    };
+    let Some(engine) = types.engine(definition.reef) else {
+        return Vec::new();
+    };
+    let Some(structure) = engine.get_structure(structure_id) else {
+        return Vec::new();
+    };
+
+    let mut methods: Vec<_> = structure
+        .methods
+        .iter()
+        .filter(|(name, _)| name.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .flat_map(|(name, functions)| {
+            functions
+                .iter()
+                .filter_map(|function| engine.get_function(*function))
+                .map(|function| MethodSignature {
+                    name: name.clone(),
+                    signature: render_signature(name, function, &types),
+                })
+        })
+        .collect();
+    methods.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| a.signature.cmp(&b.signature))
+    });
+    methods
+}
+
+/// Renders a method as `name(Param, ...) -> Return`.
+fn render_signature(name: &str, function: &FunctionDesc, types: &AnalyzedTypes) -> String {
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|param| types.new_type_view(param.ty).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "{name}({parameters}) -> {}",
+        types.new_type_view(function.return_type)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reef::LANG_REEF;
+    use crate::types::{INT, STRING};
+
+    use super::*;
+
+    fn lang_methods(ty: TypeRef) -> Vec<MethodSignature> {
+        let externals = Externals::default();
+        let lang = externals.lang();
+        list_methods(ty, LANG_REEF, &lang.typing, &lang.typed_engine, &externals)
+    }
+
+    #[test]
+    fn string_methods() {
+        let methods = lang_methods(STRING);
+        assert!(methods.contains(&MethodSignature {
+            name: "len".to_owned(),
+            signature: "len() -> Int".to_owned(),
+        }));
+        assert!(methods.contains(&MethodSignature {
+            name: "to_string".to_owned(),
+            signature: "to_string() -> String".to_owned(),
+        }));
+        assert!(methods.contains(&MethodSignature {
+            name: "add".to_owned(),
+            signature: "add(String) -> String".to_owned(),
+        }));
+    }
+
+    #[test]
+    fn int_methods() {
+        let methods = lang_methods(INT);
+        assert!(methods.contains(&MethodSignature {
+            name: "to_string".to_owned(),
+            signature: "to_string() -> String".to_owned(),
+        }));
+        assert!(methods.contains(&MethodSignature {
+            name: "to_float".to_owned(),
+            signature: "to_float() -> Float".to_owned(),
+        }));
+    }
+}
@@ -0,0 +1,65 @@
+use ast::value::LiteralValue;
+
+use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+use crate::steps::typing::exploration::{Exploration, Links};
+use crate::types::fold::{as_numeric_operation, fold_operation, Folded};
+use crate::types::hir::{ExprKind, TypedExpr};
+
+/// Reports the operations between integer constants whose result does not fit in an `Int`.
+///
+/// Only the innermost overflowing operation of a constant expression is reported.
+pub(super) fn diagnose_overflows(
+    expr: &TypedExpr,
+    exploration: &Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    fold_tree(expr, &mut |overflowing| {
+        diagnostics.push(overflow_diagnostic(overflowing, exploration, links))
+    });
+}
+
+/// Folds all the constant operations of an expression tree, visiting each expression once.
+fn fold_tree(expr: &TypedExpr, on_overflow: &mut impl FnMut(&TypedExpr)) -> Option<LiteralValue> {
+    if let ExprKind::Literal(literal @ (LiteralValue::Int(_) | LiteralValue::Float(_))) = &expr.kind
+    {
+        return Some(literal.clone());
+    }
+    let Some(method) = as_numeric_operation(expr) else {
+        expr.for_each_child(&mut |child| {
+            fold_tree(child, on_overflow);
+        });
+        return None;
+    };
+    // fold all the operands, so that the non-constant ones are still explored
+    let callee = fold_tree(&method.callee, on_overflow);
+    let arguments = method
+        .arguments
+        .iter()
+        .map(|argument| fold_tree(argument, on_overflow))
+        .collect::<Vec<_>>();
+    let arguments = arguments.into_iter().collect::<Option<Vec<_>>>()?;
+    match fold_operation(method, callee?, &arguments) {
+        Folded::Constant(value) => Some(value),
+        Folded::Overflow => {
+            on_overflow(expr);
+            None
+        }
+        Folded::Unknown => None,
+    }
+}
+
+fn overflow_diagnostic(expr: &TypedExpr, exploration: &Exploration, links: Links) -> Diagnostic {
+    Diagnostic::new(DiagnosticID::IntegerOverflow, "Integer overflow").with_observation(
+        Observation::here(
+            links.source,
+            exploration.externals.current,
+            expr.segment.clone(),
+            format!(
+                "This constant expression does not fit between {} and {}",
+                i64::MIN,
+                i64::MAX
+            ),
+        ),
+    )
+}
@@ -0,0 +1,405 @@
+use std::fmt;
+use std::fmt::{Display, Write};
+
+use crate::reef::{Externals, ReefId, LANG_REEF};
+use crate::relations::SourceId;
+use crate::steps::typing::explain::AnalyzedTypes;
+use crate::steps::typing::view::TypeLookup;
+use crate::types::engine::{FunctionId, TypedEngine};
+use crate::types::hir::{ExprKind, ForKind, MatchPattern, Substitute, TypedExpr, Var};
+use crate::types::ty::{Type, TypeRef};
+use crate::types::Typing;
+
+/// Renders a typed tree as an indented list of its expressions, with their types.
+///
+/// Each call also tells the definition it refers to. The `typing` and `engine` hold the types
+/// and the functions of the `reef`, other reefs are looked up in the externals.
+pub fn dump_hir(
+    expr: &TypedExpr,
+    reef: ReefId,
+    typing: &Typing,
+    engine: &TypedEngine,
+    externals: &Externals,
+) -> String {
+    let mut dump = HirDump {
+        types: AnalyzedTypes::new(reef, typing, engine, externals),
+        out: String::new(),
+        depth: 0,
+    };
+    dump.expr("", expr);
+    dump.out
+}
+
+/// The definition that a call refers to.
+enum Definition {
+    /// A function implemented by the virtual machine.
+    Native(FunctionId),
+
+    /// A function defined in the sources of a reef.
+    User {
+        reef: ReefId,
+        function: FunctionId,
+        source: Option<SourceId>,
+    },
+}
+
+impl Definition {
+    fn new(reef: ReefId, function: FunctionId, source: Option<SourceId>) -> Self {
+        if reef == LANG_REEF {
+            Self::Native(function)
+        } else {
+            Self::User {
+                reef,
+                function,
+                source,
+            }
+        }
+    }
+}
+
+impl Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Definition::Native(function) => write!(f, "native #{}", function.0),
+            Definition::User {
+                reef,
+                function,
+                source,
+            } => {
+                write!(f, "user #{} of reef {}", function.0, reef.0)?;
+                if let Some(source) = source {
+                    write!(f, " in source #{}", source.0)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+struct DisplayVar(Var);
+
+impl Display for DisplayVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Var::Local(local) => write!(f, "local #{}", local.0),
+            Var::External(symbol) => write!(
+                f,
+                "external #{} of reef {} in source #{}",
+                symbol.object_id.0, symbol.reef.0, symbol.source.0
+            ),
+        }
+    }
+}
+
+struct HirDump<'a> {
+    types: AnalyzedTypes<'a>,
+    out: String,
+    depth: usize,
+}
+
+impl HirDump<'_> {
+    fn type_name(&self, id: TypeRef) -> String {
+        self.types.new_type_view(id).to_string()
+    }
+
+    /// Writes a line at the current depth.
+    fn line(&mut self, line: fmt::Arguments) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.write_fmt(line).unwrap();
+        self.out.push('\n');
+    }
+
+    /// Writes the lines of the given children, one level deeper.
+    fn nested(&mut self, write: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        write(self);
+        self.depth -= 1;
+    }
+
+    fn exprs(&mut self, exprs: &[TypedExpr]) {
+        for expr in exprs {
+            self.expr("", expr);
+        }
+    }
+
+    /// Gets the name of a method, by searching it in the structure of the callee.
+    fn method_name(&self, callee: TypeRef, function: FunctionId) -> Option<(ReefId, String)> {
+        let base = match self.types.get_type(callee)? {
+            Type::Instantiated(base, _) => *base,
+            _ => callee,
+        };
+        let &Type::Structure(_, structure_id) = self.types.get_type(base)? else {
+            return None;
+        };
+        self.types
+            .engine(base.reef)?
+            .get_structure(structure_id)?
+            .methods
+            .iter()
+            .find(|(_, functions)| functions.contains(&function))
+            .map(|(name, _)| (base.reef, name.clone()))
+    }
+
+    fn expr(&mut self, prefix: &str, expr: &TypedExpr) {
+        let ty = self.type_name(expr.ty);
+        match &expr.kind {
+            ExprKind::Literal(literal) => {
+                self.line(format_args!("{prefix}Literal {literal:?}: {ty}"))
+            }
+            ExprKind::LocalAssign(assign) => {
+                self.line(format_args!(
+                    "{prefix}Assign {}: {ty}",
+                    DisplayVar(assign.identifier)
+                ));
+                self.nested(|dump| dump.expr("", &assign.rhs));
+            }
+            ExprKind::FieldAssign(assign) => {
+                self.line(format_args!(
+                    "{prefix}FieldAssign field #{} of structure #{} of reef {}: {ty}",
+                    assign.field.0, assign.structure.0, assign.structure_reef.0
+                ));
+                self.nested(|dump| {
+                    dump.expr("object: ", &assign.object);
+                    dump.expr("value: ", &assign.new_value);
+                });
+            }
+            ExprKind::Declare(declaration) => {
+                self.line(format_args!(
+                    "{prefix}Declare local #{}: {ty}",
+                    declaration.identifier.0
+                ));
+                if let Some(value) = &declaration.value {
+                    self.nested(|dump| dump.expr("", value));
+                }
+            }
+            ExprKind::Reference(var) => {
+                self.line(format_args!("{prefix}Reference {}: {ty}", DisplayVar(*var)))
+            }
+            ExprKind::FieldAccess(access) => {
+                self.line(format_args!(
+                    "{prefix}FieldAccess field #{} of structure #{} of reef {}: {ty}",
+                    access.field.0, access.structure.0, access.structure_reef.0
+                ));
+                self.nested(|dump| dump.expr("", &access.object));
+            }
+            ExprKind::Block(exprs) => {
+                self.line(format_args!("{prefix}Block: {ty}"));
+                self.nested(|dump| dump.exprs(exprs));
+            }
+            ExprKind::Redirect(redirect) => {
+                self.line(format_args!("{prefix}Redirect: {ty}"));
+                self.nested(|dump| {
+                    dump.expr("", &redirect.expression);
+                    for redir in &redirect.redirections {
+                        let prefix = format!("{:?} {:?}: ", redir.fd, redir.operator);
+                        dump.expr(&prefix, &redir.operand);
+                    }
+                });
+            }
+            ExprKind::Conditional(conditional) => {
+                self.line(format_args!("{prefix}Conditional: {ty}"));
+                self.nested(|dump| {
+                    dump.expr("condition: ", &conditional.condition);
+                    dump.expr("then: ", &conditional.then);
+                    if let Some(otherwise) = &conditional.otherwise {
+                        dump.expr("else: ", otherwise);
+                    }
+                });
+            }
+            ExprKind::Match(match_expr) => {
+                self.line(format_args!("{prefix}Match: {ty}"));
+                self.nested(|dump| {
+                    dump.expr("operand: ", &match_expr.operand);
+                    for arm in &match_expr.arms {
+                        match arm.val_name {
+                            Some(local) => {
+                                dump.line(format_args!("arm binding local #{}", local.0))
+                            }
+                            None => dump.line(format_args!("arm")),
+                        }
+                        dump.nested(|dump| {
+                            for pattern in &arm.patterns {
+                                match pattern {
+                                    MatchPattern::Wildcard => dump.line(format_args!("pattern: _")),
+                                    MatchPattern::Value(value) => dump.expr("pattern: ", value),
+                                }
+                            }
+                            if let Some(guard) = &arm.guard {
+                                dump.expr("guard: ", guard);
+                            }
+                            dump.expr("body: ", &arm.body);
+                        });
+                    }
+                });
+            }
+            ExprKind::ConditionalLoop(lp) => {
+                self.line(format_args!("{prefix}Loop: {ty}"));
+                self.nested(|dump| {
+                    if let Some(condition) = &lp.condition {
+                        dump.expr("condition: ", condition);
+                    }
+                    dump.expr("body: ", &lp.body);
+                });
+            }
+            ExprKind::ForLoop(lp) => {
+                match lp.kind.as_ref() {
+                    ForKind::Range(range) => {
+                        let receiver_type = self.type_name(range.receiver_type);
+                        self.line(format_args!(
+                            "{prefix}For local #{} of {receiver_type}: {ty}",
+                            range.receiver.0
+                        ));
+                        self.nested(|dump| dump.expr("iterable: ", &range.iterable));
+                    }
+                    ForKind::Conditional(conditional) => {
+                        self.line(format_args!("{prefix}For: {ty}"));
+                        self.nested(|dump| {
+                            dump.expr("initializer: ", &conditional.initializer);
+                            dump.expr("condition: ", &conditional.condition);
+                            dump.expr("increment: ", &conditional.increment);
+                        });
+                    }
+                }
+                self.nested(|dump| dump.expr("body: ", &lp.body));
+            }
+            ExprKind::Convert(convert) => {
+                let into = self.type_name(convert.into);
+                self.line(format_args!("{prefix}Convert into {into}: {ty}"));
+                self.nested(|dump| dump.expr("", &convert.inner));
+            }
+            ExprKind::ProcessCall(arguments) => {
+                self.line(format_args!("{prefix}ProcessCall: {ty}"));
+                self.nested(|dump| dump.exprs(arguments));
+            }
+            ExprKind::FunctionCall(call) => {
+                let definition = Definition::new(call.reef, call.function_id, call.source_id);
+                self.line(format_args!("{prefix}FunctionCall {definition}: {ty}"));
+                self.nested(|dump| dump.exprs(&call.arguments));
+            }
+            ExprKind::MethodCall(call) => {
+                match self.method_name(call.callee.ty, call.function_id) {
+                    Some((reef, name)) => {
+                        let definition = Definition::new(reef, call.function_id, None);
+                        self.line(format_args!(
+                            "{prefix}MethodCall `{name}` {definition}: {ty}"
+                        ))
+                    }
+                    None => self.line(format_args!(
+                        "{prefix}MethodCall #{}: {ty}",
+                        call.function_id.0
+                    )),
+                }
+                self.nested(|dump| {
+                    dump.expr("callee: ", &call.callee);
+                    dump.exprs(&call.arguments);
+                });
+            }
+            ExprKind::Return(value) => {
+                self.line(format_args!("{prefix}Return: {ty}"));
+                if let Some(value) = value {
+                    self.nested(|dump| dump.expr("", value));
+                }
+            }
+            ExprKind::Pipeline(pipeline) => {
+                self.line(format_args!("{prefix}Pipeline {:?}: {ty}", pipeline.pipes));
+                self.nested(|dump| dump.exprs(&pipeline.commands));
+            }
+            ExprKind::Capture(exprs) => {
+                self.line(format_args!("{prefix}Capture: {ty}"));
+                self.nested(|dump| dump.exprs(exprs));
+            }
+            ExprKind::Substitute(Substitute::In(exprs)) => {
+                self.line(format_args!("{prefix}Substitute in: {ty}"));
+                self.nested(|dump| dump.exprs(exprs));
+            }
+            ExprKind::Substitute(Substitute::Out(exprs)) => {
+                self.line(format_args!("{prefix}Substitute out: {ty}"));
+                self.nested(|dump| dump.exprs(exprs));
+            }
+            ExprKind::Subprocess(subprocess) => {
+                let awaited = if subprocess.awaited { " awaited" } else { "" };
+                self.line(format_args!("{prefix}Subprocess{awaited}: {ty}"));
+                self.nested(|dump| dump.expr("", &subprocess.inner));
+            }
+            ExprKind::Vector(elements) => {
+                self.line(format_args!("{prefix}Vector: {ty}"));
+                self.nested(|dump| dump.exprs(elements));
+            }
+            ExprKind::Tuple(elements) => {
+                self.line(format_args!("{prefix}Tuple: {ty}"));
+                self.nested(|dump| dump.exprs(elements));
+            }
+            ExprKind::Continue(0) => self.line(format_args!("{prefix}Continue: {ty}")),
+            ExprKind::Continue(depth) => {
+                self.line(format_args!("{prefix}Continue (depth {depth}): {ty}"))
+            }
+            ExprKind::Break(0) => self.line(format_args!("{prefix}Break: {ty}")),
+            ExprKind::Break(depth) => {
+                self.line(format_args!("{prefix}Break (depth {depth}): {ty}"))
+            }
+            ExprKind::Noop => self.line(format_args!("{prefix}Noop: {ty}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use context::source::Source;
+
+    use crate::steps::typing::tests::extract;
+    use crate::types::engine::ChunkKind;
+
+    use super::*;
+
+    fn dump_source(content: &str) -> String {
+        let externals = extract(Source::unknown(content)).expect("no diagnostics");
+        let reef = externals.get_reef(ReefId(1)).unwrap();
+        let chunk = reef.typed_engine.get_user(SourceId(0)).unwrap();
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            unreachable!()
+        };
+        dump_hir(
+            body,
+            ReefId(1),
+            &reef.typing,
+            &reef.typed_engine,
+            &externals,
+        )
+    }
+
+    #[test]
+    fn dump_conversions() {
+        let dump = dump_source("val n = 75 + 1;val j = $n as Float");
+        assert_eq!(
+            dump,
+            "\
+Block: Unit
+  Declare local #0: Unit
+    MethodCall `add` native #1: Int
+      callee: Literal Int(75): Int
+      Literal Int(1): Int
+  Declare local #1: Unit
+    Convert into Float: Float
+      Reference local #0: Int
+"
+        );
+    }
+
+    #[test]
+    fn dump_user_function_call() {
+        let dump = dump_source("fun id(n: Int) -> Int = $n\nid(4)");
+        assert_eq!(
+            dump,
+            "\
+Block: Int
+  Declare local #0: Unit
+  FunctionCall user #0 of reef 1 in source #1: Int
+    Literal Int(4): Int
+"
+        );
+    }
+}
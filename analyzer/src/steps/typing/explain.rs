@@ -0,0 +1,206 @@
+use context::source::SourceSegment;
+
+use crate::reef::{Externals, ReefId};
+use crate::steps::typing::bounds::TypesBounds;
+use crate::steps::typing::view::{TypeLookup, TypeView};
+use crate::types::engine::{FunctionId, TypedEngine};
+use crate::types::hir::{ExprKind, TypedExpr};
+use crate::types::ty::{FunctionDesc, Type, TypeRef};
+use crate::types::Typing;
+
+/// An expression span bound to the rendered name of its inferred type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedType {
+    pub segment: SourceSegment,
+    pub ty: String,
+}
+
+/// Lists the inferred type of every expression of a typed tree, in source order.
+///
+/// The `typing` and `engine` hold the types and the functions of the `reef` that owns the
+/// expression, other reefs are looked up in the externals.
+pub fn explain_types(
+    expr: &TypedExpr,
+    reef: ReefId,
+    typing: &Typing,
+    engine: &TypedEngine,
+    externals: &Externals,
+) -> Vec<ExplainedType> {
+    let types = AnalyzedTypes::new(reef, typing, engine, externals);
+    let mut explained = Vec::new();
+    expr.walk(&mut |expr| {
+        if matches!(expr.kind, ExprKind::Noop) {
+            return;
+        }
+        explained.push(ExplainedType {
+            segment: expr.segment.clone(),
+            ty: types.new_type_view(expr.ty).to_string(),
+        });
+    });
+    explained.sort_by_key(|explained| explained.segment.start);
+    explained
+}
+
+/// Renders the name of a type, as it would appear in diagnostics.
+///
+/// The `typing` and `engine` hold the types and the functions of the `reef`, other reefs are
+/// looked up in the externals.
+pub fn type_name(
+    id: TypeRef,
+    reef: ReefId,
+    typing: &Typing,
+    engine: &TypedEngine,
+    externals: &Externals,
+) -> String {
+    AnalyzedTypes::new(reef, typing, engine, externals)
+        .new_type_view(id)
+        .to_string()
+}
+
+/// The types of a reef whose analysis is over.
+///
+/// The `typing` and `engine` hold the types and the functions of the `reef`, other reefs are
+/// looked up in the externals.
+pub(super) struct AnalyzedTypes<'a> {
+    reef: ReefId,
+    typing: &'a Typing,
+    engine: &'a TypedEngine,
+    externals: &'a Externals<'a>,
+    bounds: TypesBounds,
+}
+
+impl<'a> AnalyzedTypes<'a> {
+    pub(super) fn new(
+        reef: ReefId,
+        typing: &'a Typing,
+        engine: &'a TypedEngine,
+        externals: &'a Externals<'a>,
+    ) -> Self {
+        Self {
+            reef,
+            typing,
+            engine,
+            externals,
+            bounds: TypesBounds::inactive(),
+        }
+    }
+
+    fn typing(&self, reef: ReefId) -> Option<&'a Typing> {
+        if reef == self.reef {
+            Some(self.typing)
+        } else {
+            self.externals.get_reef(reef).map(|reef| &reef.typing)
+        }
+    }
+
+    pub(super) fn engine(&self, reef: ReefId) -> Option<&'a TypedEngine> {
+        if reef == self.reef {
+            Some(self.engine)
+        } else {
+            self.externals.get_reef(reef).map(|reef| &reef.typed_engine)
+        }
+    }
+
+    pub(super) fn new_type_view(&self, id: TypeRef) -> TypeView<'_> {
+        TypeView::new(id, self, &self.bounds)
+    }
+}
+
+impl TypeLookup for AnalyzedTypes<'_> {
+    fn get_type(&self, id: TypeRef) -> Option<&Type> {
+        self.typing(id.reef)?.get_type(id.type_id)
+    }
+
+    fn get_type_name(&self, id: TypeRef) -> Option<&String> {
+        self.typing(id.reef)?.get_type_name(id.type_id)
+    }
+
+    fn get_function(&self, reef: ReefId, function_id: FunctionId) -> Option<&FunctionDesc> {
+        self.engine(reef)?.get_function(function_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use context::source::Source;
+    use context::str_find::find_in;
+
+    use crate::relations::SourceId;
+    use crate::steps::typing::tests::extract;
+    use crate::types::engine::ChunkKind;
+
+    use super::*;
+
+    #[test]
+    fn explain_reference() {
+        let content = "val m = 5; $m";
+        let externals = extract(Source::unknown(content)).expect("no diagnostics");
+        let reef = externals.get_reef(ReefId(1)).unwrap();
+        let chunk = reef.typed_engine.get_user(SourceId(0)).unwrap();
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            unreachable!()
+        };
+
+        let table = explain_types(
+            body,
+            ReefId(1),
+            &reef.typing,
+            &reef.typed_engine,
+            &externals,
+        );
+        assert!(table.contains(&ExplainedType {
+            segment: find_in(content, "5"),
+            ty: "Int".to_owned(),
+        }));
+        assert!(table.contains(&ExplainedType {
+            segment: find_in(content, "$m"),
+            ty: "Int".to_owned(),
+        }));
+    }
+
+    #[test]
+    fn name_script_type() {
+        let content = "'abc'.bytes()";
+        let externals = extract(Source::unknown(content)).expect("no diagnostics");
+        let reef = externals.get_reef(ReefId(1)).unwrap();
+        let chunk = reef.typed_engine.get_user(SourceId(0)).unwrap();
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            type_name(
+                body.ty,
+                ReefId(1),
+                &reef.typing,
+                &reef.typed_engine,
+                &externals
+            ),
+            "Vec[Int]"
+        );
+    }
+
+    #[test]
+    fn name_callable_type() {
+        let content = "fun apply(f: (Int) => Int, x: Int) -> Int = $x";
+        let externals = extract(Source::unknown(content)).expect("no diagnostics");
+        let reef = externals.get_reef(ReefId(1)).unwrap();
+        let chunk = reef.typed_engine.get_user(SourceId(1)).unwrap();
+        let Some(&Type::Function(_, function_id)) = reef.typing.get_type(chunk.function_type)
+        else {
+            unreachable!()
+        };
+        let apply = reef.typed_engine.get_function(function_id).unwrap();
+
+        assert_eq!(
+            type_name(
+                apply.parameters[0].ty,
+                ReefId(1),
+                &reef.typing,
+                &reef.typed_engine,
+                &externals
+            ),
+            "(Int) => Int"
+        );
+    }
+}
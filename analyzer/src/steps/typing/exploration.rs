@@ -1,3 +1,4 @@
+use crate::diagnostic::closest_name;
 use crate::engine::Engine;
 use crate::environment::symbols::Symbol;
 use crate::environment::Environment;
@@ -17,6 +18,8 @@ pub(super) struct Exploration<'a> {
     pub(super) typing: Typing,
     pub(super) ctx: TypeContext,
     pub(super) returns: Vec<Return>,
+    /// The labels of the loops being typed, from the outermost to the innermost.
+    pub(super) loop_labels: Vec<Option<String>>,
     pub(super) externals: &'a Externals<'a>,
 }
 
@@ -73,7 +76,7 @@ impl<'a> Exploration<'a> {
     }
 
     /// Gets the type instance of a type identifier.
-    pub(super) fn new_type_view(&self, id: TypeRef, bounds: &'a TypesBounds) -> TypeView {
+    pub(super) fn new_type_view(&self, id: TypeRef, bounds: &'a TypesBounds) -> TypeView<'_> {
         TypeView::new(id, self, bounds)
     }
 
@@ -122,11 +125,15 @@ impl<'a> Exploration<'a> {
 
         if definition.reef == current || id.reef == current {
             self.type_engine
-                .get_method_exact(structure_id, name, params, return_ty)
+                .get_method_exact(structure_id, name, params, return_ty, &|ty| {
+                    self.get_type(ty)
+                })
         } else {
             let reef = self.get_external_type_reef(id.reef);
             reef.typed_engine
-                .get_method_exact(structure_id, name, params, return_ty)
+                .get_method_exact(structure_id, name, params, return_ty, &|ty| {
+                    self.get_type(ty)
+                })
         }
     }
 
@@ -192,6 +199,22 @@ impl<'a> Exploration<'a> {
         }
     }
 
+    /// Finds the method name of a type that is the closest to the given misspelled name.
+    pub(super) fn suggest_method(&self, id: TypeRef, name: &str) -> Option<&str> {
+        let definition = self.get_base_type(id);
+
+        let &Type::Structure(_, structure_id) = self.get_type(definition).unwrap() else {
+            return None;
+        };
+
+        let engine = if definition.reef == self.externals.current {
+            &self.type_engine
+        } else {
+            &self.get_external_type_reef(definition.reef).typed_engine
+        };
+        closest_name(name, engine.method_names(structure_id))
+    }
+
     /// Gets the base type of a type identifier.
     pub(crate) fn get_base_type(&self, type_id: TypeRef) -> TypeRef {
         match self.get_type(type_id).unwrap_or(&Type::Error) {
@@ -270,7 +293,7 @@ impl<'a> Exploration<'a> {
         lhs == rhs
     }
 
-    fn get_external_type_reef(&self, id: ReefId) -> &Reef {
+    fn get_external_type_reef(&self, id: ReefId) -> &Reef<'_> {
         self.externals
             .get_reef(id)
             .expect("Unknown external reef found on type")
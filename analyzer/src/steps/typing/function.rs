@@ -3,6 +3,7 @@ use std::fmt;
 
 use ast::call::{MethodCall, ProgrammaticCall};
 use ast::function::{FunctionDeclaration, FunctionParameter};
+use ast::lambda::LambdaDef;
 use ast::Expr;
 use context::source::{SourceSegment, SourceSegmentHolder};
 
@@ -15,12 +16,13 @@ use crate::steps::typing::coercion::{
     convert_description, convert_expression, convert_many, resolve_type_annotation,
 };
 use crate::steps::typing::exploration::{Exploration, Links};
+use crate::steps::typing::lower::call_convert_on;
 use crate::steps::typing::view::TypeInstanceVec;
 use crate::steps::typing::{ascribe_types, ExpressionValue, TypingState};
-use crate::types::engine::{Chunk, ChunkKind, FunctionId};
+use crate::types::engine::{Chunk, ChunkKind, FunctionId, StructureId};
 use crate::types::hir::{ExprKind, TypedExpr};
 use crate::types::ty::{FunctionDesc, FunctionKind, MethodType, Parameter, Type, TypeRef};
-use crate::types::{ERROR, STRING, UNIT};
+use crate::types::{ERROR, GENERIC_VECTOR, STRING, UNIT};
 
 /// An identified return during the exploration.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -50,6 +52,19 @@ pub(super) struct FunctionMatch {
 
     /// The function's reef
     pub(super) reef: ReefId,
+
+    /// The order in which the arguments are written, if it differs from the parameters order.
+    pub(super) evaluation_order: Option<Vec<usize>>,
+}
+
+/// The structure on which a method is declared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Receiver {
+    /// The structure that will hold the method.
+    pub(super) structure: StructureId,
+
+    /// The type of the `self` parameter.
+    pub(super) ty: TypeRef,
 }
 
 /// Gets the returned type of a function.
@@ -59,17 +74,20 @@ pub(super) struct FunctionMatch {
 pub(super) fn infer_return(
     func: &FunctionDeclaration,
     expected_return_type: TypeRef,
+    inferable: bool,
     links: Links,
     typed_func_body: Option<&TypedExpr>,
     diagnostics: &mut Vec<Diagnostic>,
     exploration: &mut Exploration,
 ) -> TypeRef {
+    let explicit_returns = exploration.returns.len();
     if let Some(typed_func_body) = typed_func_body {
         let last = get_last_segment(typed_func_body);
         // If the last statement is a return, we don't need re-add it
         if exploration
             .returns
-            .last().is_none_or(|ret| ret.segment != last.segment)
+            .last()
+            .is_none_or(|ret| ret.segment != last.segment)
             && last.ty.is_something()
             && last.ty.is_ok()
         {
@@ -146,6 +164,12 @@ pub(super) fn infer_return(
     };
 
     if matches!(body.as_ref(), Expr::Block(_)) {
+        // a trailing value is the only possible return type if there are no `return` statements
+        if inferable && explicit_returns == 0 {
+            if let [trailing] = exploration.returns.as_slice() {
+                return trailing.ty;
+            }
+        }
         diagnostics.push(
             Diagnostic::new(
                 DiagnosticID::CannotInfer,
@@ -264,6 +288,7 @@ fn check_for_leaked_type_parameters(
 /// type its parameters, type parameters and return type
 pub(super) fn declare_function(
     func: &FunctionDeclaration,
+    receiver: Option<Receiver>,
     exploration: &mut Exploration,
     function_links: Links,
     diagnostics: &mut Vec<Diagnostic>,
@@ -292,8 +317,49 @@ pub(super) fn declare_function(
     }
 
     let tparam_count = func.type_parameters.len();
+    // the implicit `self` is declared right after the parameters
+    let mut receiver_local = LocalId(tparam_count + func.parameters.len());
     for (param_offset, param) in func.parameters.iter().enumerate() {
         let local_id = LocalId(tparam_count + param_offset);
+        if let FunctionParameter::Slf(segment) = param {
+            // `self` is the method's receiver, and is not part of the method's call arguments
+            match receiver {
+                Some(_) => receiver_local = local_id,
+                None => {
+                    exploration
+                        .ctx
+                        .set_local_typed(func_source, local_id, ERROR);
+                    diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticID::InvalidSelfParameter,
+                            "`self` parameter is only allowed in methods",
+                        )
+                        .with_observation(Observation::here(
+                            func_source,
+                            exploration.externals.current,
+                            segment.clone(),
+                            "Declare this function in an `impl` block",
+                        )),
+                    );
+                }
+            }
+            continue;
+        }
+        let is_last = param_offset + 1 == func.parameters.len();
+        if matches!(param, FunctionParameter::Variadic(..)) && !is_last {
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::MisplacedVariadic,
+                    "A variadic parameter must be the last parameter",
+                )
+                .with_observation(Observation::here(
+                    func_source,
+                    exploration.externals.current,
+                    param.segment(),
+                    "Move this parameter to the end of the list",
+                )),
+            );
+        }
         let param = type_parameter(local_id, exploration, param, function_links, diagnostics);
         exploration
             .ctx
@@ -301,16 +367,34 @@ pub(super) fn declare_function(
         params.push(param);
     }
 
+    if let Some(receiver) = receiver {
+        exploration
+            .ctx
+            .set_local_typed(func_source, receiver_local, receiver.ty);
+    }
+
     let return_type = func.return_type.as_ref().map_or(UNIT, |ty| {
         resolve_type_annotation(exploration, function_links, ty, diagnostics)
     });
 
-    let function_id = exploration.type_engine.add_function(FunctionDesc {
+    let function = FunctionDesc {
         type_parameters: type_params,
         parameters: params,
         return_type,
-        kind: FunctionKind::Function,
-    });
+        kind: receiver.map_or(FunctionKind::Function, |receiver| FunctionKind::Method {
+            source: func_source,
+            receiver: receiver_local,
+            receiver_ty: receiver.ty,
+        }),
+    };
+    let function_id = match receiver {
+        Some(receiver) => exploration.type_engine.add_method(
+            receiver.structure,
+            func.name.value.as_str(),
+            function,
+        ),
+        None => exploration.type_engine.add_function(function),
+    };
 
     let function_type = exploration.typing.add_type(
         Type::Function(Some(func_source), function_id),
@@ -327,6 +411,80 @@ pub(super) fn declare_function(
     }
 }
 
+/// Declares the signature of a lambda, whose return type is inferred once its body is typed.
+///
+/// As for functions, a parameter without a type annotation is a string.
+pub(super) fn declare_lambda(
+    lambda: &LambdaDef,
+    exploration: &mut Exploration,
+    lambda_links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Chunk {
+    let lambda_source = lambda_links.source;
+    exploration
+        .ctx
+        .init_locals(lambda_source, lambda_links.env().symbols.len());
+
+    let mut params = Vec::with_capacity(lambda.args.len());
+    for (local_id, arg) in lambda.args.iter().enumerate() {
+        let local_id = LocalId(local_id);
+        let ty = arg.ty.as_ref().map_or(STRING, |ty| {
+            resolve_type_annotation(exploration, lambda_links, ty, diagnostics)
+        });
+        exploration.ctx.set_local_typed(lambda_source, local_id, ty);
+        params.push(Parameter {
+            location: Some(SourceLocation::new(
+                lambda_source,
+                exploration.externals.current,
+                arg.segment(),
+            )),
+            name: Some(arg.name.to_string()),
+            ty,
+            local_id,
+            variadic: false,
+        });
+    }
+
+    let function_id = exploration.type_engine.add_function(FunctionDesc {
+        type_parameters: Vec::new(),
+        parameters: params,
+        return_type: UNIT,
+        kind: FunctionKind::Function,
+    });
+    let function_type = exploration.typing.add_type(
+        Type::Function(Some(lambda_source), function_id),
+        Some(lambda_links.env().fqn.to_string()),
+    );
+    Chunk {
+        function_type,
+        function_id,
+        kind: ChunkKind::DefinedFunction(None),
+    }
+}
+
+/// The function targeted by a call.
+#[derive(Clone, Copy)]
+struct Callee<'a> {
+    reef: ReefId,
+    function_id: FunctionId,
+
+    /// The name used to call the function.
+    name: &'a str,
+}
+
+impl Callee<'_> {
+    /// Describes the signature of the called function.
+    fn signature_help(&self, exploration: &Exploration) -> String {
+        let function = exploration
+            .get_function(self.reef, self.function_id)
+            .unwrap();
+        format!(
+            "The function signature is `{}`",
+            Signature::new(exploration, self.name, function)
+        )
+    }
+}
+
 /// Checks the type of a call expression.
 pub(super) fn type_call(
     call: &ProgrammaticCall,
@@ -386,7 +544,7 @@ pub(super) fn type_call(
 
             let arguments = arguments
                 .iter()
-                .map(|expr| ascribe_types(exploration, links, diagnostics, expr, state))
+                .map(|expr| ascribe_types(exploration, links, diagnostics, argument_value(expr), state))
                 .collect::<Vec<_>>();
 
             return FunctionMatch {
@@ -395,22 +553,85 @@ pub(super) fn type_call(
                 function_source: None,
                 return_type: ERROR,
                 reef: fun_reef,
+                evaluation_order: None,
             };
         }
     };
 
+    if function_source.is_none() {
+        // callable values are not bound to any function that could be run
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::UnsupportedFeature,
+                "Callable values cannot be invoked yet",
+            )
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                call.segment(),
+                "This call has no function to run",
+            )),
+        );
+    }
+
     let function = exploration.get_function(fun_reef, function_id).unwrap();
     let parameters = function.parameters.clone(); // TODO: avoid clone
-    let return_type = function.return_type;
+    let mut return_type = function.return_type;
+    let callee = Callee {
+        reef: fun_reef,
+        function_id,
+        name: call.path.last().map_or("", |item| item.name()),
+    };
+
+    if fun_reef == exploration.externals.current
+        && function_source.is_some_and(|source| is_inferred_in_progress(source, exploration, links))
+    {
+        // the return type is only known once the body has been typed
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::CannotInfer,
+                "Recursive functions need an explicit return type",
+            )
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                call.segment(),
+                format!("`{}` is called recursively here", callee.name),
+            ))
+            .with_help("Add an explicit return type to the function"),
+        );
+        return_type = ERROR;
+    }
+
+    let Some((arguments, evaluation_order)) =
+        order_arguments(call, &parameters, exploration, links, diagnostics)
+    else {
+        let arguments = call
+            .arguments
+            .iter()
+            .map(|expr| ascribe_types(exploration, links, diagnostics, argument_value(expr), state))
+            .collect::<Vec<_>>();
+        return FunctionMatch {
+            arguments,
+            function_id,
+            function_source,
+            return_type: ERROR,
+            reef: fun_reef,
+            evaluation_order: None,
+        };
+    };
 
-    if parameters.len() != arguments.len() {
+    let is_variadic = parameters.last().is_some_and(|param| param.variadic);
+    let required_count = parameters.len() - usize::from(is_variadic);
+    if arguments.len() < required_count || (!is_variadic && arguments.len() != required_count) {
         diagnostics.push(
             Diagnostic::new(
                 DiagnosticID::TypeMismatch,
                 format!(
-                    "This function takes {} {} but {} {} supplied",
-                    parameters.len(),
-                    pluralize(parameters.len(), "argument", "arguments"),
+                    "This function takes {}{} {} but {} {} supplied",
+                    if is_variadic { "at least " } else { "" },
+                    required_count,
+                    pluralize(required_count, "argument", "arguments"),
                     arguments.len(),
                     pluralize(arguments.len(), "was", "were"),
                 ),
@@ -420,7 +641,8 @@ pub(super) fn type_call(
                 exploration.externals.current,
                 call.segment.clone(),
                 "Function is called here",
-            )),
+            ))
+            .with_help(callee.signature_help(exploration)),
         );
 
         let arguments = arguments
@@ -434,6 +656,7 @@ pub(super) fn type_call(
             function_source,
             return_type: ERROR,
             reef: fun_reef,
+            evaluation_order: None,
         }
     } else {
         let types_parameters: Vec<_> = function
@@ -459,49 +682,40 @@ pub(super) fn type_call(
             diagnostics,
         );
 
+        let (fixed_arguments, trailing_arguments) = arguments.split_at(required_count);
         let mut casted_arguments = Vec::with_capacity(parameters.len());
-        for (param, arg) in parameters.iter().cloned().zip(arguments) {
-            let param_bound = bounds.get_bound(param.ty);
-
-            let arg = ascribe_types(
+        for (param, arg) in parameters.iter().zip(fixed_arguments) {
+            casted_arguments.push(type_argument(
+                param,
+                arg,
                 exploration,
                 links,
+                state,
+                &mut bounds,
+                callee,
                 diagnostics,
-                arg,
-                state.with_local_value(ExpressionValue::Expected(param_bound)),
-            );
+            ));
+        }
 
-            let casted_argument = convert_expression(
-                arg,
-                param_bound,
-                &mut bounds,
+        if let Some(variadic) = parameters.last().filter(|param| param.variadic) {
+            casted_arguments.push(pack_trailing_arguments(
+                variadic,
+                trailing_arguments,
+                call.segment(),
                 exploration,
-                links.source,
+                links,
+                state,
+                &mut bounds,
+                callee,
                 diagnostics,
-            );
-
-            let casted_argument = match casted_argument {
-                Ok(arg) => {
-                    bounds.update_bounds(param.ty, arg.ty, exploration);
-                    arg
-                }
-                Err(arg) => {
-                    diagnostics.push(diagnose_arg_mismatch(
-                        exploration,
-                        links.source,
-                        exploration.externals.current,
-                        fun_reef,
-                        &param,
-                        &arg,
-                        &bounds,
-                    ));
-                    arg
-                }
-            };
-
-            casted_arguments.push(casted_argument);
+            ));
         }
 
+        let evaluation_order = evaluation_order.map(|mut order| {
+            order.extend(order.len()..casted_arguments.len());
+            order
+        });
+
         let return_type = apply_bounds(exploration, return_type, &bounds);
 
         let return_type = check_for_leaked_type_parameters(
@@ -519,10 +733,268 @@ pub(super) fn type_call(
             function_source,
             return_type,
             reef: fun_reef,
+            evaluation_order,
         }
     }
 }
 
+/// Tests if the given function infers its return type from a body that is being typed.
+fn is_inferred_in_progress(source: SourceId, exploration: &Exploration, links: Links) -> bool {
+    let Some(Expr::FunctionDeclaration(func)) = links.engine.get_expression(source) else {
+        return false;
+    };
+    // the chunk of a function is taken out of the engine while its body is typed
+    func.return_type.is_none()
+        && matches!(func.body.as_deref(), Some(Expr::Block(_)))
+        && exploration.type_engine.get_user(source).is_none()
+}
+
+/// Matches the arguments of a call to the parameters of the called function.
+///
+/// Named arguments are moved to the position of the parameter they refer to, and can only follow
+/// positional arguments. The order in which the fixed arguments are written is also returned if
+/// it differs from the parameters order. Returns `None` if the arguments cannot be matched.
+fn order_arguments<'a>(
+    call: &'a ProgrammaticCall,
+    parameters: &[Parameter],
+    exploration: &Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<(Vec<&'a Expr>, Option<Vec<usize>>)> {
+    if !call
+        .arguments
+        .iter()
+        .any(|arg| matches!(arg, Expr::NamedArgument(_)))
+    {
+        return Some((call.arguments.iter().collect(), None));
+    }
+
+    let current_reef = exploration.externals.current;
+    let fixed_parameters: Vec<_> = parameters.iter().filter(|param| !param.variadic).collect();
+    let mut fixed_arguments: Vec<Option<(usize, &Expr)>> = vec![None; fixed_parameters.len()];
+    let mut trailing_arguments = Vec::new();
+    let mut matched = true;
+    let mut named_seen = false;
+
+    for (offset, arg) in call.arguments.iter().enumerate() {
+        let Expr::NamedArgument(named) = arg else {
+            if named_seen {
+                matched = false;
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::InvalidNamedArgument,
+                        "Positional argument after named arguments",
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        arg.segment(),
+                        "Name this argument or move it before the named arguments",
+                    )),
+                );
+            } else if offset < fixed_arguments.len() {
+                fixed_arguments[offset] = Some((offset, arg));
+            } else {
+                trailing_arguments.push(arg);
+            }
+            continue;
+        };
+        named_seen = true;
+
+        let position = fixed_parameters
+            .iter()
+            .position(|param| param.name.as_deref() == Some(named.name.value.as_str()));
+        match position {
+            Some(position) if fixed_arguments[position].is_none() => {
+                fixed_arguments[position] = Some((offset, named.value.as_ref()));
+            }
+            Some(_) => {
+                matched = false;
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::InvalidNamedArgument,
+                        format!("Argument `{}` is supplied more than once", named.name),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        named.segment(),
+                        "Duplicate argument",
+                    )),
+                );
+            }
+            None => {
+                matched = false;
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::InvalidNamedArgument,
+                        format!("Unknown argument name `{}`", named.name),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        current_reef,
+                        named.segment(),
+                        format!("No parameter is named `{}`", named.name),
+                    )),
+                );
+            }
+        }
+    }
+    if !matched {
+        return None;
+    }
+
+    let missing: Vec<_> = fixed_parameters
+        .iter()
+        .zip(&fixed_arguments)
+        .filter(|(_, arg)| arg.is_none())
+        .map(|(param, _)| format!("`{}`", param.name.as_deref().unwrap_or("_")))
+        .collect();
+    if !missing.is_empty() {
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::InvalidNamedArgument,
+                format!(
+                    "Missing {} {}",
+                    pluralize(missing.len(), "argument", "arguments"),
+                    missing.join(", ")
+                ),
+            )
+            .with_observation(Observation::here(
+                links.source,
+                current_reef,
+                call.segment(),
+                "Function is called here",
+            )),
+        );
+        return None;
+    }
+
+    let mut evaluation_order: Vec<usize> = (0..fixed_arguments.len()).collect();
+    evaluation_order.sort_by_key(|&index| fixed_arguments[index].map(|(offset, _)| offset));
+    let evaluation_order = Some(evaluation_order)
+        .filter(|order| order.iter().enumerate().any(|(index, &arg)| index != arg));
+
+    Some((
+        fixed_arguments
+            .into_iter()
+            .flatten()
+            .map(|(_, arg)| arg)
+            .chain(trailing_arguments)
+            .collect(),
+        evaluation_order,
+    ))
+}
+
+/// Gets the value of an argument, whether it is named or not.
+fn argument_value(arg: &Expr) -> &Expr {
+    match arg {
+        Expr::NamedArgument(named) => named.value.as_ref(),
+        arg => arg,
+    }
+}
+
+/// Types an argument and converts it to the parameter type.
+#[allow(clippy::too_many_arguments)]
+fn type_argument(
+    param: &Parameter,
+    arg: &Expr,
+    exploration: &mut Exploration,
+    links: Links,
+    state: TypingState,
+    bounds: &mut TypesBounds,
+    callee: Callee,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let param_bound = bounds.get_bound(param.ty);
+
+    let arg = ascribe_types(
+        exploration,
+        links,
+        diagnostics,
+        arg,
+        state.with_local_value(ExpressionValue::Expected(param_bound)),
+    );
+
+    match convert_expression(
+        arg,
+        param_bound,
+        bounds,
+        exploration,
+        links.source,
+        diagnostics,
+    ) {
+        Ok(arg) => {
+            bounds.update_bounds(param.ty, arg.ty, exploration);
+            arg
+        }
+        Err(arg) => {
+            diagnostics.push(
+                diagnose_arg_mismatch(
+                    exploration,
+                    links.source,
+                    exploration.externals.current,
+                    callee.reef,
+                    param,
+                    &arg,
+                    bounds,
+                )
+                .with_help(callee.signature_help(exploration)),
+            );
+            arg
+        }
+    }
+}
+
+/// Types the trailing arguments of a call and packs them into a single vector argument.
+#[allow(clippy::too_many_arguments)]
+fn pack_trailing_arguments(
+    variadic: &Parameter,
+    arguments: &[&Expr],
+    call_segment: SourceSegment,
+    exploration: &mut Exploration,
+    links: Links,
+    state: TypingState,
+    bounds: &mut TypesBounds,
+    callee: Callee,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let Type::Instantiated(_, element_types) = exploration.get_type(variadic.ty).unwrap() else {
+        panic!("variadic parameter is not a vector")
+    };
+    let element = Parameter {
+        ty: element_types[0],
+        variadic: false,
+        ..variadic.clone()
+    };
+
+    let elements: Vec<_> = arguments
+        .iter()
+        .map(|arg| {
+            type_argument(
+                &element,
+                arg,
+                exploration,
+                links,
+                state,
+                bounds,
+                callee,
+                diagnostics,
+            )
+        })
+        .collect();
+
+    let segment = match (elements.first(), elements.last()) {
+        (Some(first), Some(last)) => first.segment.start..last.segment.end,
+        _ => call_segment.end..call_segment.end,
+    };
+    TypedExpr {
+        kind: ExprKind::Vector(elements),
+        ty: apply_bounds(exploration, variadic.ty, bounds),
+        segment,
+    }
+}
+
 /// update given bounds to update type parameters bounds of the function's return type from the given hint
 pub(super) fn infer_return_from_hint(
     exploration: &Exploration,
@@ -642,7 +1114,7 @@ pub(super) fn find_operand_implementation(
     for method_id in methods {
         let method = exploration.get_function(reef, *method_id).unwrap();
         if let [param] = &method.parameters.as_slice() {
-            if param.ty == right.ty {
+            if exploration.concretize(param.ty, left.ty) == right.ty {
                 let return_type = exploration.concretize(method.return_type, left.ty);
                 return Ok(BinaryMethodMatch {
                     left,
@@ -708,23 +1180,27 @@ pub(super) fn type_method(
         .unwrap_or("apply");
     let type_methods = exploration.get_methods(callee.ty, method_name);
     if type_methods.is_none() {
-        diagnostics.push(
-            Diagnostic::new(
-                DiagnosticID::UnknownMethod,
-                if method_call.name.is_some() {
-                    format!(
-                        "No method named `{method_name}` found for type `{}`",
-                        exploration.new_type_view(callee.ty, &TypesBounds::inactive())
-                    )
-                } else {
-                    format!(
-                        "Type `{}` is not directly callable",
-                        exploration.new_type_view(callee.ty, &TypesBounds::inactive())
-                    )
-                },
-            )
-            .with_observation((source, current_reef, method_call.segment.clone()).into()),
-        );
+        let mut diagnostic = Diagnostic::new(
+            DiagnosticID::UnknownMethod,
+            if method_call.name.is_some() {
+                format!(
+                    "No method named `{method_name}` found for type `{}`",
+                    exploration.new_type_view(callee.ty, &TypesBounds::inactive())
+                )
+            } else {
+                format!(
+                    "Type `{}` is not directly callable",
+                    exploration.new_type_view(callee.ty, &TypesBounds::inactive())
+                )
+            },
+        )
+        .with_observation((source, current_reef, method_call.segment.clone()).into());
+        if method_call.name.is_some() {
+            if let Some(suggestion) = exploration.suggest_method(callee.ty, method_name) {
+                diagnostic = diagnostic.with_help(format!("Did you mean `{suggestion}`?"));
+            }
+        }
+        diagnostics.push(diagnostic);
         return None;
     }
 
@@ -763,6 +1239,31 @@ pub(super) fn type_method(
             diagnostics,
         );
 
+        // The arguments may have been accepted through an implicit conversion
+        let method = exploration
+            .get_function(method_base_reef, method_id)
+            .unwrap();
+        let parameters: Vec<_> = method
+            .parameters
+            .iter()
+            .map(|param| bounds.get_bound(exploration.concretize(param.ty, callee.ty)))
+            .collect();
+        let arguments = arguments
+            .into_iter()
+            .zip(parameters)
+            .map(|(arg, param)| {
+                call_convert_on(
+                    arg,
+                    param,
+                    exploration,
+                    |ty| format!("Cannot convert type `{ty}`"),
+                    diagnostics,
+                    &bounds,
+                    source,
+                )
+            })
+            .collect();
+
         // We have an exact match
         return Some(FunctionMatch {
             arguments,
@@ -770,6 +1271,7 @@ pub(super) fn type_method(
             function_source: None,
             return_type,
             reef: callee.ty.reef,
+            evaluation_order: None,
         });
     }
 
@@ -835,9 +1337,8 @@ pub(super) fn type_method(
                     }
                     Err(_) => {
                         let param = Parameter {
-                            location: param.location.clone(),
                             ty: param_bound,
-                            local_id: param.local_id,
+                            ..param.clone()
                         };
                         let diagnostic = diagnose_arg_mismatch(
                             exploration,
@@ -981,12 +1482,32 @@ pub(super) fn type_parameter(
                     exploration.externals.current,
                     named.segment(),
                 )),
+                name: Some(named.name.to_string()),
                 ty: type_id,
                 local_id,
+                variadic: false,
+            }
+        }
+        FunctionParameter::Slf(_) => unreachable!("`self` is typed by its declaring method"),
+        FunctionParameter::Variadic(name, ty, segment) => {
+            let element_type = ty.as_ref().map_or(STRING, |ty| {
+                resolve_type_annotation(exploration, links, ty, diagnostics)
+            });
+            let vec_type = exploration
+                .typing
+                .add_type(Type::Instantiated(GENERIC_VECTOR, vec![element_type]), None);
+            Parameter {
+                location: Some(SourceLocation::new(
+                    links.source,
+                    exploration.externals.current,
+                    segment.clone(),
+                )),
+                name: name.as_ref().map(ToString::to_string),
+                ty: TypeRef::new(exploration.externals.current, vec_type),
+                local_id,
+                variadic: true,
             }
         }
-        FunctionParameter::Slf(_) => todo!("method not supported yet"),
-        FunctionParameter::Variadic(_, _) => todo!("Arrays are not supported yet"),
     }
 }
 
@@ -1023,23 +1544,31 @@ impl<'a> Signature<'a> {
     }
 }
 
+impl Signature<'_> {
+    fn fmt_parameter(&self, f: &mut fmt::Formatter<'_>, param: &Parameter) -> fmt::Result {
+        let bounds = TypesBounds::inactive();
+        match self.exploration.get_type(param.ty) {
+            // A variadic parameter is shown with the type of its elements
+            Some(Type::Instantiated(_, elements)) if param.variadic => {
+                write!(
+                    f,
+                    "{}...",
+                    self.exploration.new_type_view(elements[0], &bounds)
+                )
+            }
+            _ => write!(f, "{}", self.exploration.new_type_view(param.ty, &bounds)),
+        }
+    }
+}
+
 impl fmt::Display for Signature<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}(", self.name)?;
         if let Some((first, parameters)) = self.function.parameters.split_first() {
-            write!(
-                f,
-                "{}",
-                self.exploration
-                    .new_type_view(first.ty, &TypesBounds::inactive())
-            )?;
+            self.fmt_parameter(f, first)?;
             for param in parameters {
-                write!(
-                    f,
-                    ", {}",
-                    self.exploration
-                        .new_type_view(param.ty, &TypesBounds::inactive())
-                )?;
+                write!(f, ", ")?;
+                self.fmt_parameter(f, param)?;
             }
         }
         if self.function.return_type.is_nothing() {
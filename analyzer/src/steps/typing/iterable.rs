@@ -2,8 +2,10 @@ use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
 use crate::reef::ReefId;
 use crate::relations::SymbolRef;
 use crate::steps::typing::bounds::TypesBounds;
+use crate::steps::typing::coercion::coerce_condition;
 use crate::steps::typing::exploration::{Exploration, Links};
-use crate::steps::typing::{ascribe_types, ExpressionValue, TypingState};
+use crate::steps::typing::lower::spread_glob;
+use crate::steps::typing::{ascribe_loop_body, ascribe_types, ExpressionValue, TypingState};
 use crate::types::builtin::STRING_STRUCT;
 use crate::types::ctx::TypedVariable;
 use crate::types::engine::StructureId;
@@ -22,7 +24,15 @@ pub(super) fn ascribe_for(
 ) -> TypedExpr {
     match it.kind.as_ref() {
         ForKind::Range(range) => {
-            let iterable = ascribe_types(exploration, links, diagnostics, &range.iterable, state);
+            let iterable = ascribe_types(
+                exploration,
+                links,
+                diagnostics,
+                &range.iterable,
+                state.with_local_value(ExpressionValue::Unspecified),
+            );
+            // Iterating over a glob pattern iterates over the matching file paths
+            let iterable = spread_glob(iterable, exploration);
             let id = links.env().get_raw_symbol(range.segment.clone()).unwrap();
             let SymbolRef::Local(receiver_id) = id else {
                 unreachable!()
@@ -60,14 +70,13 @@ pub(super) fn ascribe_for(
                     }
                 }
             }
-            let body = ascribe_types(
+            let body = ascribe_loop_body(
+                &it.body,
+                it.label.as_ref(),
                 exploration,
                 links,
                 diagnostics,
-                &it.body,
-                state
-                    .with_in_loop()
-                    .with_local_value(ExpressionValue::Unused),
+                state,
             );
             TypedExpr {
                 kind: ExprKind::ForLoop(ForLoop {
@@ -92,30 +101,30 @@ pub(super) fn ascribe_for(
                 links,
                 diagnostics,
                 &conditional.initializer,
-                state,
+                state.with_local_value(ExpressionValue::Unused),
             );
             let condition = ascribe_types(
                 exploration,
                 links,
                 diagnostics,
                 &conditional.condition,
-                state,
+                state.with_local_value(ExpressionValue::Unspecified),
             );
+            let condition = coerce_condition(condition, exploration, links.source, diagnostics);
             let increment = ascribe_types(
                 exploration,
                 links,
                 diagnostics,
                 &conditional.increment,
-                state,
+                state.with_local_value(ExpressionValue::Unused),
             );
-            let body = ascribe_types(
+            let body = ascribe_loop_body(
+                &it.body,
+                it.label.as_ref(),
                 exploration,
                 links,
                 diagnostics,
-                &it.body,
-                state
-                    .with_in_loop()
-                    .with_local_value(ExpressionValue::Unused),
+                state,
             );
             TypedExpr {
                 kind: ExprKind::ForLoop(ForLoop {
@@ -160,9 +169,9 @@ mod tests {
     use crate::reef::ReefId;
     use crate::relations::SourceId;
     use crate::steps::typing::tests::extract_type;
-    use crate::types::{STRING, UNIT};
+    use crate::types::{INT, STRING, UNIT};
     use context::source::Source;
-    use context::str_find::find_in;
+    use context::str_find::{find_in, find_in_nth};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -200,6 +209,38 @@ mod tests {
         assert_eq!(res, Ok(UNIT));
     }
 
+    #[test]
+    fn iterate_files() {
+        let source = Source::unknown("var last = ''; for f in *.rs { echo $f; last = $f }; $last");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn glob_as_string() {
+        let content = "val name: String = p'*.rs'";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "String"),
+                "Expected `String`",
+            ))
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "p'*.rs'"),
+                "Found `Glob`",
+            ))])
+        );
+    }
+
     #[test]
     fn iterate_string() {
         let source = Source::unknown("var last = ''; for c in 'hello' { last = $c }; $last");
@@ -207,6 +248,110 @@ mod tests {
         assert_eq!(res, Ok(STRING));
     }
 
+    #[test]
+    fn iterate_int_range() {
+        let source = Source::unknown("var last = 0; for i in 0..10 { last = $i }; $last");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn iterate_int_range_body() {
+        let source = Source::unknown("for i in 0..10 { $i }");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn reassign_range_receiver() {
+        let content = "for i in 0..10 { i = 5; var x = 0; x = 1 }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::CannotReassign,
+                "Cannot assign twice to immutable variable `i`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "i = 5"),
+                "Assignment happens here",
+            ))])
+        );
+    }
+
+    #[test]
+    fn iterate_descending_range() {
+        let source = Source::unknown("var last = 0; for i in 10..0..-2 { last = $i }; $last");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn non_int_range_step() {
+        let content = "for i in 0..10..2.5 { $i }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Invalid integer range",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "2.5"),
+                "Got `Float`",
+            ))])
+        );
+    }
+
+    #[test]
+    fn zero_range_step() {
+        let content = "for i in 0..10..0 { $i }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::InvalidRangeStep,
+                "Range step cannot be zero",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in_nth(content, "0", 2),
+                "The range would never reach its end",
+            ))])
+        );
+    }
+
+    #[test]
+    fn iterate_c_style() {
+        let source = Source::unknown("for ((var i = 0; $i < 10; i += 1)) {}");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(UNIT));
+    }
+
+    #[test]
+    fn non_boolean_for_condition() {
+        let content = "for ((var i = 0; $i; i += 1)) {}";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Condition must be a boolean",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$i"),
+                "Type `Int` cannot be used as a condition",
+            ))])
+        );
+    }
+
     #[test]
     fn iterate_condition() {
         let source = Source::unknown("for ((var x = 0; $x < 0; $x += 0)) { echo $x }");
@@ -5,10 +5,10 @@ use crate::relations::SourceId;
 use crate::steps::typing::bounds::TypesBounds;
 use crate::steps::typing::exploration::Exploration;
 use crate::steps::typing::view::TypeView;
-use crate::types::builtin::GENERIC_PARAMETER_1;
+use crate::types::builtin::{GENERIC_PARAMETER_1, STRING_VEC};
 use crate::types::hir::{ExprKind, MethodCall, TypedExpr};
 use crate::types::ty::{Type, TypeRef};
-use crate::types::{BOOL, FLOAT, GENERIC_OPTION, STRING};
+use crate::types::{BOOL, FLOAT, GENERIC_OPTION, GLOB, STRING};
 
 pub fn get_converter(ty: TypeRef) -> Option<&'static str> {
     Some(match ty {
@@ -107,6 +107,26 @@ pub(super) fn call_convert_on(
     expr
 }
 
+/// Expands a glob pattern into the vector of the matching file paths, if needed.
+pub(super) fn spread_glob(expr: TypedExpr, exploration: &Exploration) -> TypedExpr {
+    if expr.ty != GLOB {
+        return expr;
+    }
+    let (method, method_id) = exploration
+        .get_method_exact(GLOB, "spread", &[], STRING_VEC)
+        .expect("Glob should have a `spread` method.");
+    let segment = expr.segment.clone();
+    TypedExpr {
+        kind: ExprKind::MethodCall(MethodCall {
+            callee: Box::new(expr),
+            arguments: vec![],
+            function_id: method_id,
+        }),
+        ty: method.return_type,
+        segment,
+    }
+}
+
 /// Generates a conversion method call if needed.
 pub(super) fn generate_unwrap(typed: TypedExpr, exploration: &Exploration) -> TypedExpr {
     let Some(Type::Instantiated(instantiated, parameters)) = exploration.get_type(typed.ty) else {
@@ -1,12 +1,19 @@
 use std::str::FromStr;
 
+use ast::r#use::{Import, Use};
+use ast::value::LiteralValue;
+use ast::variable::Identifier;
 use context::source::{SourceSegment, SourceSegmentHolder};
 
+use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
 use crate::environment::symbols::MagicSymbolKind;
 use crate::name::Name;
+use crate::relations::SymbolRef;
 use crate::steps::typing::exploration::{Exploration, Links};
+use crate::steps::typing::lower::generate_unwrap;
+use crate::types::ctx::TypedVariable;
 use crate::types::hir::{Declaration, ExprKind, FunctionCall, TypedExpr};
-use crate::types::{builtin, UNIT};
+use crate::types::{builtin, STRING, UNIT};
 
 /// Checks if the given name is reserved for an external variable that is not
 /// defined in the source.
@@ -46,6 +53,7 @@ pub(super) fn prepend_implicits(
                         reef: std_reef,
                         function_id: get_args_chunk.function_id,
                         source_id: Some(get_args_function),
+                        evaluation_order: None,
                     }),
                     ty: builtin::STRING_VEC,
                     segment: Default::default(),
@@ -64,3 +72,113 @@ pub(super) fn prepend_implicits(
         body
     }
 }
+
+/// Lists the environment variables imported by the given import.
+pub(super) fn imported_env_variables(import: &Import) -> Vec<&Identifier> {
+    match import {
+        Import::Environment(ident) => vec![ident],
+        Import::List(list) => list
+            .imports
+            .iter()
+            .flat_map(imported_env_variables)
+            .collect(),
+        Import::Symbol(_) | Import::AllIn(..) => Vec::new(),
+    }
+}
+
+/// Binds the environment variables of a `use` statement to their value.
+///
+/// Environment variables are always typed as `String`, and reading an unset variable
+/// panics at runtime.
+pub(super) fn ascribe_env_import(
+    import: &Use,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let declarations: Vec<_> = imported_env_variables(&import.import)
+        .into_iter()
+        .map(|ident| {
+            let Some(SymbolRef::Local(id)) = links.env().get_raw_symbol(ident.segment()) else {
+                unreachable!("environment variable `{ident}` is not bound")
+            };
+            exploration
+                .ctx
+                .set_local(links.source, id, TypedVariable::immutable(STRING));
+            let value = read_env_variable(ident, exploration);
+            if value.is_none() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::ImportResolution,
+                        format!("Cannot read environment variable `{ident}`"),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        exploration.externals.current,
+                        ident.segment(),
+                        "The standard library is required to read environment variables",
+                    )),
+                );
+            }
+            TypedExpr {
+                kind: ExprKind::Declare(Declaration {
+                    identifier: id,
+                    value: value.map(Box::new),
+                }),
+                ty: UNIT,
+                segment: ident.segment(),
+            }
+        })
+        .collect();
+
+    if declarations.is_empty() {
+        return TypedExpr {
+            kind: ExprKind::Noop,
+            ty: UNIT,
+            segment: import.segment(),
+        };
+    }
+    TypedExpr {
+        kind: ExprKind::Block(declarations),
+        ty: UNIT,
+        segment: import.segment(),
+    }
+}
+
+/// Generates the call to `std::env` that reads the given environment variable, and unwraps
+/// its optional value.
+///
+/// Returns `None` if the standard library is not available.
+fn read_env_variable(ident: &Identifier, exploration: &Exploration) -> Option<TypedExpr> {
+    let (std_reef, env_function) = exploration
+        .externals
+        .get_reef_by_name("std")
+        .and_then(|(r, reef_id)| {
+            r.engine
+                .find_environment_by_name(&Name::new("std::env"))
+                .zip(Some(reef_id))
+        })
+        .map(|((id, _), reef_id)| (reef_id, id))?;
+
+    let env_chunk = exploration.get_chunk(std_reef, env_function)?;
+    let return_type = exploration
+        .get_function(std_reef, env_chunk.function_id)?
+        .return_type;
+
+    let call = TypedExpr {
+        kind: ExprKind::FunctionCall(FunctionCall {
+            arguments: vec![TypedExpr {
+                kind: ExprKind::Literal(LiteralValue::String(ident.value.to_string())),
+                ty: STRING,
+                segment: ident.segment(),
+            }],
+            reef: std_reef,
+            function_id: env_chunk.function_id,
+            source_id: Some(env_function),
+            evaluation_order: None,
+        }),
+        ty: return_type,
+        segment: ident.segment(),
+    };
+    Some(generate_unwrap(call, exploration))
+}
@@ -0,0 +1,455 @@
+use ast::r#match::{Match, MatchPattern};
+use ast::value::LiteralValue;
+use context::source::SourceSegmentHolder;
+
+use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+use crate::relations::SymbolRef;
+use crate::steps::typing::bounds::TypesBounds;
+use crate::steps::typing::coercion::{coerce_condition, convert_expression, convert_many};
+use crate::steps::typing::exploration::{Exploration, Links};
+use crate::steps::typing::{
+    ascribe_literal, ascribe_template_string, ascribe_types, ascribe_var_reference,
+    ExpressionValue, TypingState,
+};
+use crate::types::ctx::TypedVariable;
+use crate::types::hir::{self, ExprKind, TypedExpr};
+use crate::types::ty::TypeRef;
+use crate::types::{BOOL, ERROR, FLOAT, INT, STRING, UNIT};
+
+pub(super) fn ascribe_match(
+    match_expr: &Match,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+    state: TypingState,
+) -> TypedExpr {
+    let operand = ascribe_types(
+        exploration,
+        links,
+        diagnostics,
+        &match_expr.operand,
+        state.with_local_value(ExpressionValue::Unspecified),
+    );
+
+    check_comparable_operand(match_expr, &operand, exploration, links, diagnostics);
+
+    let mut arms = Vec::with_capacity(match_expr.arms.len());
+    for arm in &match_expr.arms {
+        let val_name = arm.val_name.as_ref().map(|name| {
+            let SymbolRef::Local(id) = links.env().get_raw_symbol(name.segment()).unwrap() else {
+                unreachable!()
+            };
+            exploration
+                .ctx
+                .set_local(links.source, id, TypedVariable::immutable(operand.ty));
+            id
+        });
+        let patterns: Vec<_> =
+            arm.patterns
+                .iter()
+                .map(|pattern| match pattern {
+                    MatchPattern::Wildcard(_) => hir::MatchPattern::Wildcard,
+                    MatchPattern::Literal(literal) => {
+                        hir::MatchPattern::Value(ascribe_literal(literal))
+                    }
+                    MatchPattern::VarRef(var_ref) => hir::MatchPattern::Value(
+                        ascribe_var_reference(var_ref, links, exploration, diagnostics),
+                    ),
+                    MatchPattern::Template(template) => {
+                        hir::MatchPattern::Value(ascribe_template_string(
+                            template,
+                            exploration,
+                            links,
+                            diagnostics,
+                            state.with_local_value(ExpressionValue::Unspecified),
+                        ))
+                    }
+                })
+                .collect();
+        if is_comparable(operand.ty) {
+            for (pattern, typed_pattern) in arm.patterns.iter().zip(&patterns) {
+                if let hir::MatchPattern::Value(value) = typed_pattern {
+                    check_pattern_type(
+                        pattern,
+                        value,
+                        match_expr,
+                        &operand,
+                        exploration,
+                        links,
+                        diagnostics,
+                    );
+                }
+            }
+        }
+        let guard = arm.guard.as_ref().map(|guard| {
+            let guard = ascribe_types(
+                exploration,
+                links,
+                diagnostics,
+                guard,
+                state.with_local_value(ExpressionValue::Unspecified),
+            );
+            coerce_condition(guard, exploration, links.source, diagnostics)
+        });
+        let body = ascribe_types(exploration, links, diagnostics, &arm.body, state);
+        arms.push(hir::MatchArm {
+            val_name,
+            patterns,
+            guard,
+            body,
+        });
+    }
+
+    check_exhaustiveness(match_expr, &operand, &arms, exploration, links, diagnostics);
+
+    let ty = if state.local_value != ExpressionValue::Unused {
+        match convert_many(
+            exploration,
+            &mut TypesBounds::inactive(),
+            arms.iter().map(|arm| arm.body.ty),
+        ) {
+            Ok(ty) => {
+                arms = arms
+                    .into_iter()
+                    .map(|arm| hir::MatchArm {
+                        body: convert_expression(
+                            arm.body,
+                            ty,
+                            &mut TypesBounds::inactive(),
+                            exploration,
+                            links.source,
+                            diagnostics,
+                        )
+                        .expect("Type mismatch should already have been caught"),
+                        ..arm
+                    })
+                    .collect();
+                ty
+            }
+            Err(_) => {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::TypeMismatch,
+                        "`match` arms have incompatible types",
+                    )
+                    .with_observations(match_expr.arms.iter().zip(&arms).map(
+                        |(arm, typed_arm)| {
+                            Observation::here(
+                                links.source,
+                                exploration.externals.current,
+                                arm.body.segment(),
+                                format!(
+                                    "Found `{}`",
+                                    exploration
+                                        .new_type_view(typed_arm.body.ty, &TypesBounds::inactive()),
+                                ),
+                            )
+                        },
+                    )),
+                );
+                ERROR
+            }
+        }
+    } else {
+        UNIT
+    };
+
+    TypedExpr {
+        kind: ExprKind::Match(hir::Match {
+            operand: Box::new(operand),
+            arms,
+        }),
+        ty,
+        segment: match_expr.segment(),
+    }
+}
+
+/// Lists all the values of a type that has a finite number of values.
+fn bounded_values(ty: TypeRef) -> Option<Vec<LiteralValue>> {
+    if ty == BOOL {
+        Some(vec![LiteralValue::Bool(true), LiteralValue::Bool(false)])
+    } else {
+        None
+    }
+}
+
+/// Tests if values of the given type can be compared to the values of patterns.
+fn is_comparable(ty: TypeRef) -> bool {
+    matches!(ty, BOOL | INT | FLOAT | STRING)
+}
+
+/// Checks that the operand can be compared with the values of the patterns, if there are any.
+fn check_comparable_operand(
+    match_expr: &Match,
+    operand: &TypedExpr,
+    exploration: &Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(pattern) = match_expr
+        .arms
+        .iter()
+        .flat_map(|arm| &arm.patterns)
+        .find(|pattern| !matches!(pattern, MatchPattern::Wildcard(_)))
+    else {
+        return;
+    };
+    if operand.ty == ERROR || is_comparable(operand.ty) {
+        return;
+    }
+    diagnostics.push(
+        Diagnostic::new(
+            DiagnosticID::TypeMismatch,
+            format!(
+                "Values of type `{}` cannot be matched against patterns",
+                exploration.new_type_view(operand.ty, &TypesBounds::inactive()),
+            ),
+        )
+        .with_observation(Observation::here(
+            links.source,
+            exploration.externals.current,
+            match_expr.operand.segment(),
+            "This value is matched",
+        ))
+        .with_observation(Observation::context(
+            links.source,
+            exploration.externals.current,
+            pattern.segment(),
+            "Compared to this pattern",
+        ))
+        .with_help("Only `Bool`, `Int`, `Float` and `String` values can be matched"),
+    );
+}
+
+/// Checks that the value of a pattern has the type of the matched value.
+fn check_pattern_type(
+    pattern: &MatchPattern,
+    value: &TypedExpr,
+    match_expr: &Match,
+    operand: &TypedExpr,
+    exploration: &Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if value.ty == operand.ty || value.ty.is_err() {
+        return;
+    }
+    diagnostics.push(
+        Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                pattern.segment(),
+                format!(
+                    "Found `{}`",
+                    exploration.new_type_view(value.ty, &TypesBounds::inactive()),
+                ),
+            ))
+            .with_observation(Observation::context(
+                links.source,
+                exploration.externals.current,
+                match_expr.operand.segment(),
+                format!(
+                    "Expected `{}` because of the matched value",
+                    exploration.new_type_view(operand.ty, &TypesBounds::inactive()),
+                ),
+            )),
+    );
+}
+
+/// Reports the values of a bounded operand that are not covered by any arm.
+///
+/// Only the literal and wildcard patterns of arms without guards are considered.
+fn check_exhaustiveness(
+    match_expr: &Match,
+    operand: &TypedExpr,
+    arms: &[hir::MatchArm],
+    exploration: &Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(mut missing) = bounded_values(operand.ty) else {
+        return;
+    };
+    for pattern in arms
+        .iter()
+        .filter(|arm| arm.guard.is_none())
+        .flat_map(|arm| &arm.patterns)
+    {
+        match pattern {
+            hir::MatchPattern::Wildcard => return,
+            hir::MatchPattern::Value(TypedExpr {
+                kind: ExprKind::Literal(value),
+                ..
+            }) => missing.retain(|missing| missing != value),
+            hir::MatchPattern::Value(_) => {}
+        }
+    }
+    if missing.is_empty() {
+        return;
+    }
+
+    let missing = missing
+        .iter()
+        .map(|value| match value {
+            LiteralValue::String(string) => format!("`'{string}'`"),
+            LiteralValue::Int(int) => format!("`{int}`"),
+            LiteralValue::Float(float) => format!("`{float}`"),
+            LiteralValue::Bool(bool) => format!("`{bool}`"),
+            LiteralValue::Duration(duration) => format!("`{}ms`", duration.as_millis()),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    diagnostics.push(
+        Diagnostic::new(DiagnosticID::NonExhaustiveMatch, "Non-exhaustive match")
+            .with_observation(Observation::here(
+                links.source,
+                exploration.externals.current,
+                match_expr.operand.segment(),
+                format!(
+                    "Not all `{}` values are covered",
+                    exploration.new_type_view(operand.ty, &TypesBounds::inactive()),
+                ),
+            ))
+            .with_help(format!(
+                "Add arms for the missing patterns {missing}, or a wildcard `*` arm"
+            )),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+    use crate::reef::ReefId;
+    use crate::relations::SourceId;
+    use crate::steps::typing::tests::extract_type;
+    use crate::types::{EXITCODE, INT, STRING};
+    use context::source::Source;
+    use context::str_find::find_in;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn match_value() {
+        let source = Source::unknown("val x = match $1 { 'a' => 1\n 'b' | 'c' => 2\n * => 3 }; $x");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(INT));
+    }
+
+    #[test]
+    fn exhaustive_bool_match() {
+        let source = Source::unknown("match true { true => echo yes\n false => echo no }");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(EXITCODE));
+    }
+
+    #[test]
+    fn wildcard_bool_match() {
+        let source = Source::unknown("match true { true => echo yes\n * => echo no }");
+        let res = extract_type(source);
+        assert_eq!(res, Ok(EXITCODE));
+    }
+
+    #[test]
+    fn non_exhaustive_bool_match() {
+        let content =
+            "val b = true; match $b { true => echo yes\n false if [ 1 == 2 ] => echo no }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::NonExhaustiveMatch,
+                "Non-exhaustive match"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$b"),
+                "Not all `Bool` values are covered",
+            ))
+            .with_help(
+                "Add arms for the missing patterns `false`, or a wildcard `*` arm"
+            )])
+        );
+    }
+
+    #[test]
+    fn guarded_arm() {
+        let source = Source::unknown(
+            "val n = 3; val size = match $n { v@* if $v > 2 => 'big'\n * => 'small' }; $size",
+        );
+        let res = extract_type(source);
+        assert_eq!(res, Ok(STRING));
+    }
+
+    #[test]
+    fn non_bool_guard() {
+        let content = "match 1 { 1 if 9.9 => echo one\n * => echo other }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Condition must be a boolean"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "9.9"),
+                "Type `Float` cannot be used as a condition",
+            ))])
+        );
+    }
+
+    #[test]
+    fn match_incomparable_values() {
+        let content = "val a = [1]; val b = [2]; match $a { $b => echo same\n * => echo other }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Values of type `Vec[Int]` cannot be matched against patterns"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$a"),
+                "This value is matched",
+            ))
+            .with_observation(Observation::context(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$b"),
+                "Compared to this pattern",
+            ))
+            .with_help(
+                "Only `Bool`, `Int`, `Float` and `String` values can be matched"
+            )])
+        );
+    }
+
+    #[test]
+    fn mismatched_pattern() {
+        let content = "match 1 { 'one' => echo one\n * => echo other }";
+        let res = extract_type(Source::unknown(content));
+        assert_eq!(
+            res,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "'one'"),
+                "Found `String`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "1"),
+                "Expected `Int` because of the matched value",
+            ))])
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use ast::r#struct::{FieldAccess, StructDeclaration};
-use ast::variable::{Assign, Identifier};
+use ast::group::TupleLiteral;
+use ast::r#struct::{FieldAccess, StructDeclaration, StructImpl};
+use ast::variable::{Assign, Identifier, TupleDeclaration, VarKind};
 use context::source::{SourceSegment, SourceSegmentHolder};
 
 use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
@@ -11,9 +12,11 @@ use crate::steps::typing::assign::ascribe_assign_rhs;
 use crate::steps::typing::bounds::{apply_bounds, TypesBounds};
 use crate::steps::typing::coercion::resolve_type_annotation;
 use crate::steps::typing::exploration::{Exploration, Links};
+use crate::steps::typing::function::{declare_function, Receiver};
 use crate::steps::typing::{ascribe_types, ExpressionValue, TypingState};
+use crate::types::ctx::TypedVariable;
 use crate::types::engine::StructureId;
-use crate::types::hir::{ExprKind, TypedExpr};
+use crate::types::hir::{Declaration, ExprKind, TypedExpr, Var};
 use crate::types::ty::{Field, FunctionDesc, Type, TypeId, TypeRef};
 use crate::types::{hir, ERROR, UNIT};
 
@@ -105,11 +108,15 @@ pub(super) fn declare_structure(
             None,
         )
     };
-    let constructor = FunctionDesc::constructor(
+    let mut constructor = FunctionDesc::constructor(
         type_parameters,
         field_types,
         TypeRef::new(current_reef, constructor_return_type),
     );
+    // fields can be passed to the constructor by name
+    for (param, field_declaration) in constructor.parameters.iter_mut().zip(&decl.fields) {
+        param.name = Some(field_declaration.name.to_string());
+    }
     let constructor_fn_id = exploration
         .type_engine
         .add_method(structure_id, "<init>", constructor);
@@ -155,6 +162,68 @@ pub(super) fn ascribe_struct_declaration(
     }
 }
 
+pub(super) fn ascribe_impl(
+    imp: &StructImpl,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> TypedExpr {
+    let noop = TypedExpr {
+        kind: ExprKind::Noop,
+        ty: UNIT,
+        segment: imp.segment(),
+    };
+    if !imp.type_parameters.is_empty() {
+        // already reported when collecting
+        return noop;
+    }
+    let current_reef = exploration.externals.current;
+    let impl_type = resolve_type_annotation(exploration, links, &imp.impl_type, diagnostics);
+
+    match exploration.get_type(impl_type) {
+        Some(&Type::Structure(_, structure)) if impl_type.reef == current_reef => {
+            let receiver = Receiver {
+                structure,
+                ty: impl_type,
+            };
+            for method in &imp.functions {
+                let method_source = links.env().get_raw_env(method.segment()).unwrap();
+
+                // methods are forward declared, so they can be called before their body is typed
+                if exploration.type_engine.get_user(method_source).is_none() {
+                    let method_links = links.with_source(method_source);
+                    let chunk = declare_function(
+                        method,
+                        Some(receiver),
+                        exploration,
+                        method_links,
+                        diagnostics,
+                    );
+                    exploration.type_engine.insert(method_source, chunk);
+                }
+            }
+        }
+        _ if impl_type.is_err() => {}
+        _ => diagnostics.push(
+            Diagnostic::new(
+                DiagnosticID::InvalidImpl,
+                format!(
+                    "Cannot declare methods on type `{}`",
+                    exploration.new_type_view(impl_type, &TypesBounds::inactive())
+                ),
+            )
+            .with_observation(Observation::here(
+                links.source,
+                current_reef,
+                imp.impl_type.segment(),
+                "Only structures of the current reef can have an `impl` block",
+            )),
+        ),
+    }
+
+    noop
+}
+
 pub(super) fn ascribe_field_assign(
     assign: &Assign,
     access: &FieldAccess,
@@ -276,9 +345,17 @@ fn type_field_access(
             field_type: apply_bounds(exploration, field.ty, &bounds),
         }),
         None => {
+            let mut fields: Vec<_> = structure.fields.iter().collect();
+            fields.sort_by_key(|(_, field)| field.local_id.0);
+            let help = if fields.is_empty() {
+                "The structure has no fields".to_owned()
+            } else {
+                let names: Vec<_> = fields.iter().map(|(name, _)| format!("`{name}`")).collect();
+                format!("Available fields are {}", names.join(", "))
+            };
             diagnostics.push(
                 Diagnostic::new(
-                    DiagnosticID::UnknownSymbol,
+                    DiagnosticID::UnknownField,
                     format!(
                         "unknown field `{}` in structure `{}`",
                         field_name,
@@ -290,7 +367,8 @@ fn type_field_access(
                     exploration.externals.current,
                     segment.clone(),
                     format!("`{}` does not exists", field_name),
-                )),
+                ))
+                .with_help(help),
             );
             None
         }
@@ -337,16 +415,234 @@ pub(super) fn ascribe_field_access(
     }
 }
 
+/// Types a tuple literal, whose type is an anonymous structure with a field per element.
+pub(super) fn ascribe_tuple(
+    tuple: &TupleLiteral,
+    links: Links,
+    exploration: &mut Exploration,
+    diagnostics: &mut Vec<Diagnostic>,
+    state: TypingState,
+) -> TypedExpr {
+    let elements: Vec<_> = tuple
+        .elements
+        .iter()
+        .map(|element| {
+            ascribe_types(
+                exploration,
+                links,
+                diagnostics,
+                element,
+                state.with_local_value(ExpressionValue::Unspecified),
+            )
+        })
+        .collect();
+    if elements.iter().any(|element| element.ty.is_err()) {
+        return TypedExpr::error(tuple.segment());
+    }
+
+    let ty = tuple_type(
+        elements.iter().map(|element| element.ty).collect(),
+        exploration,
+    );
+    TypedExpr {
+        kind: ExprKind::Tuple(elements),
+        ty,
+        segment: tuple.segment(),
+    }
+}
+
+/// Types a declaration that binds each element of a tuple to a variable.
+///
+/// The tuple is stored in a hidden variable, from which each element is then read.
+pub(super) fn ascribe_tuple_declaration(
+    decl: &TupleDeclaration,
+    exploration: &mut Exploration,
+    links: Links,
+    diagnostics: &mut Vec<Diagnostic>,
+    state: TypingState,
+) -> TypedExpr {
+    let initializer = ascribe_types(
+        exploration,
+        links,
+        diagnostics,
+        &decl.initializer,
+        state.with_local_value(ExpressionValue::Unspecified),
+    );
+    let Some(SymbolRef::Local(tuple_id)) = links.env().get_raw_symbol(decl.segment()) else {
+        unreachable!("the destructured tuple is not bound")
+    };
+    // the `_` names are not bound
+    let bound: Vec<Option<LocalId>> = decl
+        .names
+        .iter()
+        .map(|name| match links.env().get_raw_symbol(name.segment()) {
+            Some(SymbolRef::Local(id)) => Some(id),
+            _ => None,
+        })
+        .collect();
+
+    let tuple_ty = initializer.ty;
+    let elements = exploration
+        .get_types(tuple_ty.reef)
+        .and_then(|typing| typing.get_tuple_elements(tuple_ty.type_id))
+        .map(<[TypeRef]>::to_vec);
+    let structure = match exploration.get_type(tuple_ty) {
+        Some(Type::Structure(_, structure_id)) => Some(*structure_id),
+        _ => None,
+    };
+    let (elements, structure_id) = match (elements, structure) {
+        (Some(elements), Some(structure_id)) if elements.len() == decl.names.len() => {
+            (elements, structure_id)
+        }
+        (elements, _) => {
+            if !tuple_ty.is_err() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticID::TypeMismatch,
+                        format!(
+                            "Cannot destructure `{}` into {} variables",
+                            exploration.new_type_view(tuple_ty, &TypesBounds::inactive()),
+                            decl.names.len()
+                        ),
+                    )
+                    .with_observation(Observation::here(
+                        links.source,
+                        exploration.externals.current,
+                        initializer.segment(),
+                        match elements {
+                            Some(elements) if elements.len() == 1 => {
+                                "This tuple has 1 element".to_owned()
+                            }
+                            Some(elements) => format!("This tuple has {} elements", elements.len()),
+                            None => "Expected a tuple".to_owned(),
+                        },
+                    )),
+                );
+            }
+            // the variables are still typed, so that their uses are not reported
+            for id in bound.into_iter().flatten().chain([tuple_id]) {
+                exploration
+                    .ctx
+                    .set_local(links.source, id, TypedVariable::immutable(ERROR));
+            }
+            return TypedExpr::error(decl.segment());
+        }
+    };
+
+    exploration
+        .ctx
+        .set_local(links.source, tuple_id, TypedVariable::immutable(tuple_ty));
+    let mut declarations = vec![TypedExpr {
+        kind: ExprKind::Declare(Declaration {
+            identifier: tuple_id,
+            value: Some(Box::new(initializer)),
+        }),
+        ty: UNIT,
+        segment: decl.segment(),
+    }];
+    for (idx, ((name, id), element_ty)) in decl.names.iter().zip(bound).zip(elements).enumerate() {
+        let Some(id) = id else {
+            continue;
+        };
+        exploration.ctx.set_local(
+            links.source,
+            id,
+            if decl.kind == VarKind::Val {
+                TypedVariable::immutable(element_ty)
+            } else {
+                TypedVariable::assignable(element_ty)
+            },
+        );
+        let element = TypedExpr {
+            kind: ExprKind::FieldAccess(hir::FieldAccess {
+                object: Box::new(TypedExpr {
+                    kind: ExprKind::Reference(Var::Local(tuple_id)),
+                    ty: tuple_ty,
+                    segment: decl.initializer.segment(),
+                }),
+                structure: structure_id,
+                structure_reef: tuple_ty.reef,
+                field: LocalId(idx),
+            }),
+            ty: element_ty,
+            segment: name.segment(),
+        };
+        declarations.push(TypedExpr {
+            kind: ExprKind::Declare(Declaration {
+                identifier: id,
+                value: Some(Box::new(element)),
+            }),
+            ty: UNIT,
+            segment: name.segment(),
+        });
+    }
+    TypedExpr {
+        kind: ExprKind::Block(declarations),
+        ty: UNIT,
+        segment: decl.segment(),
+    }
+}
+
+/// Gets the tuple type of the given element types, declaring it the first time it is used.
+///
+/// The elements are the fields of the tuple structure, named after their index.
+pub(super) fn tuple_type(elements: Vec<TypeRef>, exploration: &mut Exploration) -> TypeRef {
+    let current_reef = exploration.externals.current;
+    if let Some(type_id) = exploration.typing.get_tuple(&elements) {
+        return TypeRef::new(current_reef, type_id);
+    }
+
+    let mut name = elements
+        .iter()
+        .map(|element| {
+            exploration
+                .new_type_view(*element, &TypesBounds::inactive())
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if elements.len() == 1 {
+        name.push(',');
+    }
+
+    let structure_id = exploration.type_engine.init_empty_structure();
+    let structure = exploration
+        .type_engine
+        .get_structure_mut(structure_id)
+        .unwrap();
+    for (idx, ty) in elements.iter().enumerate() {
+        structure.fields.insert(
+            idx.to_string(),
+            Field {
+                ty: *ty,
+                local_id: LocalId(idx),
+            },
+        );
+    }
+    let type_id = exploration.typing.add_type(
+        Type::Structure(None, structure_id),
+        Some(format!("({name})")),
+    );
+    exploration.typing.add_tuple(elements, type_id);
+    TypeRef::new(current_reef, type_id)
+}
+
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
 
     use context::source::Source;
+    use context::str_find::{find_in, find_in_nth};
 
+    use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
     use crate::reef::ReefId;
-    use crate::steps::typing::tests::extract_type;
+    use crate::relations::SourceId;
+    use crate::steps::typing::explain::type_name;
+    use crate::steps::typing::tests::{extract, extract_type};
+    use crate::types::engine::ChunkKind;
+    use crate::types::hir::ExprKind;
     use crate::types::ty::{TypeId, TypeRef};
-    use crate::types::{STRING, UNIT};
+    use crate::types::{FLOAT, INT, STRING, UNIT};
 
     #[test]
     fn constructor() {
@@ -380,6 +676,27 @@ mod test {
         assert_eq!(expr, Ok(STRING))
     }
 
+    #[test]
+    fn unknown_field() {
+        let content = "struct Point { x: Int, y: Int }\nPoint(1, 2).z";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownField,
+                "unknown field `z` in structure `Point`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, ".z"),
+                "`z` does not exists",
+            ))
+            .with_help("Available fields are `x`, `y`")])
+        )
+    }
+
     #[test]
     fn field_assign() {
         let expr = extract_type(Source::unknown(
@@ -427,4 +744,356 @@ mod test {
 
         assert_eq!(expr, Ok(UNIT))
     }
+
+    #[test]
+    fn method_with_self_parameter() {
+        let expr = extract_type(Source::unknown(
+            r#"\
+            struct Point { x: Float, y: Float }
+            impl Point {
+                fun dist(self) -> Float = $self.x * $self.x + $self.y * $self.y
+            }
+            val p = Point(3.0, 4.0)
+            $p.dist()
+        "#,
+        ));
+
+        assert_eq!(expr, Ok(FLOAT))
+    }
+
+    #[test]
+    fn method_with_implicit_self() {
+        let expr = extract_type(Source::unknown(
+            r#"\
+            struct Point { x: Float, y: Float }
+            impl Point {
+                fun scale(factor: Float) -> Point = Point($self.x * $factor, $self.y * $factor)
+                fun dist() -> Float = $self.x * $self.x + $self.y * $self.y
+            }
+            val p = Point(3.0, 4.0)
+            $p.scale(2.0).dist()
+        "#,
+        ));
+
+        assert_eq!(expr, Ok(FLOAT))
+    }
+
+    #[test]
+    fn operator_method() {
+        let expr = extract_type(Source::unknown(
+            r#"\
+            struct Vec2 { x: Int, y: Int }
+            impl Vec2 {
+                fun add(other: Vec2) -> Vec2 = Vec2($self.x + $other.x, $self.y + $other.y)
+            }
+            val a = Vec2(1, 2)
+            val b = Vec2(3, 4)
+            val c: Vec2 = $a + $b + $a
+            $c.x
+        "#,
+        ));
+
+        assert_eq!(expr, Ok(INT))
+    }
+
+    #[test]
+    fn method_int_argument_coercion() {
+        let externals = extract(Source::unknown(
+            r#"\
+            struct Point { x: Float, y: Float }
+            impl Point {
+                fun scale(factor: Float) -> Point = Point($self.x * $factor, $self.y * $factor)
+            }
+            val p = Point(3.0, 4.0)
+            $p.scale(2)
+        "#,
+        ))
+        .expect("no diagnostics");
+
+        let chunk = externals
+            .get_reef(ReefId(1))
+            .unwrap()
+            .typed_engine
+            .get_user(SourceId(0))
+            .unwrap();
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            unreachable!()
+        };
+        let ExprKind::Block(exprs) = &body.kind else {
+            unreachable!()
+        };
+        let ExprKind::MethodCall(call) = &exprs.last().unwrap().kind else {
+            panic!("expected a method call");
+        };
+        assert_eq!(
+            call.arguments.iter().map(|arg| arg.ty).collect::<Vec<_>>(),
+            vec![FLOAT]
+        );
+    }
+
+    #[test]
+    fn method_float_argument_rejected() {
+        let content = r#"\
+            struct Counter { n: Int }
+            impl Counter {
+                fun step(by: Int) -> Int = $self.n + $by
+            }
+            val c = Counter(1)
+            $c.step(2.5)
+        "#;
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Type mismatch"
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "2.5"),
+                "Expected `Int`, found `Float`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(2),
+                ReefId(1),
+                find_in(content, "by: Int"),
+                "Parameter is declared here",
+            ))
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in_nth(content, "step", 1),
+                "Arguments to this method are incorrect",
+            ))])
+        )
+    }
+
+    #[test]
+    fn undefined_operator_method() {
+        let content = "struct Vec2 { x: Int, y: Int }\nval a = Vec2(1, 2)\n$a - $a";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownMethod,
+                "Undefined operator",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$a - $a"),
+                "No operator `sub` between type `Vec2` and `Vec2`",
+            ))])
+        )
+    }
+
+    #[test]
+    fn stringify_with_to_string_method() {
+        let expr = extract_type(Source::unknown(
+            r#"\
+            struct Point { x: Int, y: Int }
+            impl Point {
+                fun to_string() -> String = "(" + $self.x.to_string() + ")"
+            }
+            val p = Point(3, 4)
+            "p = $p"
+        "#,
+        ));
+
+        assert_eq!(expr, Ok(STRING))
+    }
+
+    #[test]
+    fn stringify_without_to_string_method() {
+        let content = "struct Point { x: Int, y: Int }\nval p = Point(3, 4)\n\"p = $p\"";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Cannot stringify type `Point`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "$p"),
+                "No method `to_string` on type `Point`",
+            ))])
+        )
+    }
+
+    #[test]
+    fn parametrized_impl() {
+        let content =
+            "struct Wrapper { v: Int }\nimpl[T] Wrapper {\n  fun get() -> Int = $self.v\n}";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnsupportedFeature,
+                "Parametrized impls are not supported yet",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "T"),
+                "Remove the type parameters",
+            ))])
+        )
+    }
+
+    #[test]
+    fn self_outside_impl() {
+        let content = "fun dist(self) -> Int = 0";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::InvalidSelfParameter,
+                "`self` parameter is only allowed in methods",
+            )
+            .with_observation(Observation::here(
+                SourceId(1),
+                ReefId(1),
+                find_in(content, "self"),
+                "Declare this function in an `impl` block",
+            ))])
+        )
+    }
+
+    #[test]
+    fn tuple_literal() {
+        let externals = extract(Source::unknown("val t = (1, 'a', 2.0)\n$t")).expect("no errors");
+        let reef = externals.get_reef(ReefId(1)).unwrap();
+        let chunk = reef.typed_engine.get_user(SourceId(0)).unwrap();
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            type_name(
+                body.ty,
+                ReefId(1),
+                &reef.typing,
+                &reef.typed_engine,
+                &externals
+            ),
+            "(Int, String, Float)"
+        );
+    }
+
+    #[test]
+    fn tuple_index_access() {
+        let expr = extract_type(Source::unknown("val t = (1, 'a', 2.0)\n$t.1"));
+        assert_eq!(expr, Ok(STRING));
+    }
+
+    #[test]
+    fn nested_tuple_index_access() {
+        let expr = extract_type(Source::unknown("val t = ('a', (1, 2.0))\n$t.1.1"));
+        assert_eq!(expr, Ok(FLOAT));
+    }
+
+    #[test]
+    fn assign_same_tuple_type() {
+        let expr = extract_type(Source::unknown("var t = (1, 'a'); t = (2, 'b')"));
+        assert_eq!(expr, Ok(UNIT));
+    }
+
+    #[test]
+    fn return_tuple() {
+        let expr = extract_type(Source::unknown(
+            "fun minmax(a: Int, b: Int) -> (Int, Int) = if $a < $b { ($a, $b) } else { ($b, $a) }
+            val t = minmax(2, 1)
+            $t.0",
+        ));
+        assert_eq!(expr, Ok(INT));
+    }
+
+    #[test]
+    fn destructure_returned_tuple() {
+        let expr = extract_type(Source::unknown(
+            "fun minmax(a: Int, b: Int) -> (Int, Int) = if $a < $b { ($a, $b) } else { ($b, $a) }
+            val (lo, hi) = minmax(2, 1)
+            $hi - $lo",
+        ));
+        assert_eq!(expr, Ok(INT));
+    }
+
+    #[test]
+    fn destructure_tuple_elements() {
+        let expr = extract_type(Source::unknown("val (n, _, s) = (1, 2.0, 'a'); $s"));
+        assert_eq!(expr, Ok(STRING));
+    }
+
+    #[test]
+    fn parenthesized_type() {
+        let expr = extract_type(Source::unknown("val n: (Int) = 1; $n"));
+        assert_eq!(expr, Ok(INT));
+    }
+
+    #[test]
+    fn destructure_arity_mismatch() {
+        let content = "val (a, b, c) = (1, 2)";
+        let expr = extract_type(Source::unknown(content));
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Cannot destructure `(Int, Int)` into 3 variables",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "(1, 2)"),
+                "This tuple has 2 elements",
+            ))])
+        );
+    }
+
+    #[test]
+    fn destructure_non_tuple() {
+        let content = "val (a, b) = 1";
+        let expr = extract_type(Source::unknown(content));
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::TypeMismatch,
+                "Cannot destructure `Int` into 2 variables",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, "1"),
+                "Expected a tuple",
+            ))])
+        );
+    }
+
+    #[test]
+    fn tuple_index_out_of_bounds() {
+        let content = "val t = (1, 2)\n$t.2";
+        let expr = extract_type(Source::unknown(content));
+
+        assert_eq!(
+            expr,
+            Err(vec![Diagnostic::new(
+                DiagnosticID::UnknownField,
+                "unknown field `2` in structure `(Int, Int)`",
+            )
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                find_in(content, ".2"),
+                "`2` does not exists",
+            ))
+            .with_help("Available fields are `0`, `1`")])
+        )
+    }
 }
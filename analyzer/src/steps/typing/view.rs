@@ -1,24 +1,45 @@
 use std::fmt;
 use std::fmt::Display;
 
+use crate::reef::ReefId;
 use crate::steps::typing::bounds::TypesBounds;
 use crate::steps::typing::exploration::Exploration;
-use crate::types::ty::{Type, TypeRef};
+use crate::types::engine::FunctionId;
+use crate::types::ty::{FunctionDesc, Type, TypeRef};
+
+/// The types and functions that a [`TypeView`] can render.
+pub(super) trait TypeLookup {
+    fn get_type(&self, id: TypeRef) -> Option<&Type>;
+
+    fn get_type_name(&self, id: TypeRef) -> Option<&String>;
+
+    fn get_function(&self, reef: ReefId, function_id: FunctionId) -> Option<&FunctionDesc>;
+}
+
+impl TypeLookup for Exploration<'_> {
+    fn get_type(&self, id: TypeRef) -> Option<&Type> {
+        Exploration::get_type(self, id)
+    }
+
+    fn get_type_name(&self, id: TypeRef) -> Option<&String> {
+        Exploration::get_type_name(self, id)
+    }
+
+    fn get_function(&self, reef: ReefId, function_id: FunctionId) -> Option<&FunctionDesc> {
+        Exploration::get_function(self, reef, function_id)
+    }
+}
 
 #[derive(Copy, Clone)]
 pub(super) struct TypeView<'a> {
     pub(super) id: TypeRef,
-    pub(super) exploration: &'a Exploration<'a>,
+    pub(super) types: &'a dyn TypeLookup,
     pub(super) bounds: &'a TypesBounds,
 }
 
 impl<'a> TypeView<'a> {
-    pub(super) fn new(id: TypeRef, exploration: &'a Exploration, bounds: &'a TypesBounds) -> Self {
-        Self {
-            id,
-            exploration,
-            bounds,
-        }
+    pub(super) fn new(id: TypeRef, types: &'a dyn TypeLookup, bounds: &'a TypesBounds) -> Self {
+        Self { id, types, bounds }
     }
 }
 
@@ -32,10 +53,10 @@ impl Display for TypeView<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let ty = self.bounds.get_bound(self.id);
 
-        let tpe = self.exploration.get_type(ty).unwrap_or(&Type::Error);
+        let tpe = self.types.get_type(ty).unwrap_or(&Type::Error);
 
         if let Type::Instantiated(def, parameters) = tpe {
-            write!(f, "{}", Self::new(*def, self.exploration, self.bounds))?;
+            write!(f, "{}", Self::new(*def, self.types, self.bounds))?;
 
             if parameters.is_empty() {
                 return Ok(());
@@ -46,19 +67,33 @@ impl Display for TypeView<'_> {
                 if i > 0 {
                     write!(f, ", ")?;
                 }
-                write!(
-                    f,
-                    "{}",
-                    &Self::new(*parameter, self.exploration, self.bounds)
-                )?;
+                write!(f, "{}", &Self::new(*parameter, self.types, self.bounds))?;
             }
             return write!(f, "]");
         }
 
+        // Callable types are not named, and are displayed from their signature
+        if let (None, Type::Function(_, function_id)) = (self.types.get_type_name(ty), tpe) {
+            if let Some(function) = self.types.get_function(ty.reef, *function_id) {
+                write!(f, "(")?;
+                for (i, parameter) in function.parameters.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", Self::new(parameter.ty, self.types, self.bounds))?;
+                }
+                return write!(
+                    f,
+                    ") => {}",
+                    Self::new(function.return_type, self.types, self.bounds)
+                );
+            }
+        }
+
         write!(
             f,
             "{}",
-            self.exploration
+            self.types
                 .get_type_name(ty)
                 .map(String::as_str)
                 .unwrap_or("<?>")
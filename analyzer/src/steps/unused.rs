@@ -0,0 +1,237 @@
+//! Reports the variables and imports that are declared but never referenced.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostic::{Diagnostic, DiagnosticID, Observation};
+use crate::engine::Engine;
+use crate::imports::{Imports, ResolvedImport};
+use crate::reef::ReefId;
+use crate::relations::{RelationState, Relations, ResolvedSymbol, SourceId, SymbolRef};
+
+/// Emits a warning for each `var` or `val` declaration that is never referenced.
+///
+/// Only the environments that were collected at or after the given source are checked. A variable
+/// is used if it is referenced in its own environment, or if any relation resolves to it, such as
+/// a capture or an import from another module. Variables whose name starts with an underscore are
+/// intentionally left unused.
+pub(crate) fn diagnose_unused_variables(
+    engine: &Engine,
+    relations: &Relations,
+    reef: ReefId,
+    from: SourceId,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let referenced_externally: HashSet<ResolvedSymbol> = relations
+        .iter()
+        .filter_map(|(_, relation)| match relation.state {
+            RelationState::Resolved(target) => Some(target),
+            _ => None,
+        })
+        .collect();
+    for (env_id, env) in engine.environments().filter(|(id, _)| id.0 >= from.0) {
+        let declarations: HashMap<_, _> = env
+            .declared_variables
+            .iter()
+            .map(|(local, declaration)| (*local, declaration))
+            .collect();
+        let referenced_locally: HashSet<_> = env
+            .list_definitions()
+            .filter_map(|(segment, symbol_ref)| match symbol_ref {
+                SymbolRef::Local(local) if declarations.get(local) != Some(&segment) => {
+                    Some(*local)
+                }
+                _ => None,
+            })
+            .collect();
+        for (local, declaration) in &env.declared_variables {
+            let symbol = env
+                .symbols
+                .get(*local)
+                .expect("declared variable references an unknown symbol");
+            if symbol.name.starts_with('_') {
+                continue;
+            }
+            let target = ResolvedSymbol::new(reef, env_id, *local);
+            if referenced_locally.contains(local) || referenced_externally.contains(&target) {
+                continue;
+            }
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::UnusedSymbol,
+                    format!("Unused variable `{}`", symbol.name),
+                )
+                .with_observation(Observation::here(
+                    env_id,
+                    reef,
+                    declaration.clone(),
+                    "Declared here but never used",
+                ))
+                .with_help(format!(
+                    "Prefix it with an underscore to silence this warning: `_{}`",
+                    symbol.name
+                )),
+            );
+        }
+    }
+    diagnostics
+}
+
+/// Emits a warning for each symbol imported by name that is never referenced.
+///
+/// Only the sources that were collected at or after the given source are checked. Glob imports
+/// are exempt, as well as module imports that also make the module part of the reef.
+pub(crate) fn diagnose_unused_imports(
+    engine: &Engine,
+    relations: &Relations,
+    imports: &Imports,
+    reef: ReefId,
+    from: SourceId,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    // The symbols that are referenced from each source, along with its nested environments
+    let referenced: HashSet<(SourceId, ResolvedSymbol)> = relations
+        .iter()
+        .filter_map(|(_, relation)| match relation.state {
+            RelationState::Resolved(target) => Some((root_source(engine, relation.origin), target)),
+            _ => None,
+        })
+        .collect();
+    for (source, source_imports) in imports.iter().filter(|(id, _)| id.0 >= from.0) {
+        for (name, resolved, segment) in source_imports.explicit_imports() {
+            let ResolvedImport::Symbols(symbols) = resolved else {
+                continue;
+            };
+            let used = symbols
+                .values()
+                .any(|symbol| referenced.contains(&(source, *symbol)));
+            if used {
+                continue;
+            }
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticID::UnusedImport,
+                    format!("Unused import `{name}`"),
+                )
+                .with_observation(Observation::here(
+                    source,
+                    reef,
+                    segment.clone(),
+                    "Imported here but never used",
+                )),
+            );
+        }
+    }
+    // Imports are not stored in order, so sort the warnings by location
+    diagnostics.sort_by_key(|diagnostic| {
+        let location = &diagnostic.observations[0].location;
+        (location.source.0, location.segment.start)
+    });
+    diagnostics
+}
+
+/// Walks up the nested environments to find the source that declares the imports.
+fn root_source(engine: &Engine, mut id: SourceId) -> SourceId {
+    while let Some(parent) = engine.get_environment(id).and_then(|env| env.parent) {
+        id = parent;
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use context::str_find::find_in;
+    use parser::parse_trusted;
+
+    use crate::analyze;
+    use crate::importer::StaticImporter;
+    use crate::name::Name;
+    use crate::reef::Externals;
+
+    use super::*;
+
+    fn analyze_warnings(source: &str) -> Vec<Diagnostic> {
+        let name = Name::new("test");
+        let mut importer = StaticImporter::new([(name.clone(), source)], parse_trusted);
+        analyze(name, &mut importer, &Externals::default()).take_diagnostics()
+    }
+
+    #[test]
+    fn unused_variable() {
+        let source = "val x = 1";
+        assert_eq!(
+            analyze_warnings(source),
+            vec![
+                Diagnostic::new(DiagnosticID::UnusedSymbol, "Unused variable `x`")
+                    .with_observation(Observation::here(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in(source, "val x = 1"),
+                        "Declared here but never used",
+                    ))
+                    .with_help("Prefix it with an underscore to silence this warning: `_x`")
+            ]
+        );
+    }
+
+    #[test]
+    fn used_variable() {
+        assert_eq!(analyze_warnings("val x = 1; $x"), vec![]);
+    }
+
+    #[test]
+    fn captured_variable() {
+        assert_eq!(analyze_warnings("val x = 1; fun f() -> Int = $x"), vec![]);
+    }
+
+    #[test]
+    fn ignore_parameters_and_underscores() {
+        assert_eq!(
+            analyze_warnings("val _x = 1; fun f(n: Int) = {}; read line"),
+            vec![]
+        );
+    }
+
+    fn analyze_import_warnings(source: &str) -> Vec<Diagnostic> {
+        let name = Name::new("test");
+        let mut importer = StaticImporter::new(
+            [
+                (name.clone(), source),
+                (Name::new("lib"), "fun greet() = echo hi"),
+            ],
+            parse_trusted,
+        );
+        analyze(name, &mut importer, &Externals::default()).take_diagnostics()
+    }
+
+    #[test]
+    fn unused_import() {
+        let source = "use reef::lib::greet";
+        assert_eq!(
+            analyze_import_warnings(source),
+            vec![
+                Diagnostic::new(DiagnosticID::UnusedImport, "Unused import `greet`")
+                    .with_observation(Observation::here(
+                        SourceId(0),
+                        ReefId(1),
+                        find_in(source, "reef::lib::greet"),
+                        "Imported here but never used",
+                    ))
+            ]
+        );
+    }
+
+    #[test]
+    fn used_import() {
+        assert_eq!(
+            analyze_import_warnings("use reef::lib::greet\nfun f() = greet()"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn ignore_glob_import() {
+        assert_eq!(analyze_import_warnings("use reef::lib::*"), vec![]);
+    }
+}
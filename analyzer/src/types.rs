@@ -6,6 +6,7 @@ use crate::types::ty::{Type, TypeId, TypeRef};
 pub mod builtin;
 pub mod ctx;
 pub mod engine;
+pub mod fold;
 pub mod hir;
 pub mod operator;
 pub mod ty;
@@ -18,6 +19,9 @@ pub struct Typing {
 
     /// A list of implicit conversions from one type to another.
     pub(crate) implicits: HashMap<TypeId, TypeRef>,
+
+    /// The tuple types, bound with the types of their elements.
+    tuples: Vec<(Vec<TypeRef>, TypeId)>,
 }
 
 impl Typing {
@@ -42,6 +46,31 @@ impl Typing {
             .and_then(|(_, name)| name.as_ref())
     }
 
+    /// Gets the tuple type whose elements have the given types, if it is already known.
+    pub(crate) fn get_tuple(&self, elements: &[TypeRef]) -> Option<TypeId> {
+        self.tuples
+            .iter()
+            .find(|(tuple, _)| tuple == elements)
+            .map(|(_, type_id)| *type_id)
+    }
+
+    pub(crate) fn add_tuple(&mut self, elements: Vec<TypeRef>, type_id: TypeId) {
+        self.tuples.push((elements, type_id));
+    }
+
+    /// Gets the types of the elements of the given type, if it is a tuple.
+    pub(crate) fn get_tuple_elements(&self, type_id: TypeId) -> Option<&[TypeRef]> {
+        self.tuples
+            .iter()
+            .find(|(_, tuple)| *tuple == type_id)
+            .map(|(elements, _)| elements.as_slice())
+    }
+
+    /// Lists the tuple types, in the order they were introduced.
+    pub fn iter_tuples(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.tuples.iter().map(|(_, type_id)| *type_id)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (TypeId, &Type)> {
         self.types
             .iter()
@@ -62,6 +91,7 @@ pub const GENERIC_VECTOR: TypeRef = TypeRef::new(LANG_REEF, TypeId(8));
 pub const GENERIC_OPTION: TypeRef = TypeRef::new(LANG_REEF, TypeId(9));
 pub const GLOB: TypeRef = TypeRef::new(LANG_REEF, TypeId(10));
 pub const PID: TypeRef = TypeRef::new(LANG_REEF, TypeId(11));
+pub const DURATION: TypeRef = TypeRef::new(LANG_REEF, TypeId(12));
 
 /// An error that occurs when two types are not compatible.
 #[derive(Debug, PartialEq)]
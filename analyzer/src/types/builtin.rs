@@ -9,8 +9,8 @@ use crate::types::engine::{StructureId, TypedEngine};
 use crate::types::operator::name_operator_method;
 use crate::types::ty::{MethodType, Type, TypeId, TypeRef};
 use crate::types::{
-    Typing, BOOL, ERROR, EXITCODE, FLOAT, GENERIC_OPTION, GENERIC_VECTOR, GLOB, INT, NOTHING, PID,
-    STRING, UNIT,
+    Typing, BOOL, DURATION, ERROR, EXITCODE, FLOAT, GENERIC_OPTION, GENERIC_VECTOR, GLOB, INT,
+    NOTHING, PID, STRING, UNIT,
 };
 
 const ARITHMETIC_OPERATORS: &[BinaryOperator] = &[
@@ -33,12 +33,12 @@ const EQUALITY_OPERATORS: &[BinaryOperator] =
 const LOGICAL_OPERATORS: &[BinaryOperator] = &[BinaryOperator::And, BinaryOperator::Or];
 
 /// Some common types.
-pub const STRING_VEC: TypeRef = TypeRef::new(LANG_REEF, TypeId(12));
-pub const INT_VEC: TypeRef = TypeRef::new(LANG_REEF, TypeId(13));
+pub const STRING_VEC: TypeRef = TypeRef::new(LANG_REEF, TypeId(13));
+pub const INT_VEC: TypeRef = TypeRef::new(LANG_REEF, TypeId(14));
 
 /// generic parameters used by the lang reef.
 /// The lang reef is a special reef that reuses the same generic parameters for each functions.
-pub const GENERIC_PARAMETER_1: TypeRef = TypeRef::new(LANG_REEF, TypeId(14));
+pub const GENERIC_PARAMETER_1: TypeRef = TypeRef::new(LANG_REEF, TypeId(15));
 pub const UNIT_STRUCT: StructureId = StructureId(0);
 pub const BOOL_STRUCT: StructureId = StructureId(1);
 pub const EXITCODE_STRUCT: StructureId = StructureId(2);
@@ -49,6 +49,12 @@ pub const VEC_STRUCT: StructureId = StructureId(6);
 pub const OPTION_STRUCT: StructureId = StructureId(7);
 pub const GLOB_STRUCT: StructureId = StructureId(8);
 pub const PID_STRUCT: StructureId = StructureId(9);
+pub const DURATION_STRUCT: StructureId = StructureId(10);
+
+/// The structure that holds the signatures of the commands that the language knows about.
+///
+/// It has no type, so its methods can't be called and only describe the command arguments.
+pub const COMMAND_STRUCT: StructureId = StructureId(11);
 
 fn get_lang_struct_id(typing: &mut Typing, ty: TypeRef) -> StructureId {
     let Type::Structure(_, structure_id) = typing.get_type(ty.type_id).unwrap() else {
@@ -243,6 +249,53 @@ fn fill_lang_typed_engine(engine: &mut TypedEngine, typing: &mut Typing) {
         "to_string",
         MethodType::function(vec![], vec![], STRING),
     );
+    engine.add_method(
+        VEC_STRUCT,
+        name_operator_method(BinaryOperator::In),
+        MethodType::function(vec![], vec![generic_param1], BOOL),
+    );
+
+    engine.add_method(
+        STRING_STRUCT,
+        "trim",
+        MethodType::function(vec![], vec![], STRING),
+    );
+    engine.add_method(
+        STRING_STRUCT,
+        "replace",
+        MethodType::function(vec![], vec![STRING, STRING], STRING),
+    );
+    engine.add_method(
+        STRING_STRUCT,
+        "contains",
+        MethodType::function(vec![], vec![STRING], BOOL),
+    );
+
+    for op in EQUALITY_OPERATORS {
+        engine.add_method(
+            EXITCODE_STRUCT,
+            name_operator_method(*op),
+            MethodType::function(vec![], vec![INT], BOOL),
+        );
+    }
+    engine.add_method(
+        STRING_STRUCT,
+        "to_string",
+        MethodType::function(vec![], vec![], STRING),
+    );
+
+    let command_struct = engine.init_empty_structure();
+    debug_assert_eq!(command_struct, COMMAND_STRUCT);
+    engine.add_method(
+        COMMAND_STRUCT,
+        "echo",
+        MethodType::variadic(vec![], vec![], STRING_VEC, EXITCODE),
+    );
+    engine.add_method(
+        COMMAND_STRUCT,
+        "printf",
+        MethodType::variadic(vec![], vec![STRING], STRING_VEC, EXITCODE),
+    );
 }
 
 fn fill_lang_types(typing: &mut Typing, engine: &mut TypedEngine) {
@@ -250,6 +303,7 @@ fn fill_lang_types(typing: &mut Typing, engine: &mut TypedEngine) {
     typing.add_type(Type::Nothing, Some("Nothing".to_string()));
     for primitive_name in [
         "Unit", "Bool", "Exitcode", "Int", "Float", "String", "Vec", "Option", "Glob", "Pid",
+        "Duration",
     ] {
         let structure_id = engine.init_empty_structure();
         typing.add_type(
@@ -277,6 +331,7 @@ fn fill_lang_bindings(ctx: &mut TypeContext) {
     ctx.bind_name("Option".to_string(), GENERIC_OPTION.type_id);
     ctx.bind_name("Glob".to_string(), GLOB.type_id);
     ctx.bind_name("Pid".to_string(), PID.type_id);
+    ctx.bind_name("Duration".to_string(), DURATION.type_id);
 
     let locals = [
         ERROR,
@@ -291,6 +346,7 @@ fn fill_lang_bindings(ctx: &mut TypeContext) {
         GENERIC_OPTION,
         GLOB,
         PID,
+        DURATION,
     ];
 
     ctx.init_locals(SourceId(0), locals.len());
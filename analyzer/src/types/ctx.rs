@@ -40,6 +40,16 @@ impl TypeContext {
             .and_then(Option::clone)
     }
 
+    /// Tests if an environment's local has been typed.
+    ///
+    /// Contrary to [`TypeContext::get_local`], the environment may not be initialized yet.
+    pub(crate) fn is_local_typed(&self, source: SourceId, id: LocalId) -> bool {
+        self.locals
+            .get(&source)
+            .and_then(|locals| locals.get(id.0))
+            .is_some_and(Option::is_some)
+    }
+
     /// init a source locals area of the given len
     pub(crate) fn init_locals(&mut self, source: SourceId, len: usize) {
         match self.locals.entry(source) {
@@ -4,7 +4,7 @@ use crate::engine::Engine;
 use crate::environment::Environment;
 use crate::relations::{ObjectId, SourceId};
 use crate::types::hir::TypedExpr;
-use crate::types::ty::{Field, FunctionDesc, MethodType, StructureDesc, TypeId, TypeRef};
+use crate::types::ty::{Field, FunctionDesc, MethodType, StructureDesc, Type, TypeId, TypeRef};
 
 /// A typed [`Engine`].
 ///
@@ -98,21 +98,32 @@ impl TypedEngine {
         self.structures.get(structure_id.0)?.methods.get(name)
     }
 
+    /// Lists the names of all the methods of a given type.
+    pub fn method_names(&self, structure_id: StructureId) -> impl Iterator<Item = &str> {
+        self.structures
+            .get(structure_id.0)
+            .into_iter()
+            .flat_map(|structure| structure.methods.keys().map(String::as_str))
+    }
+
     /// Gets the method that matches exactly the given arguments and return type.
-    pub fn get_method_exact(
+    ///
+    /// A variadic method matches any number of trailing arguments that have the type of its
+    /// variadic vector elements, which is looked up with the given function.
+    pub fn get_method_exact<'t>(
         &self,
         structure_id: StructureId,
         name: &str,
         args: &[TypeRef],
         return_type: TypeRef,
+        get_type: &dyn Fn(TypeRef) -> Option<&'t Type>,
     ) -> Option<(&MethodType, FunctionId)> {
         self.get_methods(structure_id, name).and_then(|methods| {
             methods
                 .iter()
                 .find(|function_id| {
                     let method = &self.functions[function_id.0];
-                    method.return_type == return_type
-                        && method.parameters.iter().map(|p| &p.ty).eq(args)
+                    method.return_type == return_type && accepts_exactly(method, args, get_type)
                 })
                 .map(|function_id| (&self.functions[function_id.0], *function_id))
         })
@@ -193,6 +204,31 @@ impl TypedEngine {
     }
 }
 
+/// Tests if the method parameters accept exactly the given arguments.
+///
+/// A variadic last parameter accepts any number of trailing arguments of its element type.
+fn accepts_exactly<'t>(
+    method: &MethodType,
+    args: &[TypeRef],
+    get_type: &dyn Fn(TypeRef) -> Option<&'t Type>,
+) -> bool {
+    let Some((last, fixed)) = method.parameters.split_last() else {
+        return args.is_empty();
+    };
+    if !last.variadic {
+        return method.parameters.iter().map(|p| &p.ty).eq(args);
+    }
+    if args.len() < fixed.len() {
+        return false;
+    }
+    let (fixed_args, trailing_args) = args.split_at(fixed.len());
+    let Some(Type::Instantiated(_, elements)) = get_type(last.ty) else {
+        return false;
+    };
+    fixed.iter().map(|p| &p.ty).eq(fixed_args)
+        && trailing_args.iter().all(|arg| *arg == elements[0])
+}
+
 /// A chunk of typed code.
 #[derive(Debug)]
 pub struct Chunk {
@@ -305,3 +341,48 @@ impl EncodableContent {
         self.end_exclusive.0 - self.start_inclusive.0 - 1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::reef::Reef;
+    use crate::types::builtin::{lang_reef, COMMAND_STRUCT, STRING_STRUCT};
+    use crate::types::ty::MethodType;
+    use crate::types::{EXITCODE, INT, STRING};
+
+    #[test]
+    fn resolve_variadic_native_method() {
+        let lang = lang_reef();
+        let resolve = |name: &str, args: &[_]| {
+            lang.typed_engine
+                .get_method_exact(COMMAND_STRUCT, name, args, EXITCODE, &|ty| {
+                    lang.typing.get_type(ty.type_id)
+                })
+                .is_some()
+        };
+        assert!(resolve("echo", &[]));
+        assert!(resolve("echo", &[STRING, STRING, STRING]));
+        assert!(resolve("printf", &[STRING]));
+        assert!(resolve("printf", &[STRING, STRING, STRING]));
+        assert!(!resolve("printf", &[]));
+        assert!(!resolve("printf", &[STRING, INT]));
+    }
+
+    #[test]
+    fn resolve_added_method() {
+        let mut lang = lang_reef();
+        let lookup = |lang: &Reef| {
+            lang.typed_engine
+                .get_method_exact(STRING_STRUCT, "shout", &[], STRING, &|ty| {
+                    lang.typing.get_type(ty.type_id)
+                })
+                .map(|(_, id)| id)
+        };
+        assert_eq!(lookup(&lang), None);
+        let method_id = lang.typed_engine.add_method(
+            STRING_STRUCT,
+            "shout",
+            MethodType::function(vec![], vec![], STRING),
+        );
+        assert_eq!(lookup(&lang), Some(method_id));
+    }
+}
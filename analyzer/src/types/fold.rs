@@ -0,0 +1,79 @@
+//! Constant folding of the native numeric operators.
+//!
+//! Integer operations whose result does not fit in an `Int` are reported as overflows instead of
+//! being folded. Divisions and modulos by zero are never folded, so that the error is still raised
+//! when the expression gets evaluated.
+
+use ast::value::LiteralValue;
+
+use crate::types::hir::{ExprKind, MethodCall, TypedExpr};
+use crate::types::{FLOAT, INT};
+
+/// The outcome of folding a native numeric operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Folded {
+    /// The operation evaluates to a constant.
+    Constant(LiteralValue),
+
+    /// The integer result of the operation does not fit in an `Int`.
+    Overflow,
+
+    /// The operation cannot be folded safely.
+    Unknown,
+}
+
+/// Evaluates a numeric expression whose operands are all known at compile time.
+///
+/// Returns `None` if the expression is not a constant, or if it cannot be folded.
+pub fn fold_constant(expr: &TypedExpr) -> Option<LiteralValue> {
+    if let ExprKind::Literal(literal @ (LiteralValue::Int(_) | LiteralValue::Float(_))) = &expr.kind
+    {
+        return Some(literal.clone());
+    }
+    let method = as_numeric_operation(expr)?;
+    let callee = fold_constant(&method.callee)?;
+    let arguments = method
+        .arguments
+        .iter()
+        .map(fold_constant)
+        .collect::<Option<Vec<_>>>()?;
+    match fold_operation(method, callee, &arguments) {
+        Folded::Constant(value) => Some(value),
+        Folded::Overflow | Folded::Unknown => None,
+    }
+}
+
+/// Returns the method call of an expression that may be a native numeric operation.
+///
+/// Only the lang numeric types are considered, so that user-defined operators are kept.
+pub fn as_numeric_operation(expr: &TypedExpr) -> Option<&MethodCall> {
+    match &expr.kind {
+        ExprKind::MethodCall(method) if expr.ty == INT || expr.ty == FLOAT => Some(method),
+        _ => None,
+    }
+}
+
+/// Applies a native numeric operation to constant operands.
+pub fn fold_operation(
+    method: &MethodCall,
+    callee: LiteralValue,
+    arguments: &[LiteralValue],
+) -> Folded {
+    use LiteralValue::{Float, Int};
+    let folded = match (method.function_id.0, callee, arguments) {
+        (44, Int(a), []) => a.checked_neg().map(Int),
+        (45, Float(a), []) => Some(Float(-a)),
+        (1, Int(a), [Int(b)]) => a.checked_add(*b).map(Int),
+        (2, Float(a), [Float(b)]) => Some(Float(a + b)),
+        (3, Int(a), [Int(b)]) => a.checked_sub(*b).map(Int),
+        (4, Float(a), [Float(b)]) => Some(Float(a - b)),
+        (5, Int(a), [Int(b)]) => a.checked_mul(*b).map(Int),
+        (6, Float(a), [Float(b)]) => Some(Float(a * b)),
+        (7 | 9, Int(_), [Int(0)]) => return Folded::Unknown,
+        (7, Int(a), [Int(b)]) => a.checked_div(*b).map(Int),
+        (8, Float(a), [Float(b)]) if *b != 0.0 => Some(Float(a / b)),
+        (9, Int(a), [Int(b)]) => a.checked_rem(*b).map(Int),
+        _ => return Folded::Unknown,
+    };
+    folded.map_or(Folded::Overflow, Folded::Constant)
+}
@@ -1,5 +1,5 @@
 use crate::reef::ReefId;
-use ast::call::{RedirFd, RedirOp};
+use ast::call::{PipeKind, RedirFd, RedirOp};
 use ast::value::LiteralValue;
 use context::source::{SourceSegment, SourceSegmentHolder};
 
@@ -122,6 +122,10 @@ pub struct FunctionCall {
     pub reef: ReefId,
     pub function_id: FunctionId,
     pub source_id: Option<SourceId>,
+
+    /// The indexes of the arguments in the order they are written, if named arguments
+    /// made it differ from the order of the parameters.
+    pub evaluation_order: Option<Vec<usize>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -156,6 +160,37 @@ pub struct Redir {
     pub operand: Box<TypedExpr>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pipeline {
+    pub commands: Vec<TypedExpr>,
+    /// The pipes between each command, in the same order as in the source.
+    pub pipes: Vec<PipeKind>,
+}
+
+/// A match expression, whose arms are tested in order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub operand: Box<TypedExpr>,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    /// The local that binds the matched value (`x@ ..`), if any.
+    pub val_name: Option<LocalId>,
+    pub patterns: Vec<MatchPattern>,
+    pub guard: Option<TypedExpr>,
+    pub body: TypedExpr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchPattern {
+    /// Matches any value.
+    Wildcard,
+    /// Matches a value equal to the given expression.
+    Value(TypedExpr),
+}
+
 /// An expression content.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExprKind {
@@ -168,6 +203,7 @@ pub enum ExprKind {
     Block(Vec<TypedExpr>),
     Redirect(Redirect),
     Conditional(Conditional),
+    Match(Match),
     ConditionalLoop(Loop),
     ForLoop(ForLoop),
     Convert(Convert),
@@ -175,13 +211,19 @@ pub enum ExprKind {
     FunctionCall(FunctionCall),
     MethodCall(MethodCall),
     Return(Option<Box<TypedExpr>>),
-    Pipeline(Vec<TypedExpr>),
+    Pipeline(Pipeline),
     Capture(Vec<TypedExpr>),
     Substitute(Substitute),
     Subprocess(Subprocess),
+    /// A vector built from the given elements, in order.
+    Vector(Vec<TypedExpr>),
+    /// A tuple built from the given elements, in order.
+    Tuple(Vec<TypedExpr>),
 
-    Continue,
-    Break,
+    /// Jumps to the next iteration of a loop, given by the number of inner loops to leave first.
+    Continue(usize),
+    /// Exits a loop, given by the number of inner loops to leave first.
+    Break(usize),
     Noop,
 }
 
@@ -200,4 +242,97 @@ impl TypedExpr {
         self.ty = ERROR;
         self
     }
+
+    /// Visits this expression and all its sub-expressions, in pre-order.
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a TypedExpr)) {
+        visit(self);
+        self.for_each_child(&mut |child| child.walk(visit));
+    }
+
+    /// Visits the direct sub-expressions of this expression.
+    pub fn for_each_child<'a>(&'a self, visit: &mut impl FnMut(&'a TypedExpr)) {
+        match &self.kind {
+            ExprKind::LocalAssign(assign) => visit(&assign.rhs),
+            ExprKind::FieldAssign(assign) => {
+                visit(&assign.object);
+                visit(&assign.new_value);
+            }
+            ExprKind::Declare(declaration) => {
+                if let Some(value) = &declaration.value {
+                    visit(value);
+                }
+            }
+            ExprKind::FieldAccess(access) => visit(&access.object),
+            ExprKind::Block(exprs)
+            | ExprKind::ProcessCall(exprs)
+            | ExprKind::Capture(exprs)
+            | ExprKind::Vector(exprs)
+            | ExprKind::Tuple(exprs)
+            | ExprKind::Substitute(Substitute::In(exprs) | Substitute::Out(exprs)) => {
+                exprs.iter().for_each(visit)
+            }
+            ExprKind::Pipeline(pipeline) => pipeline.commands.iter().for_each(visit),
+            ExprKind::Redirect(redirect) => {
+                visit(&redirect.expression);
+                for redir in &redirect.redirections {
+                    visit(&redir.operand);
+                }
+            }
+            ExprKind::Match(match_expr) => {
+                visit(&match_expr.operand);
+                for arm in &match_expr.arms {
+                    for pattern in &arm.patterns {
+                        if let MatchPattern::Value(value) = pattern {
+                            visit(value);
+                        }
+                    }
+                    if let Some(guard) = &arm.guard {
+                        visit(guard);
+                    }
+                    visit(&arm.body);
+                }
+            }
+            ExprKind::Conditional(conditional) => {
+                visit(&conditional.condition);
+                visit(&conditional.then);
+                if let Some(otherwise) = &conditional.otherwise {
+                    visit(otherwise);
+                }
+            }
+            ExprKind::ConditionalLoop(lp) => {
+                if let Some(condition) = &lp.condition {
+                    visit(condition);
+                }
+                visit(&lp.body);
+            }
+            ExprKind::ForLoop(lp) => {
+                match lp.kind.as_ref() {
+                    ForKind::Range(range) => visit(&range.iterable),
+                    ForKind::Conditional(conditional) => {
+                        visit(&conditional.initializer);
+                        visit(&conditional.condition);
+                        visit(&conditional.increment);
+                    }
+                }
+                visit(&lp.body);
+            }
+            ExprKind::Convert(convert) => visit(&convert.inner),
+            ExprKind::FunctionCall(call) => call.arguments.iter().for_each(visit),
+            ExprKind::MethodCall(call) => {
+                visit(&call.callee);
+                call.arguments.iter().for_each(visit);
+            }
+            ExprKind::Return(value) => {
+                if let Some(value) = value {
+                    visit(value);
+                }
+            }
+            ExprKind::Subprocess(subprocess) => visit(&subprocess.inner),
+            ExprKind::Literal(_)
+            | ExprKind::Reference(_)
+            | ExprKind::Continue(_)
+            | ExprKind::Break(_)
+            | ExprKind::Noop => {}
+        }
+    }
 }
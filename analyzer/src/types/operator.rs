@@ -17,5 +17,6 @@ pub fn name_operator_method(bin_op: BinaryOperator) -> &'static str {
         LessEqual => "le",
         Greater => "gt",
         GreaterEqual => "ge",
+        In => "contains",
     }
 }
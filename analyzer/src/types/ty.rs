@@ -4,7 +4,7 @@ use crate::diagnostic::SourceLocation;
 use crate::reef::ReefId;
 use crate::relations::{LocalId, ObjectId, SourceId};
 use crate::types::engine::{FunctionId, StructureId};
-use crate::types::{BOOL, ERROR, EXITCODE, FLOAT, INT, NOTHING, UNIT};
+use crate::types::{BOOL, DURATION, ERROR, EXITCODE, FLOAT, INT, NOTHING, UNIT};
 
 /// A type identifier in a [`Typing`] instance.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -38,7 +38,10 @@ impl TypeRef {
     }
 
     pub fn is_obj(self) -> bool {
-        !matches!(self, NOTHING | UNIT | BOOL | EXITCODE | INT | FLOAT | ERROR)
+        !matches!(
+            self,
+            NOTHING | UNIT | BOOL | EXITCODE | INT | FLOAT | DURATION | ERROR
+        )
     }
 }
 
@@ -129,6 +132,20 @@ pub struct FunctionDesc {
 pub enum FunctionKind {
     Function,
     Constructor,
+
+    /// A method declared in an `impl` block.
+    ///
+    /// Its receiver is passed as an implicit argument, before the declared parameters.
+    Method {
+        /// The chunk that defines the method.
+        source: SourceId,
+
+        /// The local that holds the receiver in the method's chunk.
+        receiver: LocalId,
+
+        /// The type of the receiver.
+        receiver_ty: TypeRef,
+    },
 }
 
 impl FunctionDesc {
@@ -169,6 +186,26 @@ impl FunctionDesc {
         )
     }
 
+    /// Creates a new function whose last parameter collects all the trailing arguments.
+    ///
+    /// The variadic parameter type is the vector of the accepted arguments.
+    pub fn variadic(
+        type_parameters: Vec<TypeId>,
+        parameters: Vec<TypeRef>,
+        variadic: TypeRef,
+        return_type: TypeRef,
+    ) -> Self {
+        let mut function = Self::function(type_parameters, parameters, return_type);
+        function.parameters.push(Parameter {
+            location: None,
+            name: None,
+            ty: variadic,
+            local_id: LocalId(function.parameters.len()),
+            variadic: true,
+        });
+        function
+    }
+
     fn new(
         type_parameters: Vec<TypeId>,
         parameters: Vec<TypeRef>,
@@ -182,8 +219,10 @@ impl FunctionDesc {
                 .enumerate()
                 .map(|(param_offset, ty)| Parameter {
                     location: None,
+                    name: None,
                     ty,
                     local_id: LocalId(param_offset),
+                    variadic: false,
                 })
                 .collect(),
             return_type,
@@ -196,8 +235,14 @@ impl FunctionDesc {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Parameter {
     pub(crate) location: Option<SourceLocation>,
+
+    /// The name that named arguments can refer to, if any.
+    pub(crate) name: Option<String>,
     pub ty: TypeRef,
     pub local_id: LocalId,
+
+    /// If the parameter collects all the trailing arguments in a vector.
+    pub variadic: bool,
 }
 
 /// A method is a function that only exists on a given type.
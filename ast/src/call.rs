@@ -26,6 +26,17 @@ impl SourceSegmentHolder for Call {
     }
 }
 
+/// An argument that is matched to a parameter by its name, such as `x = 1`.
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedArgument {
+    /// The name of the targeted parameter.
+    pub name: Identifier,
+
+    /// The value of the argument.
+    pub value: Box<Expr>,
+}
+
 /// A programmatic call.
 ///
 /// Theses always have a constant name and are always called with parentheses.
@@ -114,13 +125,26 @@ pub enum RedirFd {
     Fd(u32),
 }
 
-/// Commands separated by `|`
+/// Commands separated by `|` or `|&`
 #[derive(Clone, Debug, PartialEq)]
 pub struct Pipeline {
     /// Elements of the pipeline.
     ///
     /// A valid pipeline must have at least one command.
     pub commands: Vec<Expr>,
+
+    /// The pipes between each command, where the pipe at index `i`
+    /// connects the command `i` to the command `i + 1`.
+    pub pipes: Vec<PipeKind>,
+}
+
+/// The outputs of a command that a pipe connects to the next command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipeKind {
+    /// Only the standard output is piped (`|`)
+    Stdout,
+    /// Both the standard output and the standard error are piped (`|&`)
+    StdoutAndStderr,
 }
 
 impl SourceSegmentHolder for Pipeline {
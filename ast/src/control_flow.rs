@@ -19,6 +19,8 @@ pub struct If {
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct While {
+    ///The label of the loop, that jumps can target
+    pub label: Option<Identifier>,
     ///The while condition expression
     pub condition: Box<Expr>,
     ///The loop's body expression
@@ -29,6 +31,8 @@ pub struct While {
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Loop {
+    ///The label of the loop, that jumps can target
+    pub label: Option<Identifier>,
     ///The loop's body expression
     pub body: Box<Expr>,
 }
@@ -37,13 +41,24 @@ pub struct Loop {
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct For {
+    /// The label of the loop, that jumps can target.
+    pub label: Option<Identifier>,
     /// The type of the for loop.
     pub kind: Box<ForKind>,
     /// The body of the for loop.
     pub body: Box<Expr>,
 }
 
+/// A `continue` or a `break`, that may target an enclosing loop by its label, e.g. `break@outer`.
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopJump {
+    /// The label of the targeted loop, or `None` for the innermost loop.
+    pub label: Option<Identifier>,
+}
+
 /// A for loop can be either a range loop or a conditional loop.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum ForKind {
     Range(RangeFor),
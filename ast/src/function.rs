@@ -11,9 +11,17 @@ pub struct Return {
     pub expr: Option<Box<Expr>>,
 }
 
+/// A marker placed before a declaration, such as `@test`.
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: Identifier,
+}
+
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionDeclaration {
+    pub attributes: Vec<Attribute>,
     pub name: Identifier,
     pub type_parameters: Vec<TypeParameter>,
     pub parameters: Vec<FunctionParameter>,
@@ -21,11 +29,20 @@ pub struct FunctionDeclaration {
     pub body: Option<Box<Expr>>,
 }
 
+impl FunctionDeclaration {
+    /// Tests if the declaration is marked with the given attribute.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.name.value == name)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FunctionParameter {
     Named(TypedVariable),
-    ///argument is the type of the variable (if any).
-    Variadic(Option<Type>, SourceSegment),
+    ///arguments are the name of the variable (if any) and the type of its elements (if any).
+    Variadic(Option<Identifier>, Option<Type>, SourceSegment),
     Slf(SourceSegment),
 }
 
@@ -33,7 +50,7 @@ impl SourceSegmentHolder for FunctionParameter {
     fn segment(&self) -> SourceSegment {
         match self {
             FunctionParameter::Named(n) => n.segment(),
-            FunctionParameter::Variadic(_, s) => s.clone(),
+            FunctionParameter::Variadic(_, _, s) => s.clone(),
             FunctionParameter::Slf(s) => s.clone(),
         }
     }
@@ -18,6 +18,14 @@ pub struct Parenthesis {
     pub expression: Box<Expr>,
 }
 
+/// A tuple expression `( ..., ... )`, that contains several comma-separated values
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleLiteral {
+    //underlying values
+    pub elements: Vec<Expr>,
+}
+
 /// A subshell expression `( ... )`
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
@@ -2,21 +2,25 @@
 
 use context::source::{SourceSegment, SourceSegmentHolder};
 
-use crate::call::{Call, Detached, MethodCall, Pipeline, ProgrammaticCall, Redirected};
-use crate::control_flow::{For, If, Loop, While};
+use crate::call::{
+    Call, Detached, MethodCall, NamedArgument, Pipeline, ProgrammaticCall, Redirected,
+};
+use crate::control_flow::{For, If, Loop, LoopJump, While};
 use crate::function::{FunctionDeclaration, Return};
-use crate::group::{Block, Parenthesis, Subshell};
+use crate::group::{Block, Parenthesis, Subshell, TupleLiteral};
 use crate::lambda::LambdaDef;
 use crate::operation::{BinaryOperation, UnaryOperation};
 use crate::r#match::Match;
 use crate::r#struct::{FieldAccess, StructDeclaration, StructImpl};
-use crate::r#type::CastedExpr;
+use crate::r#type::{CastedExpr, TypeAlias};
 use crate::r#use::Use;
 use crate::range::{Iterable, Subscript};
 use crate::substitution::Substitution;
 use crate::test::Test;
 use crate::value::{Literal, TemplateString};
-use crate::variable::{Assign, Path, TildeExpansion, VarDeclaration, VarReference};
+use crate::variable::{
+    Assign, Path, TildeExpansion, TupleDeclaration, VarDeclaration, VarReference,
+};
 
 pub mod call;
 pub mod control_flow;
@@ -33,6 +37,7 @@ pub mod r#type;
 pub mod r#use;
 pub mod value;
 pub mod variable;
+pub mod visit;
 
 /// A expression that can be evaluated.
 #[derive(Debug, Clone, PartialEq)]
@@ -46,6 +51,7 @@ pub enum Expr {
 
     Call(Call),
     ProgrammaticCall(ProgrammaticCall),
+    NamedArgument(NamedArgument),
     MethodCall(MethodCall),
     Pipeline(Pipeline),
     Redirected(Redirected),
@@ -64,20 +70,22 @@ pub enum Expr {
 
     StructDeclaration(StructDeclaration),
     Impl(StructImpl),
+    TypeAlias(TypeAlias),
 
     If(If),
     While(While),
     Loop(Loop),
     For(For),
 
-    Continue(SourceSegment),
-    Break(SourceSegment),
+    Continue(LoopJump),
+    Break(LoopJump),
     Return(Return),
 
     // Identifiables
     Path(Path),
     VarReference(VarReference),
     VarDeclaration(VarDeclaration),
+    TupleDeclaration(TupleDeclaration),
     Range(Iterable),
     Subscript(Subscript),
     FieldAccess(FieldAccess),
@@ -88,6 +96,8 @@ pub enum Expr {
     //Grouping expressions
     /// a parenthesis expression `( ... )` that contains one value expression
     Parenthesis(Parenthesis),
+    /// a tuple expression `( ..., ... )` that contains several comma-separated values
+    TupleLiteral(TupleLiteral),
     /// a subshell expression `( ... )` that contains several expressions
     Subshell(Subshell),
     /// a block expression `{ ... }` that contains several expressions
@@ -100,6 +110,7 @@ impl SourceSegmentHolder for Expr {
             Expr::FieldAccess(fa) => fa.segment(),
             Expr::StructDeclaration(d) => d.segment(),
             Expr::Impl(i) => i.segment(),
+            Expr::TypeAlias(alias) => alias.segment(),
             Expr::Assign(assign) => assign.segment(),
             Expr::Unary(unary) => unary.segment(),
             Expr::Binary(binary) => binary.segment(),
@@ -107,6 +118,7 @@ impl SourceSegmentHolder for Expr {
             Expr::Match(m) => m.segment.clone(),
             Expr::Call(call) => call.segment(),
             Expr::ProgrammaticCall(call) => call.segment.clone(),
+            Expr::NamedArgument(arg) => arg.segment(),
             Expr::MethodCall(method_call) => method_call.segment.clone(),
             Expr::Pipeline(pipeline) => pipeline.segment(),
             Expr::Redirected(redirected) => redirected.segment(),
@@ -121,17 +133,19 @@ impl SourceSegmentHolder for Expr {
             Expr::While(while_) => while_.segment.clone(),
             Expr::Loop(loop_) => loop_.segment.clone(),
             Expr::For(for_) => for_.segment.clone(),
-            Expr::Continue(source) => source.clone(),
-            Expr::Break(source) => source.clone(),
+            Expr::Continue(jump) => jump.segment.clone(),
+            Expr::Break(jump) => jump.segment.clone(),
             Expr::Return(return_) => return_.segment.clone(),
             Expr::Path(identifier) => identifier.segment(),
             Expr::VarReference(var_reference) => var_reference.segment(),
             Expr::VarDeclaration(var_declaration) => var_declaration.segment.clone(),
+            Expr::TupleDeclaration(declaration) => declaration.segment.clone(),
             Expr::Range(range) => range.segment(),
             Expr::Subscript(subscript) => subscript.segment(),
             Expr::Tilde(tilde) => tilde.segment(),
             Expr::FunctionDeclaration(function_declaration) => function_declaration.segment.clone(),
             Expr::Parenthesis(parenthesis) => parenthesis.segment.clone(),
+            Expr::TupleLiteral(tuple) => tuple.segment.clone(),
             Expr::Subshell(subshell) => subshell.segment.clone(),
             Expr::Block(block) => block.segment.clone(),
         }
@@ -72,6 +72,9 @@ pub enum BinaryOperator {
     Divide,
     /// The `%` operator.
     Modulo,
+
+    /// The `in` operator.
+    In,
 }
 
 impl TryFrom<TokenType> for BinaryOperator {
@@ -96,6 +99,8 @@ impl TryFrom<TokenType> for BinaryOperator {
             TokenType::Star => Ok(Times),
             TokenType::Slash => Ok(Divide),
             TokenType::Percent => Ok(Modulo),
+
+            TokenType::In => Ok(In),
             _ => Err("unexpected non-binary operator token."),
         }
     }
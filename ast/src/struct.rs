@@ -1,13 +1,14 @@
 use crate::Expr;
 use src_macros::segment_holder;
 
-use crate::function::FunctionDeclaration;
+use crate::function::{Attribute, FunctionDeclaration};
 use crate::r#type::{Type, TypeParameter};
 use crate::variable::Identifier;
 
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructDeclaration {
+    pub attributes: Vec<Attribute>,
     pub name: Identifier,
     pub parameters: Vec<TypeParameter>,
     pub fields: Vec<FieldDeclaration>,
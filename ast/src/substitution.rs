@@ -28,6 +28,9 @@ pub enum SubstitutionKind {
     /// A command standard output substitution with `$(...)`.
     Capture,
 
+    /// A command exit code substitution with `$?(...)`.
+    Status,
+
     /// A process substitution with `<(...)` or `>(...)`.
     Process { direction: Direction },
 }
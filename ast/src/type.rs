@@ -19,6 +19,9 @@ pub enum Type {
 
     ///A By name declaration (`=> X`)
     ByName(ByName),
+
+    ///A tuple of types (`(A, B, ...)`)
+    Tuple(TupleType),
 }
 
 /// A casted expression
@@ -32,6 +35,20 @@ pub struct CastedExpr {
     pub casted_type: Type,
 }
 
+/// A type alias declaration (`type Name = Type`)
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    ///the alias name
+    pub name: Identifier,
+
+    ///the alias' type parameters
+    pub parameters: Vec<TypeParameter>,
+
+    ///the aliased type
+    pub aliased: Type,
+}
+
 #[segment_holder]
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypeParameter {
@@ -62,12 +79,19 @@ pub struct CallableType {
     pub output: Box<Type>,
 }
 
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleType {
+    pub elements: Vec<Type>,
+}
+
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Type::Parametrized(m) => Display::fmt(m, f),
             Type::Callable(p) => Display::fmt(p, f),
             Type::ByName(n) => Display::fmt(n, f),
+            Type::Tuple(t) => Display::fmt(t, f),
         }
     }
 }
@@ -78,6 +102,7 @@ impl SourceSegmentHolder for Type {
             Type::Parametrized(m) => m.segment(),
             Type::Callable(p) => p.segment(),
             Type::ByName(n) => n.segment(),
+            Type::Tuple(t) => t.segment(),
         }
     }
 }
@@ -95,6 +120,12 @@ impl Display for CallableType {
     }
 }
 
+impl Display for TupleType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_comma_separated('(', ')', &self.elements, f)
+    }
+}
+
 impl Display for ParametrizedType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if let Some((first, tail)) = self.path.split_first() {
@@ -48,8 +48,9 @@ impl Display for InclusionPathItem {
 pub enum Import {
     ///A symbol (or list of symbols)
     Symbol(ImportedSymbol),
-    /// all in given module (the vec being the inclusion path where the last element is the module that is being imported)
-    AllIn(Vec<InclusionPathItem>, SourceSegment),
+    /// all in given module (the vec being the inclusion path where the last element is the module that is being imported),
+    /// except the excluded symbols
+    AllIn(Vec<InclusionPathItem>, Vec<Identifier>, SourceSegment),
     ///An environment variable, command.
     Environment(Identifier),
     ///An import list
@@ -60,7 +61,7 @@ impl SourceSegmentHolder for Import {
     fn segment(&self) -> SourceSegment {
         match self {
             Import::Symbol(s) => s.segment.clone(),
-            Import::AllIn(_, s) => s.clone(),
+            Import::AllIn(_, _, s) => s.clone(),
             Import::Environment(ident) => ident.segment(),
             Import::List(l) => l.segment.clone(),
         }
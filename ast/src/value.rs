@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use src_macros::segment_holder;
 
 use crate::Expr;
@@ -16,6 +18,7 @@ pub enum LiteralValue {
     Int(i64),
     Float(f64),
     Bool(bool),
+    Duration(Duration),
 }
 
 /// A group of expressions that can be interpolated into a string.
@@ -48,3 +51,9 @@ impl From<bool> for LiteralValue {
         Self::Bool(s)
     }
 }
+
+impl From<Duration> for LiteralValue {
+    fn from(s: Duration) -> Self {
+        Self::Duration(s)
+    }
+}
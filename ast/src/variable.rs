@@ -21,6 +21,26 @@ pub struct VarDeclaration {
     pub initializer: Option<Box<Expr>>,
 }
 
+impl VarDeclaration {
+    /// Tests if the declaration uses the `_` pattern, which evaluates the initializer without
+    /// binding its value.
+    pub fn is_ignored(&self) -> bool {
+        self.var.name.value == "_"
+    }
+}
+
+/// A declaration that binds each element of a tuple to a variable (`val (a, b) = ...`).
+#[segment_holder]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleDeclaration {
+    /// The kind of the variables.
+    pub kind: VarKind,
+    /// The names bound to the elements of the tuple, in order.
+    pub names: Vec<Identifier>,
+    /// The tuple to destructure.
+    pub initializer: Box<Expr>,
+}
+
 /// A named variable declaration.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypedVariable {
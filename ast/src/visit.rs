@@ -0,0 +1,450 @@
+//! Generic traversals over the expression tree.
+//!
+//! A [`Visitor`] inspects nodes by reference, while a [`Fold`] consumes and rebuilds them.
+//! Both come with default methods that walk into every child expression, so an
+//! implementation only overrides the nodes it is interested in. Source segments are
+//! carried over untouched when a node is rebuilt.
+
+use crate::call::{
+    Call, Detached, MethodCall, NamedArgument, Pipeline, ProgrammaticCall, Redir, Redirected,
+};
+use crate::control_flow::{ConditionalFor, For, ForKind, If, Loop, RangeFor, While};
+use crate::function::{FunctionDeclaration, Return};
+use crate::group::{Block, Parenthesis, Subshell, TupleLiteral};
+use crate::lambda::LambdaDef;
+use crate::operation::{BinaryOperation, UnaryOperation};
+use crate::r#match::{Match, MatchArm, MatchPattern};
+use crate::r#struct::{FieldAccess, StructImpl};
+use crate::r#type::CastedExpr;
+use crate::range::{FilePattern, Iterable, NumericRange, Subscript};
+use crate::substitution::Substitution;
+use crate::test::Test;
+use crate::value::TemplateString;
+use crate::variable::{
+    Assign, Tilde, TildeExpansion, TupleDeclaration, VarDeclaration, VarReference,
+};
+use crate::Expr;
+
+/// An immutable traversal of the expression tree.
+pub trait Visitor {
+    /// Visits an expression, walking into its children by default.
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+
+    /// Visits a variable reference, either an expression or a match pattern.
+    fn visit_var_reference(&mut self, _var: &VarReference) {}
+
+    /// Visits a function declaration, either an expression or an `impl` member.
+    fn visit_function_declaration(&mut self, function: &FunctionDeclaration) {
+        walk_function_declaration(self, function)
+    }
+
+    /// Visits a match arm, walking into its patterns, guard and body by default.
+    fn visit_match_arm(&mut self, arm: &MatchArm) {
+        walk_match_arm(self, arm)
+    }
+}
+
+/// Visits all the direct children of an expression.
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Assign(Assign { left, value, .. }) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(value);
+        }
+        Expr::Unary(UnaryOperation { expr, .. }) => visitor.visit_expr(expr),
+        Expr::Binary(BinaryOperation { left, right, .. }) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::Match(Match { operand, arms, .. }) => {
+            visitor.visit_expr(operand);
+            for arm in arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        Expr::Call(Call { arguments })
+        | Expr::ProgrammaticCall(ProgrammaticCall { arguments, .. }) => {
+            walk_all(visitor, arguments)
+        }
+        Expr::NamedArgument(NamedArgument { value, .. }) => visitor.visit_expr(value),
+        Expr::MethodCall(MethodCall {
+            source, arguments, ..
+        }) => {
+            visitor.visit_expr(source);
+            walk_all(visitor, arguments);
+        }
+        Expr::Pipeline(Pipeline { commands, .. }) => walk_all(visitor, commands),
+        Expr::Redirected(Redirected { expr, redirections }) => {
+            visitor.visit_expr(expr);
+            for redir in redirections {
+                visitor.visit_expr(&redir.operand);
+            }
+        }
+        Expr::Detached(Detached { underlying, .. }) => visitor.visit_expr(underlying),
+        Expr::LambdaDef(LambdaDef { body, .. }) => visitor.visit_expr(body),
+        Expr::Substitution(Substitution { underlying, .. }) => {
+            walk_all(visitor, &underlying.expressions)
+        }
+        Expr::TemplateString(TemplateString { parts, .. }) => walk_all(visitor, parts),
+        Expr::Casted(CastedExpr { expr, .. }) => visitor.visit_expr(expr),
+        Expr::Test(Test { expression, .. }) => visitor.visit_expr(expression),
+        Expr::Impl(StructImpl { functions, .. }) => {
+            for function in functions {
+                visitor.visit_function_declaration(function);
+            }
+        }
+        Expr::If(If {
+            condition,
+            success_branch,
+            fail_branch,
+            ..
+        }) => {
+            visitor.visit_expr(condition);
+            visitor.visit_expr(success_branch);
+            if let Some(fail_branch) = fail_branch {
+                visitor.visit_expr(fail_branch);
+            }
+        }
+        Expr::While(While {
+            condition, body, ..
+        }) => {
+            visitor.visit_expr(condition);
+            visitor.visit_expr(body);
+        }
+        Expr::Loop(Loop { body, .. }) => visitor.visit_expr(body),
+        Expr::For(For { kind, body, .. }) => {
+            match kind.as_ref() {
+                ForKind::Range(RangeFor { iterable, .. }) => visitor.visit_expr(iterable),
+                ForKind::Conditional(ConditionalFor {
+                    initializer,
+                    condition,
+                    increment,
+                    ..
+                }) => {
+                    visitor.visit_expr(initializer);
+                    visitor.visit_expr(condition);
+                    visitor.visit_expr(increment);
+                }
+            }
+            visitor.visit_expr(body);
+        }
+        Expr::Return(Return { expr, .. })
+        | Expr::VarDeclaration(VarDeclaration {
+            initializer: expr, ..
+        }) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        Expr::TupleDeclaration(TupleDeclaration { initializer, .. }) => {
+            visitor.visit_expr(initializer)
+        }
+        Expr::VarReference(var) => visitor.visit_var_reference(var),
+        Expr::Range(Iterable::Range(NumericRange {
+            start, end, step, ..
+        })) => {
+            visitor.visit_expr(start);
+            visitor.visit_expr(end);
+            if let Some(step) = step {
+                visitor.visit_expr(step);
+            }
+        }
+        Expr::Range(Iterable::Files(FilePattern { pattern, .. })) => visitor.visit_expr(pattern),
+        Expr::Subscript(Subscript { target, index, .. }) => {
+            visitor.visit_expr(target);
+            visitor.visit_expr(index);
+        }
+        Expr::FieldAccess(FieldAccess { expr, .. }) => visitor.visit_expr(expr),
+        Expr::Tilde(TildeExpansion { structure, .. }) => {
+            if let Tilde::HomeDir(Some(user)) = structure {
+                visitor.visit_expr(user);
+            }
+        }
+        Expr::FunctionDeclaration(function) => visitor.visit_function_declaration(function),
+        Expr::Parenthesis(Parenthesis { expression, .. }) => visitor.visit_expr(expression),
+        Expr::TupleLiteral(TupleLiteral { elements, .. }) => walk_all(visitor, elements),
+        Expr::Subshell(Subshell { expressions, .. }) | Expr::Block(Block { expressions, .. }) => {
+            walk_all(visitor, expressions)
+        }
+        Expr::Literal(_)
+        | Expr::Use(_)
+        | Expr::StructDeclaration(_)
+        | Expr::TypeAlias(_)
+        | Expr::Continue(_)
+        | Expr::Break(_)
+        | Expr::Path(_) => {}
+    }
+}
+
+/// Visits the body of a function declaration, if any.
+pub fn walk_function_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    function: &FunctionDeclaration,
+) {
+    if let Some(body) = &function.body {
+        visitor.visit_expr(body);
+    }
+}
+
+/// Visits the patterns, the guard and the body of a match arm.
+pub fn walk_match_arm<V: Visitor + ?Sized>(visitor: &mut V, arm: &MatchArm) {
+    for pattern in &arm.patterns {
+        match pattern {
+            MatchPattern::VarRef(var) => visitor.visit_var_reference(var),
+            MatchPattern::Template(template) => walk_all(visitor, &template.parts),
+            MatchPattern::Wildcard(_) | MatchPattern::Literal(_) => {}
+        }
+    }
+    if let Some(guard) = &arm.guard {
+        visitor.visit_expr(guard);
+    }
+    visitor.visit_expr(&arm.body);
+}
+
+fn walk_all<V: Visitor + ?Sized>(visitor: &mut V, exprs: &[Expr]) {
+    for expr in exprs {
+        visitor.visit_expr(expr);
+    }
+}
+
+/// An owning transformation of the expression tree.
+pub trait Fold {
+    /// Folds an expression, rebuilding it from its folded children by default.
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_children(self, expr)
+    }
+
+    /// Folds a function declaration, either an expression or an `impl` member.
+    fn fold_function_declaration(&mut self, function: FunctionDeclaration) -> FunctionDeclaration {
+        FunctionDeclaration {
+            body: function.body.map(|body| fold_box(self, body)),
+            ..function
+        }
+    }
+
+    /// Folds a match arm, rebuilding it from its folded guard and body by default.
+    fn fold_match_arm(&mut self, arm: MatchArm) -> MatchArm {
+        MatchArm {
+            patterns: arm
+                .patterns
+                .into_iter()
+                .map(|pattern| match pattern {
+                    MatchPattern::Template(template) => MatchPattern::Template(TemplateString {
+                        parts: fold_all(self, template.parts),
+                        ..template
+                    }),
+                    pattern => pattern,
+                })
+                .collect(),
+            guard: arm.guard.map(|guard| self.fold_expr(guard)),
+            body: self.fold_expr(arm.body),
+            ..arm
+        }
+    }
+}
+
+/// Rebuilds an expression from its folded direct children.
+pub fn fold_children<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Assign(assign) => Expr::Assign(Assign {
+            left: fold_box(folder, assign.left),
+            value: fold_box(folder, assign.value),
+            ..assign
+        }),
+        Expr::Unary(unary) => Expr::Unary(UnaryOperation {
+            expr: fold_box(folder, unary.expr),
+            ..unary
+        }),
+        Expr::Binary(binary) => Expr::Binary(BinaryOperation {
+            left: fold_box(folder, binary.left),
+            right: fold_box(folder, binary.right),
+            ..binary
+        }),
+        Expr::Match(m) => Expr::Match(Match {
+            operand: fold_box(folder, m.operand),
+            arms: m
+                .arms
+                .into_iter()
+                .map(|arm| folder.fold_match_arm(arm))
+                .collect(),
+            ..m
+        }),
+        Expr::Call(call) => Expr::Call(Call {
+            arguments: fold_all(folder, call.arguments),
+        }),
+        Expr::ProgrammaticCall(call) => Expr::ProgrammaticCall(ProgrammaticCall {
+            arguments: fold_all(folder, call.arguments),
+            ..call
+        }),
+        Expr::NamedArgument(arg) => Expr::NamedArgument(NamedArgument {
+            value: fold_box(folder, arg.value),
+            ..arg
+        }),
+        Expr::MethodCall(call) => Expr::MethodCall(MethodCall {
+            source: fold_box(folder, call.source),
+            arguments: fold_all(folder, call.arguments),
+            ..call
+        }),
+        Expr::Pipeline(pipeline) => Expr::Pipeline(Pipeline {
+            commands: fold_all(folder, pipeline.commands),
+            ..pipeline
+        }),
+        Expr::Redirected(redirected) => Expr::Redirected(Redirected {
+            expr: fold_box(folder, redirected.expr),
+            redirections: redirected
+                .redirections
+                .into_iter()
+                .map(|redir| Redir {
+                    operand: folder.fold_expr(redir.operand),
+                    ..redir
+                })
+                .collect(),
+        }),
+        Expr::Detached(detached) => Expr::Detached(Detached {
+            underlying: fold_box(folder, detached.underlying),
+            ..detached
+        }),
+        Expr::LambdaDef(lambda) => Expr::LambdaDef(LambdaDef {
+            body: fold_box(folder, lambda.body),
+            ..lambda
+        }),
+        Expr::Substitution(substitution) => Expr::Substitution(Substitution {
+            underlying: Subshell {
+                expressions: fold_all(folder, substitution.underlying.expressions),
+                ..substitution.underlying
+            },
+            ..substitution
+        }),
+        Expr::TemplateString(template) => Expr::TemplateString(TemplateString {
+            parts: fold_all(folder, template.parts),
+            ..template
+        }),
+        Expr::Casted(casted) => Expr::Casted(CastedExpr {
+            expr: fold_box(folder, casted.expr),
+            ..casted
+        }),
+        Expr::Test(test) => Expr::Test(Test {
+            expression: fold_box(folder, test.expression),
+            ..test
+        }),
+        Expr::Impl(imp) => Expr::Impl(StructImpl {
+            functions: imp
+                .functions
+                .into_iter()
+                .map(|function| folder.fold_function_declaration(function))
+                .collect(),
+            ..imp
+        }),
+        Expr::If(if_) => Expr::If(If {
+            condition: fold_box(folder, if_.condition),
+            success_branch: fold_box(folder, if_.success_branch),
+            fail_branch: if_.fail_branch.map(|branch| fold_box(folder, branch)),
+            ..if_
+        }),
+        Expr::While(while_) => Expr::While(While {
+            condition: fold_box(folder, while_.condition),
+            body: fold_box(folder, while_.body),
+            ..while_
+        }),
+        Expr::Loop(loop_) => Expr::Loop(Loop {
+            body: fold_box(folder, loop_.body),
+            ..loop_
+        }),
+        Expr::For(for_) => Expr::For(For {
+            kind: Box::new(match *for_.kind {
+                ForKind::Range(range) => ForKind::Range(RangeFor {
+                    iterable: folder.fold_expr(range.iterable),
+                    ..range
+                }),
+                ForKind::Conditional(conditional) => ForKind::Conditional(ConditionalFor {
+                    initializer: folder.fold_expr(conditional.initializer),
+                    condition: folder.fold_expr(conditional.condition),
+                    increment: folder.fold_expr(conditional.increment),
+                    ..conditional
+                }),
+            }),
+            body: fold_box(folder, for_.body),
+            ..for_
+        }),
+        Expr::Return(return_) => Expr::Return(Return {
+            expr: return_.expr.map(|expr| fold_box(folder, expr)),
+            ..return_
+        }),
+        Expr::VarDeclaration(declaration) => Expr::VarDeclaration(VarDeclaration {
+            initializer: declaration
+                .initializer
+                .map(|initializer| fold_box(folder, initializer)),
+            ..declaration
+        }),
+        Expr::TupleDeclaration(declaration) => Expr::TupleDeclaration(TupleDeclaration {
+            initializer: fold_box(folder, declaration.initializer),
+            ..declaration
+        }),
+        Expr::Range(Iterable::Range(range)) => Expr::Range(Iterable::Range(NumericRange {
+            start: fold_box(folder, range.start),
+            end: fold_box(folder, range.end),
+            step: range.step.map(|step| fold_box(folder, step)),
+            ..range
+        })),
+        Expr::Range(Iterable::Files(files)) => Expr::Range(Iterable::Files(FilePattern {
+            pattern: fold_box(folder, files.pattern),
+            ..files
+        })),
+        Expr::Subscript(subscript) => Expr::Subscript(Subscript {
+            target: fold_box(folder, subscript.target),
+            index: fold_box(folder, subscript.index),
+            ..subscript
+        }),
+        Expr::FieldAccess(access) => Expr::FieldAccess(FieldAccess {
+            expr: fold_box(folder, access.expr),
+            ..access
+        }),
+        Expr::Tilde(tilde) => Expr::Tilde(TildeExpansion {
+            structure: match tilde.structure {
+                Tilde::HomeDir(user) => Tilde::HomeDir(user.map(|user| fold_box(folder, user))),
+                Tilde::WorkingDir => Tilde::WorkingDir,
+            },
+            ..tilde
+        }),
+        Expr::FunctionDeclaration(function) => {
+            Expr::FunctionDeclaration(folder.fold_function_declaration(function))
+        }
+        Expr::Parenthesis(parenthesis) => Expr::Parenthesis(Parenthesis {
+            expression: fold_box(folder, parenthesis.expression),
+            ..parenthesis
+        }),
+        Expr::TupleLiteral(tuple) => Expr::TupleLiteral(TupleLiteral {
+            elements: fold_all(folder, tuple.elements),
+            ..tuple
+        }),
+        Expr::Subshell(subshell) => Expr::Subshell(Subshell {
+            expressions: fold_all(folder, subshell.expressions),
+            ..subshell
+        }),
+        Expr::Block(block) => Expr::Block(Block {
+            expressions: fold_all(folder, block.expressions),
+            ..block
+        }),
+        expr @ (Expr::Literal(_)
+        | Expr::Use(_)
+        | Expr::StructDeclaration(_)
+        | Expr::TypeAlias(_)
+        | Expr::Continue(_)
+        | Expr::Break(_)
+        | Expr::Path(_)
+        | Expr::VarReference(_)) => expr,
+    }
+}
+
+fn fold_box<F: Fold + ?Sized>(folder: &mut F, expr: Box<Expr>) -> Box<Expr> {
+    Box::new(folder.fold_expr(*expr))
+}
+
+fn fold_all<F: Fold + ?Sized>(folder: &mut F, exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs
+        .into_iter()
+        .map(|expr| folder.fold_expr(expr))
+        .collect()
+}
@@ -1,36 +1,53 @@
 use std::collections::HashMap;
-use std::io::stderr;
+use std::io::{stderr, stdout};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use clap_complete::Shell;
 
 use analyzer::diagnostic::Diagnostic;
+use analyzer::feature::LATEST_VERSION;
 use analyzer::name::Name;
-use analyzer::reef::Externals;
+use analyzer::reef::{Externals, ReefId};
 use analyzer::relations::SourceId;
+use analyzer::steps::typing::dump::dump_hir;
+use analyzer::steps::typing::explain::explain_types;
+use analyzer::types::engine::ChunkKind;
 use analyzer::Analyzer;
 use compiler::externals::CompilerExternals;
-use compiler::{compile_reef, CompilerOptions, SourceLineProvider};
+use compiler::{compile_reef, CaptureCache, CompilerOptions, SourceLineProvider};
 use context::source::ContentId;
 use vm::{VmError, VM};
 
 use crate::disassemble::display_bytecode;
-use crate::pipeline::{FileImportError, PipelineStatus, SourceHolder, SourcesCache};
-use crate::report::{display_diagnostic, display_parse_error};
+use crate::pipeline::{FileImportError, FileImporter, PipelineStatus, SourceHolder, SourcesCache};
+use crate::report::{
+    display_diagnostic, display_diagnostics_json, display_parse_error, sort_diagnostics,
+};
+
+/// The name given to the source code passed with `-c`.
+pub(crate) const COMMAND_SOURCE_NAME: &str = "<command>";
+
+/// The name given to the source code read from the standard input.
+pub(crate) const STDIN_SOURCE_NAME: &str = "<stdin>";
 
 /// The Moshell scripting language.
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// The inline source code to parse
-    #[arg(short = 'c')]
+    /// The inline source code to execute, instead of a source file
+    #[arg(short = 'c', long = "command", conflicts_with = "source")]
     pub(crate) code: Option<String>,
 
     /// Defines the source file to parse
     #[arg(index = 1)]
     pub(crate) source: Option<PathBuf>,
 
+    /// Reads the whole program from the standard input, instead of starting the REPL
+    #[arg(long = "stdin", conflicts_with_all = ["code", "source"])]
+    pub(crate) stdin: bool,
+
     /// Prints the generated bytecode
     #[arg(short = 'D', long)]
     pub(crate) disassemble: bool,
@@ -39,10 +56,59 @@ pub struct Cli {
     #[arg(short = 'A', long)]
     pub(crate) ast: bool,
 
+    /// Prints the parsed abstract syntax tree and exits before any analysis
+    #[arg(long = "dump-ast", conflicts_with_all = ["ast", "check", "emit", "test"])]
+    pub(crate) dump_ast: bool,
+
+    /// Display the inferred type of each expression
+    #[arg(long = "explain-types")]
+    pub(crate) explain_types: bool,
+
+    /// Sets the optimization level of the generated bytecode
+    #[arg(short = 'O', long = "opt-level", default_value_t = 0)]
+    pub(crate) opt_level: u8,
+
+    /// Emits debug information, such as locals names, in the generated bytecode
+    #[arg(short = 'g', long = "debug-info")]
+    pub(crate) debug_info: bool,
+
+    /// Embeds the source code in the generated bytecode, to report errors without the original files
+    #[arg(long = "embed-source")]
+    pub(crate) embed_source: bool,
+
+    /// Runs the source file again each time it is modified
+    #[arg(long = "watch", requires = "source", conflicts_with = "test")]
+    pub(crate) watch: bool,
+
+    /// Runs the functions marked with `@test` after executing the source file
+    #[arg(long = "test", requires = "source")]
+    pub(crate) test: bool,
+
+    /// Only analyze the code and report diagnostics, without generating any bytecode
+    #[arg(long = "check", conflicts_with_all = ["emit", "disassemble", "test"])]
+    pub(crate) check: bool,
+
     /// Do not execute the code
     #[arg(long = "no-execute")]
     pub(crate) no_execute: bool,
 
+    /// Prints an intermediate representation of the code instead of executing it
+    #[arg(long, value_enum)]
+    pub(crate) emit: Option<EmitKind>,
+
+    /// Sets how the diagnostics are reported
+    #[arg(long, value_enum, default_value_t = DiagnosticFormat::Human)]
+    pub(crate) format: DiagnosticFormat,
+
+    /// Restricts the sources to the features of the given language version
+    #[arg(long = "target-version", default_value_t = LATEST_VERSION,
+          value_parser = clap::value_parser!(u32).range(1..=LATEST_VERSION as i64))]
+    pub(crate) target_version: u32,
+
+    /// Prints the time spent in each phase of the pipeline to stderr
+    #[arg(long = "time")]
+    pub(crate) time: bool,
+
     /// Generate tab-completion scripts for your shell
     #[arg(long = "completions")]
     pub(crate) completions: Option<Shell>,
@@ -52,16 +118,39 @@ pub struct Cli {
     pub(crate) program_arguments: Vec<String>,
 }
 
-pub struct CachedSourceLocationLineProvider {
+/// The intermediate representations that can be emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum EmitKind {
+    /// The disassembled bytecode
+    Asm,
+
+    /// The typed intermediate representation, with the type of each expression
+    Hir,
+}
+
+/// The formats in which the diagnostics can be reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum DiagnosticFormat {
+    /// Rendered code snippets on the standard error
+    Human,
+
+    /// A JSON array on the standard output
+    Json,
+}
+
+pub struct CachedSourceLocationLineProvider<'a> {
     lines: HashMap<ContentId, Vec<usize>>,
+    sources: HashMap<ContentId, &'a str>,
 }
 
-impl CachedSourceLocationLineProvider {
-    fn compute(contents: &[ContentId], sources: &impl SourceHolder) -> Self {
+impl<'a> CachedSourceLocationLineProvider<'a> {
+    fn compute(contents: &[ContentId], sources: &'a impl SourceHolder) -> Self {
+        let mut texts = HashMap::new();
         let lines = contents
             .iter()
             .map(|&content_id| {
                 let source = sources.get_source(content_id).expect("unknown content id");
+                texts.insert(content_id, source.source);
 
                 let source_start_addr = source.source.as_ptr() as usize;
 
@@ -75,11 +164,14 @@ impl CachedSourceLocationLineProvider {
             })
             .collect();
 
-        Self { lines }
+        Self {
+            lines,
+            sources: texts,
+        }
     }
 }
 
-impl SourceLineProvider for CachedSourceLocationLineProvider {
+impl SourceLineProvider for CachedSourceLocationLineProvider<'_> {
     fn get_line(&self, content: ContentId, pos: usize) -> Option<usize> {
         self.lines.get(&content).map(|lines| {
             lines
@@ -88,6 +180,20 @@ impl SourceLineProvider for CachedSourceLocationLineProvider {
                 .unwrap_or_else(|line| line)
         })
     }
+
+    fn get_source(&self, content: ContentId) -> Option<&str> {
+        self.sources.get(&content).copied()
+    }
+}
+
+impl Cli {
+    /// Tests if the REPL should be started.
+    ///
+    /// It only starts when no program is given, and when the standard input is a terminal.
+    /// Otherwise, the program is read from the standard input.
+    pub(crate) fn is_interactive(&self, stdin_is_terminal: bool) -> bool {
+        self.source.is_none() && self.code.is_none() && !self.stdin && stdin_is_terminal
+    }
 }
 
 #[must_use = "The pipeline status should be checked"]
@@ -98,6 +204,7 @@ pub fn use_pipeline(
     analyzer: &Analyzer<'_>,
     externals: &Externals,
     compiler_externals: &mut CompilerExternals,
+    captures: &mut CaptureCache,
     vm: &mut VM,
     diagnostics: Vec<Diagnostic>,
     errors: Vec<FileImportError>,
@@ -110,31 +217,7 @@ pub fn use_pipeline(
     }
 
     let reef_id = externals.current;
-
-    let mut import_status = PipelineStatus::Success;
-    for error in errors {
-        match error {
-            FileImportError::IO { inner, path } => {
-                eprintln!("Couldn't read {}: {inner}", path.display());
-                import_status = PipelineStatus::IoError;
-            }
-            FileImportError::Parse(report) => {
-                for error in report.errors {
-                    let source = sources
-                        .get(reef_id)
-                        .and_then(|importer| importer.get_source(report.source))
-                        .unwrap();
-                    display_parse_error(source, error, &mut stderr())
-                        .expect("IO error when reporting diagnostics");
-                }
-
-                // Prefer the IO error over a generic failure
-                if import_status != PipelineStatus::IoError {
-                    import_status = PipelineStatus::AnalysisError;
-                }
-            }
-        }
-    }
+    let import_status = report_import_errors(errors, reef_id, sources);
     if import_status != PipelineStatus::Success {
         return import_status;
     }
@@ -150,30 +233,31 @@ pub fn use_pipeline(
         }
     }
 
-    let mut stderr = stderr();
-    let had_errors = !diagnostics.is_empty();
-    for diagnostic in diagnostics {
-        display_diagnostic(
-            externals,
-            engine,
-            externals.current,
-            sources,
-            diagnostic,
-            &mut stderr,
-        )
-        .expect("IO errors when reporting diagnostic");
-    }
-
-    if had_errors {
+    if report_diagnostics(analyzer, externals, diagnostics, sources, config) {
         return PipelineStatus::AnalysisError;
     }
-    let mut bytes = Vec::new();
 
     let importer = sources.get(reef_id).expect("unknown reef");
+    if config.explain_types {
+        display_types(analyzer, externals, importer);
+    }
+    if config.check {
+        if config.time {
+            display_durations(analyzer, None);
+        }
+        return PipelineStatus::Success;
+    }
+    if config.emit == Some(EmitKind::Hir) {
+        display_hir(analyzer, externals);
+        return PipelineStatus::Success;
+    }
+
+    let mut bytes = Vec::new();
     let contents = importer.list_content_ids();
     let lines = CachedSourceLocationLineProvider::compute(&contents, importer);
 
-    let compiled_reef = compile_reef(
+    let compilation_start = Instant::now();
+    let compiled_reef = match compile_reef(
         &analyzer.engine,
         &analyzer.resolution.relations,
         &analyzer.typing,
@@ -182,21 +266,33 @@ pub fn use_pipeline(
         compiler_externals,
         externals.current,
         starting_page,
+        captures,
         &mut bytes,
         CompilerOptions {
             line_provider: Some(&lines),
             last_page_storage_var: None,
+            opt_level: config.opt_level,
+            debug_info: config.debug_info,
+            embed_source: config.embed_source,
         },
-    )
-    .expect("write failed");
+    ) {
+        Ok(compiled_reef) => compiled_reef,
+        Err(err) => {
+            eprintln!("Could not compile: {err}");
+            return PipelineStatus::AnalysisError;
+        }
+    };
 
+    if config.time {
+        display_durations(analyzer, Some(compilation_start.elapsed()));
+    }
     compiler_externals.set(reef_id, compiled_reef);
 
-    if config.disassemble {
+    if config.disassemble || config.emit == Some(EmitKind::Asm) {
         display_bytecode(&bytes);
     }
 
-    if !config.no_execute {
+    if !config.no_execute && config.emit.is_none() {
         vm.register(&bytes)
             .expect("compilation created invalid bytecode");
         drop(bytes);
@@ -208,3 +304,203 @@ pub fn use_pipeline(
     }
     PipelineStatus::Success
 }
+
+/// Reports the errors that occurred while importing the sources.
+pub(crate) fn report_import_errors(
+    errors: Vec<FileImportError>,
+    reef_id: ReefId,
+    sources: &SourcesCache,
+) -> PipelineStatus {
+    let mut import_status = PipelineStatus::Success;
+    for error in errors {
+        match error {
+            FileImportError::IO { inner, path } => {
+                eprintln!("Couldn't read {}: {inner}", path.display());
+                import_status = PipelineStatus::IoError;
+            }
+            FileImportError::Parse(report) => {
+                for error in report.errors {
+                    let source = sources
+                        .get(reef_id)
+                        .and_then(|importer| importer.get_source(report.source))
+                        .unwrap();
+                    display_parse_error(source, error, &mut stderr())
+                        .expect("IO error when reporting diagnostics");
+                }
+
+                // Prefer the IO error over a generic failure
+                if import_status != PipelineStatus::IoError {
+                    import_status = PipelineStatus::AnalysisError;
+                }
+            }
+        }
+    }
+    import_status
+}
+
+/// Reports the diagnostics in the configured format, and tells if any of them is an error.
+pub(crate) fn report_diagnostics(
+    analyzer: &Analyzer<'_>,
+    externals: &Externals,
+    mut diagnostics: Vec<Diagnostic>,
+    sources: &SourcesCache,
+    config: &Cli,
+) -> bool {
+    let had_errors = diagnostics.iter().any(Diagnostic::is_critical);
+    sort_diagnostics(
+        externals,
+        &analyzer.resolution.engine,
+        externals.current,
+        &mut diagnostics,
+    );
+    match config.format {
+        DiagnosticFormat::Human => {
+            let mut stderr = stderr();
+            for diagnostic in diagnostics {
+                display_diagnostic(
+                    externals,
+                    &analyzer.resolution.engine,
+                    externals.current,
+                    sources,
+                    diagnostic,
+                    &mut stderr,
+                )
+                .expect("IO errors when reporting diagnostic");
+            }
+        }
+        DiagnosticFormat::Json => display_diagnostics_json(&diagnostics, &mut stdout())
+            .expect("IO errors when reporting diagnostic"),
+    }
+    had_errors
+}
+
+/// Prints the time spent in each phase of the pipeline.
+fn display_durations(analyzer: &Analyzer<'_>, compilation: Option<Duration>) {
+    let durations = analyzer.durations;
+    eprintln!("import:      {:?}", durations.import);
+    eprintln!("resolution:  {:?}", durations.resolution);
+    eprintln!("typing:      {:?}", durations.typing);
+    if let Some(compilation) = compilation {
+        eprintln!("compilation: {compilation:?}");
+    }
+}
+
+/// Prints the inferred type of each expression of the typed chunks.
+fn display_types(analyzer: &Analyzer<'_>, externals: &Externals, importer: &FileImporter) {
+    for (id, chunk) in analyzer.engine.iter_chunks() {
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            continue;
+        };
+        let Some(source) = analyzer
+            .resolution
+            .engine
+            .get_original_content(id)
+            .and_then(|content_id| importer.get_source(content_id))
+        else {
+            continue;
+        };
+        for explained in explain_types(
+            body,
+            externals.current,
+            &analyzer.typing,
+            &analyzer.engine,
+            externals,
+        ) {
+            println!("{}: {}", &source.source[explained.segment], explained.ty);
+        }
+    }
+}
+
+/// Prints the typed tree of each typed chunk.
+fn display_hir(analyzer: &Analyzer<'_>, externals: &Externals) {
+    for (id, chunk) in analyzer.engine.iter_chunks() {
+        let ChunkKind::DefinedFunction(Some(body)) = &chunk.kind else {
+            continue;
+        };
+        if let Some(env) = analyzer.resolution.engine.get_environment(id) {
+            println!("{}:", env.fqn);
+        }
+        print!(
+            "{}",
+            dump_hir(
+                body,
+                externals.current,
+                &analyzer.typing,
+                &analyzer.engine,
+                externals
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+    use clap::{CommandFactory, Parser};
+
+    use super::{Cli, EmitKind};
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn inline_command() {
+        let cli = Cli::try_parse_from(["moshell", "--command", "echo hello"]).unwrap();
+        assert_eq!(cli.code.as_deref(), Some("echo hello"));
+        assert_eq!(cli.source, None);
+    }
+
+    #[test]
+    fn emit_hir() {
+        let cli = Cli::try_parse_from(["moshell", "--emit=hir", "script.msh"]).unwrap();
+        assert_eq!(cli.emit, Some(EmitKind::Hir));
+    }
+
+    #[test]
+    fn watch_requires_source() {
+        let err = Cli::try_parse_from(["moshell", "--watch"]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn time_is_disabled_by_default() {
+        let cli = Cli::try_parse_from(["moshell", "script.msh"]).unwrap();
+        assert!(!cli.time);
+        let cli = Cli::try_parse_from(["moshell", "--time", "script.msh"]).unwrap();
+        assert!(cli.time);
+    }
+
+    #[test]
+    fn interactive_only_without_program() {
+        let cli = Cli::try_parse_from(["moshell"]).unwrap();
+        assert!(cli.is_interactive(true));
+        assert!(!cli.is_interactive(false));
+
+        let cli = Cli::try_parse_from(["moshell", "--stdin"]).unwrap();
+        assert!(!cli.is_interactive(true));
+
+        let cli = Cli::try_parse_from(["moshell", "-c", "echo hello"]).unwrap();
+        assert!(!cli.is_interactive(true));
+
+        let cli = Cli::try_parse_from(["moshell", "script.msh"]).unwrap();
+        assert!(!cli.is_interactive(true));
+    }
+
+    #[test]
+    fn stdin_with_source() {
+        let err = Cli::try_parse_from(["moshell", "--stdin", "script.msh"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn inline_command_with_source() {
+        let err = Cli::try_parse_from(["moshell", "-c", "echo hello", "script.msh"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+}
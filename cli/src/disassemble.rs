@@ -1,7 +1,7 @@
 use std::io;
 use std::io::{Cursor, Read};
 
-use compiler::bytecode::Opcode;
+use compiler::disasm::write_instruction;
 
 macro_rules! read {
     ($read:expr, $tpe:ty) => {{
@@ -70,6 +70,8 @@ fn display_function(
         let attribute_id = read!(cursor, u8);
         match attribute_id {
             1 => display_mappings_attribute(cursor)?,
+            2 => display_locals_attribute(cursor, constants)?,
+            3 => display_source_attribute(cursor, constants)?,
             _ => panic!("Unknown attribute {attribute_id} read from bytecode"),
         }
     }
@@ -91,6 +93,28 @@ fn display_mappings_attribute(cursor: &mut Cursor<&[u8]>) -> io::Result<()> {
     Ok(())
 }
 
+fn display_locals_attribute(cursor: &mut Cursor<&[u8]>, constants: &[String]) -> io::Result<()> {
+    println!("Locals: ");
+
+    let locals_count = read!(cursor, u32);
+    for _ in 0..locals_count {
+        let name = &constants[read!(cursor, u32) as usize];
+        let offset = read!(cursor, u32);
+        let size = read!(cursor, u8);
+
+        println!("\t@{offset} -> {name} ({size} bytes)")
+    }
+
+    Ok(())
+}
+
+fn display_source_attribute(cursor: &mut Cursor<&[u8]>, constants: &[String]) -> io::Result<()> {
+    let source = &constants[read!(cursor, u32) as usize];
+    println!("Source: {} bytes", source.len());
+
+    Ok(())
+}
+
 fn display_code(
     cursor: &mut Cursor<&[u8]>,
     constants: &[String],
@@ -114,68 +138,8 @@ fn display_code(
 
     while cursor.position() < end_pos {
         let instruction_address = cursor.position() - start_pos;
-        let opcode = Opcode::try_from(read!(cursor, u8)).expect("Unknown opcode");
-        let mnemonic = get_opcode_mnemonic(opcode);
-        print!("\t\t#{instruction_address:<instruction_pad$}: {mnemonic:7} ");
-
-        match opcode {
-            Opcode::PushInt => print!("<value {}>", read!(cursor, i64)),
-            Opcode::PushByte => print!("<value {}>", read!(cursor, u8)),
-            Opcode::PushFloat => print!("<value {}>", read!(cursor, f64)),
-            Opcode::PushStringRef => {
-                let constant_idx = read!(cursor, u32) as usize;
-                let str = &constants[constant_idx];
-                let padding = (digits(constants.len() as u64) - digits(constant_idx as u64)) + 10;
-                print!("<constant #{constant_idx}> {:padding$} // \"{str}\"", "")
-            }
-            Opcode::PushLocalRef
-            | Opcode::GetLocalByte
-            | Opcode::GetLocalQWord
-            | Opcode::SetLocalByte
-            | Opcode::SetLocalQWord => print!("<local @{}>", read!(cursor, u32)),
-
-            Opcode::SetStructQWord
-            | Opcode::GetStructQWord
-            | Opcode::SetStructByte
-            | Opcode::GetStructByte => print!("<struct index @{}>", read!(cursor, u32)),
-
-            Opcode::NewStruct => {
-                let constant_idx = read!(cursor, u32) as usize;
-                let str = &constants[constant_idx];
-                let padding = (digits(constants.len() as u64) - digits(constant_idx as u64)) + 10;
-                print!(
-                    "<constant #{constant_idx}> {:padding$} // <structure> {str}",
-                    ""
-                )
-            }
-            Opcode::StructCopyOperands => {
-                let count = read!(cursor, u32);
-                print!("<count @{count}>")
-            }
-
-            Opcode::Invoke => {
-                let constant_idx = read!(cursor, u32) as usize;
-                let str = &constants[constant_idx];
-                let padding = (digits(constants.len() as u64) - digits(constant_idx as u64)) + 10;
-                print!(
-                    "<constant #{constant_idx}> {:padding$} // <function> {str}",
-                    ""
-                )
-            }
-            Opcode::FetchByte | Opcode::FetchQWord | Opcode::StoreByte | Opcode::StoreQWord => {
-                let dynsym_idx = read!(cursor, u32) as usize;
-                let str = &constants[dynamic_symbols[dynsym_idx]];
-                let padding =
-                    (digits(dynamic_symbols.len() as u64) - digits(dynsym_idx as u64)) + 10;
-                print!("<external #{dynsym_idx}> {:padding$} // {str}", "")
-            }
-            Opcode::Exec => print!("<arity {}>", read!(cursor, u8)),
-            Opcode::Open => print!("<flags {:#x}>", read!(cursor, i32)),
-            Opcode::IfJump | Opcode::IfNotJump | Opcode::Jump | Opcode::Fork => {
-                print!("<instruction #{}>", read!(cursor, u32))
-            }
-            _ => {} // Other opcodes do not define parameters
-        }
+        print!("\t\t#{instruction_address:<instruction_pad$}: ");
+        write_instruction(cursor, constants, dynamic_symbols, &mut io::stdout())?;
         println!()
     }
 
@@ -232,6 +196,19 @@ fn display_unit(
         if is_obj_ref {
             print!(" (object reference)");
         }
+        let attribute_count = read!(cursor, u8);
+        for _ in 0..attribute_count {
+            let attribute_id = read!(cursor, u8);
+            match attribute_id {
+                1 => {
+                    let content = read!(cursor, u32);
+                    let start = read!(cursor, u32);
+                    let end = read!(cursor, u32);
+                    print!(" declared in content {content} at {start}..{end}");
+                }
+                _ => panic!("Unknown export attribute {attribute_id} read from bytecode"),
+            }
+        }
         println!()
     }
 
@@ -246,6 +223,28 @@ fn display_unit(
     for _ in 0..functions_count {
         display_function(cursor, constants, dynamic_symbols)?;
     }
+
+    display_trace_table(cursor, constants)
+}
+
+fn display_trace_table(cursor: &mut Cursor<&[u8]>, constants: &[String]) -> io::Result<()> {
+    println!("Traces: ");
+
+    let traces_count = read!(cursor, u32);
+    for _ in 0..traces_count {
+        let name = &constants[read!(cursor, u32) as usize];
+        let start = read!(cursor, u32);
+        let end = read!(cursor, u32);
+        println!("	#{start}..#{end} -> {name}");
+
+        let mappings_count = read!(cursor, u32);
+        for _ in 0..mappings_count {
+            let ip = read!(cursor, u32);
+            let line = read!(cursor, u32);
+            println!("		#{ip} -> line {line}")
+        }
+    }
+
     Ok(())
 }
 
@@ -260,81 +259,3 @@ pub fn display_bytecode(bytecode: &[u8]) {
             .expect("Read slice error when displaying function content");
     }
 }
-
-fn get_opcode_mnemonic(opcode: Opcode) -> &'static str {
-    match opcode {
-        Opcode::PushInt => "ipsh",
-        Opcode::PushByte => "bpsh",
-        Opcode::PushFloat => "fpsh",
-        Opcode::PushStringRef => "srpsh",
-        Opcode::PushLocalRef => "lrpsh",
-        Opcode::BoxQWord => "qwbox",
-        Opcode::BoxByte => "bbox",
-        Opcode::Unbox => "unbox",
-        Opcode::GetLocalByte => "lbget",
-        Opcode::SetLocalByte => "lbset",
-        Opcode::GetLocalQWord => "lqwget",
-        Opcode::SetLocalQWord => "lqwset",
-        Opcode::GetRefByte => "rbget",
-        Opcode::SetRefByte => "rbset",
-        Opcode::GetRefQWord => "rqwget",
-        Opcode::SetRefQWord => "rqwset",
-        Opcode::GetStructByte => "sbget",
-        Opcode::SetStructByte => "sbset",
-        Opcode::GetStructQWord => "sqwget",
-        Opcode::SetStructQWord => "sqwset",
-        Opcode::FetchByte => "bfetch",
-        Opcode::FetchQWord => "qwfetch",
-        Opcode::StoreByte => "bstore",
-        Opcode::StoreQWord => "qwstore",
-        Opcode::NewStruct => "new",
-        Opcode::StructCopyOperands => "copy",
-        Opcode::Invoke => "invoke",
-        Opcode::Fork => "fork",
-        Opcode::Exec => "exec",
-        Opcode::Wait => "wait",
-        Opcode::Open => "open",
-        Opcode::Close => "close",
-        Opcode::SetupRedirect => "setredir",
-        Opcode::Redirect => "redir",
-        Opcode::PopRedirect => "popredir",
-        Opcode::Pipe => "pipe",
-        Opcode::Read => "read",
-        Opcode::Write => "write",
-        Opcode::Exit => "exit",
-        Opcode::Dup => "dup",
-        Opcode::DupByte => "dupb",
-        Opcode::Swap => "swap",
-        Opcode::Swap2 => "swap2",
-        Opcode::PopByte => "bpop",
-        Opcode::PopQWord => "qwpop",
-        Opcode::IfJump => "ifjmp",
-        Opcode::IfNotJump => "ifnjmp",
-        Opcode::Jump => "jmp",
-        Opcode::Return => "ret",
-        Opcode::ConvertByteToInt => "b2i",
-        Opcode::ConvertIntToByte => "i2b",
-        Opcode::BXor => "bxor",
-        Opcode::IntAdd => "iadd",
-        Opcode::IntSub => "isub",
-        Opcode::IntMul => "imul",
-        Opcode::IntDiv => "idiv",
-        Opcode::IntMod => "imod",
-        Opcode::IntNeg => "ineg",
-        Opcode::FloatAdd => "fadd",
-        Opcode::FloatSub => "fsub",
-        Opcode::FloatMul => "fmul",
-        Opcode::FloatDiv => "fdiv",
-        Opcode::FloatNeg => "fneg",
-        Opcode::IntEqual => "ieq",
-        Opcode::IntLessThan => "ilt",
-        Opcode::IntLessOrEqual => "ile",
-        Opcode::IntGreaterThan => "igt",
-        Opcode::IntGreaterOrEqual => "ige",
-        Opcode::FloatEqual => "feq",
-        Opcode::FloatLessThan => "flt",
-        Opcode::FloatLessOrEqual => "fle",
-        Opcode::FloatGreaterThan => "fgt",
-        Opcode::FloatGreaterOrEqual => "fge",
-    }
-}
@@ -1,10 +1,14 @@
-use crate::cli::{use_pipeline, Cli};
+use crate::cli::{use_pipeline, Cli, COMMAND_SOURCE_NAME, STDIN_SOURCE_NAME};
 use crate::pipeline::{ErrorReporter, PipelineStatus, SourcesCache};
 use crate::repl::{code, repl};
+use crate::report::display_parse_error;
 use crate::std::build_std;
 use crate::terminal::signal_hook;
+use crate::test_runner::run_tests;
+use crate::watch::watch;
 use ::std::ffi::OsStr;
-use ::std::io;
+use ::std::fs;
+use ::std::io::{self, IsTerminal, Read};
 use ::std::path::Path;
 use analyzer::name::Name;
 use analyzer::reef::Externals;
@@ -12,6 +16,8 @@ use analyzer::relations::SourceId;
 use analyzer::Analyzer;
 use clap::{CommandFactory, Parser};
 use compiler::externals::CompilerExternals;
+use compiler::CaptureCache;
+use context::source::OwnedSource;
 use miette::{Context, IntoDiagnostic, MietteHandlerOpts};
 use nix::sys::signal;
 use vm::VM;
@@ -24,6 +30,8 @@ mod repl;
 mod report;
 mod std;
 mod terminal;
+mod test_runner;
+mod watch;
 
 fn main() -> Result<PipelineStatus, miette::Error> {
     if cfg!(unix) && !cfg!(miri) {
@@ -56,6 +64,10 @@ fn main() -> Result<PipelineStatus, miette::Error> {
     }))
     .expect("miette options setup");
 
+    if cli.dump_ast {
+        return dump_ast(&cli);
+    }
+
     let mut externals = Externals::default();
     let mut compiler_externals = CompilerExternals::default();
     let mut sources = SourcesCache::default();
@@ -79,13 +91,36 @@ fn main() -> Result<PipelineStatus, miette::Error> {
         &mut sources,
         &cli,
     );
+    // the standard library is always written against the latest version
+    externals.set_target_version(cli.target_version);
 
     if let Some(source) = &cli.source {
-        return run(source, &cli, sources, externals, compiler_externals, vm);
+        if cli.watch {
+            return watch(source, &cli, sources, externals, compiler_externals, vm);
+        }
+        return run(
+            source,
+            &cli,
+            &mut sources,
+            &externals,
+            &mut compiler_externals,
+            &mut vm,
+        );
     }
     if let Some(source) = cli.code.clone() {
         return code(
-            source,
+            OwnedSource::new(source, COMMAND_SOURCE_NAME.to_owned()),
+            current_dir,
+            &cli,
+            sources,
+            externals,
+            compiler_externals,
+            vm,
+        );
+    }
+    if !cli.is_interactive(io::stdin().is_terminal()) {
+        return code(
+            read_stdin()?,
             current_dir,
             &cli,
             sources,
@@ -105,13 +140,53 @@ fn main() -> Result<PipelineStatus, miette::Error> {
     )
 }
 
-fn run(
+/// Reads the whole program piped on the standard input.
+fn read_stdin() -> Result<OwnedSource, miette::Error> {
+    let mut source = String::new();
+    io::stdin()
+        .read_to_string(&mut source)
+        .into_diagnostic()
+        .context("Could not read the program from stdin")?;
+    Ok(OwnedSource::new(source, STDIN_SOURCE_NAME.to_owned()))
+}
+
+/// Parses the source given on the command line or piped on stdin, and prints its abstract syntax tree.
+fn dump_ast(cli: &Cli) -> Result<PipelineStatus, miette::Error> {
+    let source = match (&cli.source, &cli.code) {
+        (Some(path), _) => OwnedSource::new(
+            fs::read_to_string(path)
+                .into_diagnostic()
+                .with_context(|| format!("Could not read {}", path.display()))?,
+            path.display().to_string(),
+        ),
+        (None, Some(code)) => OwnedSource::new(code.clone(), COMMAND_SOURCE_NAME.to_owned()),
+        (None, None) if !cli.is_interactive(io::stdin().is_terminal()) => read_stdin()?,
+        (None, None) => {
+            miette::bail!("--dump-ast requires a source file, inline code or a program on stdin")
+        }
+    };
+
+    let report = parser::parse(&source.source);
+    if report.is_err() {
+        for error in report.errors {
+            display_parse_error(source.as_source(), error, &mut io::stderr())
+                .expect("IO error when reporting diagnostics");
+        }
+        return Ok(PipelineStatus::AnalysisError);
+    }
+    for expr in report.expr {
+        println!("{expr:#?}");
+    }
+    Ok(PipelineStatus::Success)
+}
+
+pub(crate) fn run(
     source: &Path,
     cli: &Cli,
-    mut sources: SourcesCache,
-    externals: Externals,
-    mut compiler_externals: CompilerExternals,
-    mut vm: VM,
+    sources: &mut SourcesCache,
+    externals: &Externals,
+    compiler_externals: &mut CompilerExternals,
+    vm: &mut VM,
 ) -> Result<PipelineStatus, miette::Error> {
     let name = Name::new(
         source
@@ -131,21 +206,35 @@ fn run(
     importer.add_redirection(name.clone(), source.to_path_buf());
 
     let mut analyzer = Analyzer::new();
-    analyzer.process(name.clone(), importer, &externals);
+    let mut captures = CaptureCache::default();
+    analyzer.process(name.clone(), importer, externals);
 
     let diagnostics = analyzer.take_diagnostics();
     let errors = importer.take_errors();
 
-    Ok(use_pipeline(
+    let status = use_pipeline(
         &name,
         SourceId(0),
         &analyzer,
-        &externals,
-        &mut compiler_externals,
-        &mut vm,
+        externals,
+        compiler_externals,
+        &mut captures,
+        vm,
         diagnostics,
         errors,
-        &sources,
+        sources,
         cli,
-    ))
+    );
+    if cli.test && status == PipelineStatus::Success {
+        return Ok(run_tests(
+            &mut analyzer,
+            externals,
+            compiler_externals,
+            &mut captures,
+            vm,
+            sources,
+            cli,
+        ));
+    }
+    Ok(status)
 }
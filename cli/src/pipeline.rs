@@ -105,6 +105,11 @@ impl SourcesCache {
     pub fn last_mut(&mut self) -> &mut FileImporter {
         self.importers.last_mut().unwrap()
     }
+
+    /// Removes the importers of the given reef and of the reefs registered after it.
+    pub fn unregister(&mut self, reef: ReefId) {
+        self.importers.truncate(reef.0 - 1);
+    }
 }
 
 impl FileImporter {
@@ -202,7 +207,7 @@ impl ASTImporter for FileImporter {
 
 pub trait SourceHolder {
     /// Gets a source from the importer.
-    fn get_source(&self, id: ContentId) -> Option<Source>;
+    fn get_source(&self, id: ContentId) -> Option<Source<'_>>;
 
     /// Lists all the contents ids that are available in the importer.
     fn list_content_ids(&self) -> Vec<ContentId>;
@@ -217,7 +222,7 @@ pub trait ErrorReporter {
 }
 
 impl SourceHolder for FileImporter {
-    fn get_source(&self, id: ContentId) -> Option<Source> {
+    fn get_source(&self, id: ContentId) -> Option<Source<'_>> {
         self.sources.get(id.0).map(|s| s.as_source())
     }
 
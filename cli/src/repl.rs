@@ -1,4 +1,4 @@
-use miette::{Context, IntoDiagnostic};
+use miette::Context;
 use nu_ansi_term::Color;
 use reedline::{
     default_emacs_keybindings, ColumnarMenu, Emacs, ExampleHighlighter, FileBackedHistory, KeyCode,
@@ -6,21 +6,28 @@ use reedline::{
     Reedline, ReedlineEvent, ReedlineMenu, Signal, ValidationResult, Validator,
 };
 use std::borrow::Cow;
+use std::env;
 use std::io::{self, BufRead, IsTerminal, StdinLock};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use analyzer::diagnostic::Diagnostic;
 use analyzer::importer::ImportResult;
 use analyzer::name::Name;
 use analyzer::reef::Externals;
 use analyzer::relations::SourceId;
+use analyzer::steps::typing::explain::type_name;
+use analyzer::types::engine::{Chunk, ChunkKind};
 use analyzer::{Analyzer, Inject};
 use cli::project_dir;
 use compiler::externals::CompilerExternals;
+use compiler::CaptureCache;
 use context::source::OwnedSource;
 use lexer::is_unterminated;
+use parser::err::PartialParse;
+use parser::parse_partial;
 use vm::VM;
 
-use crate::cli::{use_pipeline, Cli};
+use crate::cli::{report_diagnostics, report_import_errors, use_pipeline, Cli};
 use crate::complete::MoshellCompleter;
 use crate::pipeline::{ErrorReporter, PipelineStatus, SourcesCache};
 use crate::terminal::acquire_terminal;
@@ -35,6 +42,7 @@ pub(crate) fn repl(
     mut vm: VM,
 ) -> miette::Result<PipelineStatus> {
     let mut analyzer = Analyzer::new();
+    let mut captures = CaptureCache::default();
     sources.register(dir);
 
     let mut editor = if io::stdin().is_terminal() && cfg!(not(miri)) {
@@ -56,6 +64,19 @@ pub(crate) fn repl(
         let line = editor.read_line(&Prompt);
 
         match line {
+            Ok(Signal::Success(source)) if type_command(&source).is_some() => {
+                let expr = type_command(&source).unwrap_or_default();
+                let source = OwnedSource::new(expr.to_owned(), "stdin".to_owned());
+                status = status.compose(print_type(
+                    &name,
+                    &mut analyzer,
+                    &externals,
+                    &mut sources,
+                    config,
+                    starting_source,
+                    source,
+                ));
+            }
             Ok(Signal::Success(source)) => {
                 let source = OwnedSource::new(source, "stdin".to_owned());
                 status = status.compose(consume(
@@ -63,6 +84,7 @@ pub(crate) fn repl(
                     &mut analyzer,
                     &externals,
                     &mut compiler_externals,
+                    &mut captures,
                     &mut vm,
                     &mut sources,
                     config,
@@ -80,9 +102,17 @@ pub(crate) fn repl(
     }
 }
 
-/// Analyse and consume a source string.
+/// Extracts the expression of a `:type` command line.
+///
+/// The command word must be followed by a whitespace or end the line.
+fn type_command(line: &str) -> Option<&str> {
+    let expr = line.trim_start().strip_prefix(":type")?;
+    (expr.is_empty() || expr.starts_with(char::is_whitespace)).then_some(expr)
+}
+
+/// Analyse and consume a whole source, such as the one given with `-c` or read from stdin.
 pub(crate) fn code(
-    code: String,
+    source: OwnedSource,
     dir: PathBuf,
     config: &Cli,
     mut sources: SourcesCache,
@@ -90,15 +120,15 @@ pub(crate) fn code(
     mut compiler_externals: CompilerExternals,
     mut vm: VM,
 ) -> miette::Result<PipelineStatus> {
-    let name = "direct";
-    let source = OwnedSource::new(code, name.to_owned());
+    let name = Name::new(&source.name);
     let mut analyzer = Analyzer::new();
     sources.register(dir);
     Ok(consume(
-        &Name::new(name),
+        &name,
         &mut analyzer,
         &externals,
         &mut compiler_externals,
+        &mut CaptureCache::default(),
         &mut vm,
         &mut sources,
         config,
@@ -109,11 +139,12 @@ pub(crate) fn code(
 
 /// Processes a source and returns the pipeline status.
 #[allow(clippy::too_many_arguments)]
-fn consume(
+pub(crate) fn consume(
     name: &Name,
     analyzer: &mut Analyzer<'_>,
     externals: &Externals,
     compiler_externals: &mut CompilerExternals,
+    captures: &mut CaptureCache,
     vm: &mut VM,
     sources: &mut SourcesCache,
     config: &Cli,
@@ -137,7 +168,7 @@ fn consume(
         // able to cancel the analysis (the errors need the context that is
         // dropped when the analysis is reverted).
         let diagnostics = analysis.take_diagnostics();
-        let is_ready = diagnostics.is_empty();
+        let is_ready = !diagnostics.iter().any(Diagnostic::is_critical);
 
         let errors = importer.take_errors();
         let status = use_pipeline(
@@ -146,6 +177,7 @@ fn consume(
             analysis.analyzer(),
             externals,
             compiler_externals,
+            captures,
             vm,
             diagnostics,
             errors,
@@ -157,6 +189,7 @@ fn consume(
         if is_ready {
             *starting_source = Some(analysis.attributed_id());
         } else {
+            captures.invalidate(analysis.attributed_id());
             analysis.revert();
         }
         status
@@ -172,6 +205,7 @@ fn consume(
             analyzer,
             externals,
             compiler_externals,
+            captures,
             vm,
             diagnostics,
             importer.take_errors(),
@@ -181,6 +215,60 @@ fn consume(
     }
 }
 
+/// Analyses a source and prints its type, without executing it.
+///
+/// The source is analyzed in the context of the previous REPL inputs, but
+/// anything it declares is discarded afterwards.
+fn print_type(
+    name: &Name,
+    analyzer: &mut Analyzer<'_>,
+    externals: &Externals,
+    sources: &mut SourcesCache,
+    config: &Cli,
+    starting_source: Option<SourceId>,
+    source: OwnedSource,
+) -> PipelineStatus {
+    let importer = sources.last_mut();
+    let ImportResult::Success(imported) = importer.insert(source) else {
+        let errors = importer.take_errors();
+        return report_import_errors(errors, externals.current, sources);
+    };
+    let mut analysis = analyzer.inject(
+        Inject {
+            name: name.clone(),
+            imported,
+            attached: starting_source,
+        },
+        importer,
+        externals,
+    );
+    let diagnostics = analysis.take_diagnostics();
+    let analyzer = analysis.analyzer();
+    let status = if report_diagnostics(analyzer, externals, diagnostics, sources, config) {
+        PipelineStatus::AnalysisError
+    } else {
+        if let Some(Chunk {
+            kind: ChunkKind::DefinedFunction(Some(body)),
+            ..
+        }) = analyzer.engine.get_user(analysis.attributed_id())
+        {
+            println!(
+                "{}",
+                type_name(
+                    body.ty,
+                    externals.current,
+                    &analyzer.typing,
+                    &analyzer.engine,
+                    externals
+                )
+            );
+        }
+        PipelineStatus::Success
+    };
+    analysis.revert();
+    status
+}
+
 /// The REPL editor.
 enum Editor<'a> {
     /// An interactive line editor.
@@ -205,16 +293,8 @@ fn editor() -> miette::Result<Reedline> {
     let mut editor = Reedline::create()
         .with_validator(Box::new(TerminatedValidator))
         .with_highlighter(Box::new(highlighter));
-    if let Some(project_dir) = project_dir() {
-        let history_path = project_dir.data_dir().join("history.txt");
-        let history = Box::new(
-            FileBackedHistory::with_file(4000, history_path.clone())
-                .into_diagnostic()
-                .with_context(|| {
-                    format!("Could not open history file: {}", history_path.display())
-                })?,
-        );
-        editor = editor.with_history(history);
+    if let Some(history) = history_path().and_then(|path| open_history(&path)) {
+        editor = editor.with_history(Box::new(history));
     }
     let completion_menu = Box::new(
         ColumnarMenu::default()
@@ -240,29 +320,58 @@ fn editor() -> miette::Result<Reedline> {
     Ok(editor)
 }
 
+/// The environment variable that overrides the location of the history file.
+const HISTORY_PATH_VAR: &str = "MOSHELL_HISTORY";
+
+/// The maximum number of lines kept in the history file.
+const HISTORY_CAPACITY: usize = 4000;
+
+/// Locates the file where the REPL inputs are saved across sessions.
+fn history_path() -> Option<PathBuf> {
+    env::var_os(HISTORY_PATH_VAR)
+        .map(PathBuf::from)
+        .or_else(|| project_dir().map(|dir| dir.data_dir().join("history.txt")))
+}
+
+/// Opens the history file, creating it if needed.
+///
+/// The REPL stays usable without any history if the file cannot be opened.
+fn open_history(path: &Path) -> Option<FileBackedHistory> {
+    match FileBackedHistory::with_file(HISTORY_CAPACITY, path.to_path_buf()) {
+        Ok(history) => Some(history),
+        Err(err) => {
+            eprintln!(
+                "Could not open history file {}, history is disabled: {err}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
 struct Prompt;
 
 impl reedline::Prompt for Prompt {
-    fn render_prompt_left(&self) -> Cow<str> {
+    fn render_prompt_left(&self) -> Cow<'_, str> {
         Cow::Borrowed("=> ")
     }
 
-    fn render_prompt_right(&self) -> Cow<str> {
+    fn render_prompt_right(&self) -> Cow<'_, str> {
         Cow::Borrowed("")
     }
 
-    fn render_prompt_indicator(&self, _: PromptEditMode) -> Cow<str> {
+    fn render_prompt_indicator(&self, _: PromptEditMode) -> Cow<'_, str> {
         Cow::Borrowed("")
     }
 
-    fn render_prompt_multiline_indicator(&self) -> Cow<str> {
+    fn render_prompt_multiline_indicator(&self) -> Cow<'_, str> {
         Cow::Borrowed("... ")
     }
 
     fn render_prompt_history_search_indicator(
         &self,
         history_search: PromptHistorySearch,
-    ) -> Cow<str> {
+    ) -> Cow<'_, str> {
         match history_search.status {
             PromptHistorySearchStatus::Passing => Cow::Borrowed("(reverse-search): "),
             PromptHistorySearchStatus::Failing => Cow::Borrowed("(failed reverse-search): "),
@@ -274,9 +383,11 @@ struct TerminatedValidator;
 
 impl Validator for TerminatedValidator {
     fn validate(&self, line: &str) -> ValidationResult {
-        match is_unterminated(line) {
-            true => ValidationResult::Incomplete,
-            false => ValidationResult::Complete,
+        match parse_partial(line) {
+            PartialParse::Incomplete { .. } => ValidationResult::Incomplete,
+            // An escaped line end also continues the input
+            PartialParse::Complete(_) if is_unterminated(line) => ValidationResult::Incomplete,
+            PartialParse::Complete(_) => ValidationResult::Complete,
         }
     }
 }
@@ -313,3 +424,41 @@ impl<'a> MultilineInput<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reedline::{History, HistoryItem};
+
+    use super::*;
+
+    #[test]
+    fn history_persists_across_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("moshell").join("history.txt");
+
+        let mut history = open_history(&path).expect("history should be writable");
+        history
+            .save(HistoryItem::from_command_line("echo $("))
+            .unwrap();
+        history.sync().unwrap();
+        drop(history);
+
+        let history = open_history(&path).expect("history should be readable");
+        assert_eq!(history.count_all().unwrap(), 1);
+    }
+
+    #[test]
+    fn history_in_unwritable_location() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().join("history.txt");
+        assert!(open_history(&path).is_none());
+    }
+
+    #[test]
+    fn type_command_word() {
+        assert_eq!(type_command(" :type 1 + 2"), Some(" 1 + 2"));
+        assert_eq!(type_command(":type"), Some(""));
+        assert_eq!(type_command(":typefoo"), None);
+        assert_eq!(type_command("echo :type"), None);
+    }
+}
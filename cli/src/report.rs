@@ -1,9 +1,10 @@
-use std::io;
 use std::io::Write;
+use std::{fmt, io};
 
-use analyzer::diagnostic::Diagnostic;
+use analyzer::diagnostic::{Diagnostic, Observation};
 use analyzer::engine::Engine;
 use miette::{LabeledSpan, MietteDiagnostic, Report, Severity, SourceSpan};
+use serde_json::{json, Value};
 
 use analyzer::reef::{Externals, ReefId};
 use context::source::{ContentId, Source, SourceSegment};
@@ -13,22 +14,54 @@ use crate::pipeline::{SourceHolder, SourcesCache};
 
 fn offset_empty_span(span: SourceSegment) -> SourceSpan {
     if span.start == span.end {
-        (span.start - 1..span.end).into()
+        (span.start.saturating_sub(1)..span.end).into()
     } else {
         span.into()
     }
 }
 
+/// A position in a source, where both the line and the column start from one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+
+    /// The number of characters before the position in its line, plus one.
+    pub column: usize,
+}
+
+impl fmt::Display for LineColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Locates the start of a parse error in its source.
+///
+/// An error at the end of the source is located right after its last character.
+pub fn locate_parse_error(source: &Source, error: &ParseError) -> LineColumn {
+    let mut offset = error.position.start.min(source.source.len());
+    while !source.source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source.source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    LineColumn {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
 pub fn display_parse_error<W: Write>(
     source: Source,
     error: ParseError,
     writer: &mut W,
 ) -> io::Result<()> {
+    let location = locate_parse_error(&source, &error);
     let span = offset_empty_span(error.position);
     let mut diag = MietteDiagnostic::new(error.message)
         .with_severity(Severity::Error)
         .and_label(LabeledSpan::new(
-            Some("Here".to_string()),
+            Some(format!("Here, at {location}")),
             span.offset(),
             span.len(),
         ));
@@ -86,26 +119,20 @@ pub fn display_diagnostic<W: Write>(
 
     let mut displayed_source: Option<AttachedSource> = None;
     for obs in diagnostic.observations {
-        let loc = obs.location;
-        let engine = if engine_reef == loc.reef {
-            current_engine
-        } else {
-            &externals.get_reef(loc.reef).unwrap().engine
-        };
-
-        let content_id = engine
+        let loc = obs.location.clone();
+        let content_id = reef_engine(externals, current_engine, engine_reef, loc.reef)
             .get_original_content(loc.source)
             .expect("Unknown source");
 
         if displayed_source
-            .as_ref().is_none_or(|s| s.id == content_id && s.reef == loc.reef)
+            .as_ref()
+            .is_none_or(|s| s.id == content_id && s.reef == loc.reef)
         {
             let source = sources
                 .get(loc.reef)
                 .and_then(|importer| importer.get_source(content_id))
                 .expect("Unknown source");
-            let span = loc.segment.clone();
-            diag = diag.and_label(LabeledSpan::new(obs.message, span.start, span.len()));
+            diag = diag.and_labels(observation_labels(obs));
             displayed_source = Some(AttachedSource {
                 reef: loc.reef,
                 id: content_id,
@@ -117,6 +144,102 @@ pub fn display_diagnostic<W: Write>(
     write_diagnostic(diag, displayed_source.map(|s| s.content), writer)
 }
 
+/// Gets the engine of a reef, which is the current engine for the reef being analyzed.
+fn reef_engine<'e>(
+    externals: &'e Externals,
+    current_engine: &'e Engine,
+    engine_reef: ReefId,
+    reef: ReefId,
+) -> &'e Engine<'e> {
+    if engine_reef == reef {
+        current_engine
+    } else {
+        &externals.get_reef(reef).unwrap().engine
+    }
+}
+
+/// Creates the labels of an observation, its main location first as the primary label, followed
+/// by its highlights.
+fn observation_labels(observation: Observation) -> Vec<LabeledSpan> {
+    let span = observation.location.segment;
+    let main = LabeledSpan::new_primary_with_span(observation.message, (span.start, span.len()));
+    std::iter::once(main)
+        .chain(observation.highlights.into_iter().map(|highlight| {
+            let span = highlight.segment;
+            LabeledSpan::new_with_span(highlight.message, (span.start, span.len()))
+        }))
+        .collect()
+}
+
+/// Sorts the diagnostics by the position of their first observation, so that they are reported
+/// in the order they appear in the sources, grouped by reef.
+///
+/// The observations of a function body are located in the content that declares the function.
+/// Diagnostics without any observation are placed first, and ties are broken by their identifier.
+pub fn sort_diagnostics(
+    externals: &Externals,
+    current_engine: &Engine,
+    engine_reef: ReefId,
+    diagnostics: &mut [Diagnostic],
+) {
+    diagnostics.sort_by_cached_key(|diagnostic| {
+        let position = diagnostic.observations.first().map(|obs| {
+            let location = &obs.location;
+            let content_id = reef_engine(externals, current_engine, engine_reef, location.reef)
+                .get_original_content(location.source)
+                .expect("Unknown source");
+            (location.reef.0, content_id.0, location.segment.start)
+        });
+        (position, diagnostic.identifier.code())
+    });
+}
+
+/// Writes the diagnostics as a JSON array, to be consumed by other tools.
+pub fn display_diagnostics_json<W: Write>(
+    diagnostics: &[Diagnostic],
+    writer: &mut W,
+) -> io::Result<()> {
+    let diagnostics = diagnostics.iter().map(diagnostic_to_json).collect();
+    writeln!(writer, "{}", Value::Array(diagnostics))
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> Value {
+    let id = diagnostic.identifier;
+    let observations = diagnostic
+        .observations
+        .iter()
+        .map(|obs| {
+            let highlights = obs
+                .highlights
+                .iter()
+                .map(|highlight| {
+                    json!({
+                        "start": highlight.segment.start,
+                        "end": highlight.segment.end,
+                        "message": highlight.message,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "source": obs.location.source.0,
+                "reef": obs.location.reef.0,
+                "start": obs.location.segment.start,
+                "end": obs.location.segment.end,
+                "message": obs.message,
+                "highlights": highlights,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "id": format!("{id:?}"),
+        "code": id.code(),
+        "critical": id.critical(),
+        "message": diagnostic.global_message,
+        "observations": observations,
+        "helps": diagnostic.helps,
+    })
+}
+
 fn write_diagnostic<W: Write>(
     diagnostic: MietteDiagnostic,
     source: Option<Source>,
@@ -136,3 +259,215 @@ fn write_diagnostic<W: Write>(
         writeln!(writer, "\n{report:?}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use analyzer::diagnostic::{Diagnostic, DiagnosticID, Observation};
+    use analyzer::engine::Engine;
+    use analyzer::reef::{Externals, Reef, ReefId};
+    use analyzer::relations::{Relations, SourceId};
+
+    use context::source::{ContentId, Source};
+    use parser::err::{ParseError, ParseErrorKind};
+    use parser::parse_trusted;
+
+    use miette::LabeledSpan;
+
+    use super::{
+        display_diagnostics_json, locate_parse_error, observation_labels, sort_diagnostics,
+        LineColumn,
+    };
+
+    fn error_at(position: usize) -> ParseError {
+        ParseError {
+            message: "Unexpected token".to_owned(),
+            position: position..position,
+            kind: ParseErrorKind::Unexpected,
+        }
+    }
+
+    #[test]
+    fn parse_error_line_column() {
+        let source = Source::unknown("val été = 1\necho )");
+        let location = locate_parse_error(&source, &error_at(source.source.find(')').unwrap()));
+        assert_eq!(location, LineColumn { line: 2, column: 6 });
+        assert_eq!(location.to_string(), "2:6");
+        assert_eq!(
+            locate_parse_error(&source, &error_at(source.source.find('=').unwrap())),
+            LineColumn { line: 1, column: 9 }
+        );
+    }
+
+    #[test]
+    fn parse_error_at_end_of_source() {
+        let source = Source::unknown("echo (\n");
+        assert_eq!(
+            locate_parse_error(&source, &error_at(source.source.len())),
+            LineColumn { line: 2, column: 1 }
+        );
+        let empty = Source::unknown("");
+        assert_eq!(
+            locate_parse_error(&empty, &error_at(0)),
+            LineColumn { line: 1, column: 1 }
+        );
+    }
+
+    /// Creates an engine that tracks a script and its first function, both from the same content.
+    fn script_engine<'a>(src: &str) -> Engine<'a> {
+        let mut engine = Engine::default();
+        let script = engine.take(parse_trusted(src));
+        engine.track(ContentId(0), script);
+        engine.track(ContentId(0), script);
+        engine
+    }
+
+    fn sorted_messages(
+        externals: &Externals,
+        engine: &Engine,
+        mut diagnostics: Vec<Diagnostic>,
+    ) -> Vec<String> {
+        sort_diagnostics(externals, engine, externals.current, &mut diagnostics);
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.global_message)
+            .collect()
+    }
+
+    #[test]
+    fn diagnostics_in_source_order() {
+        // val a: Int = 'a'
+        // val b = $c
+        let externals = Externals::default();
+        let engine = script_engine("val a: Int = 'a'\nval b = $c");
+        let diagnostics = vec![
+            Diagnostic::new(DiagnosticID::UnknownSymbol, "Could not resolve symbol `c`.")
+                .with_observation(Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    25..27,
+                    "Undefined reference",
+                )),
+            Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch").with_observation(
+                Observation::here(SourceId(0), ReefId(1), 13..16, "Found `String`"),
+            ),
+        ];
+        assert_eq!(
+            sorted_messages(&externals, &engine, diagnostics),
+            vec!["Type mismatch", "Could not resolve symbol `c`."]
+        );
+    }
+
+    #[test]
+    fn diagnostics_in_function_body() {
+        // fun f() -> Int = 'a'
+        // val b = $c
+        let externals = Externals::default();
+        let engine = script_engine("fun f() -> Int = 'a'\nval b = $c");
+        let diagnostics = vec![
+            Diagnostic::new(DiagnosticID::UnknownSymbol, "Could not resolve symbol `c`.")
+                .with_observation(Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    29..31,
+                    "Undefined reference",
+                )),
+            Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch").with_observation(
+                Observation::here(SourceId(1), ReefId(1), 17..20, "Found `String`"),
+            ),
+        ];
+        assert_eq!(
+            sorted_messages(&externals, &engine, diagnostics),
+            vec!["Type mismatch", "Could not resolve symbol `c`."]
+        );
+    }
+
+    #[test]
+    fn diagnostics_grouped_by_reef() {
+        let mut externals = Externals::default();
+        externals.register(Reef::new_partial(
+            "dep".to_owned(),
+            script_engine("val x = 0\nval b = 2"),
+            Relations::default(),
+        ));
+        let engine = script_engine("val a = 1");
+        let diagnostics = vec![
+            Diagnostic::new(DiagnosticID::UnusedSymbol, "Unused variable `a`").with_observation(
+                Observation::here(SourceId(0), ReefId(2), 4..5, "Declared here but never used"),
+            ),
+            Diagnostic::new(DiagnosticID::UnusedSymbol, "Unused variable `b`").with_observation(
+                Observation::here(
+                    SourceId(0),
+                    ReefId(1),
+                    14..15,
+                    "Declared here but never used",
+                ),
+            ),
+        ];
+        assert_eq!(
+            sorted_messages(&externals, &engine, diagnostics),
+            vec!["Unused variable `b`", "Unused variable `a`"]
+        );
+    }
+
+    #[test]
+    fn type_mismatch_json() {
+        let diagnostic = Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
+            .with_observation(Observation::here(
+                SourceId(0),
+                ReefId(1),
+                4..9,
+                "Expected `Int`, found `String`",
+            ))
+            .with_observation(Observation::context(
+                SourceId(1),
+                ReefId(1),
+                12..18,
+                "Parameter is declared here",
+            ))
+            .with_help("Convert the argument with `to_int`");
+        let mut output = Vec::new();
+        display_diagnostics_json(&[diagnostic], &mut output).expect("write failed");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"[{"code":10,"critical":true,"helps":["Convert the argument with `to_int`"],"#,
+                r#""id":"TypeMismatch","message":"Type mismatch","observations":["#,
+                r#"{"end":9,"highlights":[],"message":"Expected `Int`, found `String`","reef":1,"source":0,"start":4},"#,
+                r#"{"end":18,"highlights":[],"message":"Parameter is declared here","reef":1,"source":1,"start":12}]}]"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn observation_with_highlight() {
+        // fun f() -> Int = 'a'
+        let observation = Observation::here(SourceId(0), ReefId(1), 0..20, "Found `String`")
+            .with_highlight(11..14, "Expected `Int`")
+            .with_highlight(17..20, "Returned here");
+        assert_eq!(
+            observation_labels(observation.clone()),
+            vec![
+                LabeledSpan::new_primary_with_span(Some("Found `String`".to_owned()), (0, 20)),
+                LabeledSpan::new(Some("Expected `Int`".to_owned()), 11, 3),
+                LabeledSpan::new(Some("Returned here".to_owned()), 17, 3),
+            ]
+        );
+
+        let diagnostic = Diagnostic::new(DiagnosticID::TypeMismatch, "Type mismatch")
+            .with_observation(observation);
+        let mut output = Vec::new();
+        display_diagnostics_json(&[diagnostic], &mut output).expect("write failed");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"[{"code":10,"critical":true,"helps":[],"id":"TypeMismatch","#,
+                r#""message":"Type mismatch","observations":[{"end":20,"highlights":["#,
+                r#"{"end":14,"message":"Expected `Int`","start":11},"#,
+                r#"{"end":20,"message":"Returned here","start":17}],"#,
+                r#""message":"Found `String`","reef":1,"source":0,"start":0}]}]"#,
+                "\n",
+            )
+        );
+    }
+}
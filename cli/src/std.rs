@@ -6,6 +6,7 @@ use analyzer::reef::{Externals, Reef};
 use analyzer::relations::SourceId;
 use cli::project_dir;
 use compiler::externals::CompilerExternals;
+use compiler::CaptureCache;
 use vm::VM;
 
 use crate::cli::{use_pipeline, Cli};
@@ -32,6 +33,7 @@ pub fn build_std(
         &analyzer,
         externals,
         compiler_externals,
+        &mut CaptureCache::default(),
         vm,
         diagnostics,
         importer.take_errors(),
@@ -0,0 +1,68 @@
+use analyzer::name::Name;
+use analyzer::reef::Externals;
+use analyzer::relations::SourceId;
+use analyzer::Analyzer;
+use compiler::externals::CompilerExternals;
+use compiler::CaptureCache;
+use context::source::OwnedSource;
+use vm::VM;
+
+use crate::cli::Cli;
+use crate::pipeline::{PipelineStatus, SourcesCache};
+use crate::repl::consume;
+
+/// The attribute that marks the functions to run as tests.
+const TEST_ATTRIBUTE: &str = "test";
+
+/// Calls each function of the entry script marked with `@test`, and reports the ones that failed.
+///
+/// A test passes if its call completes without a panic.
+pub(crate) fn run_tests(
+    analyzer: &mut Analyzer<'_>,
+    externals: &Externals,
+    compiler_externals: &mut CompilerExternals,
+    captures: &mut CaptureCache,
+    vm: &mut VM,
+    sources: &mut SourcesCache,
+    config: &Cli,
+) -> PipelineStatus {
+    let tests: Vec<String> = analyzer
+        .resolution
+        .engine
+        .find_attributed_functions(SourceId(0), TEST_ATTRIBUTE)
+        .map(|(_, function)| function.name.value.to_string())
+        .collect();
+
+    let name = Name::new("test");
+    let mut failed = 0;
+    for test in &tests {
+        let status = consume(
+            &name,
+            analyzer,
+            externals,
+            compiler_externals,
+            captures,
+            vm,
+            sources,
+            config,
+            &mut Some(SourceId(0)),
+            OwnedSource::new(format!("{test}()"), test.clone()),
+        );
+        if status == PipelineStatus::Success {
+            println!("test {test} ... ok");
+        } else {
+            failed += 1;
+            println!("test {test} ... FAILED");
+        }
+    }
+    println!(
+        "\ntest result: {} passed; {failed} failed",
+        tests.len() - failed
+    );
+
+    if failed == 0 {
+        PipelineStatus::Success
+    } else {
+        PipelineStatus::ExecutionFailure
+    }
+}
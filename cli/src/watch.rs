@@ -0,0 +1,156 @@
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use analyzer::reef::Externals;
+use compiler::externals::CompilerExternals;
+use nix::libc;
+use nix::sys::signal;
+use vm::VM;
+
+use crate::cli::Cli;
+use crate::pipeline::{PipelineStatus, SourcesCache};
+use crate::run;
+
+/// The delay between two checks of the watched file.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Set when the user asked to stop watching.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn interrupt(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+/// Tracks the modification time of a file.
+pub(crate) struct FileWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            last_modified: None,
+        }
+    }
+
+    /// Returns whether the file was modified since the last call.
+    ///
+    /// The first call returns `true` if the file exists. A file that cannot be read is seen as
+    /// unchanged, until it is created again.
+    pub(crate) fn has_changed(&mut self) -> bool {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_none() || modified == self.last_modified {
+            return false;
+        }
+        self.last_modified = modified;
+        true
+    }
+}
+
+/// Runs a source file each time it is modified, until the user interrupts it.
+///
+/// The pipeline is isolated from the watcher, so that an error or a panic while handling an
+/// invalid edit is reported and waits for the next modification.
+pub(crate) fn watch(
+    source: &Path,
+    cli: &Cli,
+    mut sources: SourcesCache,
+    externals: Externals,
+    mut compiler_externals: CompilerExternals,
+    mut vm: VM,
+) -> miette::Result<PipelineStatus> {
+    #[cfg(unix)]
+    unsafe {
+        // SAFETY: The handler only stores to an atomic, which is async-signal-safe.
+        signal::sigaction(
+            signal::SIGINT,
+            &signal::SigAction::new(
+                signal::SigHandler::Handler(interrupt),
+                signal::SaFlags::empty(),
+                signal::SigSet::empty(),
+            ),
+        )
+        .expect("signal handling");
+    }
+
+    let mut watcher = FileWatcher::new(source.to_path_buf());
+    let mut status = PipelineStatus::Success;
+    while !INTERRUPTED.load(Ordering::Relaxed) {
+        if !watcher.has_changed() {
+            sleep(POLL_INTERVAL);
+            continue;
+        }
+        if io::stdout().is_terminal() {
+            // Clear the screen and move the cursor to its top left corner
+            print!("\x1B[2J\x1B[1;1H");
+            let _ = io::stdout().flush();
+        }
+
+        let outcome = catch_unwind(AssertUnwindSafe(|| {
+            run(
+                source,
+                cli,
+                &mut sources,
+                &externals,
+                &mut compiler_externals,
+                &mut vm,
+            )
+        }));
+        status = match outcome {
+            Ok(Ok(status)) => status,
+            Ok(Err(err)) => {
+                eprintln!("{err:?}");
+                eprintln!("Waiting for the next modification");
+                PipelineStatus::ExecutionFailure
+            }
+            Err(_) => {
+                eprintln!("The pipeline panicked, waiting for the next modification");
+                PipelineStatus::ExecutionFailure
+            }
+        };
+        // Forget the previous sources, so that the next run reads them again
+        sources.unregister(externals.current);
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    use super::FileWatcher;
+
+    #[test]
+    fn detect_modification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script.msh");
+        let file = File::create(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+
+        let mut watcher = FileWatcher::new(path);
+        assert!(watcher.has_changed());
+        assert!(!watcher.has_changed());
+
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(60))
+            .unwrap();
+        assert!(watcher.has_changed());
+        assert!(!watcher.has_changed());
+    }
+
+    #[test]
+    fn missing_file_is_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = FileWatcher::new(dir.path().join("missing.msh"));
+        assert!(!watcher.has_changed());
+    }
+}
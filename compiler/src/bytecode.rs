@@ -2,6 +2,7 @@ use std::mem::size_of;
 
 use num_enum::TryFromPrimitive;
 
+use crate::constant_pool::ConstantPool;
 use crate::locals::LocalsLayout;
 use crate::r#type::ValueStackSize;
 use crate::structure::StructureLayout;
@@ -9,6 +10,7 @@ use analyzer::relations::{LocalId, ResolvedSymbol};
 use analyzer::types;
 use analyzer::types::hir::Var;
 use analyzer::types::ty::TypeRef;
+use ast::value::LiteralValue;
 
 #[derive(Debug, Clone)]
 pub struct Placeholder {
@@ -40,7 +42,7 @@ impl Bytecode {
     }
 
     /// emits a signed 64 bits integer
-    pub fn emit_int(&mut self, value: i64) {
+    pub fn emit_i64(&mut self, value: i64) {
         self.bytes.extend(value.to_be_bytes());
     }
 
@@ -59,7 +61,7 @@ impl Bytecode {
     }
 
     /// emits a signed 64 bits float
-    pub fn emit_float(&mut self, value: f64) {
+    pub fn emit_f64(&mut self, value: f64) {
         self.bytes.extend(value.to_be_bytes());
     }
 
@@ -141,7 +143,7 @@ impl<'a> Instructions<'a> {
     pub fn emit_box_if_primitive(&mut self, ty: TypeRef) {
         match ty {
             types::EXITCODE | types::BOOL => self.emit_code(Opcode::BoxByte),
-            types::INT | types::FLOAT => self.emit_code(Opcode::BoxQWord),
+            types::INT | types::FLOAT | types::DURATION => self.emit_code(Opcode::BoxQWord),
             _ => { /* Objects are already on the heap */ }
         }
     }
@@ -276,7 +278,7 @@ impl<'a> Instructions<'a> {
     /// emits instructions to push an integer in the operand stack
     pub fn emit_push_int(&mut self, constant: i64) {
         self.emit_code(Opcode::PushInt);
-        self.bytecode.emit_int(constant);
+        self.bytecode.emit_i64(constant);
     }
 
     /// emits instructions to push an unsigned byte in the operand stack
@@ -288,7 +290,26 @@ impl<'a> Instructions<'a> {
     /// emits instructions to push a float in the operand stack
     pub fn emit_push_float(&mut self, constant: f64) {
         self.emit_code(Opcode::PushFloat);
-        self.bytecode.emit_float(constant)
+        self.bytecode.emit_f64(constant)
+    }
+
+    /// emits instructions to push a literal in the operand stack, with the width of its type
+    pub fn emit_push_literal(&mut self, literal: &LiteralValue, cp: &mut ConstantPool) {
+        match literal {
+            LiteralValue::String(string) => {
+                let str_ref = cp.insert_string(string);
+                self.emit_push_constant_ref(str_ref);
+            }
+            LiteralValue::Int(integer) => self.emit_push_int(*integer),
+            LiteralValue::Float(f) => self.emit_push_float(*f),
+            LiteralValue::Bool(b) => self.emit_push_byte(*b as u8),
+            LiteralValue::Duration(duration) => {
+                // durations are represented as a number of milliseconds at runtime
+                let millis = i64::try_from(duration.as_millis())
+                    .expect("duration literals should fit in an Int");
+                self.emit_push_int(millis);
+            }
+        }
     }
 
     /// emits instructions to push a pool reference in the operand stack
@@ -437,4 +458,160 @@ pub enum Opcode {
     FloatLessOrEqual,
     FloatGreaterThan,
     FloatGreaterOrEqual,
+
+    IfJumpShort,
+    IfNotJumpShort,
+    JumpShort,
+}
+
+impl Opcode {
+    /// Returns the short name of the instruction, used in textual representations of the bytecode.
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::PushInt => "ipsh",
+            Opcode::PushByte => "bpsh",
+            Opcode::PushFloat => "fpsh",
+            Opcode::PushStringRef => "srpsh",
+            Opcode::PushLocalRef => "lrpsh",
+            Opcode::BoxQWord => "qwbox",
+            Opcode::BoxByte => "bbox",
+            Opcode::Unbox => "unbox",
+            Opcode::GetLocalByte => "lbget",
+            Opcode::SetLocalByte => "lbset",
+            Opcode::GetLocalQWord => "lqwget",
+            Opcode::SetLocalQWord => "lqwset",
+            Opcode::GetRefByte => "rbget",
+            Opcode::SetRefByte => "rbset",
+            Opcode::GetRefQWord => "rqwget",
+            Opcode::SetRefQWord => "rqwset",
+            Opcode::GetStructByte => "sbget",
+            Opcode::SetStructByte => "sbset",
+            Opcode::GetStructQWord => "sqwget",
+            Opcode::SetStructQWord => "sqwset",
+            Opcode::FetchByte => "bfetch",
+            Opcode::FetchQWord => "qwfetch",
+            Opcode::StoreByte => "bstore",
+            Opcode::StoreQWord => "qwstore",
+            Opcode::NewStruct => "new",
+            Opcode::StructCopyOperands => "copy",
+            Opcode::Invoke => "invoke",
+            Opcode::Fork => "fork",
+            Opcode::Exec => "exec",
+            Opcode::Wait => "wait",
+            Opcode::Open => "open",
+            Opcode::Close => "close",
+            Opcode::SetupRedirect => "setredir",
+            Opcode::Redirect => "redir",
+            Opcode::PopRedirect => "popredir",
+            Opcode::Pipe => "pipe",
+            Opcode::Read => "read",
+            Opcode::Write => "write",
+            Opcode::Exit => "exit",
+            Opcode::Dup => "dup",
+            Opcode::DupByte => "dupb",
+            Opcode::Swap => "swap",
+            Opcode::Swap2 => "swap2",
+            Opcode::PopByte => "bpop",
+            Opcode::PopQWord => "qwpop",
+            Opcode::IfJump => "ifjmp",
+            Opcode::IfNotJump => "ifnjmp",
+            Opcode::Jump => "jmp",
+            Opcode::Return => "ret",
+            Opcode::ConvertByteToInt => "b2i",
+            Opcode::ConvertIntToByte => "i2b",
+            Opcode::BXor => "bxor",
+            Opcode::IntAdd => "iadd",
+            Opcode::IntSub => "isub",
+            Opcode::IntMul => "imul",
+            Opcode::IntDiv => "idiv",
+            Opcode::IntMod => "imod",
+            Opcode::IntNeg => "ineg",
+            Opcode::FloatAdd => "fadd",
+            Opcode::FloatSub => "fsub",
+            Opcode::FloatMul => "fmul",
+            Opcode::FloatDiv => "fdiv",
+            Opcode::FloatNeg => "fneg",
+            Opcode::IntEqual => "ieq",
+            Opcode::IntLessThan => "ilt",
+            Opcode::IntLessOrEqual => "ile",
+            Opcode::IntGreaterThan => "igt",
+            Opcode::IntGreaterOrEqual => "ige",
+            Opcode::FloatEqual => "feq",
+            Opcode::FloatLessThan => "flt",
+            Opcode::FloatLessOrEqual => "fle",
+            Opcode::FloatGreaterThan => "fgt",
+            Opcode::FloatGreaterOrEqual => "fge",
+            Opcode::IfJumpShort => "ifjmps",
+            Opcode::IfNotJumpShort => "ifnjmps",
+            Opcode::JumpShort => "jmps",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use ast::value::LiteralValue;
+
+    use crate::bytecode::{Bytecode, Instructions, Opcode};
+    use crate::constant_pool::ConstantPool;
+
+    /// Emits a literal and splits the resulting bytecode into its opcode and operand.
+    fn emit(literal: LiteralValue, cp: &mut ConstantPool) -> (Opcode, Vec<u8>) {
+        let mut bytecode = Bytecode::default();
+        Instructions::wrap(&mut bytecode).emit_push_literal(&literal, cp);
+        let (opcode, operand) = bytecode.bytes().split_first().unwrap();
+        (Opcode::try_from(*opcode).unwrap(), operand.to_vec())
+    }
+
+    #[test]
+    fn int_literal() {
+        let (opcode, operand) = emit(LiteralValue::Int(-42), &mut ConstantPool::default());
+        assert_eq!(opcode, Opcode::PushInt);
+        assert_eq!(i64::from_be_bytes(operand.try_into().unwrap()), -42);
+    }
+
+    #[test]
+    fn float_literal() {
+        let (opcode, operand) = emit(LiteralValue::Float(2.5), &mut ConstantPool::default());
+        assert_eq!(opcode, Opcode::PushFloat);
+        assert_eq!(f64::from_be_bytes(operand.try_into().unwrap()), 2.5);
+    }
+
+    #[test]
+    fn bool_literal() {
+        let mut cp = ConstantPool::default();
+        assert_eq!(
+            emit(LiteralValue::Bool(true), &mut cp),
+            (Opcode::PushByte, vec![1])
+        );
+        assert_eq!(
+            emit(LiteralValue::Bool(false), &mut cp),
+            (Opcode::PushByte, vec![0])
+        );
+    }
+
+    #[test]
+    fn duration_literal() {
+        let (opcode, operand) = emit(
+            LiteralValue::Duration(Duration::from_secs(3)),
+            &mut ConstantPool::default(),
+        );
+        assert_eq!(opcode, Opcode::PushInt);
+        assert_eq!(i64::from_be_bytes(operand.try_into().unwrap()), 3000);
+    }
+
+    #[test]
+    fn string_literal() {
+        let mut cp = ConstantPool::default();
+        let existing = cp.insert_string("echo");
+        let (opcode, operand) = emit(LiteralValue::String("hello".to_owned()), &mut cp);
+        assert_eq!(opcode, Opcode::PushStringRef);
+        let constant = u32::from_be_bytes(operand.try_into().unwrap());
+        assert_ne!(constant, existing);
+        assert_eq!(cp.insert_string("hello"), constant);
+    }
 }
@@ -0,0 +1,207 @@
+//! Compaction of the jump instructions.
+//!
+//! Jumps are emitted with an absolute 4-byte destination, as their target is often unknown when
+//! they are emitted. Most jumps only skip a few instructions though, so they are rewritten to
+//! their short form, that holds a 1-byte signed offset relative to the next instruction.
+//!
+//! Shortening a jump can only bring the other jumps closer to their destination, so the jumps
+//! are shortened until no other jump fits in the short form. The wide jumps, the source positions
+//! and the short offsets are then moved to match the compacted code.
+
+use std::mem::size_of;
+
+use crate::bytecode::{InstructionPos, Opcode};
+use crate::verify::{decode, Instruction};
+
+/// The minimum optimization level at which the jumps are compacted.
+pub(crate) const COMPACT_JUMPS_OPT_LEVEL: u8 = 1;
+
+/// The size of a short jump instruction, with its opcode and its offset.
+const SHORT_JUMP_SIZE: u32 = 1 + size_of::<i8>() as u32;
+
+/// Returns the short form of a jump instruction.
+fn short_form(opcode: Opcode) -> Option<Opcode> {
+    match opcode {
+        Opcode::IfJump => Some(Opcode::IfJumpShort),
+        Opcode::IfNotJump => Some(Opcode::IfNotJumpShort),
+        Opcode::Jump => Some(Opcode::JumpShort),
+        _ => None,
+    }
+}
+
+/// Rewrites the jumps whose destination is close enough with a short relative offset.
+///
+/// The `code` holds the instructions of a single chunk, whose jumps are relative to its start.
+/// As the short jumps are not recognized by the other passes, this one has to run last.
+/// The instruction pointers of the given positions are updated to match the returned code.
+pub(crate) fn compact_jumps(code: &[u8], positions: &mut [InstructionPos]) -> Vec<u8> {
+    let Ok(decoded) = decode(code) else {
+        return code.to_vec();
+    };
+    let mut sizes: Vec<u32> = decoded
+        .iter()
+        .enumerate()
+        .map(|(idx, instruction)| {
+            decoded
+                .get(idx + 1)
+                .map_or(code.len() as u32, |next| next.offset)
+                - instruction.offset
+        })
+        .collect();
+    let mut is_short = vec![false; decoded.len()];
+
+    let mut offsets = layout(&sizes);
+    loop {
+        let mut changed = false;
+        for (idx, instruction) in decoded.iter().enumerate() {
+            if is_short[idx] || short_form(instruction.opcode).is_none() {
+                continue;
+            }
+            let mut target = relocate(&decoded, &offsets, instruction.operand);
+            if instruction.operand > instruction.offset {
+                // the destination gets closer as the jump shrinks
+                target -= sizes[idx] - SHORT_JUMP_SIZE;
+            }
+            let next = offsets[idx] + SHORT_JUMP_SIZE;
+            if i8::try_from(i64::from(target) - i64::from(next)).is_ok() {
+                is_short[idx] = true;
+                sizes[idx] = SHORT_JUMP_SIZE;
+                offsets = layout(&sizes);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut compacted = Vec::with_capacity(*offsets.last().unwrap() as usize);
+    for (idx, instruction) in decoded.iter().enumerate() {
+        let target = relocate(&decoded, &offsets, instruction.operand);
+        if is_short[idx] {
+            let next = offsets[idx] + SHORT_JUMP_SIZE;
+            let offset = (i64::from(target) - i64::from(next)) as i8;
+            compacted.push(short_form(instruction.opcode).unwrap() as u8);
+            compacted.extend(offset.to_be_bytes());
+            continue;
+        }
+        let start = instruction.offset as usize;
+        compacted.extend_from_slice(&code[start..start + sizes[idx] as usize]);
+        if matches!(
+            instruction.opcode,
+            Opcode::IfJump | Opcode::IfNotJump | Opcode::Jump | Opcode::Fork
+        ) {
+            let operand_start = compacted.len() - size_of::<u32>();
+            compacted[operand_start..].copy_from_slice(&target.to_be_bytes());
+        }
+    }
+    for position in positions {
+        position.instruction = relocate(&decoded, &offsets, position.instruction);
+    }
+    compacted
+}
+
+/// Computes the offset of each instruction given their sizes, followed by the total size.
+fn layout(sizes: &[u32]) -> Vec<u32> {
+    let mut offsets = Vec::with_capacity(sizes.len() + 1);
+    let mut len = 0;
+    for size in sizes {
+        offsets.push(len);
+        len += size;
+    }
+    offsets.push(len);
+    offsets
+}
+
+/// Maps an original instruction offset to its offset in the given layout.
+fn relocate(decoded: &[Instruction], offsets: &[u32], offset: u32) -> u32 {
+    let idx = decoded.partition_point(|instruction| instruction.offset < offset);
+    offsets[idx]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::bytecode::{Bytecode, Instructions, Opcode};
+    use crate::compact::compact_jumps;
+    use crate::constant_pool::ConstantPool;
+    use crate::r#type::ValueStackSize;
+    use crate::verify::verify;
+
+    /// Emits an `if` whose branches push and pop the given number of integers.
+    fn emit_if(branch_len: usize) -> Bytecode {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_byte(1);
+        let jump_to_else = instructions.emit_jump(Opcode::IfNotJump);
+        for _ in 0..branch_len {
+            instructions.emit_push_int(1);
+            instructions.emit_pop(ValueStackSize::QWord);
+        }
+        let jump_to_end = instructions.emit_jump(Opcode::Jump);
+        instructions.patch_jump(jump_to_else);
+        for _ in 0..branch_len {
+            instructions.emit_push_int(2);
+            instructions.emit_pop(ValueStackSize::QWord);
+        }
+        instructions.patch_jump(jump_to_end);
+        instructions.emit_push_byte(0);
+        instructions.emit_code(Opcode::Exit);
+        bytecode
+    }
+
+    #[test]
+    fn compact_tiny_if() {
+        let bytecode = emit_if(1);
+        let compacted = compact_jumps(bytecode.bytes(), &mut []);
+        assert_eq!(compacted.len(), bytecode.len() - 2 * 3);
+
+        let mut expected = vec![Opcode::PushByte as u8, 1, Opcode::IfNotJumpShort as u8, 12];
+        expected.push(Opcode::PushInt as u8);
+        expected.extend(1i64.to_be_bytes());
+        expected.extend([Opcode::PopQWord as u8, Opcode::JumpShort as u8, 10]);
+        expected.push(Opcode::PushInt as u8);
+        expected.extend(2i64.to_be_bytes());
+        expected.extend([Opcode::PopQWord as u8, Opcode::PushByte as u8, 0]);
+        expected.push(Opcode::Exit as u8);
+        assert_eq!(compacted, expected);
+        assert_eq!(verify(&compacted, &ConstantPool::default()), Ok(()));
+    }
+
+    #[test]
+    fn keep_large_if() {
+        let bytecode = emit_if(20);
+        let compacted = compact_jumps(bytecode.bytes(), &mut []);
+        assert_eq!(compacted.len(), bytecode.len());
+        assert_eq!(compacted, bytecode.bytes());
+    }
+
+    #[test]
+    fn compact_backward_jump() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        let start = instructions.current_ip();
+        instructions.emit_push_byte(1);
+        let jump_to_end = instructions.emit_jump(Opcode::IfNotJump);
+        instructions.push_position(42);
+        instructions.jump_back_to(start);
+        instructions.patch_jump(jump_to_end);
+
+        let mut positions = instructions.take_positions();
+        let compacted = compact_jumps(bytecode.bytes(), &mut positions);
+        assert_eq!(
+            compacted,
+            vec![
+                Opcode::PushByte as u8,
+                1,
+                Opcode::IfNotJumpShort as u8,
+                2,
+                Opcode::JumpShort as u8,
+                -6i8 as u8,
+            ]
+        );
+        assert_eq!(positions[0].instruction, 4);
+        assert_eq!(verify(&compacted, &ConstantPool::default()), Ok(()));
+    }
+}
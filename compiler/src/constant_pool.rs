@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
+use context::source::{ContentId, SourceSegment};
 use indexmap::IndexSet;
 
+use crate::r#type::ValueStackSize;
+
 /// Contains the constants defined in a module constant pool
 #[derive(Default)]
 pub struct ConstantPool {
@@ -13,6 +18,11 @@ pub struct ConstantPool {
     /// All symbols names are expected to be unique and stored in the
     /// [`ConstantPool::strings`] pool.
     pub exported: Vec<ExportedSymbol>,
+
+    /// The number of bytes popped and pushed by the invoked functions, by signature index.
+    ///
+    /// It is only used to verify the emitted instructions, and is not written to the bytecode.
+    pub invocations: HashMap<u32, (u32, u32)>,
 }
 
 /// A symbol exported by a module.
@@ -24,6 +34,16 @@ pub struct ExportedSymbol {
     pub name_index: u32,
     pub page_offset: u32,
     pub is_obj_ref: bool,
+
+    /// Where the symbol is declared, if it comes from the source code.
+    pub declaration: Option<ExportedDeclaration>,
+}
+
+/// The source code location of an exported symbol, used to report clashing exports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedDeclaration {
+    pub content: ContentId,
+    pub segment: SourceSegment,
 }
 
 impl ConstantPool {
@@ -33,6 +53,24 @@ impl ConstantPool {
         u32::try_from(constant_index).expect("constant pool exceeded max capacity")
     }
 
+    /// Inserts the signature of an invoked function, with the stack sizes of its parameters and of
+    /// its return value, returning the signature's pool identifier.
+    pub fn insert_invocation(
+        &mut self,
+        signature: &str,
+        parameters: impl IntoIterator<Item = ValueStackSize>,
+        returned: ValueStackSize,
+    ) -> u32 {
+        let signature_idx = self.insert_string(signature);
+        let pops = parameters
+            .into_iter()
+            .map(|size| u32::from(u8::from(size)))
+            .sum();
+        self.invocations
+            .insert(signature_idx, (pops, u32::from(u8::from(returned))));
+        signature_idx
+    }
+
     /// Adds a new symbol that will need to be found at runtime.
     pub fn insert_dynsym(&mut self, import: &str, symbol: &str) -> u32 {
         self.strings.insert(import.to_owned());
@@ -44,12 +82,19 @@ impl ConstantPool {
     ///
     /// While this method shouldn't be used for variables that are shadowed later in the code,
     /// it is not an actual problem as the linker will resolve the correct symbol.
-    pub fn insert_exported(&mut self, symbol: &str, page_offset: u32, is_obj_ref: bool) {
+    pub fn insert_exported(
+        &mut self,
+        symbol: &str,
+        page_offset: u32,
+        is_obj_ref: bool,
+        declaration: Option<ExportedDeclaration>,
+    ) {
         let name_index = self.insert_string(symbol);
         self.exported.push(ExportedSymbol {
             name_index,
             page_offset,
             is_obj_ref,
+            declaration,
         });
         self.dynsym.insert_full(name_index);
     }
@@ -32,6 +32,9 @@ pub struct EmitterContext<'a, 'e> {
 
     /// Computed layouts of the current reef
     pub(crate) layouts: &'a Vec<StructureLayout>,
+
+    /// The optimization level of the emitted code.
+    pub(crate) opt_level: u8,
 }
 
 impl<'a, 'e> EmitterContext<'a, 'e> {
@@ -72,6 +75,15 @@ impl<'a, 'e> EmitterContext<'a, 'e> {
         }
     }
 
+    pub fn get_type_name(&self, tpe: TypeRef) -> &str {
+        let typing = if tpe.reef == self.current_reef {
+            self.typing
+        } else {
+            &self.externals.get_reef(tpe.reef).unwrap().typing
+        };
+        typing.get_type_name(tpe.type_id).unwrap()
+    }
+
     pub fn get_engine(&self, reef: ReefId) -> Option<&'a Engine<'e>> {
         if self.current_reef == reef {
             Some(self.engine)
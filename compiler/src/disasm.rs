@@ -0,0 +1,197 @@
+use std::io;
+use std::io::{Cursor, Read, Write};
+
+use crate::bytecode::{Bytecode, Opcode};
+use crate::constant_pool::ConstantPool;
+
+macro_rules! read {
+    ($read:expr, $tpe:ty) => {{
+        let mut bytes: [u8; std::mem::size_of::<$tpe>()] = [0; std::mem::size_of::<$tpe>()];
+        $read.read_exact(&mut bytes)?;
+        <$tpe>::from_be_bytes(bytes)
+    }};
+}
+
+/// counts the number of digits of an u64 in base ten
+fn digits(val: u64) -> usize {
+    val.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+/// Writes a textual representation of the constant pool and of the instructions contained in the bytecode.
+///
+/// Each instruction is prefixed by its instruction pointer, relative to the start of the bytecode.
+pub fn disassemble(
+    bytecode: &Bytecode,
+    pool: &ConstantPool,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let constants: Vec<&str> = pool.strings.iter().map(String::as_str).collect();
+    let dynamic_symbols: Vec<usize> = pool.dynsym.iter().map(|idx| *idx as usize).collect();
+
+    writeln!(writer, "Constant Pool:")?;
+    let idx_pad = digits(constants.len() as u64);
+    for (idx, str) in constants.iter().enumerate() {
+        writeln!(writer, "#{idx:<idx_pad$}: \"{str}\"")?;
+    }
+
+    writeln!(writer, "Dynamic Symbols:")?;
+    let idx_pad = digits(dynamic_symbols.len() as u64);
+    for (idx, constant_idx) in dynamic_symbols.iter().enumerate() {
+        let str = constants[*constant_idx];
+        writeln!(
+            writer,
+            "#{idx:<idx_pad$}: <constant #{constant_idx}> // {str}"
+        )?;
+    }
+
+    writeln!(writer, "Code:")?;
+    let bytes = bytecode.bytes();
+    let instruction_pad = digits(bytes.len() as u64);
+    let mut cursor = Cursor::new(bytes);
+    while cursor.position() < bytes.len() as u64 {
+        let instruction_address = cursor.position();
+        write!(writer, "#{instruction_address:<instruction_pad$}: ")?;
+        write_instruction(&mut cursor, &constants, &dynamic_symbols, writer)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Decodes the instruction at the cursor's position and writes its mnemonic and its operands.
+///
+/// Constant and dynamic symbols operands are resolved against the given tables.
+pub fn write_instruction(
+    cursor: &mut Cursor<&[u8]>,
+    constants: &[impl AsRef<str>],
+    dynamic_symbols: &[usize],
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let opcode = read!(cursor, u8);
+    let opcode = Opcode::try_from(opcode).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown opcode {opcode:#x}"),
+        )
+    })?;
+    write!(writer, "{:7} ", opcode.mnemonic())?;
+
+    let constant_padding =
+        |constant_idx: usize| (digits(constants.len() as u64) - digits(constant_idx as u64)) + 10;
+
+    match opcode {
+        Opcode::PushInt => write!(writer, "<value {}>", read!(cursor, i64)),
+        Opcode::PushByte => write!(writer, "<value {}>", read!(cursor, u8)),
+        Opcode::PushFloat => write!(writer, "<value {}>", read!(cursor, f64)),
+        Opcode::PushStringRef => {
+            let constant_idx = read!(cursor, u32) as usize;
+            let str = constants[constant_idx].as_ref();
+            let padding = constant_padding(constant_idx);
+            write!(
+                writer,
+                "<constant #{constant_idx}> {:padding$} // \"{str}\"",
+                ""
+            )
+        }
+        Opcode::PushLocalRef
+        | Opcode::GetLocalByte
+        | Opcode::GetLocalQWord
+        | Opcode::SetLocalByte
+        | Opcode::SetLocalQWord => write!(writer, "<local @{}>", read!(cursor, u32)),
+
+        Opcode::SetStructQWord
+        | Opcode::GetStructQWord
+        | Opcode::SetStructByte
+        | Opcode::GetStructByte => write!(writer, "<struct index @{}>", read!(cursor, u32)),
+
+        Opcode::NewStruct => {
+            let constant_idx = read!(cursor, u32) as usize;
+            let str = constants[constant_idx].as_ref();
+            let padding = constant_padding(constant_idx);
+            write!(
+                writer,
+                "<constant #{constant_idx}> {:padding$} // <structure> {str}",
+                ""
+            )
+        }
+        Opcode::StructCopyOperands => write!(writer, "<count @{}>", read!(cursor, u32)),
+
+        Opcode::Invoke => {
+            let constant_idx = read!(cursor, u32) as usize;
+            let str = constants[constant_idx].as_ref();
+            let padding = constant_padding(constant_idx);
+            write!(
+                writer,
+                "<constant #{constant_idx}> {:padding$} // <function> {str}",
+                ""
+            )
+        }
+        Opcode::FetchByte | Opcode::FetchQWord | Opcode::StoreByte | Opcode::StoreQWord => {
+            let dynsym_idx = read!(cursor, u32) as usize;
+            let str = constants[dynamic_symbols[dynsym_idx]].as_ref();
+            let padding = (digits(dynamic_symbols.len() as u64) - digits(dynsym_idx as u64)) + 10;
+            write!(writer, "<external #{dynsym_idx}> {:padding$} // {str}", "")
+        }
+        Opcode::Open => write!(writer, "<flags {:#x}>", read!(cursor, i32)),
+        Opcode::IfJump | Opcode::IfNotJump | Opcode::Jump | Opcode::Fork => {
+            write!(writer, "<instruction #{}>", read!(cursor, u32))
+        }
+        Opcode::IfJumpShort | Opcode::IfNotJumpShort | Opcode::JumpShort => {
+            write!(writer, "<offset {:+}>", read!(cursor, i8))
+        }
+        _ => Ok(()), // Other opcodes do not define parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::bytecode::{Bytecode, Instructions, Opcode};
+    use crate::constant_pool::ConstantPool;
+    use crate::disasm::disassemble;
+    use crate::r#type::ValueStackSize;
+
+    #[test]
+    fn disassemble_instructions() {
+        let mut bytecode = Bytecode::default();
+        let mut pool = ConstantPool::default();
+        let message = pool.insert_string("hello");
+        let echo = pool.insert_string("echo");
+        pool.insert_exported("count", 0, false, None);
+
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        let start = instructions.current_ip();
+        instructions.emit_push_constant_ref(echo);
+        instructions.emit_push_constant_ref(message);
+        instructions.emit_code(Opcode::Exec);
+        instructions.emit_push_byte(2);
+        instructions.emit_code(Opcode::PopByte);
+        instructions.emit_push_int(7);
+        instructions.emit_set_external(0, ValueStackSize::QWord);
+        instructions.jump_back_to(start);
+
+        let mut out = Vec::new();
+        disassemble(&bytecode, &pool, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out.lines().map(str::trim_end).collect::<Vec<_>>(),
+            vec![
+                "Constant Pool:",
+                "#0: \"hello\"",
+                "#1: \"echo\"",
+                "#2: \"count\"",
+                "Dynamic Symbols:",
+                "#0: <constant #2> // count",
+                "Code:",
+                "#0 : srpsh   <constant #1>            // \"echo\"",
+                "#5 : srpsh   <constant #0>            // \"hello\"",
+                "#10: exec",
+                "#11: bpsh    <value 2>",
+                "#13: bpop",
+                "#14: ipsh    <value 7>",
+                "#23: qwstore <external #0>            // count",
+                "#28: jmp     <instruction #0>",
+            ]
+        );
+    }
+}
@@ -1,22 +1,25 @@
-use analyzer::relations::LocalId;
-use analyzer::types::hir::{Declaration, ExprKind, TypedExpr, Var};
-use analyzer::types::ty::TypeRef;
-use ast::value::LiteralValue;
+use analyzer::reef::ReefId;
+use analyzer::relations::{LocalId, SourceId};
+use analyzer::types::hir::{Declaration, ExprKind, MethodCall, TypedExpr, Var};
+use analyzer::types::ty::{FunctionKind, Type, TypeRef};
+use context::source::SourceSegment;
 
 use crate::bytecode::{Instructions, Opcode, Placeholder};
-use crate::constant_pool::ConstantPool;
+use crate::constant_pool::{ConstantPool, ExportedDeclaration};
 use crate::context::EmitterContext;
+use crate::emit::fold::{fold_constant, FOLD_OPT_LEVEL};
 use crate::emit::identifier::{expose_variable, Identifier};
 use crate::emit::invoke::{
-    emit_capture, emit_function_invocation, emit_pipeline, emit_process_call, emit_redirect,
-    emit_subprocess, emit_substitution,
+    emit_capture, emit_function_invocation, emit_method_invocation, emit_pipeline,
+    emit_process_call, emit_redirect, emit_subprocess, emit_substitution, emit_vector,
 };
-use crate::emit::jump::{emit_break, emit_conditional, emit_continue, emit_loop};
+use crate::emit::jump::{emit_break, emit_conditional, emit_continue, emit_loop, emit_match};
 use crate::emit::native::emit_natives;
-use crate::emit::structure::{emit_field_access, emit_field_assign};
+use crate::emit::structure::{emit_field_access, emit_field_assign, emit_tuple};
 use crate::locals::LocalsLayout;
 use crate::r#type::ValueStackSize;
 
+mod fold;
 mod identifier;
 mod invoke;
 mod iterable;
@@ -34,6 +37,11 @@ pub struct EmissionState {
     // first instruction pointer after the loop.
     pub enclosing_loop_end_placeholders: Vec<Placeholder>,
 
+    /// The jumps that target a loop enclosing the current one.
+    ///
+    /// Once the current loop is emitted, they are handed over to its parent state.
+    pub outer_loop_jumps: Vec<OuterLoopJump>,
+
     /// A collection of file descriptors that have been opened in the current scope.
     ///
     /// Some operations require to delay the closing of the file descriptor until the end of the
@@ -45,12 +53,51 @@ pub struct EmissionState {
     pub use_values: bool,
 }
 
+/// A `continue` or a `break` that leaves one or more loops.
+#[derive(Debug, Clone)]
+pub struct OuterLoopJump {
+    /// The number of loops to leave before reaching the targeted one.
+    pub depth: usize,
+
+    /// Whether the jump exits the targeted loop, or continues to its next iteration.
+    pub is_break: bool,
+
+    pub placeholder: Placeholder,
+}
+
 impl EmissionState {
     /// Create a new emission state for a loop.
     pub fn in_loop() -> Self {
         Self::default()
     }
 
+    /// Hands over the jumps of a finished nested loop that target the enclosing loops.
+    pub fn forward_outer_loop_jumps(&mut self, jumps: Vec<OuterLoopJump>) {
+        for jump in jumps {
+            match jump {
+                OuterLoopJump {
+                    depth: 1,
+                    is_break: false,
+                    placeholder,
+                } => self.enclosing_loop_start_placeholders.push(placeholder),
+                OuterLoopJump {
+                    depth: 1,
+                    is_break: true,
+                    placeholder,
+                } => self.enclosing_loop_end_placeholders.push(placeholder),
+                OuterLoopJump {
+                    depth,
+                    is_break,
+                    placeholder,
+                } => self.outer_loop_jumps.push(OuterLoopJump {
+                    depth: depth - 1,
+                    is_break,
+                    placeholder,
+                }),
+            }
+        }
+    }
+
     /// sets use_values to given value, and return last value
     pub fn use_values(&mut self, used: bool) -> bool {
         let last_state = self.use_values;
@@ -59,24 +106,6 @@ impl EmissionState {
     }
 }
 
-fn emit_literal(literal: &LiteralValue, instructions: &mut Instructions, cp: &mut ConstantPool) {
-    match literal {
-        LiteralValue::String(string) => {
-            let str_ref = cp.insert_string(string);
-            instructions.emit_push_constant_ref(str_ref);
-        }
-        LiteralValue::Int(integer) => {
-            instructions.emit_push_int(*integer);
-        }
-        LiteralValue::Float(f) => {
-            instructions.emit_push_float(*f);
-        }
-        LiteralValue::Bool(b) => {
-            instructions.emit_push_byte(*b as u8);
-        }
-    };
-}
-
 fn emit_ref(
     var: Var,
     ctx: &EmitterContext,
@@ -104,6 +133,7 @@ fn emit_ref(
 
 fn emit_declaration(
     declaration: &Declaration,
+    segment: SourceSegment,
     instructions: &mut Instructions,
     ctx: &EmitterContext,
     cp: &mut ConstantPool,
@@ -126,7 +156,11 @@ fn emit_declaration(
             let offset = locals
                 .get_index(declaration.identifier)
                 .expect("Variable just have been declared");
-            cp.insert_exported(&variable.name, offset, value.ty.is_obj());
+            let declaration = ctx
+                .engine
+                .get_original_content(ctx.chunk_id)
+                .map(|content| ExportedDeclaration { content, segment });
+            cp.insert_exported(&variable.name, offset, value.ty.is_obj(), declaration);
         }
 
         emit_assignment(
@@ -209,6 +243,24 @@ fn emit_return(
     instructions.emit_code(Opcode::Return);
 }
 
+/// Gets the reef and the chunk of a method declared in an `impl` block.
+///
+/// Returns [`None`] if the method is native.
+fn find_user_method(method: &MethodCall, ctx: &EmitterContext) -> Option<(ReefId, SourceId)> {
+    let reef = match ctx.get_type(method.callee.ty) {
+        Type::Instantiated(base, _) => base.reef,
+        _ => method.callee.ty.reef,
+    };
+    match ctx.get_function(reef, method.function_id)?.kind {
+        FunctionKind::Method { source, .. } => Some((reef, source)),
+        _ => None,
+    }
+}
+
+/// Emits the instructions of a typed expression.
+///
+/// The emission recurses into the nested expressions, whose depth is bounded by the recursion
+/// limit of the analysis that typed them.
 pub fn emit(
     expr: &TypedExpr,
     instructions: &mut Instructions,
@@ -220,16 +272,25 @@ pub fn emit(
     instructions.push_position(expr.segment.start);
     match &expr.kind {
         ExprKind::Declare(d) => {
-            emit_declaration(d, instructions, ctx, cp, locals, state);
+            emit_declaration(
+                d,
+                expr.segment.clone(),
+                instructions,
+                ctx,
+                cp,
+                locals,
+                state,
+            );
         }
         ExprKind::Block(exprs) => {
             emit_block(exprs, instructions, ctx, cp, locals, state);
         }
         ExprKind::Conditional(c) => emit_conditional(c, instructions, ctx, cp, locals, state),
+        ExprKind::Match(m) => emit_match(m, expr.ty, instructions, ctx, cp, locals, state),
         ExprKind::ConditionalLoop(l) => emit_loop(l, instructions, ctx, cp, locals, state),
         ExprKind::ForLoop(l) => iterable::emit_for_loop(l, instructions, ctx, cp, locals, state),
-        ExprKind::Continue => emit_continue(instructions, state),
-        ExprKind::Break => emit_break(instructions, state),
+        ExprKind::Continue(depth) => emit_continue(*depth, instructions, state),
+        ExprKind::Break(depth) => emit_break(*depth, instructions, state),
         ExprKind::Return(val) => emit_return(val, instructions, ctx, cp, locals, state),
         ExprKind::LocalAssign(ass) => emit_assignment(
             &ass.rhs,
@@ -253,7 +314,7 @@ pub fn emit(
         }
         ExprKind::Literal(literal) => {
             if state.use_values {
-                emit_literal(literal, instructions, cp);
+                instructions.emit_push_literal(literal, cp);
             }
         }
         ExprKind::FunctionCall(fc) => {
@@ -262,21 +323,48 @@ pub fn emit(
         ExprKind::ProcessCall(args) => {
             emit_process_call(args, &[], instructions, ctx, cp, locals, state)
         }
-        ExprKind::MethodCall(method) => emit_natives(
-            method.function_id,
-            method,
-            expr.ty,
-            instructions,
-            ctx,
-            cp,
-            locals,
-            state,
-        ),
+        ExprKind::MethodCall(method) => {
+            if let Some((reef, source)) = find_user_method(method, ctx) {
+                emit_method_invocation(
+                    method,
+                    reef,
+                    source,
+                    expr.ty,
+                    instructions,
+                    ctx,
+                    cp,
+                    locals,
+                    state,
+                );
+            } else {
+                let folded = if ctx.opt_level >= FOLD_OPT_LEVEL {
+                    fold_constant(expr)
+                } else {
+                    None
+                };
+                if let Some(literal) = folded {
+                    if state.use_values {
+                        instructions.emit_push_literal(&literal, cp);
+                    }
+                } else {
+                    emit_natives(
+                        method.function_id,
+                        method,
+                        expr.ty,
+                        instructions,
+                        ctx,
+                        cp,
+                        locals,
+                        state,
+                    )
+                }
+            }
+        }
         ExprKind::Redirect(redirect) => {
             emit_redirect(redirect, instructions, ctx, cp, locals, state)
         }
-        ExprKind::Pipeline(commands) => {
-            emit_pipeline(commands, instructions, ctx, cp, locals, state)
+        ExprKind::Pipeline(pipeline) => {
+            emit_pipeline(pipeline, instructions, ctx, cp, locals, state)
         }
         ExprKind::Capture(capture) => {
             emit_capture(capture, instructions, ctx, cp, locals, state);
@@ -287,6 +375,12 @@ pub fn emit(
         ExprKind::Substitute(substitution) => {
             emit_substitution(substitution, instructions, ctx, cp, locals, state);
         }
+        ExprKind::Vector(elements) => {
+            emit_vector(elements, instructions, ctx, cp, locals, state);
+        }
+        ExprKind::Tuple(elements) => {
+            emit_tuple(elements, expr.ty, instructions, ctx, cp, locals, state);
+        }
         ExprKind::Noop => {}
         ExprKind::Convert(_) => unimplemented!(),
     }
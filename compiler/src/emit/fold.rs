@@ -0,0 +1,10 @@
+//! Constant folding of the native numeric operators.
+//!
+//! The folding is shared with the analysis, that reports the integer operations between constants
+//! that overflow. An operation that cannot be folded safely is emitted as is, so that any error is
+//! still raised when the expression gets evaluated.
+
+pub(super) use analyzer::types::fold::fold_constant;
+
+/// The minimum optimization level at which constant arithmetic expressions are folded.
+pub(super) const FOLD_OPT_LEVEL: u8 = 1;
@@ -1,22 +1,33 @@
 use libc::{O_APPEND, O_CREAT, O_RDONLY, O_RDWR, O_TRUNC, O_WRONLY};
 
-use analyzer::relations::ResolvedSymbol;
+use analyzer::reef::ReefId;
+use analyzer::relations::{ResolvedSymbol, SourceId};
 use analyzer::types::hir::{
-    ExprKind, FunctionCall, Redir, Redirect, Subprocess, Substitute, TypedExpr, Var,
+    ExprKind, FunctionCall, MethodCall, Pipeline, Redir, Redirect, Subprocess, Substitute,
+    TypedExpr, Var,
 };
-use analyzer::types::ty::{FunctionKind, Type, TypeRef};
+use analyzer::types::ty::{FunctionDesc, FunctionKind, Type, TypeRef};
 use analyzer::types::{GENERIC_VECTOR, INT, STRING};
-use ast::call::{RedirFd, RedirOp};
+use ast::call::{PipeKind, RedirFd, RedirOp};
 
 use crate::bytecode::{Instructions, Opcode};
 use crate::constant_pool::ConstantPool;
 use crate::emit;
-use crate::emit::native::{VEC_EXTEND, VEC_PUSH};
+use crate::emit::native::{Native, VEC_EXTEND, VEC_PUSH};
 use crate::emit::{EmissionState, EmitterContext};
 use crate::locals::LocalsLayout;
 use crate::r#type::ValueStackSize;
 
-const NEW_VEC: &str = "std::new_vec";
+const NEW_VEC: Native = Native {
+    name: "std::new_vec",
+    parameters: &[],
+    returned: ValueStackSize::QWord,
+};
+const GET_FD_PATH: Native = Native {
+    name: "std::process::get_fd_path",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
 
 /// Emit any expression, knowing that we are already in a forked process.
 ///
@@ -168,19 +179,42 @@ fn emit_arguments(
     state: &mut EmissionState,
 ) {
     let last_use = state.use_values(true);
-    instructions.emit_invoke(cp.insert_string(NEW_VEC));
+    instructions.emit_invoke(NEW_VEC.signature(cp));
     for arg in arguments {
         instructions.emit_code(Opcode::Dup);
         emit(arg, instructions, ctx, cp, locals, state);
         if arg.ty == STRING {
-            instructions.emit_invoke(cp.insert_string(VEC_PUSH));
+            instructions.emit_invoke(VEC_PUSH.signature(cp));
         } else {
-            instructions.emit_invoke(cp.insert_string(VEC_EXTEND));
+            instructions.emit_invoke(VEC_EXTEND.signature(cp));
         }
     }
     state.use_values(last_use);
 }
 
+/// Emits each element and creates a vector containing them.
+pub fn emit_vector(
+    elements: &[TypedExpr],
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+    state: &mut EmissionState,
+) {
+    let last_use = state.use_values(true);
+    instructions.emit_invoke(NEW_VEC.signature(cp));
+    for element in elements {
+        instructions.emit_code(Opcode::Dup);
+        emit(element, instructions, ctx, cp, locals, state);
+        instructions.emit_box_if_primitive(element.ty);
+        instructions.emit_invoke(VEC_PUSH.signature(cp));
+    }
+    state.use_values(last_use);
+    if !state.use_values {
+        instructions.emit_pop(ValueStackSize::QWord);
+    }
+}
+
 pub fn emit_function_invocation(
     function_call: &FunctionCall,
     call_return_type: TypeRef,
@@ -196,11 +230,36 @@ pub fn emit_function_invocation(
         .get_function(function_call.reef, function_call.function_id)
         .unwrap();
 
-    for (arg, parameter) in function_call.arguments.iter().zip(&function.parameters) {
-        emit(arg, instructions, ctx, cp, locals, state);
-        // The parameter is an object but the argument isn't: may be an argument passed to a generic parameter
-        if parameter.ty.is_obj() && !arg.ty.is_obj() {
-            instructions.emit_box_if_primitive(arg.ty)
+    if let Some(evaluation_order) = &function_call.evaluation_order {
+        // Named arguments are evaluated in the order they are written, then passed in the
+        // order of the parameters
+        let mut evaluated = vec![None; function_call.arguments.len()];
+        for &index in evaluation_order {
+            let arg = &function_call.arguments[index];
+            emit(arg, instructions, ctx, cp, locals, state);
+            let local = locals.push_value_space(arg.ty);
+            instructions.emit_set_local(local, arg.ty.into(), locals);
+            evaluated[index] = Some(local);
+        }
+        for ((arg, parameter), local) in function_call
+            .arguments
+            .iter()
+            .zip(&function.parameters)
+            .zip(evaluated)
+        {
+            let local = local.expect("every argument should be evaluated");
+            instructions.emit_get_local(local, arg.ty.into(), locals);
+            if parameter.ty.is_obj() && !arg.ty.is_obj() {
+                instructions.emit_box_if_primitive(arg.ty)
+            }
+        }
+    } else {
+        for (arg, parameter) in function_call.arguments.iter().zip(&function.parameters) {
+            emit(arg, instructions, ctx, cp, locals, state);
+            // The parameter is an object but the argument isn't: may be an argument passed to a generic parameter
+            if parameter.ty.is_obj() && !arg.ty.is_obj() {
+                instructions.emit_box_if_primitive(arg.ty)
+            }
         }
     }
 
@@ -241,49 +300,116 @@ pub fn emit_function_invocation(
         // thus we can init it from all the pushed constructor's parameters in the operands
         instructions.emit_copy_operands(layout.total_size);
     } else {
-        let (env, captures) = {
-            let fun_source = function_call
-                .source_id
-                .expect("cannot invoke functions with no environment");
-            let captures: &[ResolvedSymbol] = if function_call.reef != ctx.current_reef {
-                &[]
-            } else {
-                ctx.captures[fun_source.0]
-                    .as_ref()
-                    .expect("undefined captures when the function is emitted")
-            };
-            let env = ctx
-                .get_engine(function_call.reef)
-                .unwrap()
-                .get_environment(fun_source)
-                .unwrap();
-            (env, captures)
-        };
+        let fun_source = function_call
+            .source_id
+            .expect("cannot invoke functions with no environment");
+        emit_chunk_invoke(
+            function_call.reef,
+            fun_source,
+            function,
+            instructions,
+            ctx,
+            cp,
+            locals,
+        );
+    }
 
-        for capture in captures {
-            if capture.source == ctx.chunk_id {
-                // if its a local value hosted by the caller frame, create a reference
-                // to the value
-                instructions.emit_push_stack_ref(Var::Local(capture.object_id), locals);
-            } else {
-                // if its a captured variable, get the reference's value from locals
-                instructions.emit_push_stack_ref(Var::External(*capture), locals);
-                instructions.emit_code(Opcode::GetRefQWord);
-            }
+    emit_returned_value(function.return_type, call_return_type, instructions, state);
+}
+
+/// Emits the invocation of a method declared in an `impl` block.
+///
+/// The receiver is passed as the first argument of the method's chunk.
+#[allow(clippy::too_many_arguments)]
+pub fn emit_method_invocation(
+    method_call: &MethodCall,
+    reef: ReefId,
+    method_source: SourceId,
+    call_return_type: TypeRef,
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+    state: &mut EmissionState,
+) {
+    let last_used = state.use_values(true);
+
+    let method = ctx.get_function(reef, method_call.function_id).unwrap();
+
+    emit(&method_call.callee, instructions, ctx, cp, locals, state);
+    for (arg, parameter) in method_call.arguments.iter().zip(&method.parameters) {
+        emit(arg, instructions, ctx, cp, locals, state);
+        if parameter.ty.is_obj() && !arg.ty.is_obj() {
+            instructions.emit_box_if_primitive(arg.ty)
         }
+    }
+
+    state.use_values(last_used);
+
+    emit_chunk_invoke(reef, method_source, method, instructions, ctx, cp, locals);
+    emit_returned_value(method.return_type, call_return_type, instructions, state);
+}
+
+/// Pushes the captures of a chunk and invokes it, once its arguments are on the stack.
+fn emit_chunk_invoke(
+    reef: ReefId,
+    chunk_source: SourceId,
+    function: &FunctionDesc,
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+) {
+    let captures: &[ResolvedSymbol] = if reef != ctx.current_reef {
+        &[]
+    } else {
+        ctx.captures[chunk_source.0]
+            .as_ref()
+            .expect("undefined captures when the function is emitted")
+    };
+    let env = ctx
+        .get_engine(reef)
+        .unwrap()
+        .get_environment(chunk_source)
+        .unwrap();
 
-        let signature_idx = cp.insert_string(&env.fqn);
-        instructions.emit_invoke(signature_idx);
+    for capture in captures {
+        if capture.source == ctx.chunk_id {
+            // if its a local value hosted by the caller frame, create a reference
+            // to the value
+            instructions.emit_push_stack_ref(Var::Local(capture.object_id), locals);
+        } else {
+            // if its a captured variable, get the reference's value from locals
+            instructions.emit_push_stack_ref(Var::External(*capture), locals);
+            instructions.emit_code(Opcode::GetRefQWord);
+        }
     }
 
-    let return_type = function.return_type;
-    let return_type_size = return_type.into();
+    // the receiver and the arguments are followed by the captures, passed as references
+    let receiver = match function.kind {
+        FunctionKind::Method { receiver_ty, .. } => Some(ValueStackSize::from(receiver_ty)),
+        _ => None,
+    };
+    let parameters = receiver
+        .into_iter()
+        .chain(function.parameters.iter().map(|param| param.ty.into()))
+        .chain(captures.iter().map(|_| ValueStackSize::QWord));
+    let signature_idx = cp.insert_invocation(&env.fqn, parameters, function.return_type.into());
+    instructions.emit_invoke(signature_idx);
+}
 
+/// Handles the value returned by an invocation, depending on whether it is used.
+fn emit_returned_value(
+    return_type: TypeRef,
+    call_return_type: TypeRef,
+    instructions: &mut Instructions,
+    state: &EmissionState,
+) {
     // The Invoke operation will push the return value onto the stack
     if !state.use_values {
         // in order to maintain the stack's size, we instantly pop
         // the stack if the value isn't used later in the code
-        instructions.emit_pop(return_type_size);
+        instructions.emit_pop(return_type.into());
     } else if return_type.is_obj() && !call_return_type.is_obj() {
         // The function's declared return type is an object but the call return type is not: it's a boxed return value
         instructions.emit_code(Opcode::Unbox);
@@ -425,8 +551,10 @@ fn emit_redir_self(
 /// process's stdout. After each process is launched, the parent process waits
 /// for them to finish, and returns the exit code of the last process, or the
 /// exit code of the first failing process.
+///
+/// A `|&` pipe also binds the writing process stderr to the pipe.
 pub fn emit_pipeline(
-    pipeline: &[TypedExpr],
+    pipeline: &Pipeline,
     instructions: &mut Instructions,
     ctx: &EmitterContext,
     cp: &mut ConstantPool,
@@ -436,22 +564,19 @@ pub fn emit_pipeline(
     // Pipelines work by creating N - 1 pipes between the N commands.
     // Two pipes may have to be kept on top of the stack at the same time,
     // so we need to close them as soon as possible.
+    let Pipeline { commands, pipes } = pipeline;
     assert!(
-        pipeline.len() > 1,
+        commands.len() > 1,
         "Cannot compile pipeline with less than 2 commands"
     );
     let last = state.use_values(true);
-    let (first, commands) = pipeline.split_first().expect("Empty pipeline");
+    let (first, commands) = commands.split_first().expect("Empty pipeline");
 
     instructions.emit_code(Opcode::Pipe);
 
     // First process:
     let jump_to_parent = instructions.emit_jump(Opcode::Fork);
-    // Bound this children process stdout to the writing end of the pipe, that is on top of the stack
-    instructions.emit_push_int(1);
-    instructions.emit_code(Opcode::Redirect);
-    instructions.emit_code(Opcode::Close); // Close the pipe's writing end, that we just bound to stdout
-    instructions.emit_code(Opcode::Close); // Close the pipe's reading end, since we don't need it
+    emit_pipe_output(pipes[0], instructions);
 
     emit_already_forked(first, instructions, ctx, cp, locals, state);
     emit_process_end(Some(first), instructions);
@@ -482,11 +607,7 @@ pub fn emit_pipeline(
         let jump_to_parent = instructions.emit_jump(Opcode::Fork);
 
         if it.peek().is_some() {
-            // Bound this children process stdout to the writing end of the pipe, that is on top of the stack
-            instructions.emit_push_int(1);
-            instructions.emit_code(Opcode::Redirect);
-            instructions.emit_code(Opcode::Close); // Close the pipe's writing end, that we just bound to stdout
-            instructions.emit_code(Opcode::Close); // Close the pipe's reading end, since we don't need it
+            emit_pipe_output(pipes[window + 1], instructions);
         }
 
         // Bound this children process stdin to the reading end of the pipe, that is on top of the stack
@@ -510,7 +631,7 @@ pub fn emit_pipeline(
         instructions.emit_code(Opcode::Wait);
         // Convert the exit code to a int to be able to swap it
         instructions.emit_code(Opcode::ConvertByteToInt);
-        for _ in 1..pipeline.len() {
+        for _ in 1..pipeline.commands.len() {
             instructions.emit_code(Opcode::Swap);
             instructions.emit_code(Opcode::Wait);
             instructions.emit_code(Opcode::DupByte);
@@ -527,13 +648,27 @@ pub fn emit_pipeline(
         }
         instructions.emit_code(Opcode::ConvertIntToByte);
     } else {
-        for _ in 0..pipeline.len() {
+        for _ in 0..pipeline.commands.len() {
             instructions.emit_code(Opcode::Wait);
             instructions.emit_code(Opcode::PopByte);
         }
     }
 }
 
+/// Bind the outputs of a forked pipeline process to the pipe on top of the stack.
+fn emit_pipe_output(pipe: PipeKind, instructions: &mut Instructions) {
+    // Bound this children process stdout to the writing end of the pipe, that is on top of the stack
+    instructions.emit_push_int(1);
+    instructions.emit_code(Opcode::Redirect);
+    if pipe == PipeKind::StdoutAndStderr {
+        // Also bound stderr to the same writing end
+        instructions.emit_push_int(2);
+        instructions.emit_code(Opcode::Redirect);
+    }
+    instructions.emit_code(Opcode::Close); // Close the pipe's writing end, that we just bound to stdout
+    instructions.emit_code(Opcode::Close); // Close the pipe's reading end, since we don't need it
+}
+
 pub fn emit_capture(
     commands: &[TypedExpr],
     instructions: &mut Instructions,
@@ -614,7 +749,7 @@ pub fn emit_substitution(
     instructions.emit_set_local(local, INT.into(), locals);
     if state.use_values {
         instructions.emit_get_local(local, INT.into(), locals);
-        instructions.emit_invoke(cp.insert_string("std::process::get_fd_path"));
+        instructions.emit_invoke(GET_FD_PATH.signature(cp));
     }
     // Save the fd to close it later (when the callee has finished)
     state.opened_files.push(local);
@@ -1,6 +1,7 @@
 use crate::bytecode::{Instructions, Opcode};
 use crate::constant_pool::ConstantPool;
 use crate::context::EmitterContext;
+use crate::emit::fold::fold_constant;
 use crate::emit::native::{STRING_INDEX, STRING_LEN, VEC_INDEX, VEC_LEN};
 use crate::emit::{emit, EmissionState};
 use crate::locals::LocalsLayout;
@@ -9,9 +10,16 @@ use analyzer::reef::ReefId;
 use analyzer::relations::LocalId;
 use analyzer::types::builtin::STRING_STRUCT;
 use analyzer::types::engine::StructureId;
-use analyzer::types::hir::{ForKind, ForLoop, RangeFor, TypedExpr};
+use analyzer::types::hir::{ExprKind, ForKind, ForLoop, RangeFor, TypedExpr};
 use analyzer::types::ty::Type;
 use analyzer::types::{GENERIC_VECTOR, INT, STRING};
+use ast::value::LiteralValue;
+
+/// The minimum optimization level at which constant ranges loops are unrolled.
+const UNROLL_OPT_LEVEL: u8 = 1;
+
+/// The maximum number of iterations of a loop that can be unrolled.
+const MAX_UNROLLED_ITERATIONS: i64 = 8;
 
 pub(super) fn emit_for_loop(
     it: &ForLoop,
@@ -35,15 +43,17 @@ pub(super) fn emit_for_loop(
                             instructions.emit_push_int(0);
                         },
                         |instructions, cp| {
-                            instructions.emit_invoke(cp.insert_string(VEC_INDEX));
+                            instructions.emit_invoke(VEC_INDEX.signature(cp));
                             if !param.is_obj() {
                                 instructions.emit_code(Opcode::Unbox);
                             }
                         },
                         |instructions, cp| {
-                            instructions.emit_invoke(cp.insert_string(VEC_LEN));
+                            instructions.emit_invoke(VEC_LEN.signature(cp));
+                        },
+                        |_, instructions, _| {
+                            instructions.emit_code(Opcode::IntLessThan);
                         },
-                        Opcode::IntLessThan,
                         |_, instructions, _, _| {
                             instructions.emit_push_int(1);
                         },
@@ -62,15 +72,17 @@ pub(super) fn emit_for_loop(
                             instructions.emit_push_int(0);
                         },
                         |instructions, cp| {
-                            instructions.emit_invoke(cp.insert_string(STRING_INDEX));
+                            instructions.emit_invoke(STRING_INDEX.signature(cp));
                         },
                         |instructions, cp| {
-                            instructions.emit_invoke(cp.insert_string(STRING_LEN));
+                            instructions.emit_invoke(STRING_LEN.signature(cp));
+                        },
+                        |_, instructions, _| {
+                            instructions.emit_code(Opcode::IntLessThan);
                         },
-                        Opcode::IntLessThan,
                         |_, instructions, cp, locals| {
                             instructions.emit_get_local(range.receiver, STRING.into(), locals);
-                            instructions.emit_invoke(cp.insert_string(STRING_LEN));
+                            instructions.emit_invoke(STRING_LEN.signature(cp));
                         },
                         instructions,
                         ctx,
@@ -81,6 +93,23 @@ pub(super) fn emit_for_loop(
                 }
                 Type::Structure(_, structure_id) => {
                     // Int range
+                    let inclusive = *structure_id != StructureId(0);
+                    if ctx.opt_level >= UNROLL_OPT_LEVEL {
+                        if let Some(values) = constant_range_values(&range.iterable, inclusive) {
+                            if !has_loop_jumps(&it.body) {
+                                emit_unrolled(
+                                    range,
+                                    &it.body,
+                                    values,
+                                    instructions,
+                                    ctx,
+                                    cp,
+                                    locals,
+                                );
+                                return;
+                            }
+                        }
+                    }
                     let layout = ctx.get_layout(ReefId(1), *structure_id);
                     emit_for_iterable(
                         range,
@@ -97,10 +126,40 @@ pub(super) fn emit_for_loop(
                         |instructions, _| {
                             instructions.emit_get_field(LocalId(1), layout);
                         },
-                        if *structure_id == StructureId(0) {
-                            Opcode::IntLessThan
-                        } else {
-                            Opcode::IntLessOrEqual
+                        |iterator_id, instructions, locals| {
+                            // A step that is only known at runtime may be zero, which would
+                            // never reach the end: such a range is empty
+                            instructions.emit_get_local(iterator_id, type_ref.into(), locals);
+                            instructions.emit_get_field(LocalId(2), layout);
+                            instructions.emit_push_int(0);
+                            instructions.emit_code(Opcode::IntEqual);
+                            let jump_to_zero = instructions.emit_jump(Opcode::IfJump);
+
+                            // Descending ranges are iterated while the index is above the end
+                            instructions.emit_get_local(iterator_id, type_ref.into(), locals);
+                            instructions.emit_get_field(LocalId(2), layout);
+                            instructions.emit_push_int(0);
+                            instructions.emit_code(Opcode::IntLessThan);
+                            let jump_to_descending = instructions.emit_jump(Opcode::IfJump);
+                            instructions.emit_code(if inclusive {
+                                Opcode::IntLessOrEqual
+                            } else {
+                                Opcode::IntLessThan
+                            });
+                            let jump_to_end = instructions.emit_jump(Opcode::Jump);
+                            instructions.patch_jump(jump_to_descending);
+                            instructions.emit_code(if inclusive {
+                                Opcode::IntGreaterOrEqual
+                            } else {
+                                Opcode::IntGreaterThan
+                            });
+                            let jump_over_zero = instructions.emit_jump(Opcode::Jump);
+                            instructions.patch_jump(jump_to_zero);
+                            instructions.emit_pop(ValueStackSize::QWord);
+                            instructions.emit_pop(ValueStackSize::QWord);
+                            instructions.emit_push_byte(0);
+                            instructions.patch_jump(jump_to_end);
+                            instructions.patch_jump(jump_over_zero);
                         },
                         |iterator_id, instructions, _, locals| {
                             instructions.emit_get_local(iterator_id, type_ref.into(), locals);
@@ -145,6 +204,7 @@ pub(super) fn emit_for_loop(
             for jump_to_end in loop_state.enclosing_loop_end_placeholders {
                 instructions.patch_jump(jump_to_end);
             }
+            state.forward_outer_loop_jumps(loop_state.outer_loop_jumps);
         }
     }
 }
@@ -154,6 +214,7 @@ pub(super) fn emit_for_iterable<
     V: FnOnce(LocalId, &mut Instructions, &mut ConstantPool, &mut LocalsLayout),
     F: FnOnce(&mut Instructions, &mut ConstantPool),
     L: FnOnce(&mut Instructions, &mut ConstantPool),
+    C: FnOnce(LocalId, &mut Instructions, &mut LocalsLayout),
     I: FnOnce(LocalId, &mut Instructions, &mut ConstantPool, &mut LocalsLayout),
 >(
     RangeFor {
@@ -165,7 +226,7 @@ pub(super) fn emit_for_iterable<
     initial_value: V,
     indexer: F,
     len: L,
-    comparator: Opcode,
+    comparator: C,
     increment: I,
     instructions: &mut Instructions,
     ctx: &EmitterContext,
@@ -188,7 +249,7 @@ pub(super) fn emit_for_iterable<
     instructions.emit_get_local(index_id, INT.into(), locals);
     instructions.emit_get_local(iterator_id, iterable.ty.into(), locals);
     len(instructions, cp);
-    instructions.emit_code(comparator);
+    comparator(iterator_id, instructions, locals);
     let jump_to_end = instructions.emit_jump(Opcode::IfNotJump);
     loop_state.enclosing_loop_end_placeholders.push(jump_to_end);
 
@@ -212,4 +273,74 @@ pub(super) fn emit_for_iterable<
     for jump_to_end in loop_state.enclosing_loop_end_placeholders {
         instructions.patch_jump(jump_to_end);
     }
+    state.forward_outer_loop_jumps(loop_state.outer_loop_jumps);
+}
+
+/// Computes the values taken by a range whose bounds are known at compile time.
+///
+/// Returns `None` if the range is not constant or if it has too many values to be unrolled.
+fn constant_range_values(iterable: &TypedExpr, inclusive: bool) -> Option<Vec<i64>> {
+    let ExprKind::FunctionCall(constructor) = &iterable.kind else {
+        return None;
+    };
+    let [start, end, step] = constructor
+        .arguments
+        .iter()
+        .map(|arg| match fold_constant(arg) {
+            Some(LiteralValue::Int(value)) => Some(value),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?[..]
+    else {
+        return None;
+    };
+    // Descending ranges are counted as ascending ones, with the span and step negated
+    let (span, distance) = match step {
+        0 => return None,
+        1.. => (end.checked_sub(start)?, step),
+        ..=-1 => (start.checked_sub(end)?, step.checked_neg()?),
+    };
+    let count = match (span, inclusive) {
+        (..=-1, _) | (0, false) => 0,
+        (span, false) => (span - 1) / distance + 1,
+        (span, true) => span / distance + 1,
+    };
+    if count > MAX_UNROLLED_ITERATIONS {
+        return None;
+    }
+    Some((0..count).map(|i| start + i * step).collect())
+}
+
+/// Tests if the loop body contains a `break` or a `continue`.
+fn has_loop_jumps(body: &TypedExpr) -> bool {
+    let mut found = false;
+    body.walk(&mut |expr| {
+        found |= matches!(expr.kind, ExprKind::Break(_) | ExprKind::Continue(_));
+    });
+    found
+}
+
+/// Emits the loop body once per value, with the receiver set to the constant value.
+fn emit_unrolled(
+    range: &RangeFor,
+    body: &TypedExpr,
+    values: Vec<i64>,
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+) {
+    locals.set_value_space(range.receiver, range.receiver_type);
+    for value in values {
+        instructions.emit_push_int(value);
+        instructions.emit_set_local(range.receiver, INT.into(), locals);
+        emit(
+            body,
+            instructions,
+            ctx,
+            cp,
+            locals,
+            &mut EmissionState::default(),
+        );
+    }
 }
@@ -1,9 +1,13 @@
-use analyzer::types::hir::{Conditional, Loop};
+use analyzer::types::hir::{Conditional, Loop, Match, MatchPattern};
+use analyzer::types::ty::TypeRef;
+use analyzer::types::{BOOL, FLOAT, INT, STRING};
 
 use crate::bytecode::{Instructions, Opcode};
 use crate::constant_pool::ConstantPool;
-use crate::emit::{emit, EmissionState, EmitterContext};
+use crate::emit::native::{PANIC, STRING_EQ};
+use crate::emit::{emit, EmissionState, EmitterContext, OuterLoopJump};
 use crate::locals::LocalsLayout;
+use crate::r#type::ValueStackSize;
 
 pub fn emit_conditional(
     conditional: &Conditional,
@@ -36,6 +40,106 @@ pub fn emit_conditional(
     instructions.patch_jump(jump_to_end);
 }
 
+/// Emits a match expression, whose arms are tested in order.
+///
+/// The operand is stored in a local, that is compared to the patterns of each arm. The guard of
+/// an arm is only evaluated once one of its patterns matched, and the next arm is tested if the
+/// guard does not hold.
+pub fn emit_match(
+    match_expr: &Match,
+    ty: TypeRef,
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+    state: &mut EmissionState,
+) {
+    let operand_type = match_expr.operand.ty;
+    let operand_id = locals.push_value_space(operand_type);
+    let last_used = state.use_values(true);
+    emit(&match_expr.operand, instructions, ctx, cp, locals, state);
+    state.use_values(last_used);
+    instructions.emit_set_local(operand_id, operand_type.into(), locals);
+
+    let mut jumps_to_end = Vec::new();
+    for arm in &match_expr.arms {
+        // If any pattern matches, go to BODY.
+        let mut jumps_to_body = Vec::new();
+        let mut jump_to_next_arm = None;
+        if !arm
+            .patterns
+            .iter()
+            .any(|pattern| matches!(pattern, MatchPattern::Wildcard))
+        {
+            for pattern in &arm.patterns {
+                let MatchPattern::Value(value) = pattern else {
+                    unreachable!("wildcards have been excluded")
+                };
+                instructions.emit_get_local(operand_id, operand_type.into(), locals);
+                let last_used = state.use_values(true);
+                emit(value, instructions, ctx, cp, locals, state);
+                state.use_values(last_used);
+                emit_equality(operand_type, instructions, cp);
+                jumps_to_body.push(instructions.emit_jump(Opcode::IfJump));
+            }
+            // Otherwise, go to NEXT.
+            jump_to_next_arm = Some(instructions.emit_jump(Opcode::Jump));
+        }
+
+        // BODY:
+        for jump_to_body in jumps_to_body {
+            instructions.patch_jump(jump_to_body);
+        }
+        if let Some(val_name) = arm.val_name {
+            locals.set_value_space(val_name, operand_type);
+            instructions.emit_get_local(operand_id, operand_type.into(), locals);
+            instructions.emit_set_local(val_name, operand_type.into(), locals);
+        }
+
+        // If the guard does not hold, go to NEXT.
+        let jump_guard_failed = arm.guard.as_ref().map(|guard| {
+            let last_used = state.use_values(true);
+            emit(guard, instructions, ctx, cp, locals, state);
+            state.use_values(last_used);
+            instructions.emit_jump(Opcode::IfNotJump)
+        });
+        emit(&arm.body, instructions, ctx, cp, locals, state);
+
+        // Go to END.
+        jumps_to_end.push(instructions.emit_jump(Opcode::Jump));
+
+        // NEXT:
+        for jump_to_next_arm in jump_to_next_arm.into_iter().chain(jump_guard_failed) {
+            instructions.patch_jump(jump_to_next_arm);
+        }
+    }
+
+    // No arm matched, so there is no value to give.
+    if state.use_values && ValueStackSize::from(ty) != ValueStackSize::Zero {
+        instructions.emit_push_constant_ref(cp.insert_string("No `match` arm matched the value."));
+        instructions.emit_invoke(PANIC.signature(cp));
+    }
+
+    // END:
+    for jump_to_end in jumps_to_end {
+        instructions.patch_jump(jump_to_end);
+    }
+}
+
+/// Compares the two values of the given type on top of the stack.
+fn emit_equality(ty: TypeRef, instructions: &mut Instructions, cp: &mut ConstantPool) {
+    match ty {
+        BOOL => {
+            instructions.emit_code(Opcode::BXor);
+            instructions.emit_bool_inversion();
+        }
+        INT => instructions.emit_code(Opcode::IntEqual),
+        FLOAT => instructions.emit_code(Opcode::FloatEqual),
+        STRING => instructions.emit_invoke(STRING_EQ.signature(cp)),
+        _ => unreachable!("Values of this type cannot be matched"),
+    }
+}
+
 pub fn emit_loop(
     lp: &Loop,
     instructions: &mut Instructions,
@@ -74,16 +178,31 @@ pub fn emit_loop(
     for jump_to_end in loop_state.enclosing_loop_end_placeholders {
         instructions.patch_jump(jump_to_end);
     }
+    state.forward_outer_loop_jumps(loop_state.outer_loop_jumps);
 }
 
-pub fn emit_continue(instructions: &mut Instructions, state: &mut EmissionState) {
-    state
-        .enclosing_loop_start_placeholders
-        .push(instructions.emit_jump(Opcode::Jump));
+pub fn emit_continue(depth: usize, instructions: &mut Instructions, state: &mut EmissionState) {
+    let placeholder = instructions.emit_jump(Opcode::Jump);
+    if depth == 0 {
+        state.enclosing_loop_start_placeholders.push(placeholder);
+    } else {
+        state.outer_loop_jumps.push(OuterLoopJump {
+            depth,
+            is_break: false,
+            placeholder,
+        });
+    }
 }
 
-pub fn emit_break(instructions: &mut Instructions, state: &mut EmissionState) {
-    state
-        .enclosing_loop_end_placeholders
-        .push(instructions.emit_jump(Opcode::Jump));
+pub fn emit_break(depth: usize, instructions: &mut Instructions, state: &mut EmissionState) {
+    let placeholder = instructions.emit_jump(Opcode::Jump);
+    if depth == 0 {
+        state.enclosing_loop_end_placeholders.push(placeholder);
+    } else {
+        state.outer_loop_jumps.push(OuterLoopJump {
+            depth,
+            is_break: true,
+            placeholder,
+        });
+    }
 }
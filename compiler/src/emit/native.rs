@@ -8,22 +8,134 @@ use crate::emit::{emit, EmissionState, EmitterContext};
 use crate::locals::LocalsLayout;
 use crate::r#type::ValueStackSize;
 
-const STRING_EQ: &str = "lang::String::eq";
-const STRING_CONCAT: &str = "lang::String::concat";
-const INT_TO_STRING: &str = "lang::Int::to_string";
-const FLOAT_TO_STRING: &str = "lang::Float::to_string";
-pub(super) const STRING_LEN: &str = "lang::String::len";
-pub(super) const STRING_INDEX: &str = "lang::String::[]";
-pub(super) const VEC_INDEX: &str = "lang::Vec::[]";
-const VEC_INDEX_EQ: &str = "lang::Vec::[]=";
-const VEC_POP: &str = "lang::Vec::pop";
-pub(super) const VEC_PUSH: &str = "lang::Vec::push";
-pub(super) const VEC_EXTEND: &str = "lang::Vec::extend";
-pub(super) const VEC_LEN: &str = "lang::Vec::len";
-const VEC_POP_HEAD: &str = "lang::Vec::pop_head";
-const STRING_SPLIT: &str = "lang::String::split";
-const STRING_BYTES: &str = "lang::String::bytes";
-const GLOB_EXPAND: &str = "lang::glob::expand";
+/// A function implemented by the VM, with the stack sizes of its parameters and of its return
+/// value.
+pub(super) struct Native {
+    pub(super) name: &'static str,
+    pub(super) parameters: &'static [ValueStackSize],
+    pub(super) returned: ValueStackSize,
+}
+
+impl Native {
+    /// Inserts the signature of the native in the constant pool, returning its pool identifier.
+    pub(super) fn signature(&self, cp: &mut ConstantPool) -> u32 {
+        cp.insert_invocation(self.name, self.parameters.iter().copied(), self.returned)
+    }
+}
+
+pub(super) const STRING_EQ: Native = Native {
+    name: "lang::String::eq",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::Byte,
+};
+const STRING_CONCAT: Native = Native {
+    name: "lang::String::concat",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const INT_TO_STRING: Native = Native {
+    name: "lang::Int::to_string",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const FLOAT_TO_STRING: Native = Native {
+    name: "lang::Float::to_string",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+pub(super) const STRING_LEN: Native = Native {
+    name: "lang::String::len",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+pub(super) const STRING_INDEX: Native = Native {
+    name: "lang::String::[]",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+pub(super) const VEC_INDEX: Native = Native {
+    name: "lang::Vec::[]",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const VEC_INDEX_EQ: Native = Native {
+    name: "lang::Vec::[]=",
+    parameters: &[
+        ValueStackSize::QWord,
+        ValueStackSize::QWord,
+        ValueStackSize::QWord,
+    ],
+    returned: ValueStackSize::Zero,
+};
+const VEC_POP: Native = Native {
+    name: "lang::Vec::pop",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+pub(super) const VEC_PUSH: Native = Native {
+    name: "lang::Vec::push",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::Zero,
+};
+pub(super) const VEC_EXTEND: Native = Native {
+    name: "lang::Vec::extend",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::Zero,
+};
+pub(super) const VEC_LEN: Native = Native {
+    name: "lang::Vec::len",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const VEC_CONTAINS: Native = Native {
+    name: "lang::Vec::contains",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::Byte,
+};
+const VEC_POP_HEAD: Native = Native {
+    name: "lang::Vec::pop_head",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const STRING_SPLIT: Native = Native {
+    name: "lang::String::split",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const STRING_BYTES: Native = Native {
+    name: "lang::String::bytes",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const STRING_TRIM: Native = Native {
+    name: "lang::String::trim",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+const STRING_REPLACE: Native = Native {
+    name: "lang::String::replace",
+    parameters: &[
+        ValueStackSize::QWord,
+        ValueStackSize::QWord,
+        ValueStackSize::QWord,
+    ],
+    returned: ValueStackSize::QWord,
+};
+const STRING_CONTAINS: Native = Native {
+    name: "lang::String::contains",
+    parameters: &[ValueStackSize::QWord, ValueStackSize::QWord],
+    returned: ValueStackSize::Byte,
+};
+const GLOB_EXPAND: Native = Native {
+    name: "lang::glob::expand",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::QWord,
+};
+pub(super) const PANIC: Native = Native {
+    name: "std::panic",
+    parameters: &[ValueStackSize::QWord],
+    returned: ValueStackSize::Zero,
+};
 
 /// Emits a primitive sequence of instructions.
 #[allow(clippy::get_first, clippy::too_many_arguments)]
@@ -42,7 +154,9 @@ pub(crate) fn emit_natives(
     state: &mut EmissionState,
 ) {
     let last_used = state.use_values(true);
-    emit(callee, instructions, ctx, cp, locals, state);
+    if native.0 != 55 {
+        emit(callee, instructions, ctx, cp, locals, state);
+    }
 
     match native.0 {
         0 => {
@@ -99,11 +213,11 @@ pub(crate) fn emit_natives(
                 }
                 13 => {
                     // String == String
-                    instructions.emit_invoke(cp.insert_string(STRING_EQ));
+                    instructions.emit_invoke(STRING_EQ.signature(cp));
                 }
                 14 => {
                     // String != String
-                    instructions.emit_invoke(cp.insert_string(STRING_EQ));
+                    instructions.emit_invoke(STRING_EQ.signature(cp));
                     instructions.emit_bool_inversion();
                 }
                 15 => instructions.emit_code(Opcode::IntEqual),
@@ -149,19 +263,19 @@ pub(crate) fn emit_natives(
         28 => {
             // ExitCode -> String
             instructions.emit_code(Opcode::ConvertByteToInt);
-            instructions.emit_invoke(cp.insert_string(INT_TO_STRING));
+            instructions.emit_invoke(INT_TO_STRING.signature(cp));
         }
         29 | 54 => {
             // Int -> String
-            instructions.emit_invoke(cp.insert_string(INT_TO_STRING));
+            instructions.emit_invoke(INT_TO_STRING.signature(cp));
         }
         30 => {
             // Float -> String
-            instructions.emit_invoke(cp.insert_string(FLOAT_TO_STRING));
+            instructions.emit_invoke(FLOAT_TO_STRING.signature(cp));
         }
         32 => {
             // String.len() -> Int
-            instructions.emit_invoke(cp.insert_string(STRING_LEN));
+            instructions.emit_invoke(STRING_LEN.signature(cp));
         }
         33 => {
             // String + String -> String
@@ -174,7 +288,7 @@ pub(crate) fn emit_natives(
                 locals,
                 state,
             );
-            instructions.emit_invoke(cp.insert_string(STRING_CONCAT));
+            instructions.emit_invoke(STRING_CONCAT.signature(cp));
         }
         34 => {
             // vector[Int] -> T
@@ -186,7 +300,7 @@ pub(crate) fn emit_natives(
                 locals,
                 state,
             );
-            instructions.emit_invoke(cp.insert_string(VEC_INDEX));
+            instructions.emit_invoke(VEC_INDEX.signature(cp));
         }
         35 => {
             // vector.push(T)
@@ -197,15 +311,15 @@ pub(crate) fn emit_natives(
             if state.use_values {
                 instructions.emit_box_if_primitive(first.ty);
             }
-            instructions.emit_invoke(cp.insert_string(VEC_PUSH));
+            instructions.emit_invoke(VEC_PUSH.signature(cp));
         }
         36 => {
             // vector.pop() T
-            instructions.emit_invoke(cp.insert_string(VEC_POP));
+            instructions.emit_invoke(VEC_POP.signature(cp));
         }
         37 => {
             // vector.len()
-            instructions.emit_invoke(cp.insert_string(VEC_LEN));
+            instructions.emit_invoke(VEC_LEN.signature(cp));
         }
         38 => {
             // string.split(delim)
@@ -218,11 +332,11 @@ pub(crate) fn emit_natives(
                 locals,
                 state,
             );
-            instructions.emit_invoke(cp.insert_string(STRING_SPLIT));
+            instructions.emit_invoke(STRING_SPLIT.signature(cp));
         }
         39 => {
             // string.bytes()
-            instructions.emit_invoke(cp.insert_string(STRING_BYTES));
+            instructions.emit_invoke(STRING_BYTES.signature(cp));
         }
         40 | 42 => {
             // Bool && Bool -> Bool
@@ -289,7 +403,7 @@ pub(crate) fn emit_natives(
             instructions.emit_code(Opcode::IntEqual);
             let end_jump = instructions.emit_jump(Opcode::IfNotJump);
             instructions.emit_push_constant_ref(cp.insert_string("Cannot unwrap `None`."));
-            instructions.emit_invoke(cp.insert_string("std::panic"));
+            instructions.emit_invoke(PANIC.signature(cp));
             instructions.patch_jump(end_jump);
         }
         49 => {
@@ -298,7 +412,7 @@ pub(crate) fn emit_natives(
                 emit(arg, instructions, ctx, cp, locals, state);
             }
             instructions.emit_box_if_primitive(args[1].ty);
-            instructions.emit_invoke(cp.insert_string(VEC_INDEX_EQ));
+            instructions.emit_invoke(VEC_INDEX_EQ.signature(cp));
         }
         50 => {
             // Int -> Exitcode
@@ -310,11 +424,68 @@ pub(crate) fn emit_natives(
         }
         52 => {
             // Vec[A]::pop_head() -> Option[A]
-            instructions.emit_invoke(cp.insert_string(VEC_POP_HEAD));
+            instructions.emit_invoke(VEC_POP_HEAD.signature(cp));
         }
         53 => {
             // Glob::spread() -> Vec[String]
-            instructions.emit_invoke(cp.insert_string(GLOB_EXPAND));
+            instructions.emit_invoke(GLOB_EXPAND.signature(cp));
+        }
+        55 => {
+            // A in Vec[A] -> Bool
+            // The element is written before the vector, so it is evaluated first
+            let element = args
+                .first()
+                .expect("Cannot test the membership of a vector without a value");
+            emit(element, instructions, ctx, cp, locals, state);
+            instructions.emit_box_if_primitive(element.ty);
+            emit(callee, instructions, ctx, cp, locals, state);
+            instructions.emit_code(Opcode::Swap);
+            instructions.emit_invoke(VEC_CONTAINS.signature(cp));
+        }
+        56 => {
+            // string.trim()
+            instructions.emit_invoke(STRING_TRIM.signature(cp));
+        }
+        57 => {
+            // string.replace(from, to)
+            for arg in args {
+                emit(arg, instructions, ctx, cp, locals, state);
+            }
+            instructions.emit_invoke(STRING_REPLACE.signature(cp));
+        }
+        58 => {
+            // string.contains(needle)
+            emit(
+                args.get(0)
+                    .expect("Cannot search a string without a substring"),
+                instructions,
+                ctx,
+                cp,
+                locals,
+                state,
+            );
+            instructions.emit_invoke(STRING_CONTAINS.signature(cp));
+        }
+        59 | 60 => {
+            // Exitcode == Int
+            instructions.emit_code(Opcode::ConvertByteToInt);
+            emit(
+                args.get(0)
+                    .expect("Cannot compare an exit code without an integer"),
+                instructions,
+                ctx,
+                cp,
+                locals,
+                state,
+            );
+            instructions.emit_code(Opcode::IntEqual);
+            if native.0 == 60 {
+                instructions.emit_bool_inversion();
+            }
+        }
+        61 => {
+            // String -> String
+            // The string is already on the stack
         }
         id => todo!("Native function with id {id}"),
     };
@@ -1,9 +1,11 @@
-use analyzer::types::hir::{FieldAccess, FieldAssign};
+use analyzer::types::hir::{FieldAccess, FieldAssign, TypedExpr};
+use analyzer::types::ty::{Type, TypeRef};
 
 use crate::bytecode::Instructions;
 use crate::constant_pool::ConstantPool;
 use crate::emit::{emit, EmissionState, EmitterContext};
 use crate::locals::LocalsLayout;
+use crate::r#type::ValueStackSize;
 
 pub fn emit_field_access(
     access: &FieldAccess,
@@ -37,3 +39,31 @@ pub fn emit_field_assign(
 
     instructions.emit_set_field(assign.field, layout);
 }
+
+/// Emits a tuple, as a structure whose fields are the given elements, laid out in order.
+pub fn emit_tuple(
+    elements: &[TypedExpr],
+    tuple_type: TypeRef,
+    instructions: &mut Instructions,
+    ctx: &EmitterContext,
+    cp: &mut ConstantPool,
+    locals: &mut LocalsLayout,
+    state: &mut EmissionState,
+) {
+    let last_use = state.use_values(true);
+    for element in elements {
+        emit(element, instructions, ctx, cp, locals, state);
+    }
+    state.use_values(last_use);
+
+    let &Type::Structure(_, structure_id) = ctx.get_type(tuple_type) else {
+        panic!("tuple type is not a structure")
+    };
+    let layout = ctx.get_layout(tuple_type.reef, structure_id);
+
+    instructions.emit_new(cp.insert_string(ctx.get_type_name(tuple_type)));
+    instructions.emit_copy_operands(layout.total_size);
+    if !state.use_values {
+        instructions.emit_pop(ValueStackSize::QWord);
+    }
+}
@@ -1,49 +1,163 @@
 use std::collections::HashSet;
-use std::io;
 use std::io::Write;
+use std::{fmt, io};
 
 use ::context::source::ContentId;
 use analyzer::engine::Engine;
 use analyzer::environment::symbols::SymbolInfo;
 use analyzer::reef::{Externals, ReefId};
-use analyzer::relations::{Relations, ResolvedSymbol, SourceId};
+use analyzer::relations::{LocalId, RelationState, Relations, ResolvedSymbol, SourceId};
 use analyzer::types::engine::{Chunk, ChunkKind, StructureId, TypedEngine};
 use analyzer::types::hir::ExprKind;
-use analyzer::types::ty::Type;
+use analyzer::types::ty::{FunctionKind, Type};
 use analyzer::types::Typing;
 
 use crate::bytecode::{Bytecode, InstructionPos, Instructions};
+use crate::compact::{compact_jumps, COMPACT_JUMPS_OPT_LEVEL};
 use crate::constant_pool::ConstantPool;
 use crate::context::EmitterContext;
 use crate::emit::{emit, EmissionState};
 use crate::externals::{CompiledReef, CompilerExternals};
 use crate::locals::LocalsLayout;
+use crate::peephole::{remove_redundant_pops, PEEPHOLE_OPT_LEVEL};
 use crate::r#type::{get_type_stack_size, ValueStackSize};
 use crate::structure::StructureLayout;
+use crate::verify::verify;
 
 pub mod bytecode;
-mod constant_pool;
+mod compact;
+pub mod constant_pool;
 mod context;
+pub mod disasm;
 mod emit;
 pub mod externals;
 mod locals;
+mod peephole;
 mod structure;
 mod r#type;
+pub mod verify;
 
 pub(crate) type Captures = Vec<Option<Vec<ResolvedSymbol>>>;
 
+/// The resolved captures of a reef, kept across its compilations.
+///
+/// The captures of a module are only resolved the first time it is compiled, and are reused
+/// until the module is invalidated.
+#[derive(Debug, Default)]
+pub struct CaptureCache {
+    captures: Captures,
+
+    /// The number of modules whose captures have been resolved.
+    resolutions: usize,
+}
+
+impl CaptureCache {
+    /// Clears the cached captures of a module, so that the next compilation resolves the captures
+    /// of the module and of its inner functions again.
+    ///
+    /// The given identifier must be the root environment of the module.
+    pub fn invalidate(&mut self, module: SourceId) {
+        if let Some(captures) = self.captures.get_mut(module.0) {
+            *captures = None;
+        }
+    }
+
+    /// Returns how many times the captures of a module have been resolved.
+    pub fn resolutions(&self) -> usize {
+        self.resolutions
+    }
+}
+
 pub trait SourceLineProvider {
     /// returns the line, starting from one, attributed to the given byte position of given content.
     fn get_line(&self, content: ContentId, byte_pos: usize) -> Option<usize>;
+
+    /// returns the original source text of given content, if it is still available.
+    ///
+    /// Columns cannot be computed for the contents whose source is not provided.
+    fn get_source(&self, _content: ContentId) -> Option<&str> {
+        None
+    }
+
+    /// returns the line and the column, both starting from one, of the given byte position.
+    ///
+    /// The column counts the characters from the start of the line, not the bytes.
+    fn get_line_col(&self, content: ContentId, byte_pos: usize) -> Option<(usize, usize)> {
+        let line = self.get_line(content, byte_pos)?;
+        let before = self.get_source(content)?.get(..byte_pos)?;
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        Some((line, before[line_start..].chars().count() + 1))
+    }
 }
 
 #[derive(Default)]
 pub struct CompilerOptions<'a> {
     pub line_provider: Option<&'a dyn SourceLineProvider>,
     pub last_page_storage_var: Option<String>,
+    /// The optimization level, where `0` disables all optional optimizations.
+    pub opt_level: u8,
+    /// Emits the names and emplacements of each chunk's locals and captures.
+    pub debug_info: bool,
+    /// Embeds the source text of each chunk, as given by the line provider, to report errors.
+    pub embed_source: bool,
 }
 
+/// An error that prevents a reef from being compiled.
+#[derive(Debug)]
+pub enum CompileError {
+    /// The bytecode could not be written.
+    Io(io::Error),
+
+    /// A symbol referenced by the given environment was not resolved by the analysis.
+    ///
+    /// This happens when the compilation is attempted despite analysis errors.
+    UnresolvedRelation { origin: SourceId },
+}
+
+impl From<io::Error> for CompileError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Io(err) => write!(f, "could not write the bytecode: {err}"),
+            CompileError::UnresolvedRelation { origin } => {
+                write!(f, "unresolved symbol in environment {}", origin.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
 const MAPPINGS_ATTRIBUTE: u8 = 1;
+const LOCALS_ATTRIBUTE: u8 = 2;
+const SOURCE_ATTRIBUTE: u8 = 3;
+
+/// The attribute of an exported symbol that locates its declaration.
+const DECLARATION_ATTRIBUTE: u8 = 1;
+
+/// A named value in the locals area of a chunk.
+struct LocalDebugEntry {
+    /// The constant pool index of the name.
+    name_index: u32,
+    /// The starting byte index in the locals area.
+    offset: u32,
+    size: ValueStackSize,
+}
+
+/// The instructions of a chunk in its page, with the lines they are attributed to.
+struct ChunkTrace {
+    /// The constant pool index of the chunk's name.
+    name_index: u32,
+    /// The number of instruction bytes of the chunk.
+    instruction_count: u32,
+    /// The lines of the chunk, bound to the instruction, relative to the chunk, where they start.
+    mappings: Vec<(usize, u32)>,
+}
 
 fn compile_layouts(typed_engine: &TypedEngine) -> Vec<StructureLayout> {
     let mut layouts = Vec::new();
@@ -64,11 +178,13 @@ pub fn compile_reef(
     compiler_externals: &CompilerExternals,
     reef_id: ReefId,
     starting_page: SourceId,
+    captures: &mut CaptureCache,
     writer: &mut impl Write,
     options: CompilerOptions,
-) -> Result<CompiledReef, io::Error> {
+) -> Result<CompiledReef, CompileError> {
     let layouts = compile_layouts(typed_engine);
-    let captures = resolve_captures(link_engine, relations, reef_id);
+    resolve_captures(link_engine, relations, reef_id, captures)?;
+    let captures = &captures.captures;
 
     let mut bytecode = Bytecode::default();
     let mut cp = ConstantPool::default();
@@ -85,25 +201,31 @@ pub fn compile_reef(
             externals,
             compiler_externals,
             environment: main_env,
-            captures: &captures,
+            captures,
             chunk_id,
             layouts: &layouts,
+            opt_level: options.opt_level,
         };
 
-        let page_size =
-            compile_function_chunk(main_chunk, chunk_id, &ctx, &mut bytecode, &mut cp, &options)
-                .unwrap();
-        write_exported(&mut cp, page_size, &mut bytecode)?;
+        let (page_size, main_trace) =
+            compile_function_chunk(main_chunk, chunk_id, &ctx, &mut bytecode, &mut cp, &options);
+        write_exported(&mut cp, page_size.unwrap(), &mut bytecode)?;
+        let mut traces = vec![main_trace];
 
         // compile structures
-        let structures: Vec<_> = iter_structs(typing).collect();
+        let structures: Vec<_> = iter_structs(typing)
+            .map(|(structure_env_id, structure_id)| {
+                let structure_env = link_engine.get_environment(structure_env_id).unwrap();
+                (structure_env.fqn.to_string(), structure_id)
+            })
+            .chain(iter_tuples(typing))
+            .collect();
 
         bytecode.emit_u32(structures.len() as u32);
 
-        for (structure_env_id, structure_id) in structures {
-            let structure_env = link_engine.get_environment(structure_env_id).unwrap();
+        for (structure_fqn, structure_id) in structures {
             let structure = typed_engine.get_structure(structure_id).unwrap();
-            bytecode.emit_constant_ref(cp.insert_string(structure_env.fqn.to_string()));
+            bytecode.emit_constant_ref(cp.insert_string(structure_fqn));
             let fields = structure.get_fields();
 
             // set structure bytes length and objects indexes
@@ -142,9 +264,10 @@ pub fn compile_reef(
                 captures: &captures,
                 chunk_id,
                 layouts: &layouts,
+                opt_level: options.opt_level,
             };
 
-            compile_function_chunk(
+            let (_, trace) = compile_function_chunk(
                 chunk,
                 chunk_id,
                 &ctx,
@@ -155,7 +278,10 @@ pub fn compile_reef(
                     ..options
                 },
             );
+            traces.push(trace);
         }
+
+        compile_trace_table(traces, &mut bytecode);
     }
 
     write(writer, &bytecode, &cp)?;
@@ -170,6 +296,18 @@ fn iter_structs(typing: &Typing) -> impl Iterator<Item = (SourceId, StructureId)
     })
 }
 
+/// Lists the tuple structures, that are identified by the name of their type.
+fn iter_tuples(typing: &Typing) -> impl Iterator<Item = (String, StructureId)> + '_ {
+    typing
+        .iter_tuples()
+        .filter_map(|type_id| match typing.get_type(type_id) {
+            Some(&Type::Structure(_, structure_id)) => {
+                Some((typing.get_type_name(type_id)?.clone(), structure_id))
+            }
+            _ => None,
+        })
+}
+
 fn compile_function_chunk(
     chunk: &Chunk,
     id: SourceId,
@@ -177,36 +315,149 @@ fn compile_function_chunk(
     bytecode: &mut Bytecode,
     cp: &mut ConstantPool,
     options: &CompilerOptions,
-) -> Option<u32> {
+) -> (Option<u32>, ChunkTrace) {
     // emit the function's name
     let signature_idx = cp.insert_string(ctx.environment.fqn.clone());
     bytecode.emit_constant_ref(signature_idx);
 
     // emits chunk's code attribute
-    let (page_size, segments) = compile_code(chunk, id, bytecode, ctx, cp, options);
+    let (page_size, instruction_count, segments, locals) =
+        compile_code(chunk, id, bytecode, ctx, cp, options);
 
     let line_provider = options.line_provider;
-    let attribute_count = line_provider.map_or(0, |_| 1);
+    let content = ctx.engine.get_original_content(id);
+    let line_mappings = line_provider.zip(content);
+    let source = line_mappings
+        .filter(|_| options.embed_source)
+        .and_then(|(line_provider, content_id)| line_provider.get_source(content_id));
+    let attribute_count = u8::from(line_mappings.is_some())
+        + u8::from(options.debug_info)
+        + u8::from(source.is_some());
     bytecode.emit_byte(attribute_count);
 
-    if let Some(line_provider) = line_provider {
-        let content = ctx.engine.get_original_content(id);
+    let mappings = line_mappings
+        .map(|(line_provider, content_id)| {
+            compute_line_mappings(segments, content_id, line_provider)
+        })
+        .unwrap_or_default();
+    if line_mappings.is_some() {
+        compile_line_mapping_attribute(&mappings, bytecode);
+    }
+    if options.debug_info {
+        compile_locals_attribute(locals, bytecode);
+    }
+    if let Some(source) = source {
+        // the constant pool deduplicates the source shared by the chunks of a same content
+        bytecode.emit_byte(SOURCE_ATTRIBUTE);
+        bytecode.emit_constant_ref(cp.insert_string(source));
+    }
+    let trace = ChunkTrace {
+        name_index: signature_idx,
+        instruction_count,
+        mappings,
+    };
+    (page_size, trace)
+}
+
+/// Lists the named locals and captures of a chunk, in the order of their emplacement.
+fn collect_locals_debug_entries(
+    ctx: &EmitterContext,
+    captures: &[ResolvedSymbol],
+    locals: &LocalsLayout,
+    cp: &mut ConstantPool,
+) -> Vec<LocalDebugEntry> {
+    let mut entries: Vec<_> = ctx
+        .environment
+        .symbols
+        .all()
+        .iter()
+        .enumerate()
+        .filter_map(|(id, symbol)| {
+            let (offset, size) = locals.get_emplacement(LocalId(id))?;
+            Some(LocalDebugEntry {
+                name_index: cp.insert_string(&symbol.name),
+                offset,
+                size,
+            })
+        })
+        .collect();
 
-        let Some(content_id) = content else {
-            return page_size;
+    for capture in captures {
+        let Some(offset) = locals.get_capture_index(*capture) else {
+            continue;
         };
-        compile_line_mapping_attribute(segments, content_id, bytecode, line_provider);
+        let symbol = ctx
+            .get_engine(capture.reef)
+            .and_then(|engine| engine.get_environment(capture.source))
+            .and_then(|env| env.symbols.get(capture.object_id))
+            .expect("captured symbol should be declared");
+        entries.push(LocalDebugEntry {
+            name_index: cp.insert_string(&symbol.name),
+            offset,
+            size: ValueStackSize::QWord,
+        });
+    }
+    entries.sort_by_key(|entry| entry.offset);
+    entries
+}
+
+fn compile_locals_attribute(entries: Vec<LocalDebugEntry>, bytecode: &mut Bytecode) {
+    bytecode.emit_byte(LOCALS_ATTRIBUTE);
+    bytecode.emit_u32(entries.len() as u32);
+    for entry in entries {
+        bytecode.emit_constant_ref(entry.name_index);
+        bytecode.emit_u32(entry.offset);
+        bytecode.emit_byte(entry.size.into());
     }
-    page_size
 }
 
-fn compile_line_mapping_attribute(
+fn compile_line_mapping_attribute(mappings: &[(usize, u32)], bytecode: &mut Bytecode) {
+    bytecode.emit_byte(MAPPINGS_ATTRIBUTE);
+    bytecode.emit_u32(mappings.len() as u32);
+    for &(line, instruction) in mappings {
+        bytecode.emit_u32(instruction);
+        bytecode.emit_u32(line as u32);
+    }
+}
+
+/// Emits the function-boundary table of a page, that attributes an instruction to its chunk and
+/// its line.
+///
+/// The chunks are given in the order they were written, and their instructions are addressed as
+/// if they were concatenated.
+fn compile_trace_table(traces: Vec<ChunkTrace>, bytecode: &mut Bytecode) {
+    bytecode.emit_u32(traces.len() as u32);
+    let mut start = 0;
+    for trace in traces {
+        let end = start + trace.instruction_count;
+        bytecode.emit_constant_ref(trace.name_index);
+        bytecode.emit_u32(start);
+        bytecode.emit_u32(end);
+        let mut mappings: Vec<(u32, u32)> = Vec::with_capacity(trace.mappings.len());
+        for (line, instruction) in trace.mappings {
+            let mapping = (start + instruction, line as u32);
+            match mappings.last_mut() {
+                // a position bound twice is attributed to its last line
+                Some(last) if last.0 == mapping.0 => *last = mapping,
+                Some(last) if last.1 == mapping.1 => {}
+                _ => mappings.push(mapping),
+            }
+        }
+        bytecode.emit_u32(mappings.len() as u32);
+        for (position, line) in mappings {
+            bytecode.emit_u32(position);
+            bytecode.emit_u32(line);
+        }
+        start = end;
+    }
+}
+
+/// Binds the lines of a chunk to the first instruction that is attributed to them.
+fn compute_line_mappings(
     positions: Vec<InstructionPos>,
     content_id: ContentId,
-    bytecode: &mut Bytecode,
     line_provider: &dyn SourceLineProvider,
-) {
-    bytecode.emit_byte(MAPPINGS_ATTRIBUTE);
+) -> Vec<(usize, u32)> {
     let mut mappings: Vec<(usize, u32)> = Vec::new();
 
     let positions: Vec<_> = positions
@@ -215,8 +466,7 @@ fn compile_line_mapping_attribute(
         .collect();
 
     let Some(((first_pos, first_ip), positions)) = positions.split_first() else {
-        bytecode.emit_u32(0);
-        return;
+        return mappings;
     };
     let mut last_pos = *first_pos;
     let mut last_ip = *first_ip;
@@ -241,21 +491,22 @@ fn compile_line_mapping_attribute(
         let line = line_provider.get_line(content_id, *first_pos).unwrap();
         mappings.push((line, 0))
     }
-
-    bytecode.emit_u32(mappings.len() as u32);
-    for (line, instruction) in mappings {
-        bytecode.emit_u32(instruction);
-        bytecode.emit_u32(line as u32);
-    }
+    mappings
 }
 
 /// Resolves all captured variables of a given chunk identifier.
 ///
 /// This function will resolve all direct captures of the chunk and the captures of its inner chunks.
-/// All resolved captures are set into the given `captures` vector.
-fn resolve_captures(engine: &Engine, relations: &Relations, compiled_reef: ReefId) -> Captures {
-    let mut externals = HashSet::new();
-    let mut captures = vec![None; engine.len()];
+/// All resolved captures are set into the given cache, and the modules already present in it
+/// are skipped.
+/// An error is returned if a referenced symbol has not been resolved.
+fn resolve_captures(
+    engine: &Engine,
+    relations: &Relations,
+    compiled_reef: ReefId,
+    cache: &mut CaptureCache,
+) -> Result<(), CompileError> {
+    cache.captures.resize(engine.len(), None);
 
     fn resolve(
         chunk_id: SourceId,
@@ -264,7 +515,7 @@ fn resolve_captures(engine: &Engine, relations: &Relations, compiled_reef: ReefI
         relations: &Relations,
         captures: &mut Vec<Option<Vec<ResolvedSymbol>>>,
         externals: &mut HashSet<ResolvedSymbol>,
-    ) {
+    ) -> Result<(), CompileError> {
         let env = engine.get_environment(chunk_id).unwrap();
 
         // recursively resolve all inner functions
@@ -276,29 +527,26 @@ fn resolve_captures(engine: &Engine, relations: &Relations, compiled_reef: ReefI
                 relations,
                 captures,
                 externals,
-            );
+            )?;
             // filter out external symbols that refers to the current chunk
             externals.retain(|symbol| symbol.source != chunk_id);
         }
 
         // add this function's external referenced variables
-        externals.extend(
-            env.symbols
-                .external_symbols()
-                .map(|(_, relation)| {
-                    relations[relation]
-                        .state
-                        .expect_resolved("unresolved relation during compilation")
-                })
-                .filter(|symbol| {
-                    symbol.reef == compiled_reef && {
-                        // filter out functions
-                        let env = engine.get_environment(symbol.source).unwrap();
-                        let var = env.symbols.get(symbol.object_id).unwrap();
-                        var.ty == SymbolInfo::Variable && !(env.is_script && var.is_exported())
-                    }
-                }),
-        );
+        for (_, relation) in env.symbols.external_symbols() {
+            let RelationState::Resolved(symbol) = relations[relation].state else {
+                return Err(CompileError::UnresolvedRelation { origin: chunk_id });
+            };
+            if symbol.reef != compiled_reef {
+                continue;
+            }
+            // filter out functions
+            let env = engine.get_environment(symbol.source).unwrap();
+            let var = env.symbols.get(symbol.object_id).unwrap();
+            if var.ty == SymbolInfo::Variable && !(env.is_script && var.is_exported()) {
+                externals.insert(symbol);
+            }
+        }
 
         let mut chunk_captures: Vec<ResolvedSymbol> = externals.iter().copied().collect();
 
@@ -309,28 +557,34 @@ fn resolve_captures(engine: &Engine, relations: &Relations, compiled_reef: ReefI
                 .then_with(|| a.object_id.0.cmp(&b.object_id.0))
         });
 
-        captures[chunk_id.0] = Some(chunk_captures)
+        captures[chunk_id.0] = Some(chunk_captures);
+        Ok(())
     }
 
     // Resolve captures of all environments, starting from the roots of each module
     for (engine_id, _) in engine.environments().filter(|(_, chunk)| chunk.is_script) {
+        if cache.captures[engine_id.0].is_some() {
+            continue;
+        }
         resolve(
             engine_id,
             compiled_reef,
             engine,
             relations,
-            &mut captures,
-            &mut externals,
-        );
+            &mut cache.captures,
+            &mut HashSet::new(),
+        )?;
+        cache.resolutions += 1;
     }
-    captures
+    Ok(())
 }
 
 /// compiles chunk's code attribute
 /// the code attribute of a chunk is a special attribute that contains the bytecode instructions and
 /// locals specifications
 ///
-/// returns the page length (if chunk is a script) and the hir's segments associated with their first instruction.
+/// returns the page length (if chunk is a script), the hir's segments associated with their first instruction
+/// and the named locals if debug information is requested.
 fn compile_code(
     chunk: &Chunk,
     chunk_id: SourceId,
@@ -338,7 +592,7 @@ fn compile_code(
     ctx: &EmitterContext,
     cp: &mut ConstantPool,
     options: &CompilerOptions,
-) -> (Option<u32>, Vec<InstructionPos>) {
+) -> (Option<u32>, u32, Vec<InstructionPos>, Vec<LocalDebugEntry>) {
     let locals_byte_count = bytecode.emit_u32_placeholder();
 
     let chunk_captures = ctx.captures[chunk_id.0]
@@ -359,9 +613,15 @@ fn compile_code(
             .iter()
             .map(|p| Into::<u8>::into(get_type_stack_size(p.ty)) as u32)
             .sum::<u32>();
+        let receiver_count: u32 = match function.kind {
+            FunctionKind::Method { receiver_ty, .. } => {
+                u8::from(get_type_stack_size(receiver_ty)) as u32
+            }
+            _ => 0,
+        };
         let captures_params_count: u32 =
             chunk_captures.len() as u32 * u8::from(ValueStackSize::QWord) as u32;
-        explicit_params_count + captures_params_count
+        receiver_count + explicit_params_count + captures_params_count
     };
 
     bytecode.emit_u32(parameters_bytes_count);
@@ -374,10 +634,21 @@ fn compile_code(
     // emit instruction count placeholder
     let instruction_count = bytecode.emit_u32_placeholder();
 
+    let code_start = bytecode.len();
     let mut instructions = Instructions::wrap(bytecode);
     let var_count = ctx.environment.symbols.all().len() + chunk_captures.len();
     let mut locals = LocalsLayout::new(var_count);
 
+    // set space for the receiver of methods, that is passed first
+    if let FunctionKind::Method {
+        receiver,
+        receiver_ty,
+        ..
+    } = function.kind
+    {
+        locals.set_value_space(receiver, receiver_ty);
+    }
+
     // set space for explicit parameters
     for param in function.parameters.iter() {
         locals.set_value_space(param.local_id, param.ty)
@@ -414,7 +685,12 @@ fn compile_code(
             let page_offset = cp.exported.last().map_or(0, |exp| {
                 exp.page_offset + u8::from(ValueStackSize::QWord) as u32
             });
-            cp.insert_exported(storage_exported_val, page_offset, last_expr.ty.is_obj());
+            cp.insert_exported(
+                storage_exported_val,
+                page_offset,
+                last_expr.ty.is_obj(),
+                None,
+            );
             instructions.emit_set_external(
                 cp.get_external(storage_exported_val).unwrap(),
                 last_expr.ty.into(),
@@ -422,14 +698,32 @@ fn compile_code(
         }
     }
 
+    let mut segments = instructions.take_positions();
+    if options.opt_level >= PEEPHOLE_OPT_LEVEL {
+        let code = bytecode.bytes.split_off(code_start);
+        let optimized = remove_redundant_pops(&code, &mut segments);
+        bytecode.bytes.extend(optimized);
+    }
+    if options.opt_level >= COMPACT_JUMPS_OPT_LEVEL {
+        let code = bytecode.bytes.split_off(code_start);
+        let compacted = compact_jumps(&code, &mut segments);
+        bytecode.bytes.extend(compacted);
+    }
+
     // patch instruction count placeholder
-    let instruction_byte_count = instructions.current_ip();
-    let segments = instructions.take_positions();
+    let instruction_byte_count = (bytecode.len() - code_start) as u32;
     bytecode.patch_u32_placeholder(instruction_count, instruction_byte_count);
+    debug_assert_eq!(verify(&bytecode.bytes()[code_start..], cp), Ok(()));
 
     let locals_length = locals.byte_count();
     bytecode.patch_u32_placeholder(locals_byte_count, locals_length);
 
+    let debug_entries = if options.debug_info {
+        collect_locals_debug_entries(ctx, chunk_captures, &locals, cp)
+    } else {
+        Vec::new()
+    };
+
     let offsets = locals.refs_offset();
     bytecode.emit_u32(offsets.len() as u32);
     for offset in offsets {
@@ -437,14 +731,19 @@ fn compile_code(
     }
 
     if !chunk_is_script {
-        return (None, segments);
+        return (None, instruction_byte_count, segments, debug_entries);
     }
 
     let mut page_length = locals_length;
     if options.last_page_storage_var.is_some() {
         page_length += u8::from(ValueStackSize::QWord) as u32
     }
-    (Some(page_length), segments)
+    (
+        Some(page_length),
+        instruction_byte_count,
+        segments,
+        debug_entries,
+    )
 }
 
 fn write(
@@ -486,6 +785,13 @@ fn write_exported(
         bytecode.emit_u32(symbol.name_index);
         bytecode.emit_u32(symbol.page_offset);
         bytecode.emit_byte(symbol.is_obj_ref as u8);
+        bytecode.emit_byte(u8::from(symbol.declaration.is_some()));
+        if let Some(declaration) = &symbol.declaration {
+            bytecode.emit_byte(DECLARATION_ATTRIBUTE);
+            bytecode.emit_u32(declaration.content.0 as u32);
+            bytecode.emit_u32(declaration.segment.start as u32);
+            bytecode.emit_u32(declaration.segment.end as u32);
+        }
     }
     pool.exported.clear();
     Ok(())
@@ -493,15 +799,519 @@ fn write_exported(
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Range;
+
     use pretty_assertions::assert_eq;
 
+    use analyzer::diagnostic::Diagnostic;
     use analyzer::importer::StaticImporter;
     use analyzer::name::Name;
-    use analyzer::reef::{Externals, ReefId};
+    use analyzer::reef::{Externals, Reef, ReefId};
     use analyzer::relations::{LocalId, ResolvedSymbol, SourceId};
+    use context::source::ContentId;
+    use context::str_find::find_in;
     use parser::parse_trusted;
 
-    use crate::resolve_captures;
+    use crate::bytecode::Opcode;
+    use crate::externals::CompilerExternals;
+    use crate::{
+        compile_reef, resolve_captures, CaptureCache, CompileError, CompilerOptions,
+        SourceLineProvider, DECLARATION_ATTRIBUTE,
+    };
+
+    /// Compiles the given source, with a minimal `std` reef providing ranges.
+    fn compile_with_ranges(src: &str, opt_level: u8) -> Vec<u8> {
+        compile_with_options(
+            src,
+            CompilerOptions {
+                opt_level,
+                ..CompilerOptions::default()
+            },
+        )
+    }
+
+    fn compile_with_options(src: &str, options: CompilerOptions) -> Vec<u8> {
+        let std_src = "\
+            struct Range { start: Int, end: Int, step: Int }
+            struct InclusiveRange { start: Int, end: Int, step: Int }";
+        let mut externals = Externals::default();
+        let mut compiler_externals = CompilerExternals::default();
+        let std_name = Name::new("std");
+        let std = analyzer::analyze(
+            std_name.clone(),
+            &mut StaticImporter::new([(std_name, std_src)], parse_trusted),
+            &externals,
+        );
+        let compiled = compile_reef(
+            &std.engine,
+            &std.resolution.relations,
+            &std.typing,
+            &std.resolution.engine,
+            &externals,
+            &compiler_externals,
+            externals.current,
+            SourceId(0),
+            &mut CaptureCache::default(),
+            &mut Vec::new(),
+            CompilerOptions::default(),
+        )
+        .unwrap();
+        compiler_externals.set(externals.current, compiled);
+        externals.register(Reef::new("std".to_owned(), std));
+
+        let name = Name::new("test");
+        let mut analyzer = analyzer::analyze(
+            name.clone(),
+            &mut StaticImporter::new([(name, src)], parse_trusted),
+            &externals,
+        );
+        let diagnostics = analyzer.take_diagnostics();
+        assert!(
+            !diagnostics.iter().any(Diagnostic::is_critical),
+            "{diagnostics:?}"
+        );
+        let mut bytes = Vec::new();
+        compile_reef(
+            &analyzer.engine,
+            &analyzer.resolution.relations,
+            &analyzer.typing,
+            &analyzer.resolution.engine,
+            &externals,
+            &compiler_externals,
+            externals.current,
+            SourceId(0),
+            &mut CaptureCache::default(),
+            &mut bytes,
+            options,
+        )
+        .unwrap();
+        bytes
+    }
+
+    /// Lists the strings of the constant pool heading the bytecode.
+    fn constant_strings(bytes: &[u8]) -> Vec<&[u8]> {
+        let count = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
+        let mut strings = Vec::with_capacity(count as usize);
+        let mut cursor = 4;
+        for _ in 0..count {
+            let len = u64::from_be_bytes(bytes[cursor..cursor + 8].try_into().unwrap()) as usize;
+            cursor += 8;
+            strings.push(&bytes[cursor..cursor + len]);
+            cursor += len;
+        }
+        strings
+    }
+
+    /// Finds the index of a string in the constant pool heading the bytecode.
+    fn constant_index(bytes: &[u8], str: &str) -> Option<u32> {
+        constant_strings(bytes)
+            .into_iter()
+            .position(|constant| constant == str.as_bytes())
+            .map(|idx| idx as u32)
+    }
+
+    fn read_u32(bytes: &[u8], cursor: &mut usize) -> u32 {
+        let value = u32::from_be_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap());
+        *cursor += 4;
+        value
+    }
+
+    /// Reads the constant pool and skips the dynamic symbols heading the bytecode.
+    ///
+    /// Returns the constant strings, with the position of the first page.
+    fn first_page(bytes: &[u8]) -> (Vec<&[u8]>, usize) {
+        let constants = constant_strings(bytes);
+        let mut cursor = 4 + constants.iter().map(|str| 8 + str.len()).sum::<usize>();
+        let dynsym_count = read_u32(bytes, &mut cursor) as usize;
+        cursor += 4 * dynsym_count;
+        (constants, cursor)
+    }
+
+    /// Skips a chunk, and appends its instructions to the given page instructions.
+    fn skip_chunk(bytes: &[u8], cursor: &mut usize, instructions: &mut Vec<u8>) {
+        *cursor += 4 + 4 + 4 + 1;
+        let instruction_count = read_u32(bytes, cursor) as usize;
+        instructions.extend(&bytes[*cursor..*cursor + instruction_count]);
+        *cursor += instruction_count;
+        let refs_count = read_u32(bytes, cursor) as usize;
+        *cursor += 4 * refs_count;
+        let attribute_count = bytes[*cursor];
+        *cursor += 1;
+        for _ in 0..attribute_count {
+            let attribute = bytes[*cursor];
+            *cursor += 1;
+            let count = match attribute {
+                1 => 8 * read_u32(bytes, cursor) as usize,
+                2 => 9 * read_u32(bytes, cursor) as usize,
+                3 => 4,
+                _ => panic!("unknown chunk attribute {attribute}"),
+            };
+            *cursor += count;
+        }
+    }
+
+    /// An exported symbol, with the content and the segment of its declaration.
+    type ExportedEntry = (String, Option<(u32, Range<u32>)>);
+
+    /// Reads the exported symbols of the first page.
+    fn exported_declarations(bytes: &[u8]) -> Vec<ExportedEntry> {
+        let (constants, mut cursor) = first_page(bytes);
+        skip_chunk(bytes, &mut cursor, &mut Vec::new());
+        read_exported(bytes, &constants, &mut cursor)
+    }
+
+    /// Reads the page size and the exported symbols that follow the main chunk of a page.
+    fn read_exported(bytes: &[u8], constants: &[&[u8]], cursor: &mut usize) -> Vec<ExportedEntry> {
+        let _page_size = read_u32(bytes, cursor);
+        let exported_count = read_u32(bytes, cursor);
+        (0..exported_count)
+            .map(|_| {
+                let name = constants[read_u32(bytes, cursor) as usize];
+                let _page_offset = read_u32(bytes, cursor);
+                let attribute_count = bytes[*cursor + 1];
+                *cursor += 2;
+                let declaration = (attribute_count == 1).then(|| {
+                    assert_eq!(bytes[*cursor], DECLARATION_ATTRIBUTE);
+                    *cursor += 1;
+                    let content = read_u32(bytes, cursor);
+                    let start = read_u32(bytes, cursor);
+                    (content, start..read_u32(bytes, cursor))
+                });
+                (String::from_utf8(name.to_vec()).unwrap(), declaration)
+            })
+            .collect()
+    }
+
+    /// A chunk's name and instructions range, with its line mappings.
+    type TraceEntry = (String, Range<u32>, Vec<(u32, u32)>);
+
+    /// Reads the instructions and the trace table of the first page.
+    fn page_traces(bytes: &[u8]) -> (Vec<u8>, Vec<TraceEntry>) {
+        let (constants, mut cursor) = first_page(bytes);
+        let mut instructions = Vec::new();
+        skip_chunk(bytes, &mut cursor, &mut instructions);
+        read_exported(bytes, &constants, &mut cursor);
+        for _ in 0..read_u32(bytes, &mut cursor) {
+            cursor += 4 + 4;
+            let obj_count = read_u32(bytes, &mut cursor) as usize;
+            cursor += 4 * obj_count;
+        }
+        for _ in 0..read_u32(bytes, &mut cursor) {
+            skip_chunk(bytes, &mut cursor, &mut instructions);
+        }
+
+        let traces = (0..read_u32(bytes, &mut cursor))
+            .map(|_| {
+                let name = constants[read_u32(bytes, &mut cursor) as usize];
+                let start = read_u32(bytes, &mut cursor);
+                let end = read_u32(bytes, &mut cursor);
+                let mappings = (0..read_u32(bytes, &mut cursor))
+                    .map(|_| {
+                        let instruction = read_u32(bytes, &mut cursor);
+                        (instruction, read_u32(bytes, &mut cursor))
+                    })
+                    .collect();
+                (
+                    String::from_utf8(name.to_vec()).unwrap(),
+                    start..end,
+                    mappings,
+                )
+            })
+            .collect();
+        (instructions, traces)
+    }
+
+    fn count_push_int(bytes: &[u8], value: i64) -> usize {
+        let mut pattern = vec![Opcode::PushInt as u8];
+        pattern.extend(value.to_be_bytes());
+        bytes
+            .windows(pattern.len())
+            .filter(|window| *window == pattern)
+            .count()
+    }
+
+    #[test]
+    fn exported_declaration_round_trip() {
+        let src = "val answer = 42\nval greeting = 'hi'";
+        let bytes = compile_with_options(src, CompilerOptions::default());
+        let segment = |declaration: &str| {
+            let segment = find_in(src, declaration);
+            segment.start as u32..segment.end as u32
+        };
+        assert_eq!(
+            exported_declarations(&bytes),
+            vec![
+                ("answer".to_owned(), Some((0, segment("val answer = 42")))),
+                (
+                    "greeting".to_owned(),
+                    Some((0, segment("val greeting = 'hi'")))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn locals_debug_attribute() {
+        let src = "fun outer() = { val n = 1; fun inner() -> Int = { val x = 2; $n + $x } }";
+        let bytes = compile_with_options(
+            src,
+            CompilerOptions {
+                debug_info: true,
+                ..CompilerOptions::default()
+            },
+        );
+        let n = constant_index(&bytes, "n").expect("local name should be in the constant pool");
+        let x = constant_index(&bytes, "x").expect("local name should be in the constant pool");
+
+        let mut attribute = vec![2u8];
+        attribute.extend(2u32.to_be_bytes());
+        attribute.extend(n.to_be_bytes());
+        attribute.extend(0u32.to_be_bytes());
+        attribute.push(8);
+        attribute.extend(x.to_be_bytes());
+        attribute.extend(8u32.to_be_bytes());
+        attribute.push(8);
+        assert!(bytes
+            .windows(attribute.len())
+            .any(|window| window == attribute));
+    }
+
+    #[test]
+    fn deduplicate_constant_strings() {
+        let src = "echo 'repeated'; echo 'repeated'\nfun f() = echo 'repeated'; f()";
+        let bytes = compile_with_ranges(src, 0);
+        let strings = constant_strings(&bytes);
+        assert_eq!(
+            strings
+                .iter()
+                .filter(|constant| *constant == b"repeated")
+                .count(),
+            1
+        );
+        assert_eq!(
+            strings
+                .iter()
+                .filter(|constant| *constant == b"echo")
+                .count(),
+            1
+        );
+    }
+
+    struct WholeSourceProvider<'a>(&'a str);
+
+    impl SourceLineProvider for WholeSourceProvider<'_> {
+        fn get_line(&self, _: ContentId, _: usize) -> Option<usize> {
+            Some(1)
+        }
+
+        fn get_source(&self, _: ContentId) -> Option<&str> {
+            Some(self.0)
+        }
+    }
+
+    struct IndexedSourceProvider<'a> {
+        source: &'a str,
+        lines: Vec<usize>,
+    }
+
+    impl<'a> IndexedSourceProvider<'a> {
+        fn new(source: &'a str) -> Self {
+            let lines = std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(pos, _)| pos + 1))
+                .collect();
+            Self { source, lines }
+        }
+    }
+
+    impl SourceLineProvider for IndexedSourceProvider<'_> {
+        fn get_line(&self, _: ContentId, byte_pos: usize) -> Option<usize> {
+            Some(
+                self.lines
+                    .binary_search(&byte_pos)
+                    .map_or_else(|line| line, |line| line + 1),
+            )
+        }
+
+        fn get_source(&self, _: ContentId) -> Option<&str> {
+            Some(self.source)
+        }
+    }
+
+    #[test]
+    fn line_col_after_multibyte_characters() {
+        let src = "val été = 'çà'\necho 🦀 $été";
+        let provider = IndexedSourceProvider::new(src);
+        assert_eq!(provider.get_line_col(ContentId(0), 0), Some((1, 1)));
+        assert_eq!(
+            provider.get_line_col(ContentId(0), src.find('=').unwrap()),
+            Some((1, 9))
+        );
+        assert_eq!(
+            provider.get_line_col(ContentId(0), src.find('$').unwrap()),
+            Some((2, 8))
+        );
+    }
+
+    #[test]
+    fn line_col_inside_character() {
+        let src = "'é'";
+        let provider = IndexedSourceProvider::new(src);
+        assert_eq!(provider.get_line_col(ContentId(0), 2), None);
+    }
+
+    #[test]
+    fn embedded_source_attribute() {
+        let src = "val greeting = 'hello'\necho $greeting";
+        let bytes = compile_with_options(
+            src,
+            CompilerOptions {
+                line_provider: Some(&WholeSourceProvider(src)),
+                embed_source: true,
+                ..CompilerOptions::default()
+            },
+        );
+        let source = constant_index(&bytes, src).expect("source should be in the constant pool");
+
+        let mut attribute = vec![3u8];
+        attribute.extend(source.to_be_bytes());
+        assert!(bytes
+            .windows(attribute.len())
+            .any(|window| window == attribute));
+    }
+
+    #[test]
+    fn no_embedded_source_by_default() {
+        let src = "val greeting = 'hello'\necho $greeting";
+        let bytes = compile_with_options(
+            src,
+            CompilerOptions {
+                line_provider: Some(&WholeSourceProvider(src)),
+                ..CompilerOptions::default()
+            },
+        );
+        assert_eq!(constant_index(&bytes, src), None);
+    }
+
+    #[test]
+    fn trace_instruction_to_function_line() {
+        let src = "fun f() -> Int = {\n    val y = 2\n    $(( $y * 7919 ))\n}\nf()";
+        let bytes = compile_with_options(
+            src,
+            CompilerOptions {
+                line_provider: Some(&IndexedSourceProvider::new(src)),
+                ..CompilerOptions::default()
+            },
+        );
+        let (instructions, traces) = page_traces(&bytes);
+        let mut pattern = vec![Opcode::PushInt as u8];
+        pattern.extend(7919i64.to_be_bytes());
+        let ip = instructions
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+            .expect("constant should be pushed") as u32;
+
+        let (name, range, mappings) = traces
+            .iter()
+            .find(|(_, range, _)| range.contains(&ip))
+            .expect("instruction should be attributed to a function");
+        assert_eq!(name, "test::f");
+        assert_eq!(*range, traces[0].1.end..instructions.len() as u32);
+        assert!(
+            mappings
+                .windows(2)
+                .all(|pair| pair[0].0 < pair[1].0 && pair[0].1 != pair[1].1),
+            "consecutive mappings should be distinct: {mappings:?}"
+        );
+        let line = mappings
+            .iter()
+            .take_while(|(instruction, _)| *instruction <= ip)
+            .last()
+            .map(|(_, line)| *line);
+        assert_eq!(line, Some(3));
+    }
+
+    #[test]
+    fn unroll_constant_range() {
+        let src = "var x = 0; for i in 0..3 { $x = $x * 7919 + $i }";
+        assert_eq!(count_push_int(&compile_with_ranges(src, 0), 7919), 1);
+        assert_eq!(count_push_int(&compile_with_ranges(src, 1), 7919), 3);
+    }
+
+    #[test]
+    fn keep_loop_with_break() {
+        let src = "var x = 0; for i in 0..3 { $x = $x * 7919; if $x > 5 { break } }";
+        assert_eq!(count_push_int(&compile_with_ranges(src, 1), 7919), 1);
+    }
+
+    #[test]
+    fn keep_large_range() {
+        let src = "var x = 0; for i in 0..=100 { $x = $x * 7919 }";
+        assert_eq!(count_push_int(&compile_with_ranges(src, 1), 7919), 1);
+    }
+
+    #[test]
+    fn fold_constant_arithmetic() {
+        let src = "val x = $(( 2 + 3 * 4 ))";
+        let unfolded = compile_with_ranges(src, 0);
+        let folded = compile_with_ranges(src, 1);
+        assert_eq!(count_push_int(&unfolded, 3), 1);
+        assert_eq!(count_push_int(&unfolded, 14), 0);
+        assert_eq!(count_push_int(&folded, 3), 0);
+        assert_eq!(count_push_int(&folded, 14), 1);
+        assert!(folded.len() < unfolded.len());
+    }
+
+    #[test]
+    fn remove_discarded_values() {
+        let src = "fun f(x: Int) -> Int = { $x + 2; $x }";
+        let unoptimized = compile_with_ranges(src, 0);
+        let optimized = compile_with_ranges(src, 1);
+        // `GetLocalQWord x, PushInt 2, IntAdd, PopQWord` is removed
+        assert_eq!(unoptimized.len() - optimized.len(), 5 + 9 + 1 + 1);
+    }
+
+    #[test]
+    fn keep_discarded_division() {
+        let src = "fun f(x: Int) -> Int = { $x / 2; $x }";
+        assert_eq!(
+            compile_with_ranges(src, 1).len(),
+            compile_with_ranges(src, 0).len()
+        );
+    }
+
+    #[test]
+    fn keep_division_by_zero() {
+        let src = "val x = $(( 7919 / 0 ))";
+        assert_eq!(
+            compile_with_ranges(src, 1).len(),
+            compile_with_ranges(src, 0).len()
+        );
+    }
+
+    #[test]
+    fn compact_short_jumps() {
+        let tiny = "var x = 0; if $x == 0 { x = 1 } else { x = 2 }";
+        assert_eq!(
+            compile_with_ranges(tiny, 0).len() - compile_with_ranges(tiny, 1).len(),
+            2 * 3
+        );
+
+        let large = format!(
+            "var x = 0; if $x == 0 {{ {} }} else {{ x = 2 }}",
+            "x = 1; ".repeat(30)
+        );
+        // only the jump over the `else` branch is close enough to its destination
+        assert_eq!(
+            compile_with_ranges(&large, 0).len() - compile_with_ranges(&large, 1).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn unroll_folded_range() {
+        let src = "var x = 0; for i in 0..1 + 2 { $x = $x * 7919 + $i }";
+        assert_eq!(count_push_int(&compile_with_ranges(src, 1), 7919), 3);
+    }
 
     #[test]
     fn test_inner_functions_captures() {
@@ -531,14 +1341,17 @@ mod tests {
             &mut StaticImporter::new([(Name::new("test"), src)], parse_trusted),
             &externals,
         );
-        let captures = resolve_captures(
+        let mut cache = CaptureCache::default();
+        resolve_captures(
             &analyzer.resolution.engine,
             &analyzer.resolution.relations,
             reef_id,
-        );
+            &mut cache,
+        )
+        .unwrap();
 
         assert_eq!(
-            captures,
+            cache.captures,
             vec![
                 Some(vec![]), //root
                 Some(vec![]), //foo
@@ -567,4 +1380,74 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn reuse_cached_captures() {
+        let src = "var i = 0; fun foo() = $i";
+        let externals = Externals::default();
+        let analyzer = analyzer::analyze(
+            Name::new("test"),
+            &mut StaticImporter::new([(Name::new("test"), src)], parse_trusted),
+            &externals,
+        );
+        let mut cache = CaptureCache::default();
+        let compile = |cache: &mut CaptureCache| {
+            compile_reef(
+                &analyzer.engine,
+                &analyzer.resolution.relations,
+                &analyzer.typing,
+                &analyzer.resolution.engine,
+                &externals,
+                &CompilerExternals::default(),
+                externals.current,
+                SourceId(0),
+                cache,
+                &mut Vec::new(),
+                CompilerOptions::default(),
+            )
+            .unwrap();
+        };
+        compile(&mut cache);
+        compile(&mut cache);
+        assert_eq!(cache.resolutions(), 1);
+
+        cache.invalidate(SourceId(0));
+        compile(&mut cache);
+        assert_eq!(cache.resolutions(), 2);
+    }
+
+    #[test]
+    fn dead_relation() {
+        let src = "fun foo() = $undefined";
+        let externals = Externals::default();
+        let mut analyzer = analyzer::analyze(
+            Name::new("test"),
+            &mut StaticImporter::new([(Name::new("test"), src)], parse_trusted),
+            &externals,
+        );
+        assert!(analyzer
+            .take_diagnostics()
+            .iter()
+            .any(Diagnostic::is_critical));
+
+        let res = compile_reef(
+            &analyzer.engine,
+            &analyzer.resolution.relations,
+            &analyzer.typing,
+            &analyzer.resolution.engine,
+            &externals,
+            &CompilerExternals::default(),
+            externals.current,
+            SourceId(0),
+            &mut CaptureCache::default(),
+            &mut Vec::new(),
+            CompilerOptions::default(),
+        );
+        assert!(matches!(
+            res,
+            Err(CompileError::UnresolvedRelation {
+                origin: SourceId(1)
+            })
+        ));
+    }
 }
@@ -7,10 +7,21 @@ use analyzer::types::ty::TypeRef;
 
 use crate::r#type::ValueStackSize;
 
+/// The emplacement of a local value in the locals area.
+#[derive(Clone, Copy)]
+struct LocalSlot {
+    /// the start index of the value
+    pos: u32,
+    /// the size of the value
+    size: ValueStackSize,
+    /// true if the value is an object reference
+    is_obj: bool,
+}
+
 /// contains the different index per local value allocated in the locals area
 pub struct LocalsLayout {
     /// the start indexes of bound Locals
-    values_indexes: Vec<Option<(u32, bool)>>,
+    values_indexes: Vec<Option<LocalSlot>>,
     /// the start indexes of bound external values
     external_refs_indexes: HashMap<ResolvedSymbol, u32>,
     /// the length in bytes
@@ -35,20 +46,28 @@ impl LocalsLayout {
     /// # Panics
     /// Panics if the local id is out of bounds.
     pub fn set_value_space(&mut self, id: LocalId, tpe: TypeRef) {
-        let size: u8 = ValueStackSize::from(tpe).into();
-        self.values_indexes[id.0] = Some((self.len, tpe.is_obj()));
-        self.len += size as u32;
+        self.values_indexes[id.0] = Some(self.allocate(tpe));
     }
 
     /// Creates a new local and reserves the space for it.
     pub fn push_value_space(&mut self, tpe: TypeRef) -> LocalId {
         let id = LocalId(self.values_indexes.len());
-        let size: u8 = ValueStackSize::from(tpe).into();
-        self.values_indexes.push(Some((self.len, tpe.is_obj())));
-        self.len += size as u32;
+        let slot = self.allocate(tpe);
+        self.values_indexes.push(Some(slot));
         id
     }
 
+    fn allocate(&mut self, tpe: TypeRef) -> LocalSlot {
+        let size = ValueStackSize::from(tpe);
+        let slot = LocalSlot {
+            pos: self.len,
+            size,
+            is_obj: tpe.is_obj(),
+        };
+        self.len += u8::from(size) as u32;
+        slot
+    }
+
     /// Reserves the space in local's of the external reference, if not already set.
     ///
     /// Different initialization orders will result in different indexes.
@@ -70,12 +89,20 @@ impl LocalsLayout {
     /// # Panics
     /// Panics if the local id is out of bounds.
     pub fn get_index(&self, id: LocalId) -> Option<u32> {
-        self.values_indexes[id.0].map(|(pos, _)| pos)
+        self.values_indexes[id.0].map(|slot| slot.pos)
+    }
+
+    /// Get the starting byte index and the size allocated for the given local.
+    ///
+    /// # Panics
+    /// Panics if the local id is out of bounds.
+    pub fn get_emplacement(&self, id: LocalId) -> Option<(u32, ValueStackSize)> {
+        self.values_indexes[id.0].map(|slot| (slot.pos, slot.size))
     }
 
     pub fn get_var_index(&self, var: Var) -> Option<u32> {
         match var {
-            Var::Local(LocalId(id)) => self.values_indexes[id].map(|(pos, _)| pos),
+            Var::Local(LocalId(id)) => self.values_indexes[id].map(|slot| slot.pos),
             Var::External(symbol) => self.external_refs_indexes.get(&symbol).copied(),
         }
     }
@@ -83,7 +110,7 @@ impl LocalsLayout {
     pub fn refs_offset(self) -> Vec<u32> {
         self.values_indexes
             .into_iter()
-            .filter_map(|val| val.and_then(|(pos, is_obj)| is_obj.then_some(pos)))
+            .filter_map(|val| val.and_then(|slot| slot.is_obj.then_some(slot.pos)))
             .chain(self.external_refs_indexes.into_values())
             .collect()
     }
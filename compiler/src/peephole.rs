@@ -0,0 +1,300 @@
+//! Peephole optimization of the emitted instructions.
+//!
+//! The emitter discards the values of unused expressions by popping them right after they are
+//! computed. When the computation has no side effects, both the computation and the pop can be
+//! removed. As the removal shifts the following instructions, every jump destination and source
+//! position is then moved to the instruction that took the place of its original target.
+
+use std::mem::size_of;
+
+use crate::bytecode::{InstructionPos, Opcode};
+use crate::r#type::ValueStackSize;
+use crate::verify::decode;
+
+/// The minimum optimization level at which the redundant push and pop pairs are removed.
+pub(crate) const PEEPHOLE_OPT_LEVEL: u8 = 1;
+
+/// An instruction of the rewritten stream.
+struct Rewritten<'a> {
+    /// The offset of the original instruction this one stands for.
+    origin: u32,
+
+    /// The opcode followed by its operand bytes.
+    bytes: &'a [u8],
+
+    /// If a jump may land on this instruction.
+    is_target: bool,
+}
+
+const POP_BYTE: &[u8] = &[Opcode::PopByte as u8];
+const POP_QWORD: &[u8] = &[Opcode::PopQWord as u8];
+
+/// Returns the sizes of the values consumed and the size of the value produced by an instruction
+/// that has no side effect, or `None` if the instruction cannot be removed.
+///
+/// The consumed values are listed from the top of the stack. `Dup` is seen as producing a copy of
+/// the top value without consuming it.
+fn pure_effect(opcode: Opcode) -> Option<(&'static [ValueStackSize], ValueStackSize)> {
+    use ValueStackSize::{Byte, QWord};
+    Some(match opcode {
+        Opcode::PushInt
+        | Opcode::PushFloat
+        | Opcode::PushStringRef
+        | Opcode::PushLocalRef
+        | Opcode::GetLocalQWord
+        | Opcode::Dup => (&[], QWord),
+        Opcode::PushByte | Opcode::GetLocalByte | Opcode::DupByte => (&[], Byte),
+        Opcode::IntNeg | Opcode::FloatNeg => (&[QWord], QWord),
+        Opcode::ConvertByteToInt => (&[Byte], QWord),
+        Opcode::ConvertIntToByte => (&[QWord], Byte),
+        Opcode::BXor => (&[Byte, Byte], Byte),
+        // Integer divisions and modulos are kept, as they may fail at runtime
+        Opcode::IntAdd
+        | Opcode::IntSub
+        | Opcode::IntMul
+        | Opcode::FloatAdd
+        | Opcode::FloatSub
+        | Opcode::FloatMul
+        | Opcode::FloatDiv => (&[QWord, QWord], QWord),
+        Opcode::IntEqual
+        | Opcode::IntLessThan
+        | Opcode::IntLessOrEqual
+        | Opcode::IntGreaterThan
+        | Opcode::IntGreaterOrEqual
+        | Opcode::FloatEqual
+        | Opcode::FloatLessThan
+        | Opcode::FloatLessOrEqual
+        | Opcode::FloatGreaterThan
+        | Opcode::FloatGreaterOrEqual => (&[QWord, QWord], Byte),
+        _ => return None,
+    })
+}
+
+fn popped_size(bytes: &[u8]) -> Option<ValueStackSize> {
+    match Opcode::try_from(bytes[0]) {
+        Ok(Opcode::PopByte) => Some(ValueStackSize::Byte),
+        Ok(Opcode::PopQWord) => Some(ValueStackSize::QWord),
+        _ => None,
+    }
+}
+
+fn is_jump(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::Jump | Opcode::IfJump | Opcode::IfNotJump | Opcode::Fork
+    )
+}
+
+/// Appends an instruction to the rewritten stream.
+///
+/// A pop that follows an instruction without side effects producing a value of the same size
+/// cancels it, and pops the operands of that instruction instead. The pop is only folded if no
+/// jump lands on it, so that every path reaching it still has the same operand stack.
+fn push_rewritten<'a>(rewritten: &mut Vec<Rewritten<'a>>, instruction: Rewritten<'a>) {
+    let foldable = match (popped_size(instruction.bytes), rewritten.last()) {
+        (Some(size), Some(last)) if !instruction.is_target => last
+            .bytes
+            .first()
+            .and_then(|opcode| Opcode::try_from(*opcode).ok())
+            .and_then(pure_effect)
+            .filter(|(_, produced)| *produced == size),
+        _ => None,
+    };
+    let Some((operands, _)) = foldable else {
+        rewritten.push(instruction);
+        return;
+    };
+
+    let cancelled = rewritten.pop().unwrap();
+    let mut is_target = cancelled.is_target;
+    for operand in operands {
+        push_rewritten(
+            rewritten,
+            Rewritten {
+                origin: cancelled.origin,
+                bytes: match operand {
+                    ValueStackSize::Byte => POP_BYTE,
+                    _ => POP_QWORD,
+                },
+                is_target,
+            },
+        );
+        is_target = false;
+    }
+    if is_target {
+        // The instruction was entirely removed, so the jumps now land on the next one.
+        // It must not be folded with the previous instructions anymore.
+        rewritten.push(Rewritten {
+            origin: cancelled.origin,
+            bytes: &[],
+            is_target,
+        });
+    }
+}
+
+/// Removes the values that are pushed on the operand stack only to be popped right after.
+///
+/// The `code` holds the instructions of a single chunk, whose jumps are relative to its start.
+/// The instruction pointers of the given positions are updated to match the returned code.
+pub(crate) fn remove_redundant_pops(code: &[u8], positions: &mut [InstructionPos]) -> Vec<u8> {
+    let Ok(decoded) = decode(code) else {
+        return code.to_vec();
+    };
+    let targets: Vec<u32> = decoded
+        .iter()
+        .filter(|instruction| is_jump(instruction.opcode))
+        .map(|instruction| instruction.operand)
+        .collect();
+
+    let mut rewritten = Vec::with_capacity(decoded.len());
+    for (idx, instruction) in decoded.iter().enumerate() {
+        let end = decoded
+            .get(idx + 1)
+            .map_or(code.len(), |next| next.offset as usize);
+        push_rewritten(
+            &mut rewritten,
+            Rewritten {
+                origin: instruction.offset,
+                bytes: &code[instruction.offset as usize..end],
+                is_target: targets.contains(&instruction.offset),
+            },
+        );
+    }
+
+    let mut offsets = Vec::with_capacity(rewritten.len());
+    let mut len = 0;
+    for instruction in &rewritten {
+        offsets.push(len);
+        len += instruction.bytes.len() as u32;
+    }
+    // An original offset is mapped to the first instruction that stands for it or for any
+    // instruction after it, as the instructions in between were removed.
+    let relocate = |offset: u32| {
+        let idx = rewritten.partition_point(|instruction| instruction.origin < offset);
+        offsets.get(idx).copied().unwrap_or(len)
+    };
+
+    let mut optimized = Vec::with_capacity(len as usize);
+    for instruction in &rewritten {
+        let Some(&opcode) = instruction.bytes.first() else {
+            continue;
+        };
+        optimized.extend_from_slice(instruction.bytes);
+        if Opcode::try_from(opcode).is_ok_and(is_jump) {
+            let operand_start = optimized.len() - size_of::<u32>();
+            let target = u32::from_be_bytes(optimized[operand_start..].try_into().unwrap());
+            optimized[operand_start..].copy_from_slice(&relocate(target).to_be_bytes());
+        }
+    }
+    for position in positions {
+        position.instruction = relocate(position.instruction);
+    }
+    optimized
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::bytecode::{Bytecode, Instructions, Opcode};
+    use crate::peephole::remove_redundant_pops;
+    use crate::r#type::ValueStackSize;
+
+    #[test]
+    fn remove_discarded_arithmetic() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(1);
+        instructions.emit_push_int(2);
+        instructions.emit_code(Opcode::IntAdd);
+        instructions.emit_pop(ValueStackSize::QWord);
+        instructions.emit_push_byte(0);
+        instructions.emit_code(Opcode::Exit);
+
+        let mut positions = Vec::new();
+        assert_eq!(
+            remove_redundant_pops(bytecode.bytes(), &mut positions),
+            vec![Opcode::PushByte as u8, 0, Opcode::Exit as u8]
+        );
+    }
+
+    #[test]
+    fn keep_division() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(1);
+        instructions.emit_push_int(0);
+        instructions.emit_code(Opcode::IntDiv);
+        instructions.emit_pop(ValueStackSize::QWord);
+
+        let mut positions = Vec::new();
+        assert_eq!(
+            remove_redundant_pops(bytecode.bytes(), &mut positions),
+            bytecode.bytes()
+        );
+    }
+
+    #[test]
+    fn patch_crossing_jumps() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_byte(1);
+        let jump = instructions.emit_jump(Opcode::IfJump);
+        instructions.emit_push_int(7);
+        instructions.emit_pop(ValueStackSize::QWord);
+        instructions.push_position(42);
+        instructions.patch_jump(jump);
+        instructions.emit_push_byte(0);
+        instructions.emit_code(Opcode::Exit);
+
+        let mut positions = instructions.take_positions();
+        let mut expected = vec![Opcode::PushByte as u8, 1, Opcode::IfJump as u8];
+        expected.extend(7u32.to_be_bytes());
+        expected.extend([Opcode::PushByte as u8, 0, Opcode::Exit as u8]);
+        assert_eq!(
+            remove_redundant_pops(bytecode.bytes(), &mut positions),
+            expected
+        );
+        assert_eq!(positions[0].instruction, 7);
+    }
+
+    #[test]
+    fn keep_pop_of_jump_target() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(3);
+        instructions.emit_push_byte(1);
+        let jump = instructions.emit_jump(Opcode::IfJump);
+        instructions.emit_push_int(7);
+        instructions.patch_jump(jump);
+        instructions.emit_pop(ValueStackSize::QWord);
+
+        let mut positions = Vec::new();
+        assert_eq!(
+            remove_redundant_pops(bytecode.bytes(), &mut positions),
+            bytecode.bytes()
+        );
+    }
+
+    #[test]
+    fn keep_pop_after_removed_jump_target() {
+        let mut bytecode = Bytecode::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(3);
+        let start = instructions.current_ip();
+        instructions.emit_push_int(7);
+        instructions.emit_pop(ValueStackSize::QWord);
+        instructions.emit_pop(ValueStackSize::QWord);
+        instructions.jump_back_to(start);
+
+        let mut positions = Vec::new();
+        let mut expected = vec![Opcode::PushInt as u8];
+        expected.extend(3i64.to_be_bytes());
+        expected.extend([Opcode::PopQWord as u8, Opcode::Jump as u8]);
+        expected.extend(9u32.to_be_bytes());
+        assert_eq!(
+            remove_redundant_pops(bytecode.bytes(), &mut positions),
+            expected
+        );
+    }
+}
@@ -1,12 +1,12 @@
 use analyzer::types::ty::TypeRef;
-use analyzer::types::{BOOL, ERROR, EXITCODE, FLOAT, INT, NOTHING, UNIT};
+use analyzer::types::{BOOL, DURATION, ERROR, EXITCODE, FLOAT, INT, NOTHING, UNIT};
 
 /// returns the size of a given type identifier
 pub fn get_type_stack_size(tpe: TypeRef) -> ValueStackSize {
     match tpe {
         NOTHING | UNIT => ValueStackSize::Zero,
         BOOL | EXITCODE => ValueStackSize::Byte,
-        INT | FLOAT => ValueStackSize::QWord,
+        INT | FLOAT | DURATION => ValueStackSize::QWord,
         ERROR => panic!("Received 'ERROR' type in compilation phase."),
         _ => ValueStackSize::QWord, //other types are object types which are references (q-words)
     }
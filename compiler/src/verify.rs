@@ -0,0 +1,511 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::bytecode::Opcode;
+use crate::constant_pool::ConstantPool;
+
+/// An inconsistency found in an instruction stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    /// The instruction pointer of the offending instruction, relative to the start of the stream.
+    pub offset: u32,
+    pub kind: VerifyErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyErrorKind {
+    /// The byte does not correspond to any opcode.
+    UnknownOpcode(u8),
+
+    /// The operands of the instruction exceed the end of the stream.
+    Truncated,
+
+    /// The constant pool index is greater than the number of strings in the pool.
+    ConstantOutOfRange(u32),
+
+    /// The invoked signature has no known stack effect in the pool.
+    UnknownInvocation(u32),
+
+    /// The dynamic symbol index is greater than the number of dynamic symbols in the pool.
+    DynamicSymbolOutOfRange(u32),
+
+    /// The jump destination is not the start of an instruction.
+    InvalidJumpTarget(u32),
+
+    /// The instruction pops more bytes than what the operand stack may hold.
+    StackUnderflow { required: u32, available: u32 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "instruction #{}: ", self.offset)?;
+        match &self.kind {
+            VerifyErrorKind::UnknownOpcode(opcode) => write!(f, "unknown opcode {opcode:#x}"),
+            VerifyErrorKind::Truncated => write!(f, "truncated operand"),
+            VerifyErrorKind::ConstantOutOfRange(idx) => {
+                write!(f, "constant #{idx} is out of the constant pool")
+            }
+            VerifyErrorKind::UnknownInvocation(idx) => {
+                write!(f, "invoked constant #{idx} has no known signature")
+            }
+            VerifyErrorKind::DynamicSymbolOutOfRange(idx) => {
+                write!(f, "external #{idx} is out of the dynamic symbols")
+            }
+            VerifyErrorKind::InvalidJumpTarget(target) => {
+                write!(f, "jump to #{target} does not land on an instruction")
+            }
+            VerifyErrorKind::StackUnderflow {
+                required,
+                available,
+            } => write!(
+                f,
+                "requires {required} bytes on the operand stack, found at most {available}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// A decoded instruction, with its operand if it is an index or an instruction pointer.
+///
+/// The operand of a short jump is its absolute destination.
+pub(crate) struct Instruction {
+    pub(crate) offset: u32,
+    pub(crate) opcode: Opcode,
+    pub(crate) operand: u32,
+}
+
+/// The maximum number of bytes that the operand stack may hold before an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Height {
+    Unreachable,
+    Bounded(u32),
+    Unbounded,
+}
+
+impl Height {
+    fn merge(self, other: Height) -> Height {
+        match (self, other) {
+            (Height::Unreachable, other) | (other, Height::Unreachable) => other,
+            (Height::Bounded(a), Height::Bounded(b)) => Height::Bounded(a.max(b)),
+            _ => Height::Unbounded,
+        }
+    }
+}
+
+/// Checks that an instruction stream is well-formed.
+///
+/// Every constant and dynamic symbol reference must be in range of the pool, and every jump must
+/// land on an instruction boundary or at the end of the stream. The operand stack is also checked
+/// not to underflow, by tracking an upper bound of its size. The invoked functions pop and push
+/// the number of bytes recorded in the pool for their signature.
+pub fn verify(instructions: &[u8], pool: &ConstantPool) -> Result<(), VerifyError> {
+    let decoded = decode(instructions)?;
+    let boundaries: HashSet<u32> = decoded
+        .iter()
+        .map(|instruction| instruction.offset)
+        .collect();
+    for instruction in &decoded {
+        check_operand(instruction, pool, &boundaries, instructions.len())?;
+    }
+
+    // The heights at the jump targets are refined until they are stable, so that backward jumps
+    // are accounted for. A height that keeps growing is widened to be unbounded.
+    let mut entries = HashMap::new();
+    loop {
+        let mut incoming = entries.clone();
+        let result = check_stack(&decoded, pool, &mut incoming);
+        let mut changed = false;
+        for (target, height) in incoming {
+            let previous = entries.insert(target, height);
+            match (previous, height) {
+                (Some(previous), height) if previous == height => {}
+                (Some(Height::Bounded(_)), Height::Bounded(_)) => {
+                    entries.insert(target, Height::Unbounded);
+                    changed = true;
+                }
+                _ => changed = true,
+            }
+        }
+        if !changed {
+            return result;
+        }
+    }
+}
+
+pub(crate) fn decode(instructions: &[u8]) -> Result<Vec<Instruction>, VerifyError> {
+    let mut decoded = Vec::new();
+    let mut pos = 0;
+    while pos < instructions.len() {
+        let offset = pos as u32;
+        let byte = instructions[pos];
+        let opcode = Opcode::try_from(byte).map_err(|_| VerifyError {
+            offset,
+            kind: VerifyErrorKind::UnknownOpcode(byte),
+        })?;
+        pos += 1;
+        let operand_size = match opcode {
+            Opcode::PushInt | Opcode::PushFloat => 8,
+            Opcode::PushByte => 1,
+            Opcode::PushStringRef
+            | Opcode::PushLocalRef
+            | Opcode::GetLocalByte
+            | Opcode::SetLocalByte
+            | Opcode::GetLocalQWord
+            | Opcode::SetLocalQWord
+            | Opcode::GetStructByte
+            | Opcode::SetStructByte
+            | Opcode::GetStructQWord
+            | Opcode::SetStructQWord
+            | Opcode::FetchByte
+            | Opcode::FetchQWord
+            | Opcode::StoreByte
+            | Opcode::StoreQWord
+            | Opcode::NewStruct
+            | Opcode::StructCopyOperands
+            | Opcode::Invoke
+            | Opcode::Open
+            | Opcode::IfJump
+            | Opcode::IfNotJump
+            | Opcode::Jump
+            | Opcode::Fork => 4,
+            Opcode::IfJumpShort | Opcode::IfNotJumpShort | Opcode::JumpShort => 1,
+            _ => 0,
+        };
+        let operand = instructions
+            .get(pos..pos + operand_size)
+            .ok_or(VerifyError {
+                offset,
+                kind: VerifyErrorKind::Truncated,
+            })?;
+        decoded.push(Instruction {
+            offset,
+            opcode,
+            operand: match (opcode, operand_size) {
+                // short jumps are relative to the next instruction, their target is made absolute
+                (Opcode::IfJumpShort | Opcode::IfNotJumpShort | Opcode::JumpShort, _) => {
+                    let target = (pos + 1) as i64 + i64::from(operand[0] as i8);
+                    u32::try_from(target).unwrap_or(u32::MAX)
+                }
+                (_, 4) => u32::from_be_bytes(operand.try_into().unwrap()),
+                _ => 0,
+            },
+        });
+        pos += operand_size;
+    }
+    Ok(decoded)
+}
+
+fn check_operand(
+    instruction: &Instruction,
+    pool: &ConstantPool,
+    boundaries: &HashSet<u32>,
+    len: usize,
+) -> Result<(), VerifyError> {
+    let operand = instruction.operand;
+    let kind = match instruction.opcode {
+        Opcode::PushStringRef | Opcode::NewStruct | Opcode::Invoke
+            if operand as usize >= pool.strings.len() =>
+        {
+            VerifyErrorKind::ConstantOutOfRange(operand)
+        }
+        Opcode::Invoke if !pool.invocations.contains_key(&operand) => {
+            VerifyErrorKind::UnknownInvocation(operand)
+        }
+        Opcode::FetchByte | Opcode::FetchQWord | Opcode::StoreByte | Opcode::StoreQWord
+            if operand as usize >= pool.dynsym.len() =>
+        {
+            VerifyErrorKind::DynamicSymbolOutOfRange(operand)
+        }
+        Opcode::IfJump
+        | Opcode::IfNotJump
+        | Opcode::Jump
+        | Opcode::Fork
+        | Opcode::IfJumpShort
+        | Opcode::IfNotJumpShort
+        | Opcode::JumpShort
+            if operand as usize != len && !boundaries.contains(&operand) =>
+        {
+            VerifyErrorKind::InvalidJumpTarget(operand)
+        }
+        _ => return Ok(()),
+    };
+    Err(VerifyError {
+        offset: instruction.offset,
+        kind,
+    })
+}
+
+/// Returns the number of bytes popped and pushed by an instruction.
+///
+/// The pushed bytes are an upper bound for the instructions whose result size depends on the
+/// runtime values.
+fn stack_effect(instruction: &Instruction, pool: &ConstantPool) -> (u32, u32) {
+    match instruction.opcode {
+        Opcode::PushInt
+        | Opcode::PushFloat
+        | Opcode::PushStringRef
+        | Opcode::PushLocalRef
+        | Opcode::GetLocalQWord
+        | Opcode::FetchQWord
+        | Opcode::NewStruct => (0, 8),
+        Opcode::PushByte | Opcode::GetLocalByte | Opcode::FetchByte => (0, 1),
+        Opcode::BoxQWord
+        | Opcode::Unbox
+        | Opcode::GetRefQWord
+        | Opcode::GetStructQWord
+        | Opcode::Open
+        | Opcode::Read
+        | Opcode::IntNeg
+        | Opcode::FloatNeg => (8, 8),
+        Opcode::BoxByte | Opcode::ConvertByteToInt => (1, 8),
+        Opcode::SetLocalByte | Opcode::StoreByte | Opcode::PopByte | Opcode::Exit => (1, 0),
+        Opcode::SetLocalQWord
+        | Opcode::StoreQWord
+        | Opcode::PopQWord
+        | Opcode::Exec
+        | Opcode::Close => (8, 0),
+        Opcode::GetRefByte | Opcode::GetStructByte | Opcode::Wait | Opcode::ConvertIntToByte => {
+            (8, 1)
+        }
+        Opcode::SetRefByte | Opcode::SetStructByte => (9, 0),
+        Opcode::SetRefQWord | Opcode::SetStructQWord | Opcode::Write => (16, 0),
+        Opcode::StructCopyOperands => (instruction.operand.saturating_add(8), 8),
+        Opcode::Invoke => pool.invocations[&instruction.operand],
+        Opcode::SetupRedirect | Opcode::Redirect => (16, 8),
+        Opcode::Pipe => (0, 16),
+        Opcode::Dup => (8, 16),
+        Opcode::DupByte => (1, 2),
+        Opcode::Swap => (16, 16),
+        Opcode::Swap2 => (24, 24),
+        Opcode::IfJump | Opcode::IfNotJump | Opcode::IfJumpShort | Opcode::IfNotJumpShort => (1, 0),
+        Opcode::BXor => (2, 1),
+        Opcode::IntAdd
+        | Opcode::IntSub
+        | Opcode::IntMul
+        | Opcode::IntDiv
+        | Opcode::IntMod
+        | Opcode::FloatAdd
+        | Opcode::FloatSub
+        | Opcode::FloatMul
+        | Opcode::FloatDiv => (16, 8),
+        Opcode::IntEqual
+        | Opcode::IntLessThan
+        | Opcode::IntLessOrEqual
+        | Opcode::IntGreaterThan
+        | Opcode::IntGreaterOrEqual
+        | Opcode::FloatEqual
+        | Opcode::FloatLessThan
+        | Opcode::FloatLessOrEqual
+        | Opcode::FloatGreaterThan
+        | Opcode::FloatGreaterOrEqual => (16, 1),
+        Opcode::Fork | Opcode::PopRedirect | Opcode::Jump | Opcode::JumpShort | Opcode::Return => {
+            (0, 0)
+        }
+    }
+}
+
+/// Walks the instructions once, merging the heights of the jumps into `entries`.
+///
+/// It returns the first underflow found.
+fn check_stack(
+    decoded: &[Instruction],
+    pool: &ConstantPool,
+    entries: &mut HashMap<u32, Height>,
+) -> Result<(), VerifyError> {
+    let mut result = Ok(());
+    let mut height = Height::Bounded(0);
+    for instruction in decoded {
+        if let Some(entry) = entries.get(&instruction.offset) {
+            height = height.merge(*entry);
+        }
+        let (pops, pushes) = stack_effect(instruction, pool);
+        height = match height {
+            Height::Unreachable => continue,
+            Height::Bounded(available) => {
+                if available < pops && result.is_ok() {
+                    result = Err(VerifyError {
+                        offset: instruction.offset,
+                        kind: VerifyErrorKind::StackUnderflow {
+                            required: pops,
+                            available,
+                        },
+                    });
+                }
+                Height::Bounded(available.saturating_sub(pops).saturating_add(pushes))
+            }
+            Height::Unbounded => Height::Unbounded,
+        };
+
+        let mut jump = |height: Height| {
+            let entry = entries
+                .entry(instruction.operand)
+                .or_insert(Height::Unreachable);
+            *entry = entry.merge(height);
+        };
+        match instruction.opcode {
+            Opcode::IfJump | Opcode::IfNotJump | Opcode::IfJumpShort | Opcode::IfNotJumpShort => {
+                jump(height)
+            }
+            Opcode::Fork => {
+                // the parent process jumps with the child pid on the stack
+                jump(match height {
+                    Height::Bounded(available) => Height::Bounded(available.saturating_add(8)),
+                    height => height,
+                });
+            }
+            Opcode::Jump | Opcode::JumpShort => {
+                jump(height);
+                height = Height::Unreachable;
+            }
+            Opcode::Return | Opcode::Exit => height = Height::Unreachable,
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::bytecode::{Bytecode, Instructions, Opcode};
+    use crate::constant_pool::ConstantPool;
+    use crate::r#type::ValueStackSize;
+    use crate::verify::{verify, VerifyError, VerifyErrorKind};
+
+    #[test]
+    fn verify_well_formed() {
+        let mut bytecode = Bytecode::default();
+        let mut pool = ConstantPool::default();
+        let echo = pool.insert_string("echo");
+        pool.insert_exported("count", 0, false, None);
+
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        let start = instructions.current_ip();
+        instructions.emit_push_constant_ref(echo);
+        instructions.emit_code(Opcode::Exec);
+        instructions.emit_push_byte(1);
+        let end = instructions.emit_jump(Opcode::IfJump);
+        instructions.emit_push_int(7);
+        instructions.emit_set_external(0, ValueStackSize::QWord);
+        instructions.jump_back_to(start);
+        instructions.patch_jump(end);
+
+        assert_eq!(verify(bytecode.bytes(), &pool), Ok(()));
+    }
+
+    #[test]
+    fn verify_constant_out_of_range() {
+        let mut bytecode = Bytecode::default();
+        let pool = ConstantPool::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(4);
+        instructions.emit_invoke(0);
+
+        assert_eq!(
+            verify(bytecode.bytes(), &pool),
+            Err(VerifyError {
+                offset: 9,
+                kind: VerifyErrorKind::ConstantOutOfRange(0),
+            })
+        );
+    }
+
+    #[test]
+    fn verify_unknown_invocation() {
+        let mut bytecode = Bytecode::default();
+        let mut pool = ConstantPool::default();
+        let exit = pool.insert_string("std::exit");
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_byte(0);
+        instructions.emit_invoke(exit);
+
+        assert_eq!(
+            verify(bytecode.bytes(), &pool),
+            Err(VerifyError {
+                offset: 2,
+                kind: VerifyErrorKind::UnknownInvocation(exit),
+            })
+        );
+    }
+
+    #[test]
+    fn verify_invocation_underflow() {
+        let mut bytecode = Bytecode::default();
+        let mut pool = ConstantPool::default();
+        let concat = pool.insert_invocation(
+            "lang::String::concat",
+            [ValueStackSize::QWord, ValueStackSize::QWord],
+            ValueStackSize::QWord,
+        );
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_constant_ref(concat);
+        instructions.emit_invoke(concat);
+        instructions.emit_pop(ValueStackSize::QWord);
+
+        assert_eq!(
+            verify(bytecode.bytes(), &pool),
+            Err(VerifyError {
+                offset: 5,
+                kind: VerifyErrorKind::StackUnderflow {
+                    required: 16,
+                    available: 8,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn verify_jump_inside_instruction() {
+        let mut bytecode = Bytecode::default();
+        let pool = ConstantPool::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_int(4);
+        instructions.jump_back_to(3);
+
+        assert_eq!(
+            verify(bytecode.bytes(), &pool),
+            Err(VerifyError {
+                offset: 9,
+                kind: VerifyErrorKind::InvalidJumpTarget(3),
+            })
+        );
+    }
+
+    #[test]
+    fn verify_stack_underflow() {
+        let mut bytecode = Bytecode::default();
+        let pool = ConstantPool::default();
+        let mut instructions = Instructions::wrap(&mut bytecode);
+        instructions.emit_push_byte(1);
+        instructions.emit_push_int(2);
+        instructions.emit_code(Opcode::IntAdd);
+
+        assert_eq!(
+            verify(bytecode.bytes(), &pool),
+            Err(VerifyError {
+                offset: 11,
+                kind: VerifyErrorKind::StackUnderflow {
+                    required: 16,
+                    available: 9,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn verify_truncated_operand() {
+        let pool = ConstantPool::default();
+        let bytes = [Opcode::PushInt as u8, 0, 0, 0];
+
+        assert_eq!(
+            verify(&bytes, &pool),
+            Err(VerifyError {
+                offset: 0,
+                kind: VerifyErrorKind::Truncated,
+            })
+        );
+    }
+}
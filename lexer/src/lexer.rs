@@ -1,8 +1,10 @@
 use std::iter::Peekable;
 use std::str::CharIndices;
 
+use context::source::SourceSegment;
+
 use crate::delimiter::UnmatchedDelimiter;
-use crate::token::{Token, TokenType};
+use crate::token::{Comment, CommentKind, Token, TokenType};
 
 /// A lexer that iterates over the input string and produces tokens.
 pub(crate) struct Lexer<'a> {
@@ -18,7 +20,16 @@ pub(crate) struct Lexer<'a> {
     /// The vector of unmatched delimiter errors.
     pub(crate) mismatches: Vec<UnmatchedDelimiter>,
 
+    /// The comments that were skipped.
+    pub(crate) comments: Vec<Comment>,
+
     state: LexerState,
+
+    /// A token that has already been scanned and must be yielded next.
+    pending: Option<Token>,
+
+    /// The offset after the last here-document body, to skip at the end of the current line.
+    here_doc_end: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -47,11 +58,17 @@ impl<'a> Lexer<'a> {
             input,
             open_delimiters: Vec::new(),
             mismatches: Vec::new(),
+            comments: Vec::new(),
             state: LexerState::Normal,
+            pending: None,
+            here_doc_end: None,
         }
     }
 
     fn next_token(&mut self) -> Token {
+        if let Some(token) = self.pending.take() {
+            return token;
+        }
         if let Some((pos, c)) = self.iter.next() {
             if self.is_in_string() {
                 if c == '$' {
@@ -100,10 +117,10 @@ impl<'a> Lexer<'a> {
             '/' => {
                 if self.matches_next('/', &mut size) {
                     return if self.matches_next('*', &mut size) {
-                        self.skip_multiline_comment();
+                        self.skip_multiline_comment(pos);
                         self.next_token()
                     } else {
-                        self.skip_line()
+                        self.skip_line(pos)
                     };
                 } else {
                     TokenType::Slash
@@ -163,7 +180,11 @@ impl<'a> Lexer<'a> {
                     TokenType::Colon
                 }
             }
-            '\r' | '\n' => TokenType::NewLine,
+            '\n' => {
+                self.skip_here_doc_bodies();
+                TokenType::NewLine
+            }
+            '\r' => TokenType::NewLine,
             '!' => {
                 if self.matches_next('=', &mut size) {
                     TokenType::NotEqual
@@ -172,6 +193,9 @@ impl<'a> Lexer<'a> {
                 }
             }
             '<' => {
+                if let Some(token) = self.next_here_doc(pos) {
+                    return token;
+                }
                 if self.matches_next('=', &mut size) {
                     TokenType::LessEqual
                 } else {
@@ -253,38 +277,103 @@ impl<'a> Lexer<'a> {
         false
     }
 
-    /// Skip the remaining characters of the current line.
-    fn skip_line(&mut self) -> Token {
-        for (pos, c) in self.iter.by_ref() {
+    /// Skip the remaining characters of the current line comment.
+    fn skip_line(&mut self, start: usize) -> Token {
+        while let Some((pos, c)) = self.iter.next() {
             if c == '\n' {
+                self.push_comment(CommentKind::Line, start..pos);
+                self.skip_here_doc_bodies();
                 return Token::new(TokenType::NewLine, pos..pos + 1);
             }
         }
+        self.push_comment(CommentKind::Line, start..self.input.len());
         Token::new(TokenType::EndOfFile, self.input.len()..self.input.len())
     }
 
+    /// Yields a here-document operator if the input continues with `<DELIMITER`.
+    ///
+    /// The body of the here-document spans the lines after the current one, up to a line that
+    /// only contains the delimiter. It is yielded as a string literal right after the operator,
+    /// and skipped once the current line ends.
+    fn next_here_doc(&mut self, start: usize) -> Option<Token> {
+        // the operator is part of a here-string `<<<word`
+        if self.input[..start].ends_with('<') {
+            return None;
+        }
+        let mut lookahead = self.iter.clone();
+        lookahead.next_if(|(_, c)| *c == '<')?;
+        lookahead.next_if(|(_, c)| *c == '_' || c.is_alphabetic())?;
+        while lookahead
+            .next_if(|(_, c)| *c == '_' || c.is_alphanumeric())
+            .is_some()
+        {}
+        let delimiter_end = lookahead.peek().map_or(self.input.len(), |(pos, _)| *pos);
+        self.iter = lookahead;
+        let delimiter = &self.input[start + 2..delimiter_end];
+
+        let line_end = self.here_doc_end.unwrap_or(delimiter_end);
+        let body_start = self.input[line_end..]
+            .find('\n')
+            .map_or(self.input.len(), |offset| line_end + offset + 1);
+        let mut line_start = body_start;
+        let body = loop {
+            if line_start >= self.input.len() {
+                self.mismatches.push(UnmatchedDelimiter {
+                    opening: Some(start),
+                    candidate: None,
+                    closing: None,
+                });
+                self.here_doc_end = Some(self.input.len());
+                break body_start..self.input.len();
+            }
+            let line_len = self.input[line_start..]
+                .find('\n')
+                .unwrap_or(self.input.len() - line_start);
+            let line = &self.input[line_start..line_start + line_len];
+            if line.strip_suffix('\r').unwrap_or(line) == delimiter {
+                self.here_doc_end = Some(line_start + line_len);
+                break body_start..line_start;
+            }
+            line_start += line_len + 1;
+        };
+        self.pending = Some(Token::new(TokenType::StringLiteral, body));
+        Some(Token::new(TokenType::HereDoc, start..delimiter_end))
+    }
+
+    /// Skips the here-document bodies that were opened on the line that just ended.
+    fn skip_here_doc_bodies(&mut self) {
+        if let Some(end) = self.here_doc_end.take() {
+            while self.iter.next_if(|(pos, _)| *pos < end).is_some() {}
+        }
+    }
+
     /// Skip the remaining characters of the current multiline comment.
-    fn skip_multiline_comment(&mut self) {
-        while let Some((_, c)) = self.iter.next() {
+    fn skip_multiline_comment(&mut self, start: usize) {
+        let mut end = self.input.len();
+        while let Some((pos, c)) = self.iter.next() {
             if c == '*' && self.iter.next_if(|(_, c)| *c == '/').is_some() {
+                end = pos + 2;
                 break;
             }
         }
+        self.push_comment(CommentKind::Block, start..end);
     }
 
+    /// Records a skipped comment, without its trailing line feed.
+    fn push_comment(&mut self, kind: CommentKind, span: SourceSegment) {
+        let text = &self.input[span.clone()];
+        let end = span.start + text.strip_suffix('\r').unwrap_or(text).len();
+        self.comments.push(Comment {
+            kind,
+            span: span.start..end,
+        });
+    }
+
+    /// Yields a single-quoted string, that ends at the next quote since it cannot contain escapes.
     fn next_string(&mut self, start: usize) -> Token {
         let mut end = start + 1;
-        let mut escape = false;
         for (pos, c) in self.iter.by_ref() {
             end = pos;
-            if escape {
-                escape = false;
-                continue;
-            }
-            if c == '\\' {
-                escape = true;
-                continue;
-            }
             if c == '\'' {
                 return Token::new(TokenType::StringLiteral, start + 1..end);
             }
@@ -326,7 +415,8 @@ impl<'a> Lexer<'a> {
 
     fn is_in_string(&self) -> bool {
         self.open_delimiters
-            .last().is_some_and(|token| token.token_type == TokenType::StringStart)
+            .last()
+            .is_some_and(|token| token.token_type == TokenType::StringStart)
     }
 
     fn pop_delimiter_if(&mut self, token_type: TokenType) {
@@ -1,5 +1,5 @@
 use crate::delimiter::{TokenStream, UnmatchedDelimiter};
-use crate::token::Token;
+use crate::token::{Comment, Token};
 
 pub mod delimiter;
 mod lexer;
@@ -8,10 +8,16 @@ pub mod token;
 
 /// Scans the input string and tokenizes it.
 pub fn lex(input: &str) -> (Vec<Token>, Vec<UnmatchedDelimiter>) {
+    let (tokens, mismatches, _) = lex_with_comments(input);
+    (tokens, mismatches)
+}
+
+/// Scans the input string and tokenizes it, also returning the skipped comments.
+pub fn lex_with_comments(input: &str) -> (Vec<Token>, Vec<UnmatchedDelimiter>, Vec<Comment>) {
     let mut stream = TokenStream::new(input);
     let tokens = Vec::from_iter(&mut stream);
-    let mismatches = stream.lexer.mismatches;
-    (tokens, mismatches)
+    let lexer = stream.lexer;
+    (tokens, lexer.mismatches, lexer.comments)
 }
 
 /// Tests if the delimiters in the input string are balanced, but not terminated.
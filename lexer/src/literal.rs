@@ -47,16 +47,23 @@ impl Lexer<'_> {
     }
 
     pub(crate) fn next_number(&mut self, start_pos: usize) -> Token {
+        if let Some(token) = self.next_radix_number(start_pos) {
+            return token;
+        }
         let mut pos = start_pos + 1;
         let mut is_float = false;
+        let mut after_separator = false;
+        let start = self.iter.clone();
         let mut it = self.iter.clone();
         while let Some((p, c)) = it.peek().copied() {
-            if c.is_ascii_digit() {
+            if c.is_ascii_digit() || (c == '_' && !after_separator) {
+                after_separator = c == '_';
                 pos = p + 1;
                 it.next();
                 self.iter = it.clone();
             } else if c == '.'
                 && !is_float
+                && !after_separator
                 && it.next().is_some()
                 && it.peek().map(|(_, c)| c.is_ascii_digit()).unwrap_or(false)
             {
@@ -65,15 +72,14 @@ impl Lexer<'_> {
             } else if !is_xid_continue(c) {
                 break;
             } else {
-                return self.next_identifier(
-                    start_pos,
-                    self.input[start_pos..]
-                        .chars()
-                        .next()
-                        .expect("Invalid starting position"),
-                );
+                return self.next_number_as_identifier(start_pos);
             }
         }
+        if after_separator {
+            // a separator must be followed by a digit, so the whole word is read again
+            self.iter = start;
+            return self.next_number_as_identifier(start_pos);
+        }
         Token::new(
             if is_float {
                 TokenType::FloatLiteral
@@ -84,6 +90,53 @@ impl Lexer<'_> {
         )
     }
 
+    /// Yields the rest of a word that started as a number, but that is not a valid one.
+    fn next_number_as_identifier(&mut self, start_pos: usize) -> Token {
+        self.next_identifier(
+            start_pos,
+            self.input[start_pos..]
+                .chars()
+                .next()
+                .expect("Invalid starting position"),
+        )
+    }
+
+    /// Yields an integer token written with a `0x`, `0o` or `0b` prefix, if the input starts with one.
+    ///
+    /// The prefix must be followed by at least one digit of its base, otherwise the input is left
+    /// untouched. Digits may be separated by single `_` separators.
+    fn next_radix_number(&mut self, start_pos: usize) -> Option<Token> {
+        let radix = match self.input[start_pos..].as_bytes() {
+            [b'0', b'x', ..] => 16,
+            [b'0', b'o', ..] => 8,
+            [b'0', b'b', ..] => 2,
+            _ => return None,
+        };
+        let mut it = self.iter.clone();
+        it.next();
+        if !it.peek().is_some_and(|(_, c)| c.is_digit(radix)) {
+            return None;
+        }
+        let mut pos = start_pos + 2;
+        let mut after_separator = false;
+        while let Some((p, c)) = it.peek().copied() {
+            if c.is_digit(radix) || (c == '_' && !after_separator) {
+                after_separator = c == '_';
+                pos = p + 1;
+                it.next();
+            } else if is_xid_continue(c) {
+                return None;
+            } else {
+                break;
+            }
+        }
+        if after_separator {
+            return None;
+        }
+        self.iter = it;
+        Some(Token::new(TokenType::IntLiteral, start_pos..pos))
+    }
+
     pub(crate) fn next_space(&mut self, start_pos: usize, start_char: char) -> Token {
         let mut pos = start_pos + start_char.len_utf8();
         while let Some((p, c)) = self.iter.peek().copied() {
@@ -19,6 +19,24 @@ impl Token {
     }
 }
 
+/// A comment that is not part of the token stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+
+    /// The whole comment, including its opening and closing markers.
+    pub span: SourceSegment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// A `//` comment, that ends with the line.
+    Line,
+
+    /// A `//*` comment, that ends with `*/`.
+    Block,
+}
+
 #[derive(Assoc, Debug, PartialEq, Eq, Clone, Copy)]
 #[func(pub fn str(&self) -> Option<&'static str>)]
 pub enum TokenType {
@@ -135,6 +153,9 @@ pub enum TokenType {
     Greater,
     #[assoc(str = ">=")]
     GreaterEqual,
+    /// The `<<DELIMITER` operator that opens a here-document.
+    #[assoc(str = "<<")]
+    HereDoc,
 
     #[assoc(str = "+")]
     Plus,
@@ -230,6 +251,7 @@ impl TokenType {
                 | GreaterEqual
                 | DotDot
                 | As
+                | In
         )
     }
 
@@ -264,6 +286,7 @@ impl TokenType {
             Ampersand
                 | Less
                 | Greater
+                | HereDoc
                 | Bar
                 | Or
                 | And
@@ -340,6 +363,7 @@ impl TokenType {
                 | Not
                 | Less
                 | Greater
+                | HereDoc
                 | Plus
                 | Minus
                 | Star
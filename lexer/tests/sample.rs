@@ -1,5 +1,5 @@
 use lexer::delimiter::UnmatchedDelimiter;
-use lexer::token::TokenType;
+use lexer::token::{Comment, CommentKind, TokenType};
 use lexer::unescape;
 use pretty_assertions::assert_eq;
 
@@ -10,12 +10,12 @@ struct Token<'a> {
 }
 
 impl Token<'_> {
-    fn new(token_type: TokenType, text: &str) -> Token {
+    fn new(token_type: TokenType, text: &str) -> Token<'_> {
         Token { token_type, text }
     }
 }
 
-fn lex(input: &str) -> Vec<Token> {
+fn lex(input: &str) -> Vec<Token<'_>> {
     let (tokens, unmatched) = lexer::lex(input);
     assert_eq!(unmatched, &[]);
     tokens
@@ -29,11 +29,11 @@ fn lex(input: &str) -> Vec<Token> {
 
 #[test]
 fn string_literal() {
-    let tokens = lex("'It\\'s http://localhost  //true' // comment\nyes");
+    let tokens = lex("'It\\s http://localhost  //true' // comment\nyes");
     assert_eq!(
         tokens,
         vec![
-            Token::new(TokenType::StringLiteral, "It\\'s http://localhost  //true"),
+            Token::new(TokenType::StringLiteral, "It\\s http://localhost  //true"),
             Token::new(TokenType::Space, " "),
             Token::new(TokenType::NewLine, "\n"),
             Token::new(TokenType::Identifier, "yes"),
@@ -267,6 +267,94 @@ fn numbers() {
     );
 }
 
+#[test]
+fn prefixed_numbers() {
+    let tokens = lex("0xFF 0o17 0b1010 1_000_000 0xG");
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenType::IntLiteral, "0xFF"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::IntLiteral, "0o17"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::IntLiteral, "0b1010"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::IntLiteral, "1_000_000"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "0xG"),
+        ]
+    );
+}
+
+#[test]
+fn misplaced_number_separators() {
+    let tokens = lex("1__000 1_000_ 0xF__F 0b1_");
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenType::Identifier, "1__000"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "1_000_"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "0xF__F"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "0b1_"),
+        ]
+    );
+}
+
+#[test]
+fn here_document() {
+    let tokens = lex("cat <<END | wc\nhello\nworld\nEND\necho");
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenType::Identifier, "cat"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::HereDoc, "<<END"),
+            Token::new(TokenType::StringLiteral, "hello\nworld\n"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Bar, "|"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "wc"),
+            Token::new(TokenType::NewLine, "\n"),
+            Token::new(TokenType::NewLine, "\n"),
+            Token::new(TokenType::Identifier, "echo"),
+        ]
+    );
+}
+
+#[test]
+fn here_string_is_not_here_document() {
+    let tokens = lex("cat <<<word");
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenType::Identifier, "cat"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Less, "<"),
+            Token::new(TokenType::Less, "<"),
+            Token::new(TokenType::Less, "<"),
+            Token::new(TokenType::Identifier, "word"),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_here_document() {
+    let input = "cat <<EOF\nhello";
+    let unmatched = lexer::lex(input).1;
+    assert_eq!(
+        unmatched,
+        vec![UnmatchedDelimiter {
+            opening: Some(input.find('<').unwrap()),
+            candidate: None,
+            closing: None,
+        }]
+    );
+    assert!(lexer::is_unterminated(input));
+}
+
 #[test]
 fn multiline_comments() {
     let tokens = lex("ls//*\n * This is a comment\n*/exit");
@@ -279,6 +367,42 @@ fn multiline_comments() {
     );
 }
 
+#[test]
+fn comments_are_reported() {
+    let input = "ls // list\r\n//* block\n */ exit // end";
+    let (tokens, unmatched, comments) = lexer::lex_with_comments(input);
+    assert_eq!(unmatched, &[]);
+    assert_eq!(
+        comments,
+        vec![
+            Comment {
+                kind: CommentKind::Line,
+                span: input.find("// list").unwrap()..input.find('\r').unwrap(),
+            },
+            Comment {
+                kind: CommentKind::Block,
+                span: input.find("//*").unwrap()..input.find("*/").unwrap() + 2,
+            },
+            Comment {
+                kind: CommentKind::Line,
+                span: input.find("// end").unwrap()..input.len(),
+            },
+        ]
+    );
+    assert_eq!(tokens, lexer::lex(input).0);
+    assert_eq!(
+        lex(input),
+        vec![
+            Token::new(TokenType::Identifier, "ls"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::NewLine, "\n"),
+            Token::new(TokenType::Space, " "),
+            Token::new(TokenType::Identifier, "exit"),
+            Token::new(TokenType::Space, " "),
+        ]
+    );
+}
+
 #[test]
 fn short_divide() {
     let tokens = lex("$(($a/$b))");
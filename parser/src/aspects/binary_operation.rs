@@ -13,10 +13,11 @@ pub(crate) const fn infix_precedence(tok: TokenType) -> u8 {
         DotDot => 2,
         Or => 3,
         And => 4,
-        EqualEqual | NotEqual | Less | LessEqual | Greater | GreaterEqual => 5,
-        Plus | Minus => 6,
-        Star | Slash | Percent => 7,
-        As => 8,
+        In => 5,
+        EqualEqual | NotEqual | Less | LessEqual | Greater | GreaterEqual => 6,
+        Plus | Minus => 7,
+        Star | Slash | Percent => 8,
+        As => 9,
         _ => NOT_AN_OPERATOR,
     }
 }
@@ -181,6 +182,76 @@ mod tests {
         )
     }
 
+    #[test]
+    fn membership_below_comparison() {
+        let source = "1 + 2 < 3 in 4";
+        let mut parser = Parser::new(source);
+        let ast = parser.value().expect("parsing error");
+        assert_eq!(
+            ast,
+            Expr::Binary(BinaryOperation {
+                left: Box::new(Expr::Binary(BinaryOperation {
+                    left: Box::new(Expr::Binary(BinaryOperation {
+                        left: Box::new(Expr::Literal(Literal {
+                            parsed: 1.into(),
+                            segment: find_in(source, "1")
+                        })),
+                        op: Plus,
+                        right: Box::new(Expr::Literal(Literal {
+                            parsed: 2.into(),
+                            segment: find_in(source, "2")
+                        })),
+                    })),
+                    op: Less,
+                    right: Box::new(Expr::Literal(Literal {
+                        parsed: 3.into(),
+                        segment: find_in(source, "3")
+                    })),
+                })),
+                op: In,
+                right: Box::new(Expr::Literal(Literal {
+                    parsed: 4.into(),
+                    segment: find_in(source, "4")
+                })),
+            })
+        )
+    }
+
+    #[test]
+    fn membership_above_logical() {
+        let source = "1 in 2 && 3 in 4";
+        let mut parser = Parser::new(source);
+        let ast = parser.value().expect("parsing error");
+        assert_eq!(
+            ast,
+            Expr::Binary(BinaryOperation {
+                left: Box::new(Expr::Binary(BinaryOperation {
+                    left: Box::new(Expr::Literal(Literal {
+                        parsed: 1.into(),
+                        segment: find_in(source, "1")
+                    })),
+                    op: In,
+                    right: Box::new(Expr::Literal(Literal {
+                        parsed: 2.into(),
+                        segment: find_in(source, "2")
+                    })),
+                })),
+                op: And,
+                right: Box::new(Expr::Binary(BinaryOperation {
+                    left: Box::new(Expr::Literal(Literal {
+                        parsed: 3.into(),
+                        segment: find_in(source, "3")
+                    })),
+                    op: In,
+                    right: Box::new(Expr::Literal(Literal {
+                        parsed: 4.into(),
+                        segment: find_in(source, "4")
+                    })),
+                })),
+            })
+        )
+    }
+
     #[test]
     fn complete_prioritization_test() {
         let source = "1 +\\\n 2 \\\n*\\\n 3\\\n < 874\\\n / 78 \\\n||\\\n 7\\\n % 4 \\\n== 3 \\\n&& \\\n7 ==\\\n 1";
@@ -267,7 +338,7 @@ mod tests {
             result,
             Err(ParseError {
                 message: "Unexpected token ')'.".to_string(),
-                position: source.find(')').map(|p| (p..p + 1)).unwrap(),
+                position: source.find(')').map(|p| p..p + 1).unwrap(),
                 kind: ParseErrorKind::Unexpected,
             })
         )
@@ -1,4 +1,4 @@
-use ast::call::{Call, MethodCall, ProgrammaticCall};
+use ast::call::{Call, MethodCall, NamedArgument, ProgrammaticCall};
 use ast::r#struct::FieldAccess;
 use ast::r#type::Type;
 use ast::variable::{Identifier, Path};
@@ -108,15 +108,34 @@ impl Parser<'_> {
                     self.cursor.advance(blanks());
                     let dot_token = self.cursor.next()?;
                     let field_token = self.cursor.force(
-                        of_type(TokenType::Identifier),
+                        of_types(&[
+                            TokenType::Identifier,
+                            TokenType::IntLiteral,
+                            TokenType::FloatLiteral,
+                        ]),
                         "identifier expected when accessing attribute",
                     )?;
-                    let segment = dot_token.span.start..field_token.span.end;
-                    Expr::FieldAccess(FieldAccess {
-                        expr: Box::new(expr),
-                        field: Identifier::extract(self.source, field_token.span),
-                        segment,
-                    })
+                    let span = field_token.span;
+                    if field_token.token_type == TokenType::FloatLiteral {
+                        // Nested tuple indexes such as `.0.1` are read as a single float
+                        let dot = span.start + self.source[span.clone()].find('.').unwrap();
+                        let inner = Expr::FieldAccess(FieldAccess {
+                            expr: Box::new(expr),
+                            field: Identifier::extract(self.source, span.start..dot),
+                            segment: dot_token.span.start..dot,
+                        });
+                        Expr::FieldAccess(FieldAccess {
+                            expr: Box::new(inner),
+                            field: Identifier::extract(self.source, dot + 1..span.end),
+                            segment: dot..span.end,
+                        })
+                    } else {
+                        Expr::FieldAccess(FieldAccess {
+                            expr: Box::new(expr),
+                            field: Identifier::extract(self.source, span.clone()),
+                            segment: dot_token.span.start..span.end,
+                        })
+                    }
                 }
                 _ => break,
             };
@@ -162,7 +181,7 @@ impl Parser<'_> {
 
         let pivot = self.cursor.peek().token_type;
         match pivot {
-            TokenType::RoundedLeftBracket => Ok(Expr::Parenthesis(self.parenthesis()?)),
+            TokenType::RoundedLeftBracket => self.parenthesis(),
             TokenType::CurlyLeftBracket => Ok(Expr::Block(self.block()?)),
             TokenType::Less | TokenType::Greater => {
                 let redir = self.cursor.next()?;
@@ -199,7 +218,7 @@ impl Parser<'_> {
                 ));
                 continue;
             }
-            match self.value() {
+            match self.comma_separated_argument() {
                 Ok(arg) => args.push(arg),
                 Err(err) => {
                     self.recover_from(err, of_type(TokenType::Comma));
@@ -231,13 +250,36 @@ impl Parser<'_> {
 
         Ok((args, segment))
     }
+
+    /// Parses an argument of a parenthesised argument list, that may be named.
+    fn comma_separated_argument(&mut self) -> ParseResult<Expr> {
+        if self
+            .cursor
+            .lookahead(
+                of_type(TokenType::Identifier).then(spaces().then(of_type(TokenType::Equal))),
+            )
+            .is_none()
+        {
+            return self.value();
+        }
+        let name = self.cursor.next()?;
+        self.cursor
+            .advance(spaces().then(of_type(TokenType::Equal)).then(spaces()));
+        let value = self.value()?;
+        let segment = name.span.start..value.segment().end;
+        Ok(Expr::NamedArgument(NamedArgument {
+            name: Identifier::extract(self.source, name.span),
+            value: Box::new(value),
+            segment,
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use ast::call::{Call, MethodCall, ProgrammaticCall};
+    use ast::call::{Call, MethodCall, NamedArgument, ProgrammaticCall};
     use ast::group::Block;
     use ast::r#struct::FieldAccess;
     use ast::r#type::{ParametrizedType, Type};
@@ -442,6 +484,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn named_arguments() {
+        let source = "Foo('a', y = 2, z='c')";
+        let expr = parse(source).expect("Failed to parse");
+        assert_eq!(
+            expr,
+            vec![Expr::ProgrammaticCall(ProgrammaticCall {
+                path: vec![InclusionPathItem::Symbol(identifier(source, "Foo"))],
+                arguments: vec![
+                    literal(source, "'a'"),
+                    Expr::NamedArgument(NamedArgument {
+                        name: identifier(source, "y"),
+                        value: Box::new(Expr::Literal(Literal {
+                            parsed: 2.into(),
+                            segment: find_in(source, "2")
+                        })),
+                        segment: find_in(source, "y = 2"),
+                    }),
+                    Expr::NamedArgument(NamedArgument {
+                        name: identifier(source, "z"),
+                        value: Box::new(literal(source, "'c'")),
+                        segment: find_in(source, "z='c'"),
+                    }),
+                ],
+                type_parameters: vec![],
+                segment: source.segment(),
+            })],
+        );
+    }
+
     #[test]
     fn constructor_with_newlines_and_space() {
         let source = "Foo( \\\n'this' , \\\n  'is',\\\n'fine')";
@@ -675,4 +747,29 @@ mod tests {
             })])
         )
     }
+
+    #[test]
+    fn tuple_index_access() {
+        let source = "$t.1.0.2";
+        let expr: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            expr,
+            Ok(vec![Expr::FieldAccess(FieldAccess {
+                expr: Box::new(Expr::FieldAccess(FieldAccess {
+                    expr: Box::new(Expr::FieldAccess(FieldAccess {
+                        expr: Box::new(Expr::VarReference(VarReference {
+                            name: VarName::User("t".into()),
+                            segment: find_in(source, "$t"),
+                        })),
+                        field: identifier(source, "1"),
+                        segment: find_in(source, ".1"),
+                    })),
+                    field: identifier(source, "0"),
+                    segment: find_in(source, ".0"),
+                })),
+                field: identifier(source, "2"),
+                segment: find_in(source, ".2"),
+            })])
+        )
+    }
 }
@@ -1,6 +1,7 @@
-use ast::function::{FunctionDeclaration, FunctionParameter, Return};
+use ast::function::{Attribute, FunctionDeclaration, FunctionParameter, Return};
 use ast::r#type::Type;
 use ast::variable::Identifier;
+use ast::Expr;
 use context::source::SourceSegmentHolder;
 use lexer::token::{Token, TokenType};
 
@@ -39,6 +40,7 @@ impl Parser<'_> {
             .lookahead(blanks().then(of_type(TokenType::SemiColon)))
         {
             return Ok(FunctionDeclaration {
+                attributes: Vec::new(),
                 name: Identifier::extract(self.source, name.span),
                 type_parameters: tparams,
                 parameters: params,
@@ -60,6 +62,7 @@ impl Parser<'_> {
         let segment = segment_start..body.segment().end;
 
         Ok(FunctionDeclaration {
+            attributes: Vec::new(),
             name: Identifier::extract(self.source, name.span),
             type_parameters: tparams,
             parameters: params,
@@ -69,6 +72,43 @@ impl Parser<'_> {
         })
     }
 
+    /// Parses a function or structure declaration preceded by its attributes,
+    /// such as `@test fun f() = ...`.
+    pub(crate) fn parse_attributed_declaration(&mut self) -> ParseResult<Expr> {
+        let mut attributes = Vec::new();
+        let start = self.cursor.peek().span.start;
+        let mut segment = start..start;
+        while let Some(at) = self.cursor.advance(of_type(TokenType::At)) {
+            let name = self.cursor.force(
+                of_type(TokenType::Identifier),
+                "Expected attribute name after '@'.",
+            )?;
+            attributes.push(Attribute {
+                name: Identifier::extract(self.source, name.span.clone()),
+                segment: at.span.start..name.span.end,
+            });
+            segment.end = name.span.end;
+            self.cursor.advance(blanks());
+        }
+        match self.cursor.peek().token_type {
+            TokenType::Fun => {
+                let mut function = self.parse_function_declaration()?;
+                function.attributes = attributes;
+                Ok(Expr::FunctionDeclaration(function))
+            }
+            TokenType::Struct => {
+                let mut structure = self.parse_struct()?;
+                structure.attributes = attributes;
+                Ok(Expr::StructDeclaration(structure))
+            }
+            _ => self.expected_with(
+                "Attributes can only be placed on function or structure declarations.",
+                segment,
+                ParseErrorKind::Unexpected,
+            ),
+        }
+    }
+
     /// Parse a return statement.
     pub(crate) fn parse_return(&mut self) -> ParseResult<Return> {
         let start = self
@@ -138,7 +178,7 @@ impl Parser<'_> {
                     } else {
                         vararg_token_segment
                     };
-                    FunctionParameter::Variadic(t, segment)
+                    FunctionParameter::Variadic(None, t, segment)
                 })?;
             self.cursor
                 .force(of_type(TokenType::Vararg), "expected '...'")?;
@@ -147,6 +187,13 @@ impl Parser<'_> {
 
         let current_token = self.cursor.peek();
 
+        // a named variadic parameter, such as `@rest: String`
+        if self.cursor.advance(of_type(TokenType::At)).is_some() {
+            let var = self.parse_typed_var()?;
+            let segment = current_token.span.start..var.segment().end;
+            return Ok(FunctionParameter::Variadic(Some(var.name), var.ty, segment));
+        }
+
         if self.cursor.advance(of_type(TokenType::Slf)).is_some() {
             return Ok(FunctionParameter::Slf(current_token.span));
         }
@@ -205,7 +252,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use ast::call::Call;
-    use ast::function::{FunctionDeclaration, FunctionParameter, Return};
+    use ast::function::{Attribute, FunctionDeclaration, FunctionParameter, Return};
     use ast::operation::{BinaryOperation, BinaryOperator};
     use ast::r#type::{ParametrizedType, Type, TypeParameter};
     use ast::r#use::InclusionPathItem;
@@ -256,6 +303,7 @@ mod tests {
         assert_eq!(
             errs,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "foo"),
                 type_parameters: vec![],
                 parameters: vec![],
@@ -357,6 +405,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(src, "test"),
                 type_parameters: vec![],
                 parameters: vec![],
@@ -381,6 +430,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![],
                 parameters: vec![],
@@ -400,6 +450,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![],
                 parameters: vec![FunctionParameter::Named(TypedVariable {
@@ -423,6 +474,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "non_implemented_function"),
                 type_parameters: vec![],
                 parameters: vec![FunctionParameter::Named(TypedVariable {
@@ -443,6 +495,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![],
                 parameters: vec![
@@ -480,6 +533,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![
                     TypeParameter {
@@ -527,9 +581,11 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![],
                 parameters: vec![FunctionParameter::Variadic(
+                    None,
                     Some(Type::Parametrized(ParametrizedType {
                         path: vec![InclusionPathItem::Symbol(identifier(source, "X"))],
                         params: Vec::new(),
@@ -554,6 +610,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![],
                 parameters: vec![
@@ -565,7 +622,7 @@ mod tests {
                             segment: find_in(source, "int")
                         })),
                     }),
-                    FunctionParameter::Variadic(None, find_in(source, "..."))
+                    FunctionParameter::Variadic(None, None, find_in(source, "..."))
                 ],
 
                 return_type: None,
@@ -578,6 +635,47 @@ mod tests {
         )
     }
 
+    #[test]
+    fn function_declaration_named_vararg() {
+        let source = "fun test(first: String, @rest: String) = $rest";
+        let ast = parse(source).expect("parse failed");
+        assert_eq!(
+            ast,
+            vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
+                name: identifier(source, "test"),
+                type_parameters: vec![],
+                parameters: vec![
+                    FunctionParameter::Named(TypedVariable {
+                        name: identifier(source, "first"),
+                        ty: Some(Type::Parametrized(ParametrizedType {
+                            path: vec![InclusionPathItem::Symbol(identifier(source, "String"))],
+                            params: Vec::new(),
+                            segment: find_in(source, "String")
+                        })),
+                    }),
+                    FunctionParameter::Variadic(
+                        Some(identifier(source, "rest")),
+                        Some(Type::Parametrized(ParametrizedType {
+                            path: vec![InclusionPathItem::Symbol(identifier_nth(
+                                source, "String", 1
+                            ))],
+                            params: Vec::new(),
+                            segment: find_in_nth(source, "String", 1)
+                        })),
+                        find_in(source, "@rest: String")
+                    ),
+                ],
+                return_type: None,
+                body: Some(Box::new(Expr::VarReference(VarReference {
+                    name: VarName::User("rest".into()),
+                    segment: find_in(source, "$rest")
+                }))),
+                segment: source.segment()
+            })]
+        )
+    }
+
     #[test]
     fn function_declaration_complete() {
         let source = "fun test[X, Y](  x : X  ,  y : Y   ) -> X = x";
@@ -585,6 +683,7 @@ mod tests {
         assert_eq!(
             ast,
             vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "test"),
                 type_parameters: vec![
                     TypeParameter {
@@ -628,4 +727,49 @@ mod tests {
             })]
         );
     }
+
+    #[test]
+    fn attributed_function() {
+        let source = "@test\n@slow fun check() = 1";
+        let result = parse(source).expect("parse fail");
+        assert_eq!(
+            result,
+            vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![
+                    Attribute {
+                        name: identifier(source, "test"),
+                        segment: find_in(source, "@test"),
+                    },
+                    Attribute {
+                        name: identifier(source, "slow"),
+                        segment: find_in(source, "@slow"),
+                    },
+                ],
+                name: identifier(source, "check"),
+                type_parameters: vec![],
+                parameters: vec![],
+                return_type: None,
+                body: Some(Box::new(Expr::Literal(Literal {
+                    parsed: 1.into(),
+                    segment: find_in(source, "1"),
+                }))),
+                segment: find_in(source, "fun check() = 1"),
+            })]
+        );
+    }
+
+    #[test]
+    fn attribute_without_declaration() {
+        let source = "@test @slow echo hi";
+        let errs = parse(source).errors;
+        assert_eq!(
+            errs.first(),
+            Some(&ParseError {
+                message: "Attributes can only be placed on function or structure declarations."
+                    .to_string(),
+                position: find_in(source, "@test @slow"),
+                kind: ParseErrorKind::Unexpected,
+            })
+        );
+    }
 }
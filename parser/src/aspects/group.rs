@@ -1,10 +1,10 @@
-use ast::group::{Block, Parenthesis, Subshell};
+use ast::group::{Block, Parenthesis, Subshell, TupleLiteral};
 use ast::Expr;
-use context::source::SourceSegment;
+use context::source::{SourceSegment, SourceSegmentHolder};
 use lexer::token::{Token, TokenType};
 
 use crate::err::ParseErrorKind;
-use crate::moves::{line_end, of_type, of_types, repeat, repeat_n, spaces, Move};
+use crate::moves::{blanks, line_end, of_type, of_types, repeat, repeat_n, spaces, Move};
 use crate::parser::{ParseResult, Parser};
 
 impl Parser<'_> {
@@ -31,12 +31,16 @@ impl Parser<'_> {
         })
     }
 
-    pub(crate) fn parenthesis(&mut self) -> ParseResult<Parenthesis> {
+    /// Parses a parenthesis expression, or a tuple if the values are separated by commas.
+    pub(crate) fn parenthesis(&mut self) -> ParseResult<Expr> {
         let start = self.ensure_at_group_start(TokenType::RoundedLeftBracket)?;
         let expr = self.value().inspect_err(|err| {
             self.repos_delimiter_due_to(err);
         })?;
         self.cursor.advance(spaces());
+        if self.cursor.lookahead(of_type(TokenType::Comma)).is_some() {
+            return self.tuple_literal(start, expr);
+        }
         if !self.cursor.peek().token_type.is_closing_punctuation() {
             self.expected(
                 "parenthesis in value expression can only contain one expression",
@@ -48,10 +52,38 @@ impl Parser<'_> {
         }
         let end = self.expect_delimiter(start.clone(), TokenType::RoundedRightBracket)?;
 
-        Ok(Parenthesis {
+        Ok(Expr::Parenthesis(Parenthesis {
             expression: Box::new(expr),
             segment: start.span.start..end.span.end,
-        })
+        }))
+    }
+
+    /// Parses the remaining values of a tuple, after its first value.
+    ///
+    /// A trailing comma is allowed, so that `(1,)` is a tuple of a single value.
+    fn tuple_literal(&mut self, start: Token, first: Expr) -> ParseResult<Expr> {
+        let mut elements = vec![first];
+        while self.cursor.advance(of_type(TokenType::Comma)).is_some() {
+            self.cursor.advance(blanks());
+            if self
+                .cursor
+                .lookahead(of_type(TokenType::RoundedRightBracket))
+                .is_some()
+            {
+                break;
+            }
+            let element = self.value().inspect_err(|err| {
+                self.repos_delimiter_due_to(err);
+            })?;
+            elements.push(element);
+            self.cursor.advance(blanks());
+        }
+        let end = self.expect_delimiter(start.clone(), TokenType::RoundedRightBracket)?;
+
+        Ok(Expr::TupleLiteral(TupleLiteral {
+            elements,
+            segment: start.span.start..end.span.end,
+        }))
     }
 
     fn ensure_at_group_start(&mut self, start: TokenType) -> ParseResult<Token> {
@@ -67,6 +99,8 @@ impl Parser<'_> {
     ///
     /// This parser will always end by consuming the closing delimiter of the group,
     /// so it is not necessary to call [`Parser::repos_delimiter_due_to`] on errors.
+    /// If the group is never closed, it is closed where the closing delimiter was most
+    /// likely forgotten, so that the following statements can still be parsed.
     fn sub_exprs<F>(
         &mut self,
         start_token: Token,
@@ -78,6 +112,7 @@ impl Parser<'_> {
     {
         let mut statements: Vec<Expr> = Vec::new();
         let mut segment = start_token.span.clone();
+        let mut statement_end = self.cursor.get_pos();
 
         //consume all heading spaces and end of expressions (\n or ;)
         self.cursor.advance(repeat(of_types(&[
@@ -92,11 +127,21 @@ impl Parser<'_> {
         }
 
         loop {
-            if self.cursor.is_at_end() {
-                self.expected(
+            self.cursor.advance(spaces());
+            if self.cursor.is_at_end() || self.is_outside_unclosed_group(&start_token) {
+                let error = self.mk_parse_error(
                     "Expected closing bracket.",
+                    self.cursor.peek().span,
                     ParseErrorKind::Unpaired(start_token.span.clone()),
-                )?;
+                );
+                self.report_error(error);
+
+                // Leave the end of the last statement to the enclosing parser.
+                self.cursor.repos(statement_end);
+                let end = statements
+                    .last()
+                    .map_or(start_token.span.end, |statement| statement.segment().end);
+                return Ok((statements, segment.start..end));
             }
             let statement = parser(self);
             match statement {
@@ -108,6 +153,8 @@ impl Parser<'_> {
                 }
             }
 
+            statement_end = self.cursor.get_pos();
+
             //expects at least one newline or ';'
             let eox_res = self.cursor.advance(repeat_n(1, spaces().then(line_end())));
 
@@ -134,6 +181,35 @@ impl Parser<'_> {
         }
         Ok((statements, segment))
     }
+
+    /// Tests if the next statement should be placed after a group that is never closed.
+    ///
+    /// Such a group is closed before the first statement of a later line that is not
+    /// more indented than the line that opened the group.
+    fn is_outside_unclosed_group(&self, start_token: &Token) -> bool {
+        if !self.unclosed_delimiters.contains(&start_token.span.start) {
+            return false;
+        }
+        let opening_line = line_start(self.source, start_token.span.start);
+        let next = self.cursor.peek().span.start;
+        let line = line_start(self.source, next);
+        line != opening_line
+            && line + indentation(self.source, line) == next
+            && indentation(self.source, line) <= indentation(self.source, opening_line)
+    }
+}
+
+/// Finds the offset of the line that contains the given offset.
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |pos| pos + 1)
+}
+
+/// Counts the blank characters at the start of the line beginning at the given offset.
+fn indentation(source: &str, line_start: usize) -> usize {
+    source[line_start..]
+        .bytes()
+        .take_while(|byte| matches!(byte, b' ' | b'\t'))
+        .count()
 }
 
 #[cfg(test)]
@@ -141,7 +217,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use ast::call::Call;
-    use ast::group::{Block, Subshell};
+    use ast::group::{Block, Subshell, TupleLiteral};
     use ast::r#type::ParametrizedType;
     use ast::r#type::Type;
     use ast::r#use::InclusionPathItem;
@@ -155,7 +231,7 @@ mod tests {
     use crate::err::{ParseError, ParseErrorKind, ParseReport};
     use crate::parse;
     use crate::parser::{ParseResult, Parser};
-    use crate::source::{identifier, identifier_nth, literal};
+    use crate::source::{identifier, identifier_nth, literal, literal_nth};
 
     //noinspection DuplicatedCode
     #[test]
@@ -240,6 +316,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn statements_after_unclosed_block() {
+        let source = "{\n    echo a\necho b";
+        let report = parse(source);
+        assert_eq!(
+            report,
+            ParseReport {
+                expr: vec![
+                    Expr::Block(Block {
+                        expressions: vec![Expr::Call(Call {
+                            arguments: vec![literal(source, "echo"), literal(source, "a")],
+                        })],
+                        segment: 0..find_in(source, "echo a").end,
+                    }),
+                    Expr::Call(Call {
+                        arguments: vec![literal_nth(source, "echo", 1), literal(source, "b")],
+                    }),
+                ],
+                errors: vec![ParseError {
+                    message: "Expected closing bracket.".to_string(),
+                    position: find_in_nth(source, "echo", 1),
+                    kind: ParseErrorKind::Unpaired(0..1),
+                }]
+            }
+        );
+    }
+
     #[test]
     fn neighbour_parenthesis() {
         let source = "{ {} {} }";
@@ -407,6 +510,61 @@ mod tests {
         )
     }
 
+    #[test]
+    fn tuple_literal() {
+        let source = "val t = (1, 'a', 2.0)";
+        let result: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            result,
+            Ok(vec![Expr::VarDeclaration(VarDeclaration {
+                kind: VarKind::Val,
+                var: TypedVariable {
+                    name: identifier(source, "t"),
+                    ty: None,
+                },
+                initializer: Some(Box::new(Expr::TupleLiteral(TupleLiteral {
+                    elements: vec![
+                        Expr::Literal(Literal {
+                            parsed: Int(1),
+                            segment: find_in(source, "1"),
+                        }),
+                        Expr::Literal(Literal {
+                            parsed: "a".into(),
+                            segment: find_in(source, "'a'"),
+                        }),
+                        Expr::Literal(Literal {
+                            parsed: Float(2.0),
+                            segment: find_in(source, "2.0"),
+                        }),
+                    ],
+                    segment: find_in(source, "(1, 'a', 2.0)"),
+                }))),
+                segment: source.segment(),
+            })])
+        );
+    }
+
+    #[test]
+    fn tuple_literal_trailing_comma() {
+        let source = "echo (4,\n)";
+        let result: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            result,
+            Ok(vec![Expr::Call(Call {
+                arguments: vec![
+                    literal(source, "echo"),
+                    Expr::TupleLiteral(TupleLiteral {
+                        elements: vec![Expr::Literal(Literal {
+                            parsed: Int(4),
+                            segment: find_in(source, "4"),
+                        })],
+                        segment: find_in(source, "(4,\n)"),
+                    }),
+                ],
+            })])
+        );
+    }
+
     #[test]
     fn unmatched_closing() {
         let source = "{]}";
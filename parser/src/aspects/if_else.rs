@@ -1,8 +1,11 @@
 use ast::control_flow::If;
+use ast::variable::{Assign, AssignOperator};
+use ast::Expr;
 use context::source::SourceSegmentHolder;
 use lexer::token::TokenType;
 use lexer::token::TokenType::{Else, SemiColon};
 
+use crate::err::ParseErrorKind;
 use crate::moves::{aerated, blanks, of_type, Move};
 use crate::parser::{ParseResult, Parser};
 
@@ -14,6 +17,7 @@ impl Parser<'_> {
             "expected 'if' at start of if expression",
         )?;
         let condition = self.statement()?;
+        let condition = self.check_condition(condition)?;
 
         //skip only one semicolon if any, surrounded by newlines and spaces
         self.cursor
@@ -50,6 +54,28 @@ impl Parser<'_> {
             segment,
         })
     }
+
+    /// Rejects a condition that is an assignment, where a comparison was most likely intended.
+    ///
+    /// Only conditions that are syntactically assignments are rejected, commands taking a `=`
+    /// argument are valid conditions.
+    pub(crate) fn check_condition(&self, condition: Expr) -> ParseResult<Expr> {
+        let is_assignment = matches!(
+            &condition,
+            Expr::Assign(Assign {
+                operator: AssignOperator::Assign,
+                ..
+            })
+        );
+        if is_assignment {
+            return self.expected_with(
+                "Unexpected assignment in condition.",
+                condition.segment(),
+                ParseErrorKind::UnexpectedInContext("Use `==` for comparison.".to_string()),
+            );
+        }
+        Ok(condition)
+    }
 }
 
 #[cfg(test)]
@@ -294,4 +320,56 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn if_assignment_condition() {
+        let source = "if x = 1 {}";
+        let ast: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            ast,
+            Err(ParseError {
+                message: "Unexpected assignment in condition.".to_string(),
+                position: find_in(source, "x = 1"),
+                kind: ParseErrorKind::UnexpectedInContext("Use `==` for comparison.".to_string()),
+            })
+        )
+    }
+
+    #[test]
+    fn if_command_with_equal_argument() {
+        let source = "if ./check = ok {}";
+        let ast = parse(source).expect("parse fail");
+        assert_eq!(
+            ast,
+            vec![Expr::If(If {
+                condition: Box::new(Expr::Call(Call {
+                    arguments: vec![
+                        literal(source, "./check"),
+                        literal(source, "="),
+                        literal(source, "ok"),
+                    ],
+                })),
+                success_branch: Box::new(Expr::Block(Block {
+                    expressions: vec![],
+                    segment: find_between(source, "{", "}")
+                })),
+                fail_branch: None,
+                segment: source.segment(),
+            })]
+        )
+    }
+
+    #[test]
+    fn if_assignment_value_condition() {
+        let source = "if $x = 1; echo one";
+        let ast: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            ast,
+            Err(ParseError {
+                message: "Unexpected assignment in condition.".to_string(),
+                position: find_in(source, "$x = 1"),
+                kind: ParseErrorKind::UnexpectedInContext("Use `==` for comparison.".to_string()),
+            })
+        )
+    }
 }
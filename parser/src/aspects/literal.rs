@@ -1,4 +1,5 @@
-use std::num::IntErrorKind;
+use std::num::{IntErrorKind, ParseIntError};
+use std::time::Duration;
 
 use ast::range::{FilePattern, Iterable};
 use ast::value::{Literal, LiteralValue, TemplateString};
@@ -73,10 +74,57 @@ impl Parser<'_> {
                     self.expected("Unexpected string format", ParseErrorKind::Unexpected)
                 }
             }
+            Identifier
+                if token
+                    .text(self.source)
+                    .starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                self.duration_literal().map(Expr::Literal)
+            }
             _ => self.expected("Unexpected word literal", ParseErrorKind::Unexpected),
         }
     }
 
+    /// Parses a duration literal, such as `5s` or `500ms`.
+    fn duration_literal(&mut self) -> ParseResult<Literal> {
+        let token = self.cursor.next()?;
+        let text = token.text(self.source);
+        let (amount, unit) = text.split_at(
+            text.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len()),
+        );
+        let millis_per_unit = match unit {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            _ => {
+                return Err(self.mk_parse_error(
+                    format!("Unknown duration unit `{unit}`, expected `ms`, `s`, `m` or `h`."),
+                    token.span,
+                    ParseErrorKind::InvalidFormat,
+                ))
+            }
+        };
+        let millis = amount
+            .parse::<u64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(millis_per_unit))
+            // durations are represented as a signed number of milliseconds at runtime
+            .filter(|millis| i64::try_from(*millis).is_ok())
+            .ok_or_else(|| {
+                self.mk_parse_error(
+                    "Duration constant is too large.",
+                    token.span.clone(),
+                    ParseErrorKind::InvalidFormat,
+                )
+            })?;
+        Ok(Literal {
+            parsed: LiteralValue::Duration(Duration::from_millis(millis)),
+            segment: token.span,
+        })
+    }
+
     fn number_literal(&mut self) -> ParseResult<Literal> {
         let start = self.cursor.next()?;
         Ok(Literal {
@@ -90,10 +138,9 @@ impl Parser<'_> {
             .cursor
             .force(of_type(StringLiteral), "Expected string literal.")?;
         let segment = literal.span.clone();
+        // single-quoted strings are kept verbatim, as in POSIX shells
         Ok(Literal {
-            parsed: LiteralValue::String(
-                unescape(literal.text(self.source)).map_err(|err| (literal.span, err))?,
-            ),
+            parsed: LiteralValue::String(literal.text(self.source).to_owned()),
             segment: (segment.start - 1)..(segment.end + 1),
         })
     }
@@ -304,7 +351,16 @@ impl Parser<'_> {
                     parsed: match start.token_type {
                         True => LiteralValue::Bool(true),
                         False => LiteralValue::Bool(false),
-                        IntLiteral | FloatLiteral => self.parse_number_value(start)?,
+                        // only plain decimal numbers are numbers in arguments, other
+                        // notations are kept as the words they are written with
+                        IntLiteral | FloatLiteral
+                            if start
+                                .text(self.source)
+                                .bytes()
+                                .all(|b| b.is_ascii_digit() || b == b'.') =>
+                        {
+                            self.parse_number_value(start)?
+                        }
                         _ => {
                             return Ok(parts.pop().unwrap());
                         }
@@ -352,33 +408,48 @@ impl Parser<'_> {
     fn parse_number_value(&self, token: Token) -> ParseResult<LiteralValue> {
         match token.token_type {
             IntLiteral => Ok(LiteralValue::Int(
+                parse_int(token.text(self.source)).map_err(|e| match e.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => self.mk_parse_error(
+                        "Integer constant is too large.".to_string(),
+                        token.span,
+                        ParseErrorKind::InvalidFormat,
+                    ),
+                    _ => self.mk_parse_error(
+                        e.to_string(),
+                        token.span,
+                        ParseErrorKind::InvalidFormat,
+                    ),
+                })?,
+            )),
+            FloatLiteral => Ok(LiteralValue::Float(
                 token
                     .text(self.source)
-                    .parse::<i64>()
-                    .map_err(|e| match e.kind() {
-                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => self
-                            .mk_parse_error(
-                                "Integer constant is too large.".to_string(),
-                                token.span,
-                                ParseErrorKind::InvalidFormat,
-                            ),
-                        _ => self.mk_parse_error(
+                    .replace('_', "")
+                    .parse::<f64>()
+                    .map_err(|e| {
+                        self.mk_parse_error(
                             e.to_string(),
                             token.span,
                             ParseErrorKind::InvalidFormat,
-                        ),
+                        )
                     })?,
             )),
-            FloatLiteral => Ok(LiteralValue::Float(
-                token.text(self.source).parse::<f64>().map_err(|e| {
-                    self.mk_parse_error(e.to_string(), token.span, ParseErrorKind::InvalidFormat)
-                })?,
-            )),
             _ => self.expected("Expected a literal.", ParseErrorKind::Unexpected),
         }
     }
 }
 
+/// Parses an integer literal, which may have a base prefix and `_` digit separators.
+fn parse_int(text: &str) -> Result<i64, ParseIntError> {
+    let (digits, radix) = match text.get(..2) {
+        Some("0x") => (&text[2..], 16),
+        Some("0o") => (&text[2..], 8),
+        Some("0b") => (&text[2..], 2),
+        _ => (text, 10),
+    };
+    i64::from_str_radix(&digits.replace('_', ""), radix)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -407,6 +478,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn prefixed_ints() {
+        for (source, value) in [
+            ("0xFF", 255),
+            ("0o17", 15),
+            ("0b1010", 10),
+            ("1_000_000", 1_000_000),
+        ] {
+            let parsed = Parser::new(source)
+                .parse_specific(Parser::expression)
+                .expect("Failed to parse.");
+            assert_eq!(
+                parsed,
+                Expr::Literal(Literal {
+                    parsed: LiteralValue::Int(value),
+                    segment: source.segment(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn prefixed_int_overflow() {
+        let source = "0x1_0000_0000_0000_0000";
+        let parsed: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            parsed,
+            Err(ParseError {
+                message: "Integer constant is too large.".to_string(),
+                position: source.segment(),
+                kind: InvalidFormat,
+            })
+        );
+    }
+
     #[test]
     fn int_but_str() {
         let source = "5@5";
@@ -422,6 +528,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn duration_literals() {
+        for (source, duration) in [
+            ("500ms", Duration::from_millis(500)),
+            ("5s", Duration::from_secs(5)),
+            ("2m", Duration::from_secs(120)),
+            ("1h", Duration::from_secs(3600)),
+        ] {
+            let parsed = Parser::new(source).value().expect("Failed to parse.");
+            assert_eq!(
+                parsed,
+                Expr::Literal(Literal {
+                    parsed: LiteralValue::Duration(duration),
+                    segment: source.segment(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_duration_unit() {
+        let source = "5y";
+        let parsed = Parser::new(source).value();
+        assert_eq!(
+            parsed,
+            Err(ParseError {
+                message: "Unknown duration unit `y`, expected `ms`, `s`, `m` or `h`.".to_string(),
+                position: source.segment(),
+                kind: InvalidFormat,
+            })
+        );
+    }
+
+    #[test]
+    fn too_large_duration() {
+        let source = "9223372036854775808ms";
+        let parsed = Parser::new(source).value();
+        assert_eq!(
+            parsed,
+            Err(ParseError {
+                message: "Duration constant is too large.".to_string(),
+                position: source.segment(),
+                kind: InvalidFormat,
+            })
+        );
+    }
+
+    #[test]
+    fn duration_argument() {
+        let source = "5s";
+        let parsed = Parser::new(source)
+            .call_argument()
+            .expect("Failed to parse.");
+        assert_eq!(
+            parsed,
+            Expr::Literal(Literal {
+                parsed: "5s".into(),
+                segment: source.segment(),
+            })
+        );
+    }
+
+    #[test]
+    fn prefixed_int_argument() {
+        for source in ["0xFF", "1_000", "0x1_0000_0000_0000_0000"] {
+            let parsed = Parser::new(source)
+                .call_argument()
+                .expect("Failed to parse.");
+            assert_eq!(
+                parsed,
+                Expr::Literal(Literal {
+                    parsed: source.into(),
+                    segment: source.segment(),
+                })
+            );
+        }
+    }
+
     #[test]
     fn string_literal() {
         let source = "'hello $world! $(this is a test) @(of course)'";
@@ -451,13 +635,29 @@ mod tests {
     }
 
     #[test]
-    fn escaped_string_literal() {
-        let source = "'a\\'a'";
+    fn verbatim_string_literal() {
+        let source = "'a\\nb'";
         let parsed = Parser::new(source).expression().expect("Failed to parse.");
         assert_eq!(
             parsed,
             Expr::Literal(Literal {
-                parsed: "a'a".into(),
+                parsed: "a\\nb".into(),
+                segment: source.segment()
+            })
+        );
+    }
+
+    #[test]
+    fn escaped_newline_template_string_literal() {
+        let source = "\"a\\nb\"";
+        let parsed = Parser::new(source).expression().expect("Failed to parse.");
+        assert_eq!(
+            parsed,
+            Expr::TemplateString(TemplateString {
+                parts: vec![Expr::Literal(Literal {
+                    parsed: "a\nb".into(),
+                    segment: find_in(source, "a\\nb")
+                })],
                 segment: source.segment()
             })
         );
@@ -537,7 +737,7 @@ mod tests {
 
     #[test]
     fn non_standard_escape() {
-        let source = r"'a\ b'";
+        let source = r#""a\ b""#;
         let parsed: ParseResult<_> = Parser::new(source).expression();
         assert_eq!(
             parsed,
@@ -1,8 +1,10 @@
-use ast::control_flow::{ConditionalFor, For, ForKind, Loop, RangeFor, While};
+use ast::control_flow::{ConditionalFor, For, ForKind, Loop, LoopJump, RangeFor, While};
 use ast::variable::Identifier;
+use ast::Expr;
 use context::source::SourceSegmentHolder;
 use lexer::token::{Token, TokenType};
 
+use crate::aspects::literal::LiteralLeniency;
 use crate::err::ParseErrorKind;
 use crate::moves::{blanks, eog, line_end, of_type, Move};
 use crate::parser::{ParseResult, Parser};
@@ -27,6 +29,7 @@ impl Parser<'_> {
         let segment = start.span.start..body.segment().end;
 
         Ok(While {
+            label: None,
             condition,
             body,
             segment,
@@ -43,7 +46,62 @@ impl Parser<'_> {
         let body = Box::new(self.statement()?);
         let segment = start.span.start..body.segment().end;
 
-        Ok(Loop { body, segment })
+        Ok(Loop {
+            label: None,
+            body,
+            segment,
+        })
+    }
+
+    /// Parses a loop preceded by its label, such as `outer@ loop { ... }`.
+    pub(crate) fn parse_labeled_loop(&mut self) -> ParseResult<Expr> {
+        let name = self
+            .cursor
+            .force(of_type(TokenType::Identifier), "Expected loop label.")?;
+        self.cursor
+            .force(of_type(TokenType::At), "Expected '@' after loop label.")?;
+        self.cursor.advance(blanks());
+        let label = Some(Identifier::extract(self.source, name.span.clone()));
+        let start = name.span.start;
+        match self.cursor.peek().token_type {
+            TokenType::Loop => self.parse_loop().map(|lp| {
+                Expr::Loop(Loop {
+                    label,
+                    segment: start..lp.segment.end,
+                    ..lp
+                })
+            }),
+            TokenType::While => self.parse_while().map(|wh| {
+                Expr::While(While {
+                    label,
+                    segment: start..wh.segment.end,
+                    ..wh
+                })
+            }),
+            TokenType::For => self.parse_for().map(|fr| {
+                Expr::For(For {
+                    label,
+                    segment: start..fr.segment.end,
+                    ..fr
+                })
+            }),
+            _ => self.expected("Expected loop after label.", ParseErrorKind::Unexpected),
+        }
+    }
+
+    /// Parses a `continue` or a `break`, with the label of the loop it targets if any.
+    pub(crate) fn parse_loop_jump(&mut self) -> ParseResult<LoopJump> {
+        let keyword = self.cursor.next()?;
+        let label = self
+            .cursor
+            .advance(of_type(TokenType::At).and_then(of_type(TokenType::Identifier)));
+        let end = label
+            .as_ref()
+            .map_or(keyword.span.end, |label| label.span.end);
+        Ok(LoopJump {
+            label: label.map(|label| Identifier::extract(self.source, label.span)),
+            segment: keyword.span.start..end,
+        })
     }
 
     /// Parses a `for` expression.
@@ -59,6 +117,7 @@ impl Parser<'_> {
         let segment = start.span.start..body.segment().end;
 
         Ok(For {
+            label: None,
             kind,
             body,
             segment,
@@ -116,7 +175,12 @@ impl Parser<'_> {
             "expected 'in' after receiver in range for",
         )?;
         self.cursor.advance(blanks());
-        let iterable = self.value()?;
+        // A leading wildcard starts a pattern of the files to iterate over
+        let iterable = if self.cursor.lookahead(of_type(TokenType::Star)).is_some() {
+            self.literal(LiteralLeniency::Lenient)?
+        } else {
+            self.value()?
+        };
         let segment = receiver.span.start..iterable.segment().end;
 
         Ok(RangeFor {
@@ -208,7 +272,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use ast::call::Call;
-    use ast::control_flow::{ConditionalFor, For, ForKind, Loop, RangeFor, While};
+    use ast::control_flow::{ConditionalFor, For, ForKind, Loop, LoopJump, RangeFor, While};
     use ast::group::{Block, Parenthesis};
     use ast::operation::BinaryOperator::And;
     use ast::operation::{BinaryOperation, BinaryOperator};
@@ -238,10 +302,17 @@ mod tests {
         assert_eq!(
             res,
             vec![Expr::Loop(Loop {
+                label: None,
                 body: Box::new(Expr::Block(Block {
                     expressions: vec![
-                        Continue(find_in(source, "continue")),
-                        Break(find_in(source, "break"))
+                        Continue(LoopJump {
+                            label: None,
+                            segment: find_in(source, "continue"),
+                        }),
+                        Break(LoopJump {
+                            label: None,
+                            segment: find_in(source, "break"),
+                        })
                     ],
                     segment: find_between(source, "{", "}")
                 })),
@@ -257,6 +328,7 @@ mod tests {
         assert_eq!(
             res,
             vec![Expr::Loop(Loop {
+                label: None,
                 body: Box::new(Expr::Binary(BinaryOperation {
                     left: Box::new(Expr::Call(Call {
                         arguments: vec![
@@ -271,7 +343,10 @@ mod tests {
                         ],
                     })),
                     op: And,
-                    right: Box::new(Break(find_in(source, "break"))),
+                    right: Box::new(Break(LoopJump {
+                        label: None,
+                        segment: find_in(source, "break"),
+                    })),
                 })),
                 segment: source.segment(),
             })]
@@ -285,6 +360,7 @@ mod tests {
         assert_eq!(
             res,
             vec![Expr::Loop(Loop {
+                label: None,
                 body: Box::new(Expr::Call(Call {
                     arguments: vec![Expr::Literal(Literal {
                         parsed: "date".into(),
@@ -317,6 +393,7 @@ mod tests {
         assert_eq!(
             res,
             vec![Expr::While(While {
+                label: None,
                 condition: Box::new(Expr::VarReference(VarReference {
                     name: VarName::User("1".into()),
                     segment: find_in(source, "$1"),
@@ -339,6 +416,7 @@ mod tests {
         assert_eq!(
             expr,
             vec![Expr::For(For {
+                label: None,
                 kind: Box::new(ForKind::Range(RangeFor {
                     receiver: identifier(source, "i"),
                     iterable: Expr::Range(Iterable::Range(NumericRange {
@@ -382,6 +460,7 @@ mod tests {
         assert_eq!(
             expr,
             vec![Expr::For(For {
+                label: None,
                 kind: Box::new(ForKind::Range(RangeFor {
                     receiver: identifier(source, "n"),
                     iterable: Expr::Range(Iterable::Range(NumericRange {
@@ -416,6 +495,7 @@ mod tests {
         assert_eq!(
             expr,
             vec![Expr::For(For {
+                label: None,
                 kind: Box::new(ForKind::Range(RangeFor {
                     receiver: identifier(source, "i"),
                     iterable: Expr::Range(Iterable::Range(NumericRange {
@@ -460,6 +540,7 @@ mod tests {
         assert_eq!(
             expr,
             vec![Expr::For(For {
+                label: None,
                 kind: Box::new(ForKind::Range(RangeFor {
                     receiver: identifier_nth(source, "f", 1),
                     iterable: Expr::Range(Iterable::Files(FilePattern {
@@ -488,6 +569,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn for_in_wildcard() {
+        let source = "for f in *.rs { echo $f }";
+        let expr = parse(source).expect("Failed to parse");
+        assert_eq!(
+            expr,
+            vec![Expr::For(For {
+                label: None,
+                kind: Box::new(ForKind::Range(RangeFor {
+                    receiver: identifier(source, "f"),
+                    iterable: Expr::Range(Iterable::Files(FilePattern {
+                        pattern: Box::new(literal(source, "*.rs")),
+                        segment: find_in(source, "*.rs")
+                    })),
+                    segment: find_in(source, "f in *.rs"),
+                })),
+                body: Box::new(Expr::Block(Block {
+                    expressions: vec![Expr::Call(Call {
+                        arguments: vec![
+                            literal(source, "echo"),
+                            Expr::VarReference(VarReference {
+                                name: VarName::User("f".into()),
+                                segment: find_in(source, "$f")
+                            }),
+                        ],
+                    })],
+                    segment: find_in(source, "{ echo $f }")
+                })),
+                segment: source.segment(),
+            })]
+        );
+    }
+
     #[test]
     fn classical_for() {
         let source = "for (( var i=0; $i<10; i=$i + 1 ))\necho $i";
@@ -495,6 +609,7 @@ mod tests {
         assert_eq!(
             expr,
             vec![Expr::For(For {
+                label: None,
                 kind: Box::new(ForKind::Conditional(ConditionalFor {
                     initializer: Expr::VarDeclaration(VarDeclaration {
                         kind: VarKind::Var,
@@ -593,4 +708,31 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn labeled_loop() {
+        let source = "outer@ loop { loop { break@outer } }";
+        let result = parse(source).expect("parse fail");
+        assert_eq!(
+            result,
+            vec![Expr::Loop(Loop {
+                label: Some(identifier(source, "outer")),
+                body: Box::new(Expr::Block(Block {
+                    expressions: vec![Expr::Loop(Loop {
+                        label: None,
+                        body: Box::new(Expr::Block(Block {
+                            expressions: vec![Break(LoopJump {
+                                label: Some(identifier_nth(source, "outer", 1)),
+                                segment: find_in(source, "break@outer"),
+                            })],
+                            segment: find_in(source, "{ break@outer }"),
+                        })),
+                        segment: find_in(source, "loop { break@outer }"),
+                    })],
+                    segment: find_in(source, "{ loop { break@outer } }"),
+                })),
+                segment: source.segment(),
+            })]
+        );
+    }
 }
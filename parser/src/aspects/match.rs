@@ -177,7 +177,8 @@ impl Parser<'_> {
             return Ok(None);
         }
 
-        self.expression().map(Some)
+        let guard = self.expression()?;
+        self.check_condition(guard).map(Some)
     }
 
     fn parse_body(&mut self) -> ParseResult<Expr> {
@@ -206,7 +207,7 @@ mod tests {
 
     use crate::aspects::literal::literal_expr;
     use crate::err::ParseError;
-    use crate::err::ParseErrorKind::Unexpected;
+    use crate::err::ParseErrorKind::{Unexpected, UnexpectedInContext};
     use crate::parse;
     use crate::source::{identifier, identifier_nth, literal};
 
@@ -629,4 +630,18 @@ mod tests {
             }]
         )
     }
+
+    #[test]
+    fn match_guard_assignment() {
+        let src = "match $1 {\n    n@* if $n = 0 => ()\n}";
+        let res = parse(src).errors;
+        assert_eq!(
+            res,
+            vec![ParseError {
+                message: "Unexpected assignment in condition.".to_owned(),
+                kind: UnexpectedInContext("Use `==` for comparison.".to_owned()),
+                position: find_in(src, "$n = 0"),
+            }]
+        )
+    }
 }
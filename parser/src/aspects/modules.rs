@@ -2,12 +2,13 @@ use ast::r#use::{Import, ImportList, ImportedSymbol, InclusionPathItem, Use};
 use ast::Expr;
 use context::source::SourceSegmentHolder;
 use lexer::token::TokenType::{
-    As, At, Colon, ColonColon, CurlyLeftBracket, CurlyRightBracket, Dot, Identifier, Reef, Star,
+    As, At, Backtick, Colon, ColonColon, CurlyLeftBracket, CurlyRightBracket, Dot, EndOfFile,
+    Identifier, NewLine, Reef, Space, Star,
 };
 use lexer::token::{Token, TokenType};
 
 use crate::err::ParseErrorKind;
-use crate::moves::{any, blanks, of_type, of_types, spaces, Move};
+use crate::moves::{any, blanks, of_type, of_types, predicate, spaces, Move};
 use crate::parser::{ParseResult, Parser};
 
 impl Parser<'_> {
@@ -59,6 +60,9 @@ impl Parser<'_> {
                 ast::variable::Identifier::extract(self.source, start.span),
             )),
             Reef => Ok(InclusionPathItem::Reef(start.span)),
+            Backtick => self
+                .parse_escaped_identifier(start)
+                .map(InclusionPathItem::Symbol),
             _ => self.expected_with(
                 "Expected identifier or `reef`.",
                 start.span,
@@ -67,6 +71,45 @@ impl Parser<'_> {
         }
     }
 
+    /// Parses an identifier escaped between backticks, given that the opening one is consumed.
+    ///
+    /// The escaped identifier may be a keyword or contain any character but a backtick or a blank.
+    fn parse_escaped_identifier(
+        &mut self,
+        opening: Token,
+    ) -> ParseResult<ast::variable::Identifier> {
+        let start = opening.span.end;
+        let end = loop {
+            let token = self.cursor.peek();
+            match token.token_type {
+                Backtick => {
+                    self.cursor.next_opt();
+                    break token.span.start;
+                }
+                Space | NewLine | EndOfFile => {
+                    return self.expected(
+                        "Unterminated escaped identifier.",
+                        ParseErrorKind::Unpaired(opening.span),
+                    );
+                }
+                _ => {
+                    self.cursor.next_opt();
+                }
+            }
+        };
+        if start == end {
+            return self.expected_with(
+                "Escaped identifier cannot be empty.",
+                opening.span.start..end + 1,
+                ParseErrorKind::Expected("<identifier>".to_owned()),
+            );
+        }
+        Ok(ast::variable::Identifier::new(
+            self.source[start..end].into(),
+            start,
+        ))
+    }
+
     fn parse_import(&mut self) -> ParseResult<Import> {
         self.cursor.advance(blanks()); //consume blanks
 
@@ -129,6 +172,8 @@ impl Parser<'_> {
         let start = self.cursor.peek();
 
         let symbol_path = self.parse_path()?;
+        // the path ends with its last token, which may be the closing backtick of an escaped name
+        let path_end = self.cursor.peek().span.start;
         self.cursor.advance(spaces()); //consume spaces
         let token = self.cursor.peek();
 
@@ -136,9 +181,28 @@ impl Parser<'_> {
             if token.token_type == Star {
                 self.cursor.next()?;
 
+                let source = self.source;
+                let except = self.cursor.advance(spaces().then(predicate(move |token| {
+                    token.token_type == Identifier && token.text(source) == "except"
+                })));
+                let (exclusions, end) = match except {
+                    Some(_) => {
+                        let (exclusions, segment) = self.parse_explicit_list(
+                            CurlyLeftBracket,
+                            CurlyRightBracket,
+                            "Expected list of excluded symbols.",
+                            "Expected excluded symbol.",
+                            Self::parse_excluded_symbol,
+                        )?;
+                        (exclusions, segment.end)
+                    }
+                    None => (Vec::new(), token.span.end),
+                };
+
                 return Ok(Import::AllIn(
                     symbol_path.path,
-                    start.span.start..token.span.end,
+                    exclusions,
+                    start.span.start..end,
                 ));
             }
             return self
@@ -153,17 +217,18 @@ impl Parser<'_> {
                 .then(of_type(Identifier)),
         );
 
-        let end = alias.as_ref().map(|t| t.span.end).unwrap_or(
-            if let Some(val) = symbol_path.path.last() {
-                val.segment().end
+        let end = alias
+            .as_ref()
+            .map(|t| t.span.end)
+            .unwrap_or(if !symbol_path.path.is_empty() {
+                path_end
             } else {
                 self.expected_with(
                     "identifier expected",
                     start.span.start..self.cursor.peek().span.end,
                     ParseErrorKind::Expected("<identifier>".to_owned()),
                 )?
-            },
-        );
+            });
 
         Ok(Import::Symbol(ImportedSymbol {
             path: symbol_path.path,
@@ -172,6 +237,15 @@ impl Parser<'_> {
         }))
     }
 
+    fn parse_excluded_symbol(&mut self) -> ParseResult<ast::variable::Identifier> {
+        let symbol = self.cursor.force_with(
+            of_type(Identifier),
+            "Expected symbol name.",
+            ParseErrorKind::Expected("<identifier>".to_owned()),
+        )?;
+        Ok(ast::variable::Identifier::extract(self.source, symbol.span))
+    }
+
     pub(crate) fn is_path(&self) -> bool {
         self.cursor
             .lookahead(any().then(of_types(&[
@@ -222,6 +296,60 @@ mod tests {
         )
     }
 
+    #[test]
+    fn use_escaped_identifier() {
+        let source = "use foo::`type`";
+        let result = parse(source).expect("parser failed");
+        assert_eq!(
+            result,
+            vec![Expr::Use(Use {
+                import: Import::Symbol(ImportedSymbol {
+                    path: vec![
+                        InclusionPathItem::Symbol(identifier(source, "foo")),
+                        InclusionPathItem::Symbol(identifier(source, "type")),
+                    ],
+                    alias: None,
+                    segment: find_in(source, "foo::`type`"),
+                }),
+                segment: source.segment(),
+            })]
+        )
+    }
+
+    #[test]
+    fn use_all_in_escaped_keyword() {
+        let source = "use reef::`match`::*";
+        let result = parse(source).expect("parser failed");
+        assert_eq!(
+            result,
+            vec![Expr::Use(Use {
+                import: Import::AllIn(
+                    vec![
+                        InclusionPathItem::Reef(find_in(source, "reef")),
+                        InclusionPathItem::Symbol(identifier(source, "match")),
+                    ],
+                    vec![],
+                    find_in(source, "reef::`match`::*"),
+                ),
+                segment: source.segment(),
+            })]
+        )
+    }
+
+    #[test]
+    fn use_empty_escaped_identifier() {
+        let source = "use foo::``";
+        let result: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "Escaped identifier cannot be empty.".to_string(),
+                kind: ParseErrorKind::Expected("<identifier>".to_string()),
+                position: find_in(source, "``"),
+            })
+        )
+    }
+
     #[test]
     fn wrong_env_name() {
         let source = "use @9";
@@ -287,6 +415,7 @@ mod tests {
             vec![Expr::Use(Use {
                 import: Import::AllIn(
                     vec![InclusionPathItem::Symbol(identifier(source, "std"))],
+                    vec![],
                     find_in(source, "std::*"),
                 ),
                 segment: source.segment(),
@@ -294,6 +423,54 @@ mod tests {
         )
     }
 
+    #[test]
+    fn use_all_in_except() {
+        let source = "use reef::std::* except {foo, bar}";
+        let result = parse(source).expect("parser failed");
+        assert_eq!(
+            result,
+            vec![Expr::Use(Use {
+                import: Import::AllIn(
+                    vec![
+                        InclusionPathItem::Reef(find_in(source, "reef")),
+                        InclusionPathItem::Symbol(identifier(source, "std")),
+                    ],
+                    vec![identifier(source, "foo"), identifier(source, "bar")],
+                    find_in(source, "reef::std::* except {foo, bar}"),
+                ),
+                segment: source.segment(),
+            })]
+        )
+    }
+
+    #[test]
+    fn use_all_in_except_list() {
+        let source = "use std::{io::* except {read}, foo}";
+        let result = parse(source).expect("parser failed");
+        assert_eq!(
+            result,
+            vec![Expr::Use(Use {
+                import: Import::List(ImportList {
+                    root: vec![InclusionPathItem::Symbol(identifier(source, "std"))],
+                    imports: vec![
+                        Import::AllIn(
+                            vec![InclusionPathItem::Symbol(identifier(source, "io"))],
+                            vec![identifier(source, "read")],
+                            find_in(source, "io::* except {read}"),
+                        ),
+                        Import::Symbol(ImportedSymbol {
+                            path: vec![InclusionPathItem::Symbol(identifier(source, "foo"))],
+                            alias: None,
+                            segment: find_in(source, "foo"),
+                        }),
+                    ],
+                    segment: find_in(source, "std::{io::* except {read}, foo}"),
+                }),
+                segment: source.segment(),
+            })]
+        )
+    }
+
     #[test]
     fn uses() {
         let source = "use \nreef::{std::TOKEN as X,    @A \n , @B \\\n , reef::foo::{my_function}}";
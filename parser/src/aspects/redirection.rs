@@ -1,5 +1,6 @@
-use ast::call::{Pipeline, Redir, RedirFd, RedirOp, Redirected};
+use ast::call::{PipeKind, Pipeline, Redir, RedirFd, RedirOp, Redirected};
 use ast::substitution::{Substitution, SubstitutionKind};
+use ast::value::{Literal, LiteralValue};
 use ast::{substitution, Expr};
 use context::source::SourceSegmentHolder;
 use lexer::token::{Token, TokenType};
@@ -11,18 +12,28 @@ use crate::parser::{ParseResult, Parser};
 impl Parser<'_> {
     pub(crate) fn pipeline(&mut self, first_call: Expr) -> ParseResult<Expr> {
         let mut commands = vec![first_call];
+        let mut pipes = Vec::new();
         // Continue as long as we have a pipe
         while self
             .cursor
             .advance(spaces().then(of_type(TokenType::Bar)))
             .is_some()
         {
+            // `|&` also pipes the standard error
+            if self.cursor.advance(of_type(TokenType::Ampersand)).is_some() {
+                pipes.push(PipeKind::StdoutAndStderr);
+            } else {
+                pipes.push(PipeKind::Stdout);
+            }
             match self.statement()? {
-                Expr::Pipeline(pipeline) => commands.extend(pipeline.commands),
+                Expr::Pipeline(pipeline) => {
+                    commands.extend(pipeline.commands);
+                    pipes.extend(pipeline.pipes);
+                }
                 call => commands.push(call),
             }
         }
-        Ok(Expr::Pipeline(Pipeline { commands }))
+        Ok(Expr::Pipeline(Pipeline { commands, pipes }))
     }
 
     fn redirection(&mut self) -> ParseResult<Redir> {
@@ -67,6 +78,19 @@ impl Parser<'_> {
                 None => RedirOp::Write,
                 Some(_) => RedirOp::Append,
             },
+            TokenType::HereDoc => {
+                // The lexer yields the here-document body right after its operator
+                let body = self.cursor.next()?;
+                return Ok(Redir {
+                    fd,
+                    operator: RedirOp::String,
+                    operand: Expr::Literal(Literal {
+                        parsed: LiteralValue::String(body.text(self.source).to_owned()),
+                        segment: body.span,
+                    }),
+                    segment: start.span.start..token.span.end,
+                });
+            }
             _ => self.expected_with(
                 "Expected redirection operator.",
                 token.span,
@@ -105,14 +129,18 @@ impl Parser<'_> {
 
         while self.cursor.lookahead(eox()).is_none() {
             match self.cursor.peek().token_type {
-                TokenType::Less | TokenType::Greater | TokenType::Backtick => {
+                TokenType::Less | TokenType::Greater | TokenType::HereDoc | TokenType::Backtick => {
                     redirections.push(self.redirection()?);
                 }
                 // Detect redirections with a specific file descriptor, or with a wildcard file descriptor
                 // To be a redirection, it must immediately be followed by a '<' or '>'
                 _ if self
                     .cursor
-                    .lookahead(next().then(of_types(&[TokenType::Less, TokenType::Greater])))
+                    .lookahead(next().then(of_types(&[
+                        TokenType::Less,
+                        TokenType::Greater,
+                        TokenType::HereDoc,
+                    ])))
                     .is_some() =>
                 {
                     redirections.push(self.redirection()?)
@@ -145,16 +173,19 @@ impl Parser<'_> {
     pub(crate) fn is_at_redirection_sign(&self) -> bool {
         let pivot = self.cursor.peek();
         match pivot.token_type {
-            TokenType::Ampersand | TokenType::Less | TokenType::Greater | TokenType::Backtick => {
-                true
-            }
+            TokenType::Ampersand
+            | TokenType::Less
+            | TokenType::Greater
+            | TokenType::HereDoc
+            | TokenType::Backtick => true,
             //search for '>' or '<' in case of std-determined redirection sign (ex: 2>>)
             _ => self
                 .cursor
-                .lookahead(
-                    of_type(TokenType::IntLiteral)
-                        .then(of_types(&[TokenType::Less, TokenType::Greater])),
-                )
+                .lookahead(of_type(TokenType::IntLiteral).then(of_types(&[
+                    TokenType::Less,
+                    TokenType::Greater,
+                    TokenType::HereDoc,
+                ])))
                 .is_some(),
         }
     }
@@ -254,6 +285,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn here_document() {
+        let source = "cat <<END\nhello $USER\nEND\necho";
+        let parsed = parse(source).expect("Failed to parse");
+        assert_eq!(
+            parsed,
+            vec![
+                Expr::Redirected(Redirected {
+                    expr: Box::new(Expr::Call(Call {
+                        arguments: vec![literal(source, "cat")],
+                    })),
+                    redirections: vec![Redir {
+                        fd: RedirFd::Default,
+                        operator: RedirOp::String,
+                        operand: Expr::Literal(Literal {
+                            parsed: "hello $USER\n".into(),
+                            segment: find_in(source, "hello $USER\n"),
+                        }),
+                        segment: find_in(source, "<<END"),
+                    }],
+                }),
+                Expr::Call(Call {
+                    arguments: vec![literal(source, "echo")],
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn dupe_fd() {
         let source = "ls>&2";
@@ -340,6 +399,7 @@ mod test {
             vec![Expr::Redirected(Redirected {
                 expr: Box::new(Expr::Block(Block {
                     expressions: vec![Expr::Loop(Loop {
+                        label: None,
                         body: Box::new(Expr::Block(Block {
                             expressions: vec![],
                             segment: find_in(source, "{}"),
@@ -41,6 +41,7 @@ impl Parser<'_> {
         let segment = segment_start..segment_end;
 
         Ok(StructDeclaration {
+            attributes: Vec::new(),
             name: Identifier::extract(self.source, name.span),
             parameters,
             fields,
@@ -134,7 +135,7 @@ mod tests {
     use crate::parse;
     use crate::parser::ParseResult;
     use crate::source::{identifier, identifier_nth};
-    use ast::function::FunctionDeclaration;
+    use ast::function::{Attribute, FunctionDeclaration};
     use ast::r#struct::{FieldDeclaration, StructDeclaration, StructImpl};
     use ast::r#type::{ParametrizedType, Type, TypeParameter};
     use ast::r#use::InclusionPathItem;
@@ -155,6 +156,7 @@ mod tests {
         assert_eq!(
             result,
             vec![Expr::StructDeclaration(StructDeclaration {
+                attributes: vec![],
                 name: identifier(src, "Foo"),
                 parameters: vec![
                     TypeParameter {
@@ -193,6 +195,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_attributed_struct_declaration() {
+        let src = "@deprecated\nstruct Foo {}";
+        let result = parse(src).expect("errors");
+
+        assert_eq!(
+            result,
+            vec![Expr::StructDeclaration(StructDeclaration {
+                attributes: vec![Attribute {
+                    name: identifier(src, "deprecated"),
+                    segment: find_in(src, "@deprecated"),
+                }],
+                name: identifier(src, "Foo"),
+                parameters: vec![],
+                fields: vec![],
+                segment: find_in(src, "struct Foo {}"),
+            })]
+        );
+    }
+
     #[test]
     fn test_impl_block() {
         let src = "impl[A] A {
@@ -216,6 +238,7 @@ mod tests {
                 }),
                 functions: vec![
                     FunctionDeclaration {
+                        attributes: vec![],
                         name: identifier(src, "push"),
                         type_parameters: vec![],
                         parameters: vec![],
@@ -227,6 +250,7 @@ mod tests {
                         segment: find_in(src, "fun push() = 0"),
                     },
                     FunctionDeclaration {
+                        attributes: vec![],
                         name: identifier(src, "len"),
                         type_parameters: vec![],
                         parameters: vec![],
@@ -257,6 +281,7 @@ mod tests {
                     segment: find_in(source, "A"),
                 }),
                 functions: vec![FunctionDeclaration {
+                    attributes: vec![],
                     name: identifier(source, "test"),
                     type_parameters: vec![],
                     parameters: vec![],
@@ -5,7 +5,7 @@ use lexer::token::TokenType;
 use lexer::token::TokenType::{RoundedLeftBracket, RoundedRightBracket};
 
 use crate::err::ParseErrorKind;
-use crate::moves::{line_end, not, of_type, repeat_n, spaces, Move};
+use crate::moves::{line_end, lookahead, not, of_type, repeat_n, spaces, Move};
 use crate::parser::{ParseResult, Parser};
 
 impl Parser<'_> {
@@ -22,14 +22,19 @@ impl Parser<'_> {
             {
                 self.cursor.advance(of_type(RoundedLeftBracket));
                 let mut parenthesis = self.parenthesis()?;
-                parenthesis.segment.start -= 2; // Include the '$('
-                parenthesis.segment.end += 1;
+                let segment = match &mut parenthesis {
+                    Expr::Parenthesis(parenthesis) => &mut parenthesis.segment,
+                    Expr::TupleLiteral(tuple) => &mut tuple.segment,
+                    _ => unreachable!("a parenthesis expression or a tuple is expected"),
+                };
+                segment.start -= 2; // Include the '$('
+                segment.end += 1;
                 self.cursor.force_with(
                     of_type(RoundedRightBracket),
                     "Expected a second closing parenthesis.",
                     ParseErrorKind::Unpaired(dollar.span.start..start.span.end),
                 )?;
-                return Ok(Expr::Parenthesis(parenthesis));
+                return Ok(parenthesis);
             }
             return Ok(Expr::Substitution(Substitution {
                 // Read the expression inside the parentheses as a new statement
@@ -41,6 +46,23 @@ impl Parser<'_> {
             }));
         }
 
+        //if $ is followed by '?(' then it's the start of a Status substitution.
+        if self
+            .cursor
+            .advance(
+                of_type(TokenType::QuestionMark).and_then(lookahead(of_type(RoundedLeftBracket))),
+            )
+            .is_some()
+        {
+            return Ok(Expr::Substitution(Substitution {
+                underlying: self.subshell().map(|mut subshell| {
+                    subshell.segment.start -= 2; // Include the '$?('
+                    subshell
+                })?,
+                kind: SubstitutionKind::Status,
+            }));
+        }
+
         // Short pass for variable references
         if self
             .cursor
@@ -80,7 +102,7 @@ mod tests {
     #[test]
     fn unterminated_substitution() {
         let source = "$(echo";
-        let ast = Parser::new(source).substitution();
+        let ast = Parser::new(source).parse_specific(Parser::substitution);
         assert_eq!(
             ast,
             Err(ParseError {
@@ -137,6 +159,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn status_substitution() {
+        let source = "$?(test -f file)";
+        let ast = Parser::new(source).substitution().expect("Failed to parse");
+        assert_eq!(
+            ast,
+            Expr::Substitution(Substitution {
+                underlying: Subshell {
+                    expressions: vec![Expr::Call(Call {
+                        arguments: vec![
+                            literal(source, "test"),
+                            literal(source, "-f"),
+                            literal(source, "file"),
+                        ],
+                    })],
+                    segment: source.segment()
+                },
+                kind: SubstitutionKind::Status,
+            })
+        );
+    }
+
     #[test]
     fn unexpected_closing_parenthesis() {
         let source = "some stuff)";
@@ -159,8 +203,8 @@ mod tests {
             ast,
             Err(ParseError {
                 message: "Mismatched closing delimiter.".to_string(),
-                position: source.find('}').map(|p| (p..p + 1)).unwrap(),
-                kind: ParseErrorKind::Unpaired(source.find('(').map(|p| (p..p + 1)).unwrap())
+                position: source.find('}').map(|p| p..p + 1).unwrap(),
+                kind: ParseErrorKind::Unpaired(source.find('(').map(|p| p..p + 1).unwrap())
             })
         );
     }
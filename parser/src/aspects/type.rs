@@ -1,11 +1,12 @@
-use ast::r#type::{ByName, CallableType, ParametrizedType, Type, TypeParameter};
+use ast::r#type::{
+    ByName, CallableType, ParametrizedType, TupleType, Type, TypeAlias, TypeParameter,
+};
 use ast::variable::Identifier;
-use context::display::fmt_comma_separated;
 use context::source::{SourceSegment, SourceSegmentHolder};
 use lexer::token::TokenType;
 
 use crate::err::ParseErrorKind::{Expected, Unexpected};
-use crate::moves::{blanks, not, of_type, spaces, Move};
+use crate::moves::{blanks, next, not, of_type, of_types, spaces, Move};
 use crate::parser::{ParseResult, Parser};
 
 impl Parser<'_> {
@@ -58,6 +59,54 @@ impl Parser<'_> {
         Ok(tpe)
     }
 
+    /// Tests if the cursor is at the `type` keyword of a type alias declaration.
+    ///
+    /// The keyword is not reserved, so that `type` can still be invoked as a command.
+    pub(crate) fn is_at_type_alias(&self) -> bool {
+        self.cursor.peek().text(self.source) == "type"
+            && self
+                .cursor
+                .lookahead(
+                    next().then(spaces().then(of_type(TokenType::Identifier).then(
+                        spaces().then(of_types(&[TokenType::Equal, TokenType::SquaredLeftBracket])),
+                    ))),
+                )
+                .is_some()
+    }
+
+    /// Parses a type alias declaration, whose `type` keyword is lexed as an identifier.
+    pub(crate) fn parse_type_alias(&mut self) -> ParseResult<TypeAlias> {
+        let start = self
+            .cursor
+            .force(of_type(TokenType::Identifier), "`type` expected")?;
+
+        let name = self.cursor.force(
+            spaces().then(of_type(TokenType::Identifier)),
+            "identifier expected",
+        )?;
+
+        let (parameters, _) = self.parse_optional_list(
+            TokenType::SquaredLeftBracket,
+            TokenType::SquaredRightBracket,
+            "Expected type parameter.",
+            Parser::parse_type_parameter,
+        )?;
+
+        self.cursor.force(
+            spaces().then(of_type(TokenType::Equal)),
+            "Expected `=` after the type alias name.",
+        )?;
+
+        let aliased = self.parse_type()?;
+        let segment = start.span.start..aliased.segment().end;
+        Ok(TypeAlias {
+            name: Identifier::extract(self.source, name.span),
+            parameters,
+            aliased,
+            segment,
+        })
+    }
+
     pub(crate) fn parse_type_parameter(&mut self) -> ParseResult<TypeParameter> {
         let name = self.cursor.next()?;
 
@@ -140,21 +189,19 @@ impl Parser<'_> {
                 .map(Type::Callable);
         }
 
-        //its a type of form `(A)`
-        if let Some(ty) = inputs.first() {
-            if inputs.len() == 1 {
-                return Ok(ty.clone());
-            }
+        match inputs.len() {
+            0 => self.expected(
+                "Empty tuples are not supported. A lambda declaration was expected here",
+                Expected("() => <types>".to_string()),
+            ),
+            //its a type of form `(A)`, the parentheses only group the type
+            1 => Ok(inputs.into_iter().next().unwrap()),
+            //its a tuple of form `(A, B, ...)`
+            _ => Ok(Type::Tuple(TupleType {
+                elements: inputs,
+                segment,
+            })),
         }
-
-        let mut rendered_tuple = String::new();
-        fmt_comma_separated('(', ')', &inputs, &mut rendered_tuple).unwrap();
-
-        rendered_tuple += " => <types>";
-        self.expected(
-            "Tuples are not yet supported. A lambda declaration was expected here",
-            Expected(rendered_tuple),
-        )
     }
 
     fn parse_lambda_with_inputs(
@@ -209,15 +256,18 @@ impl Parser<'_> {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use ast::r#type::{ByName, CallableType, ParametrizedType, Type};
+    use ast::call::Call;
+    use ast::r#type::{ByName, CallableType, ParametrizedType, TupleType, Type, TypeAlias};
     use ast::r#use::InclusionPathItem;
+    use ast::Expr;
     use context::source::SourceSegmentHolder;
     use context::str_find::find_in;
 
     use crate::err::ParseError;
     use crate::err::ParseErrorKind::{Expected, Unexpected};
+    use crate::parse;
     use crate::parser::Parser;
-    use crate::source::identifier;
+    use crate::source::{identifier, literal};
 
     #[test]
     fn simple_type() {
@@ -548,12 +598,26 @@ mod tests {
         let ast = Parser::new(source).parse_specific(Parser::parse_type);
         assert_eq!(
             ast,
-            Err(ParseError {
-                message: "Tuples are not yet supported. A lambda declaration was expected here"
-                    .to_string(),
-                position: source.len()..source.len(),
-                kind: Expected("(A, B, C) => <types>".to_string()),
-            })
+            Ok(Type::Tuple(TupleType {
+                elements: vec![
+                    Type::Parametrized(ParametrizedType {
+                        path: vec![InclusionPathItem::Symbol(identifier(source, "A"))],
+                        params: Vec::new(),
+                        segment: find_in(source, "A")
+                    }),
+                    Type::Parametrized(ParametrizedType {
+                        path: vec![InclusionPathItem::Symbol(identifier(source, "B"))],
+                        params: Vec::new(),
+                        segment: find_in(source, "B")
+                    }),
+                    Type::Parametrized(ParametrizedType {
+                        path: vec![InclusionPathItem::Symbol(identifier(source, "C"))],
+                        params: Vec::new(),
+                        segment: find_in(source, "C")
+                    }),
+                ],
+                segment: source.segment(),
+            }))
         );
     }
 
@@ -618,4 +682,37 @@ mod tests {
         assert_eq!(ast1, expected1);
         assert_eq!(ast2, expected2);
     }
+
+    #[test]
+    fn type_alias_declaration() {
+        let source = "type Ints = Vec[Int]";
+        assert_eq!(
+            parse(source).expect("errors"),
+            vec![Expr::TypeAlias(TypeAlias {
+                name: identifier(source, "Ints"),
+                parameters: Vec::new(),
+                aliased: Type::Parametrized(ParametrizedType {
+                    path: vec![InclusionPathItem::Symbol(identifier(source, "Vec"))],
+                    params: vec![Type::Parametrized(ParametrizedType {
+                        path: vec![InclusionPathItem::Symbol(identifier(source, "Int"))],
+                        params: Vec::new(),
+                        segment: find_in(source, "Int"),
+                    })],
+                    segment: find_in(source, "Vec[Int]"),
+                }),
+                segment: source.segment(),
+            })]
+        );
+    }
+
+    #[test]
+    fn type_command() {
+        let source = "type ls";
+        assert_eq!(
+            parse(source).expect("errors"),
+            vec![Expr::Call(Call {
+                arguments: vec![literal(source, "type"), literal(source, "ls")],
+            })]
+        );
+    }
 }
@@ -1,7 +1,7 @@
-use ast::variable::{Identifier, TypedVariable, VarDeclaration, VarKind};
+use ast::variable::{Identifier, TupleDeclaration, TypedVariable, VarDeclaration, VarKind};
 use ast::Expr;
 use context::source::SourceSegmentHolder;
-use lexer::token::TokenType;
+use lexer::token::{Token, TokenType};
 
 use crate::err::ParseErrorKind;
 use crate::moves::{blanks, of_type, spaces, Move};
@@ -22,6 +22,14 @@ impl Parser<'_> {
             }
         };
 
+        if self
+            .cursor
+            .lookahead(blanks().then(of_type(TokenType::RoundedLeftBracket)))
+            .is_some()
+        {
+            return self.tuple_declaration(start, kind);
+        }
+
         let var = self.parse_typed_var()?;
         let mut segment = start.span.start..var.segment().end;
 
@@ -45,6 +53,36 @@ impl Parser<'_> {
         }))
     }
 
+    /// Parses the destructuring of a tuple into multiple variables.
+    fn tuple_declaration(&mut self, start: Token, kind: VarKind) -> ParseResult<Expr> {
+        let (names, _) = self.parse_explicit_list(
+            TokenType::RoundedLeftBracket,
+            TokenType::RoundedRightBracket,
+            "",
+            "Expected name.",
+            |parser| {
+                let name = parser.cursor.force(
+                    blanks().then(of_type(TokenType::Identifier)),
+                    "Expected name.",
+                )?;
+                Ok(Identifier::extract(parser.source, name.span))
+            },
+        )?;
+        self.cursor.force(
+            spaces().then(of_type(TokenType::Equal)),
+            "A destructuring declaration requires a value.",
+        )?;
+        let initializer = self.value()?;
+        let segment = start.span.start..initializer.segment().end;
+
+        Ok(Expr::TupleDeclaration(TupleDeclaration {
+            kind,
+            names,
+            initializer: Box::new(initializer),
+            segment,
+        }))
+    }
+
     pub(crate) fn parse_typed_var(&mut self) -> ParseResult<TypedVariable> {
         let name = self.cursor.force(
             blanks().then(of_type(TokenType::Identifier)),
@@ -74,6 +112,7 @@ mod tests {
     use ast::r#type::{ParametrizedType, Type};
     use ast::r#use::InclusionPathItem;
     use ast::value::{Literal, LiteralValue};
+    use ast::variable::{VarName, VarReference};
     use context::str_find::{find_in, find_in_nth};
 
     use crate::err::ParseError;
@@ -100,6 +139,38 @@ mod tests {
         )
     }
 
+    #[test]
+    fn tuple_declaration() {
+        let source = "val (lo, hi) = $pair";
+        let ast = parse(source).expect("failed to parse");
+        assert_eq!(
+            ast,
+            vec![Expr::TupleDeclaration(TupleDeclaration {
+                kind: VarKind::Val,
+                names: vec![identifier(source, "lo"), identifier(source, "hi")],
+                initializer: Box::new(Expr::VarReference(VarReference {
+                    name: VarName::User("pair".into()),
+                    segment: find_in(source, "$pair"),
+                })),
+                segment: source.segment(),
+            })]
+        )
+    }
+
+    #[test]
+    fn tuple_declaration_without_value() {
+        let source = "var (a, b)";
+        let res: ParseResult<_> = parse(source).into();
+        assert_eq!(
+            res,
+            Err(ParseError {
+                message: "A destructuring declaration requires a value.".to_string(),
+                kind: ParseErrorKind::Unexpected,
+                position: source.len()..source.len(),
+            })
+        )
+    }
+
     #[test]
     fn val_declaration_with_type() {
         let source = "val variable: Int";
@@ -184,7 +255,7 @@ mod tests {
             Err(ParseError {
                 message: "parenthesis in value expression can only contain one expression"
                     .to_string(),
-                position: source.find('\n').map(|p| (p..p + 1)).unwrap(),
+                position: source.find('\n').map(|p| p..p + 1).unwrap(),
                 kind: ParseErrorKind::Unexpected,
             })
         )
@@ -87,6 +87,24 @@ impl ParseReport {
     }
 }
 
+/// The result of parsing an input that may be continued on the next lines.
+#[derive(Debug, PartialEq)]
+pub enum PartialParse {
+    /// The input does not end inside a delimiter, and has been parsed.
+    ///
+    /// The report may still contain errors if the input is malformed.
+    Complete(ParseReport),
+
+    /// The input ends while a delimiter is still open.
+    Incomplete {
+        /// The location of the innermost opening delimiter.
+        opening: SourceSegment,
+
+        /// The text that would close this delimiter.
+        expecting: String,
+    },
+}
+
 impl From<ParseResult<Vec<Expr>>> for ParseReport {
     fn from(result: ParseResult<Vec<Expr>>) -> Self {
         match result {
@@ -5,7 +5,7 @@ use ast::group::Block;
 use ast::Expr;
 use context::source::SourceSegmentHolder;
 
-use crate::err::ParseReport;
+use crate::err::{ParseReport, PartialParse};
 use crate::parser::Parser;
 
 mod aspects;
@@ -15,10 +15,22 @@ mod moves;
 mod parser;
 pub mod source;
 
+/// Parses the whole input, recovering from errors at statement boundaries.
+///
+/// The well-formed statements are returned along with every error found, so that
+/// tooling can report multiple errors at once.
 pub fn parse(src: &str) -> ParseReport {
     Parser::new(src).parse()
 }
 
+/// Parses an input that may be continued, such as the lines typed in a REPL.
+///
+/// If the input ends inside an open delimiter, it is reported as incomplete instead of
+/// emitting an unpaired delimiter error.
+pub fn parse_partial(src: &str) -> PartialParse {
+    Parser::new(src).parse_partial()
+}
+
 /// Parses a supposedly valid string expression
 pub fn parse_trusted(src: &str) -> Expr {
     let expressions = parse(src).expect("trusted source input contains invalid expressions");
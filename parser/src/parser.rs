@@ -14,17 +14,36 @@ use std::num::NonZeroU8;
 use crate::aspects::binary_operation::{infix_precedence, shell_infix_precedence};
 use crate::aspects::literal::LiteralLeniency;
 use crate::cursor::ParserCursor;
-use crate::err::{determine_skip_sections, ParseError, ParseErrorKind, ParseReport, SkipSections};
+use crate::err::{
+    determine_skip_sections, ParseError, ParseErrorKind, ParseReport, PartialParse, SkipSections,
+};
 use crate::moves::{any, blanks, like, line_end, next, of_type, of_types, repeat, spaces, Move};
 
 pub(crate) type ParseResult<T> = Result<T, ParseError>;
 
+/// The maximum number of statements and values that can be nested in each other.
+///
+/// The parser recurses for each nesting level, so deeper inputs are rejected instead of
+/// overflowing the stack.
+pub(crate) const MAX_NESTING_DEPTH: usize = 256;
+
 /// A parser for the Moshell scripting language.
 pub(crate) struct Parser<'a> {
     pub(crate) cursor: ParserCursor<'a>,
     pub(crate) source: &'a str,
     pub(crate) skip: SkipSections,
+
+    /// The offsets of the opening delimiters that are never closed.
+    pub(crate) unclosed_delimiters: Vec<usize>,
+
+    /// If the input has no misplaced closing delimiters.
+    ///
+    /// Unclosed delimiters may then be closed by appending more input.
+    continuable: bool,
     errors: Vec<ParseError>,
+
+    /// The number of statements and values currently being parsed in each other.
+    depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -33,6 +52,14 @@ impl<'a> Parser<'a> {
         let (tokens, unmatched) = lex(source);
         let cursor = ParserCursor::new_with_source(tokens, source);
         let skip = determine_skip_sections(source.len(), &unmatched);
+        let unclosed_delimiters = unmatched
+            .iter()
+            .filter(|unmatched| unmatched.candidate.is_none())
+            .filter_map(|unmatched| unmatched.opening)
+            .collect();
+        let continuable = unmatched
+            .iter()
+            .all(|unmatched| unmatched.candidate.is_none());
         let errors = unmatched
             .into_iter()
             .filter_map(|unmatched| {
@@ -66,7 +93,21 @@ impl<'a> Parser<'a> {
             cursor,
             source,
             skip,
+            unclosed_delimiters,
+            continuable,
             errors,
+            depth: 0,
+        }
+    }
+
+    /// Parses the input if it is complete, or reports the innermost delimiter left open.
+    pub fn parse_partial(self) -> PartialParse {
+        match self.unclosed_delimiters.first() {
+            Some(&opening) if self.continuable => {
+                let (opening, expecting) = closing_delimiter(self.source, opening);
+                PartialParse::Incomplete { opening, expecting }
+            }
+            _ => PartialParse::Complete(self.parse()),
         }
     }
 
@@ -139,6 +180,13 @@ impl<'a> Parser<'a> {
             Fun => self
                 .parse_function_declaration()
                 .map(Expr::FunctionDeclaration),
+            At if self
+                .cursor
+                .lookahead(of_type(At).and_then(of_type(Identifier)))
+                .is_some() =>
+            {
+                self.parse_attributed_declaration()
+            }
             Loop => self.parse_loop().map(Expr::Loop),
             _ => self.statement(),
         }
@@ -193,14 +241,8 @@ impl<'a> Parser<'a> {
             RoundedLeftBracket => self.subshell_or_parentheses(),
             SquaredLeftBracket => self.parse_test(),
 
-            Continue => {
-                let current = self.cursor.next()?;
-                Ok(Expr::Continue(current.span))
-            }
-            Break => {
-                let current = self.cursor.next()?;
-                Ok(Expr::Break(current.span))
-            }
+            Continue => self.parse_loop_jump().map(Expr::Continue),
+            Break => self.parse_loop_jump().map(Expr::Break),
             Return => self.parse_return().map(Expr::Return),
 
             _ => self.value(),
@@ -214,6 +256,10 @@ impl<'a> Parser<'a> {
 
     /// Parses the left-hand side of the next statement.
     fn next_statement(&mut self) -> ParseResult<Expr> {
+        self.nested(Self::statement_lhs)
+    }
+
+    fn statement_lhs(&mut self) -> ParseResult<Expr> {
         self.repos("Expected statement")?;
 
         let pivot = self.cursor.peek().token_type;
@@ -222,6 +268,19 @@ impl<'a> Parser<'a> {
             Impl => self.parse_impl().map(Expr::Impl),
             While => self.parse_while().map(Expr::While),
             For => self.parse_for().map(Expr::For),
+            Identifier
+                if self
+                    .cursor
+                    .lookahead(
+                        of_type(Identifier)
+                            .and_then(of_type(At))
+                            .and_then(blanks().then(of_types(&[Loop, While, For]))),
+                    )
+                    .is_some() =>
+            {
+                self.parse_labeled_loop()
+            }
+            Identifier if self.is_at_type_alias() => self.parse_type_alias().map(Expr::TypeAlias),
             Identifier
                 if self
                     .cursor
@@ -314,6 +373,10 @@ impl<'a> Parser<'a> {
 
     /// Parses the left-hand side of the next value.
     fn lhs(&mut self) -> ParseResult<Expr> {
+        self.nested(Self::value_lhs)
+    }
+
+    fn value_lhs(&mut self) -> ParseResult<Expr> {
         self.repos("Expected value")?;
         match self.cursor.peek().token_type {
             RoundedLeftBracket => self.lambda_or_parentheses(),
@@ -472,7 +535,7 @@ impl<'a> Parser<'a> {
             self.parse_lambda_definition().map(Expr::LambdaDef)
         } else {
             self.cursor.repos(initial);
-            self.parenthesis().map(Expr::Parenthesis)
+            self.parenthesis()
         }
     }
 
@@ -513,6 +576,20 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Runs a parsing function one nesting level deeper, unless the maximum depth is reached.
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<Expr>) -> ParseResult<Expr> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return self.expected(
+                "Expression is nested too deeply.",
+                ParseErrorKind::Unexpected,
+            );
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
     /// Raise an error on the current token.
     ///
     /// Use [Parser::expected_with] if the error is not on the current token.
@@ -679,3 +756,22 @@ impl<'a> Parser<'a> {
         }
     }
 }
+
+/// Finds the opening delimiter at the given offset and the text that closes it.
+fn closing_delimiter(source: &str, opening: usize) -> (SourceSegment, String) {
+    let rest = &source[opening..];
+    if let Some(word) = rest.strip_prefix("<<") {
+        let len = word
+            .find(|c: char| c != '_' && !c.is_alphanumeric())
+            .unwrap_or(word.len());
+        return (opening..opening + 2, word[..len].to_owned());
+    }
+    let closing = match rest.chars().next() {
+        Some('{') => '}',
+        Some('(') => ')',
+        Some('[') => ']',
+        Some(c) => c,
+        None => unreachable!("delimiter offset is out of bounds"),
+    };
+    (opening..opening + 1, closing.to_string())
+}
@@ -1,6 +1,7 @@
 use pretty_assertions::assert_eq;
 
 use ast::call::{Call, ProgrammaticCall};
+use ast::control_flow::LoopJump;
 use ast::function::{FunctionDeclaration, FunctionParameter};
 use ast::group::Block;
 use ast::r#type::{ParametrizedType, Type, TypeParameter};
@@ -10,9 +11,9 @@ use ast::variable::{TypedVariable, VarDeclaration, VarKind, VarName, VarReferenc
 use ast::Expr;
 use context::source::SourceSegmentHolder;
 use context::str_find::{find_in, find_in_nth};
-use parser::err::{ParseError, ParseErrorKind, ParseReport};
-use parser::parse;
+use parser::err::{ParseError, ParseErrorKind, ParseReport, PartialParse};
 use parser::source::{identifier, identifier_nth, literal, literal_nth};
+use parser::{parse, parse_partial};
 
 #[test]
 fn repos_delimiter_stack() {
@@ -78,6 +79,33 @@ fn repos_delimiter_in_var_reference() {
     )
 }
 
+#[test]
+fn parse_reports_independent_errors() {
+    let source = "val x=9!3\necho ok\necho $(var m = ${..})\necho end";
+    let ParseReport { expr, errors } = parse(source);
+    assert_eq!(
+        errors,
+        vec![
+            ParseError {
+                message: "expected end of expression or file".to_string(),
+                position: find_in(source, "!"),
+                kind: ParseErrorKind::Unexpected
+            },
+            ParseError {
+                message: "Expected variable name.".to_string(),
+                position: find_in(source, ".."),
+                kind: ParseErrorKind::Unexpected
+            }
+        ]
+    );
+    assert!(expr.contains(&Expr::Call(Call {
+        arguments: vec![literal(source, "echo"), literal(source, "ok")],
+    })));
+    assert!(expr.contains(&Expr::Call(Call {
+        arguments: vec![literal_nth(source, "echo", 2), literal(source, "end")],
+    })));
+}
+
 #[test]
 fn what_is_an_import() {
     let source = "{
@@ -122,6 +150,7 @@ fn tolerance_in_multiple_groups() {
         report,
         ParseReport {
             expr: vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier_nth(source, "f", 1),
                 type_parameters: vec![TypeParameter {
                     name: identifier(source, "T"),
@@ -313,6 +342,7 @@ fn do_not_self_lock() {
         report,
         ParseReport {
             expr: vec![Expr::FunctionDeclaration(FunctionDeclaration {
+                attributes: vec![],
                 name: identifier(source, "g"),
                 parameters: vec![],
                 type_parameters: vec![],
@@ -433,7 +463,10 @@ fn expected_double_delimiter_mismatched() {
     assert_eq!(
         report,
         ParseReport {
-            expr: vec![Expr::Continue(find_in(source, "continue"))],
+            expr: vec![Expr::Continue(LoopJump {
+                label: None,
+                segment: find_in(source, "continue"),
+            })],
             errors: vec![ParseError {
                 message: "Mismatched closing delimiter.".to_string(),
                 position: source.find(']').map(|p| p..p + 1).unwrap(),
@@ -548,3 +581,65 @@ fn triple_equal() {
         }]
     );
 }
+
+#[test]
+fn incomplete_block() {
+    let source = "{ echo";
+    assert_eq!(
+        parse_partial(source),
+        PartialParse::Incomplete {
+            opening: source.find('{').map(|p| p..p + 1).unwrap(),
+            expecting: "}".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn incomplete_innermost_delimiter() {
+    let source = "val x = {\n    echo $(pwd";
+    assert_eq!(
+        parse_partial(source),
+        PartialParse::Incomplete {
+            opening: source.find('(').map(|p| p..p + 1).unwrap(),
+            expecting: ")".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn malformed_is_complete() {
+    let source = "{ echo ) }";
+    let PartialParse::Complete(report) = parse_partial(source) else {
+        panic!("malformed input should not be continued");
+    };
+    assert_eq!(
+        report.errors.first(),
+        Some(&ParseError {
+            message: "Mismatched closing delimiter.".to_owned(),
+            position: source.find(')').map(|p| p..p + 1).unwrap(),
+            kind: ParseErrorKind::Unpaired(source.find('{').map(|p| p..p + 1).unwrap())
+        })
+    );
+}
+
+#[test]
+fn deeply_nested_input() {
+    // Run with the stack size of a main thread, as the parser is by the CLI
+    let errors = std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(|| {
+            let source = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+            parse(&source).errors
+        })
+        .unwrap()
+        .join()
+        .expect("parsing should not overflow the stack");
+    assert_eq!(
+        errors,
+        vec![ParseError {
+            message: "Expression is nested too deeply.".to_owned(),
+            position: 256..257,
+            kind: ParseErrorKind::Unexpected,
+        }]
+    );
+}
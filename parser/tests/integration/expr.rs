@@ -3,7 +3,7 @@ use pretty_assertions::assert_eq;
 use ast::call::{
     Call, Detached, MethodCall, ProgrammaticCall, Redir, RedirFd, RedirOp, Redirected,
 };
-use ast::control_flow::{For, ForKind, If, RangeFor};
+use ast::control_flow::{For, ForKind, If, LoopJump, RangeFor};
 use ast::function::Return;
 use ast::group::{Block, Parenthesis};
 use ast::lambda::LambdaDef;
@@ -404,6 +404,7 @@ fn for_in_step_2_range() {
     assert_eq!(
         parsed,
         vec![Expr::For(For {
+            label: None,
             kind: Box::new(ForKind::Range(RangeFor {
                 receiver: identifier(source, "i"),
                 iterable: Expr::Range(Iterable::Range(NumericRange {
@@ -423,12 +424,85 @@ fn for_in_step_2_range() {
                 })),
                 segment: find_in(source, "i in 1..=10..2")
             })),
-            body: Box::new(Expr::Break(find_in(source, "break"))),
+            body: Box::new(Expr::Break(LoopJump {
+                label: None,
+                segment: find_in(source, "break"),
+            })),
+            segment: source.segment()
+        })]
+    );
+}
+
+#[test]
+fn for_in_descending_range() {
+    let source = "for i in 10..0..-2; break";
+    let parsed = parse(source).expect("Failed to parse");
+    assert_eq!(
+        parsed,
+        vec![Expr::For(For {
+            label: None,
+            kind: Box::new(ForKind::Range(RangeFor {
+                receiver: identifier(source, "i"),
+                iterable: Expr::Range(Iterable::Range(NumericRange {
+                    start: Box::new(Expr::Literal(Literal {
+                        parsed: 10.into(),
+                        segment: find_in(source, "10")
+                    })),
+                    end: Box::new(Expr::Literal(Literal {
+                        parsed: 0.into(),
+                        segment: find_in(source, "0")
+                    })),
+                    step: Some(Box::new(Expr::Unary(UnaryOperation {
+                        op: UnaryOperator::Negate,
+                        expr: Box::new(Expr::Literal(Literal {
+                            parsed: 2.into(),
+                            segment: find_in(source, "2")
+                        })),
+                        segment: find_in(source, "-2"),
+                    }))),
+                    upper_inclusive: false,
+                })),
+                segment: find_in(source, "i in 10..0..-2")
+            })),
+            body: Box::new(Expr::Break(LoopJump {
+                label: None,
+                segment: find_in(source, "break"),
+            })),
             segment: source.segment()
         })]
     );
 }
 
+#[test]
+fn assign_stepped_iterable() {
+    let source = "it = $a..$b..$c";
+    let parsed = parse(source).expect("Failed to parse");
+    assert_eq!(
+        parsed,
+        vec![Expr::Assign(Assign {
+            left: Box::new(Expr::Path(Path {
+                path: vec![InclusionPathItem::Symbol(identifier(source, "it"))],
+            })),
+            operator: AssignOperator::Assign,
+            value: Box::new(Expr::Range(Iterable::Range(NumericRange {
+                start: Box::new(Expr::VarReference(VarReference {
+                    name: VarName::User("a".into()),
+                    segment: find_in(source, "$a")
+                })),
+                end: Box::new(Expr::VarReference(VarReference {
+                    name: VarName::User("b".into()),
+                    segment: find_in(source, "$b")
+                })),
+                step: Some(Box::new(Expr::VarReference(VarReference {
+                    name: VarName::User("c".into()),
+                    segment: find_in(source, "$c")
+                }))),
+                upper_inclusive: false,
+            }))),
+        })]
+    );
+}
+
 #[test]
 fn call_not_assign() {
     let source = "a '=' 5";
@@ -1,3 +1,4 @@
 mod err;
 mod expr;
+mod visit;
 mod with_lexer;
@@ -0,0 +1,36 @@
+use pretty_assertions::assert_eq;
+
+use ast::variable::VarReference;
+use ast::visit::Visitor;
+use parser::parse;
+
+#[derive(Default)]
+struct VarReferenceCounter {
+    count: usize,
+}
+
+impl Visitor for VarReferenceCounter {
+    fn visit_var_reference(&mut self, _var: &VarReference) {
+        self.count += 1;
+    }
+}
+
+#[test]
+fn count_var_references() {
+    let source = "\
+        val n = $a + 1
+        fun double(x: Int) -> Int = $x * 2
+        while $n < 10 {
+            echo \"n is $n\" $(echo $n)
+        }
+        match $n {
+            v@* if $v > 3 => double($v)
+            * => {}
+        }";
+    let exprs = parse(source).expect("no parse errors");
+    let mut counter = VarReferenceCounter::default();
+    for expr in &exprs {
+        counter.visit_expr(expr);
+    }
+    assert_eq!(counter.count, 8);
+}
@@ -1,7 +1,8 @@
 use pretty_assertions::assert_eq;
 
 use ast::call::{
-    Call, Detached, MethodCall, Pipeline, ProgrammaticCall, Redir, RedirFd, RedirOp, Redirected,
+    Call, Detached, MethodCall, PipeKind, Pipeline, ProgrammaticCall, Redir, RedirFd, RedirOp,
+    Redirected,
 };
 use ast::control_flow::{Loop, While};
 use ast::function::Return;
@@ -210,6 +211,7 @@ fn with_lexer_pipe_and_redirection() {
                     }],
                 }),
             ],
+            pipes: vec![PipeKind::Stdout],
         }),]
     );
 }
@@ -236,6 +238,30 @@ fn with_lexer_pipe_and_pipe() {
                     ],
                 }),
             ],
+            pipes: vec![PipeKind::Stdout; 2],
+        }),]
+    );
+}
+
+#[test]
+fn with_lexer_merged_pipe() {
+    let source = "make |& grep error | wc -l";
+    let parsed = parse(source).expect("Failed to parse");
+    assert_eq!(
+        parsed,
+        vec![Expr::Pipeline(Pipeline {
+            commands: vec![
+                Expr::Call(Call {
+                    arguments: vec![literal(source, "make")],
+                }),
+                Expr::Call(Call {
+                    arguments: vec![literal(source, "grep"), literal(source, "error")],
+                }),
+                Expr::Call(Call {
+                    arguments: vec![literal(source, "wc"), literal(source, "-l")],
+                }),
+            ],
+            pipes: vec![PipeKind::StdoutAndStderr, PipeKind::Stdout],
         }),]
     );
 }
@@ -335,6 +361,7 @@ fn pipe_expressions() {
                     arguments: vec![literal(source, "find"), literal(source, "."),],
                 }),
                 Expr::While(While {
+                    label: None,
                     condition: Box::new(Expr::Call(Call {
                         arguments: vec![
                             literal(source, "read"),
@@ -357,6 +384,7 @@ fn pipe_expressions() {
                     segment: find_in(source, "while read -r filename { echo $filename }"),
                 })
             ],
+            pipes: vec![PipeKind::Stdout],
         })]
     );
 }
@@ -379,6 +407,7 @@ fn pipe_to_command() {
                     arguments: vec![literal(source, "cat")],
                 }),
             ],
+            pipes: vec![PipeKind::Stdout],
         })]
     );
 }
@@ -458,6 +487,7 @@ fn loop_assign() {
     assert_eq!(
         parsed,
         vec![Expr::Loop(Loop {
+            label: None,
             body: Box::new(Expr::Assign(Assign {
                 left: Box::new(Expr::VarReference(VarReference {
                     name: VarName::User("a".into()),
@@ -513,6 +543,7 @@ fn here_string_pipeline() {
                     arguments: vec![literal(source, "uniq")],
                 }),
             ],
+            pipes: vec![PipeKind::Stdout],
         })]
     );
 }
@@ -5,7 +5,7 @@ use analyzer::reef::{Externals, ReefId};
 use analyzer::relations::SourceId;
 use ast::Expr;
 use compiler::externals::CompilerExternals;
-use compiler::{compile_reef, CompilerOptions};
+use compiler::{compile_reef, CaptureCache, CompilerOptions};
 use context::source::ContentId;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use parser::parse_trusted;
@@ -46,6 +46,7 @@ fn prepare_bytecode(code: &str) -> Vec<u8> {
         &compiler_externals,
         ReefId(1),
         SourceId(0),
+        &mut CaptureCache::default(),
         &mut bytes,
         CompilerOptions::default(),
     )
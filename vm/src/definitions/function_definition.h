@@ -5,6 +5,24 @@
 #include <string_view>
 #include <vector>
 
+/**
+ * Debug information about a named value of a function's locals area
+ */
+struct local_debug_info {
+    /**
+     * The name of the local or captured variable
+     */
+    std::string_view name;
+    /**
+     * Start of the value in the locals area, in bytes
+     */
+    uint32_t offset;
+    /**
+     * Size of the value, in bytes
+     */
+    uint8_t size;
+};
+
 /**
  * Contains all the information about a function
  */
@@ -57,4 +75,16 @@ struct function_definition {
      * The vector must be sorted in ascending order by instructions count.
      * */
     std::vector<std::pair<size_t, size_t>> mappings;
+
+    /**
+     * Names and emplacements of the function's locals and captures, sorted by offset.
+     * Empty if the function was compiled without debug information.
+     */
+    std::vector<local_debug_info> locals;
+
+    /**
+     * Original source text the function was compiled from.
+     * Empty if the source was not embedded in the bytecode.
+     */
+    std::string_view source;
 };
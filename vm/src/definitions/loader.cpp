@@ -1,10 +1,16 @@
 #include "loader.h"
 
+#include <algorithm>
+
 #include "byte_reader.h"
 #include "memory/constant_pool.h"
 #include "pager.h"
 
 #define MAPPINGS_ATTRIBUTE 1
+#define LOCALS_ATTRIBUTE 2
+#define SOURCE_ATTRIBUTE 3
+
+#define DECLARATION_ATTRIBUTE 1
 
 namespace msh {
     void loader::load_raw_bytes(const std::byte *bytes, size_t size, pager &pager, msh::heap &heap) {
@@ -21,6 +27,7 @@ namespace msh {
         }
 
         while (reader.position() < size) {
+            size_t page_start = concatened_instructions.size();
             // Read main function
             {
                 const auto &[identifier, function] = load_function(reader, pool, pool_index);
@@ -36,7 +43,19 @@ namespace msh {
                     bool is_obj_ref = reader.read<bool>();
 
                     const std::string &identifier = pool.get_string(id_idx);
-                    exported[identifier] = exported_variable{page, offset, is_obj_ref};
+                    std::optional<export_declaration> declaration;
+                    uint8_t attributes_count = reader.read<uint8_t>();
+                    for (uint8_t j = 0; j < attributes_count; j++) {
+                        uint8_t attribute_kind = reader.read<uint8_t>();
+                        if (attribute_kind != DECLARATION_ATTRIBUTE || declaration) {
+                            throw InvalidBytecodeError("Invalid attribute kind " + std::to_string(attribute_kind) + " for exported symbol " + identifier);
+                        }
+                        uint32_t content = reader.read<uint32_t>();
+                        uint32_t start = reader.read<uint32_t>();
+                        uint32_t end = reader.read<uint32_t>();
+                        declaration = export_declaration{content, start, end};
+                    }
+                    exported[identifier] = exported_variable{page, offset, is_obj_ref, declaration};
                 }
 
                 std::vector<uint32_t> obj_refs_offsets;
@@ -51,6 +70,11 @@ namespace msh {
             for (uint32_t i = 0; i < functions_len; ++i) {
                 load_function(reader, pool, pool_index);
             }
+
+            uint32_t traces_len = reader.read<uint32_t>();
+            for (uint32_t i = 0; i < traces_len; ++i) {
+                load_trace(reader, pool, page_start);
+            }
         }
     }
 
@@ -89,6 +113,37 @@ namespace msh {
         return concatened_instructions.data() + index;
     }
 
+    const function_trace *loader::find_trace(size_t instruction) const {
+        auto it = std::upper_bound(traces.cbegin(), traces.cend(), instruction, [](size_t instruction, const function_trace &trace) {
+            return instruction < trace.start;
+        });
+        if (it == traces.cbegin()) {
+            return nullptr;
+        }
+        --it;
+        if (instruction >= it->end) {
+            return nullptr;
+        }
+        return &*it;
+    }
+
+    void loader::load_trace(ByteReader &reader, const ConstantPool &pool, size_t page_start) {
+        constant_index id_idx = reader.read<constant_index>();
+        size_t start = page_start + reader.read<uint32_t>();
+        size_t end = page_start + reader.read<uint32_t>();
+
+        uint32_t mappings_count = reader.read<uint32_t>();
+        std::vector<std::pair<size_t, size_t>> mappings;
+        mappings.reserve(mappings_count);
+        for (uint32_t i = 0; i < mappings_count; i++) {
+            size_t instruction = page_start + reader.read<uint32_t>();
+            size_t line = reader.read<uint32_t>();
+            mappings.push_back({instruction, line});
+        }
+
+        traces.push_back(function_trace{pool.get_string(id_idx), start, end, std::move(mappings)});
+    }
+
     std::pair<const std::string &, const msh::struct_definition &> loader::load_structure(ByteReader &reader, const ConstantPool &pool) {
         constant_index id_idx = reader.read<constant_index>();
         const std::string &identifier = pool.get_string(id_idx);
@@ -139,6 +194,8 @@ namespace msh {
             pool_index,
             offsets,
             {},
+            {},
+            {},
         };
 
         uint8_t attributes_count = reader.read<uint8_t>();
@@ -159,6 +216,26 @@ namespace msh {
                 }
                 break;
             }
+            case LOCALS_ATTRIBUTE: {
+                if (!def.locals.empty()) {
+                    throw InvalidBytecodeError("Locals (2) attribute defined multiple times for function " + identifier);
+                }
+                uint32_t locals_count = reader.read<uint32_t>();
+                for (uint32_t i = 0; i < locals_count; i++) {
+                    constant_index name_idx = reader.read<constant_index>();
+                    uint32_t offset = reader.read<uint32_t>();
+                    uint8_t size = reader.read<uint8_t>();
+                    def.locals.push_back({pool.get_string(name_idx), offset, size});
+                }
+                break;
+            }
+            case SOURCE_ATTRIBUTE: {
+                if (!def.source.empty()) {
+                    throw InvalidBytecodeError("Source (3) attribute defined multiple times for function " + identifier);
+                }
+                def.source = pool.get_string(reader.read<constant_index>());
+                break;
+            }
             default:
                 throw InvalidBytecodeError("Unknown attribute kind: " + std::to_string(attribute_kind));
             }
@@ -1,6 +1,8 @@
 #pragma once
 
 #include <cstddef>
+#include <cstdint>
+#include <optional>
 #include <stack>
 #include <string>
 #include <unordered_map>
@@ -22,6 +24,21 @@ namespace msh {
         std::string symbol_name;
     };
 
+    /**
+     * The location in the source code where an exported symbol is declared.
+     */
+    struct export_declaration {
+        /**
+         * The identifier of the source content, as given by the compiler.
+         */
+        uint32_t content;
+
+        /**
+         * The byte range of the declaration in the source content.
+         */
+        uint32_t start, end;
+    };
+
     /**
      * The effective location in the virtual memory for a given exported symbol.
      */
@@ -40,6 +57,32 @@ namespace msh {
          * GC hint to mark this variable as being a reference to a heap object
          * */
         bool is_obj_ref;
+
+        /**
+         * Where the symbol is declared, if known.
+         */
+        std::optional<export_declaration> declaration;
+    };
+
+    /**
+     * The instructions of a function in the loaded instructions, with the source lines they are attributed to.
+     */
+    struct function_trace {
+        /**
+         * The identifier of the function.
+         */
+        std::string_view function;
+
+        /**
+         * The range of the function's instructions in the concatenated instructions, end excluded.
+         */
+        size_t start, end;
+
+        /**
+         * Instructions of the concatenated instructions bound with the source line they start.
+         * The vector must be sorted in ascending order by instruction.
+         */
+        std::vector<std::pair<size_t, size_t>> mappings;
     };
 
     class loader {
@@ -72,8 +115,14 @@ namespace msh {
          */
         std::stack<unresolved_variable> unresolved;
 
+        /**
+         * The function traces of the loaded pages, sorted by instructions range.
+         */
+        std::vector<function_trace> traces;
+
         std::pair<const std::string &, const function_definition &> load_function(ByteReader &reader, const ConstantPool &pool, size_t pool_index);
         std::pair<const std::string &, const struct_definition &> load_structure(ByteReader &reader, const ConstantPool &pool);
+        void load_trace(ByteReader &reader, const ConstantPool &pool, size_t page_start);
 
     public:
         /**
@@ -132,6 +181,14 @@ namespace msh {
          */
         const std::byte *get_instructions(size_t index) const;
 
+        /**
+         * Finds the function that contains the given instruction.
+         *
+         * @param instruction The index of the instruction in the concatenated instructions.
+         * @return The trace of the function, or nullptr if the instruction was not traced.
+         */
+        const function_trace *find_trace(size_t instruction) const;
+
         /**
          * Resolves all the unresolved symbols.
          *
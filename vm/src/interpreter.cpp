@@ -16,6 +16,7 @@
 #include <fcntl.h>
 #include <iostream>
 #include <memory>
+#include <optional>
 #include <sys/wait.h>
 #include <unistd.h>
 #include <vector>
@@ -81,7 +82,7 @@ enum Opcode {
 
     OP_RETURN, // stops frame interpretation
 
-    OP_BYTE_TO_INT, // replaces last value of operand stack from byte to int
+    OP_BYTE_TO_INT, // replaces last value of operand stack from unsigned byte to int
     OP_INT_TO_BYTE, // replaces last value of operand stack from int to byte
 
     OP_BYTE_XOR,  // pops last two bytes, apply xor operation then push the resulting byte
@@ -108,6 +109,10 @@ enum Opcode {
     OP_FLOAT_LE, // pops two floats, checks if the first is less than or equal to the second, and pushes the resulting byte
     OP_FLOAT_GT, // pops two floats, checks if the first is greater than the second, and pushes the resulting byte
     OP_FLOAT_GE, // pops two floats, checks if the first is greater than or equal to the second, and pushes the resulting byte
+
+    OP_IF_JUMP_SHORT,     // with 1 byte signed offset from the next instruction, jumps only if value popped from operand stack is not 0
+    OP_IF_NOT_JUMP_SHORT, // with 1 byte signed offset from the next instruction, jumps only if value popped from operand stack is 0
+    OP_JUMP_SHORT,        // with 1 byte signed offset from the next instruction
 };
 
 /**
@@ -268,23 +273,31 @@ inline bool apply_comparison(Opcode code, double a, double b) {
     }
 }
 
-void panic(const std::string &msg, CallStack &stack) {
+void panic(const std::string &msg, CallStack &stack, const msh::loader &loader) {
     std::cerr << "panic: " << msg;
 
     while (!stack.is_empty()) {
         stack_frame &frame = stack.peek_frame();
         const function_definition &def = frame.function;
-        std::cerr << "\n\tat " << def.identifier;
 
-        if (!def.mappings.empty()) {
-            size_t instruction_line;
-            for (const auto &[instruction, line] : def.mappings) {
-                if (instruction >= frame.instruction_pointer) {
+        // the instruction pointer is already past the instruction that was executed last
+        size_t executed = def.instructions_start + (frame.instruction_pointer > 0 ? frame.instruction_pointer - 1 : 0);
+        const msh::function_trace *trace = loader.find_trace(executed);
+        if (trace == nullptr) {
+            std::cerr << "\n\tat " << def.identifier;
+        } else {
+            std::cerr << "\n\tat " << trace->function;
+
+            std::optional<size_t> instruction_line;
+            for (const auto &[instruction, line] : trace->mappings) {
+                if (instruction > executed) {
                     break;
                 }
                 instruction_line = line;
             }
-            std::cerr << " (line " << instruction_line << ")";
+            if (instruction_line) {
+                std::cerr << " (line " << *instruction_line << ")";
+            }
         }
         stack.pop_frame();
     }
@@ -557,7 +570,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             pid_t pid = fork();
             switch (pid) {
             case -1:
-                panic(strerror(errno), call_stack);
+                panic(strerror(errno), call_stack, state.loader);
                 return frame_status::ABORT;
             case 0:
                 // Child process
@@ -603,7 +616,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             // Replace the current process with a new process image
             if (execvp(argv[0], const_cast<char *const *>(argv.data())) == -1) {
                 std::string command = argv[0];
-                panic("Unable to execute command \"" + command + "\": " + std::string(strerror(errno)), call_stack);
+                panic("Unable to execute command \"" + command + "\": " + std::string(strerror(errno)), call_stack, state.loader);
                 return frame_status::ABORT;
             }
             break;
@@ -615,7 +628,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             int status = 0;
             // Wait for the process to finish
             if (waitpid(pid, &status, 0) == -1) {
-                panic(strerror(errno), call_stack);
+                panic(strerror(errno), call_stack, state.loader);
                 return frame_status::ABORT;
             }
             status = WEXITSTATUS(status) & 0xFF;
@@ -638,7 +651,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             // Open the file
             int fd = open(path.c_str(), flags, S_IRUSR | S_IWUSR | S_IRGRP | S_IWGRP | S_IROTH | S_IWOTH);
             if (fd == -1) {
-                panic("Cannot open file \"" + path + "\": " + std::string(strerror(errno)), call_stack);
+                panic("Cannot open file \"" + path + "\": " + std::string(strerror(errno)), call_stack, state.loader);
                 return frame_status::ABORT;
             }
 
@@ -662,7 +675,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
 
             // Redirect the file descriptors
             if (state.table.push_redirection(fd1, fd2) == -1) {
-                panic("Unable to redirect " + std::to_string(fd1) + " to " + std::to_string(fd2) + ": " + strerror(errno), call_stack);
+                panic("Unable to redirect " + std::to_string(fd1) + " to " + std::to_string(fd2) + ": " + strerror(errno), call_stack, state.loader);
                 return frame_status::ABORT;
             }
             operands.push_int(fd1);
@@ -675,7 +688,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
 
             // Redirect the file descriptors
             if (dup2(fd1, fd2) == -1) {
-                panic("Unable to redirect " + std::to_string(fd1) + " to " + std::to_string(fd2) + ": " + strerror(errno), call_stack);
+                panic("Unable to redirect " + std::to_string(fd1) + " to " + std::to_string(fd2) + ": " + strerror(errno), call_stack, state.loader);
                 return frame_status::ABORT;
             }
             operands.push_int(fd1);
@@ -689,7 +702,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             // Create the pipe
             int pipefd[2];
             if (pipe(pipefd) == -1) {
-                panic("Cannot create pipeline : " + std::string(strerror(errno)), call_stack);
+                panic("Cannot create pipeline : " + std::string(strerror(errno)), call_stack, state.loader);
                 return frame_status::ABORT;
             }
 
@@ -709,7 +722,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
                 r = read(fd, buffer.data(), buffer.size());
                 if (r == -1) {
                     if (errno != EAGAIN && errno != EINTR) {
-                        panic(strerror(errno), call_stack);
+                        panic(strerror(errno), call_stack, state.loader);
                         return frame_status::ABORT;
                     }
                 }
@@ -736,7 +749,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
 
             // Write the string to the file
             if (write(fd, str.data(), str.length()) == -1) {
-                panic("Cannot write in fd " + std::to_string(fd) + ": " + strerror(errno), call_stack);
+                panic("Cannot write in fd " + std::to_string(fd) + ": " + strerror(errno), call_stack, state.loader);
                 return frame_status::ABORT;
             }
             close(fd);
@@ -809,7 +822,7 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             break;
         }
         case OP_BYTE_TO_INT: {
-            char value = operands.pop_byte();
+            uint8_t value = operands.pop_byte();
             operands.push_int(value);
             break;
         }
@@ -837,6 +850,22 @@ frame_status run_frame(runtime_state &state, stack_frame &frame, CallStack &call
             ip = destination;
             break;
         }
+        case OP_IF_NOT_JUMP_SHORT:
+        case OP_IF_JUMP_SHORT: {
+            char value = operands.pop_byte();
+            int8_t offset = static_cast<int8_t>(*(instructions + ip));
+            ip++;
+            if (value == (opcode == OP_IF_JUMP_SHORT)) {
+                ip += offset;
+            }
+            break;
+        }
+        case OP_JUMP_SHORT: {
+            int8_t offset = static_cast<int8_t>(*(instructions + ip));
+            ip++;
+            ip += offset;
+            break;
+        }
         case OP_DUP: {
             operands.dup_qword();
             break;
@@ -982,11 +1011,11 @@ bool run_unit(CallStack &call_stack, const msh::loader &loader, msh::pager &page
         }
         return true;
     } catch (const VirtualMachineError &e) {
-        panic("An unexpected Virtual Machine Error occurred.\n" + std::string(e.name()) + " : " + e.what(), call_stack);
+        panic("An unexpected Virtual Machine Error occurred.\n" + std::string(e.name()) + " : " + e.what(), call_stack, state.loader);
     } catch (const RuntimeException &e) {
-        panic(e.what(), call_stack);
+        panic(e.what(), call_stack, state.loader);
     } catch (const std::exception &e) {
-        panic("An unexpected internal error occurred.\nwhat : " + std::string(e.what()), call_stack);
+        panic("An unexpected internal error occurred.\nwhat : " + std::string(e.what()), call_stack, state.loader);
     }
     return false;
 }
@@ -1,14 +1,18 @@
 #include "stdlib_natives.h"
 #include "interpreter.h"
 #include "memory/heap.h"
+#include <algorithm>
 #include <charconv>
+#include <chrono>
 #include <cmath>
+#include <csignal>
 #include <cstring>
 #include <filesystem>
 #include <glob.h>
 #include <iostream>
 #include <pwd.h>
 #include <sys/wait.h>
+#include <thread>
 #include <unistd.h>
 
 static void int_to_string(OperandStack &caller_stack, runtime_memory &mem) {
@@ -205,6 +209,44 @@ static void str_bytes(OperandStack &caller_stack, runtime_memory &mem) {
     }
 }
 
+static void str_trim(OperandStack &caller_stack, runtime_memory &mem) {
+    const std::string &str = caller_stack.pop_reference().get<const std::string>();
+    const char *whitespaces = " \t\n\r\f\v";
+    size_t start = str.find_first_not_of(whitespaces);
+    std::string result;
+    if (start != std::string::npos) {
+        size_t end = str.find_last_not_of(whitespaces);
+        result = str.substr(start, end - start + 1);
+    }
+    caller_stack.push_reference(mem.emplace(std::move(result)));
+}
+
+static void str_replace(OperandStack &caller_stack, runtime_memory &mem) {
+    const std::string &to = caller_stack.pop_reference().get<const std::string>();
+    const std::string &from = caller_stack.pop_reference().get<const std::string>();
+    const std::string &str = caller_stack.pop_reference().get<const std::string>();
+
+    if (from.empty()) {
+        throw RuntimeException("The replaced pattern is empty.");
+    }
+
+    std::string result;
+    size_t start = 0, end;
+    while ((end = str.find(from, start)) != std::string::npos) {
+        result.append(str, start, end - start);
+        result += to;
+        start = end + from.length();
+    }
+    result.append(str, start);
+    caller_stack.push_reference(mem.emplace(std::move(result)));
+}
+
+static void str_contains(OperandStack &caller_stack, runtime_memory &) {
+    const std::string &needle = caller_stack.pop_reference().get<const std::string>();
+    const std::string &str = caller_stack.pop_reference().get<const std::string>();
+    caller_stack.push_byte(static_cast<int8_t>(str.find(needle) != std::string::npos));
+}
+
 static void str_len(OperandStack &caller_stack, runtime_memory &) {
     const std::string &str = caller_stack.pop_reference().get<const std::string>();
     caller_stack.push_int(static_cast<int64_t>(str.length()));
@@ -275,6 +317,39 @@ static void vec_push(OperandStack &caller_stack, runtime_memory &) {
     vec.push_back(&ref);
 }
 
+/**
+ * Compares primitives and strings by value, and other objects by identity.
+ */
+static bool obj_equals(const msh::obj &left, const msh::obj &right) {
+    const msh::obj_data &left_data = left.get_data();
+    const msh::obj_data &right_data = right.get_data();
+    if (left_data.index() != right_data.index()) {
+        return false;
+    }
+    if (const int64_t *value = std::get_if<int64_t>(&left_data)) {
+        return *value == std::get<int64_t>(right_data);
+    }
+    if (const int8_t *value = std::get_if<int8_t>(&left_data)) {
+        return *value == std::get<int8_t>(right_data);
+    }
+    if (const double *value = std::get_if<double>(&left_data)) {
+        return *value == std::get<double>(right_data);
+    }
+    if (const std::string *value = std::get_if<const std::string>(&left_data)) {
+        return *value == std::get<const std::string>(right_data);
+    }
+    return &left == &right;
+}
+
+static void vec_contains(OperandStack &caller_stack, runtime_memory &) {
+    const msh::obj &element = caller_stack.pop_reference();
+    const msh::obj_vector &vec = caller_stack.pop_reference().get<msh::obj_vector>();
+    bool found = std::any_of(vec.begin(), vec.end(), [&element](const msh::obj *candidate) {
+        return obj_equals(*candidate, element);
+    });
+    caller_stack.push_byte(static_cast<int8_t>(found));
+}
+
 static void vec_extend(OperandStack &caller_stack, runtime_memory &) {
     msh::obj_vector &right = caller_stack.pop_reference().get<msh::obj_vector>();
     msh::obj_vector &left = caller_stack.pop_reference().get<msh::obj_vector>();
@@ -370,6 +445,47 @@ static void process_wait_all(OperandStack &, runtime_memory &) {
     }
 }
 
+/// The exit code of a command that was stopped because it exceeded its time limit.
+static constexpr int8_t TIMEOUT_EXIT_CODE = 124;
+
+static void timeout(OperandStack &caller_stack, runtime_memory &) {
+    const std::string &command = caller_stack.pop_reference().get<const std::string>();
+    int64_t millis = caller_stack.pop_int();
+
+    pid_t pid = fork();
+    if (pid == -1) {
+        throw RuntimeException("Failed to run the command: " + std::string(strerror(errno)) + ".");
+    }
+    if (pid == 0) {
+        execl("/bin/sh", "sh", "-c", command.c_str(), nullptr);
+        _exit(127);
+    }
+
+    const auto deadline = std::chrono::steady_clock::now() + std::chrono::milliseconds(millis);
+    int status;
+    while (true) {
+        pid_t waited = waitpid(pid, &status, WNOHANG);
+        if (waited == -1) {
+            throw RuntimeException("Failed to wait for process " + std::to_string(pid) + ": " + strerror(errno) + ".");
+        }
+        if (waited == pid) {
+            break;
+        }
+        if (std::chrono::steady_clock::now() >= deadline) {
+            kill(pid, SIGKILL);
+            waitpid(pid, &status, 0);
+            caller_stack.push_byte(TIMEOUT_EXIT_CODE);
+            return;
+        }
+        std::this_thread::sleep_for(std::chrono::milliseconds(1));
+    }
+    if (WIFEXITED(status)) {
+        caller_stack.push_byte(static_cast<int8_t>(WEXITSTATUS(status)));
+    } else {
+        caller_stack.push_byte(static_cast<int8_t>(128 + WTERMSIG(status)));
+    }
+}
+
 natives_functions_t load_natives() {
     return natives_functions_t{
         {"lang::Int::to_string", int_to_string},
@@ -380,6 +496,9 @@ natives_functions_t load_natives() {
         {"lang::String::split", str_split},
         {"lang::String::bytes", str_bytes},
         {"lang::String::len", str_len},
+        {"lang::String::trim", str_trim},
+        {"lang::String::replace", str_replace},
+        {"lang::String::contains", str_contains},
         {"lang::String::[]", str_index},
 
         {"lang::Vec::pop", vec_pop},
@@ -387,6 +506,7 @@ natives_functions_t load_natives() {
         {"lang::Vec::len", vec_len},
         {"lang::Vec::push", vec_push},
         {"lang::Vec::extend", vec_extend},
+        {"lang::Vec::contains", vec_contains},
         {"lang::Vec::[]", vec_index},
         {"lang::Vec::[]=", vec_index_set},
 
@@ -404,6 +524,7 @@ natives_functions_t load_natives() {
         {"std::working_dir", working_dir},
         {"std::home_dir", home_dir},
         {"std::current_home_dir", current_home_dir},
+        {"std::timeout", timeout},
 
         {"std::memory::gc", gc},
         {"std::memory::empty_operands", is_operands_empty},
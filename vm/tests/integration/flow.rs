@@ -20,6 +20,66 @@ fn break_loop() {
     assert_eq!(runner.eval("$res"), Some(VmValue::Int(3)))
 }
 
+#[test]
+fn match_guard() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "\
+        fun size(n: Int) -> String = match $n {
+            0 => 'none'
+            v@* if $v > 10 => 'many'
+            1 | 2 => 'few'
+            * => 'some'
+        }
+    ",
+    );
+    assert_eq!(runner.eval("size(0)"), Some("none".into()));
+    assert_eq!(runner.eval("size(2)"), Some("few".into()));
+    assert_eq!(runner.eval("size(5)"), Some("some".into()));
+    assert_eq!(runner.eval("size(42)"), Some("many".into()));
+}
+
+#[test]
+fn labeled_loops() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "\
+        var res = 0
+        outer@ for i in 1..=3 {
+            for j in 1..=3 {
+                if $j == 2 {
+                    continue@outer
+                }
+                if $i == 3 {
+                    break@outer
+                }
+                res += $i * 10 + $j
+            }
+        }
+    ",
+    );
+    assert_eq!(runner.eval("$res"), Some(VmValue::Int(32)))
+}
+
+#[test]
+fn compact_jumps() {
+    let mut runner = Runner::with_opt_level(1);
+    runner.eval(
+        "\
+        var res = 0
+        var i = 0
+        while $i < 10 {
+            i += 1
+            if $i % 2 == 0 {
+                continue
+            }
+            res += $i
+        }
+    ",
+    );
+    assert_eq!(runner.eval("$res"), Some(VmValue::Int(25)))
+}
+
 #[test]
 fn factorial() {
     let mut runner = Runner::default();
@@ -91,6 +151,41 @@ fn iter_range() {
     assert_eq!(res, Some(vec![1, 2, 3, 4].into()))
 }
 
+#[test]
+fn iter_descending_range() {
+    let mut runner = Runner::default();
+    let res = runner.eval(
+        "
+        var vec = std::new_vec::[Int]()
+        var step = -3
+        for i in 10..=1..$step {
+            $vec.push($i)
+        }
+        for i in 2..0..-1 {
+            $vec.push($i)
+        }
+        $vec
+    ",
+    );
+    assert_eq!(res, Some(vec![10, 7, 4, 1, 2, 1].into()))
+}
+
+#[test]
+fn iter_zero_step_range() {
+    let mut runner = Runner::default();
+    let res = runner.eval(
+        "
+        var vec = std::new_vec::[Int]()
+        var step = 0
+        for i in 0..3..$step {
+            $vec.push($i)
+        }
+        $vec
+    ",
+    );
+    assert_eq!(res, Some(Vec::<i64>::new().into()))
+}
+
 #[test]
 fn test_assertion() {
     let mut runner = Runner::default();
@@ -227,6 +322,20 @@ fn str_split() {
     )
 }
 
+#[test]
+fn str_scalar_methods() {
+    let mut runner = Runner::default();
+    runner.eval("val line = '  key=a=b  '.trim()");
+    assert_eq!(runner.eval("$line"), Some("key=a=b".into()));
+    assert_eq!(
+        runner.eval("$line.replace('=', ': ')"),
+        Some("key: a: b".into())
+    );
+    assert_eq!(runner.eval("$line.contains('a=b')"), Some(VmValue::Byte(1)));
+    assert_eq!(runner.eval("$line.contains('c')"), Some(VmValue::Byte(0)));
+    assert_eq!(runner.eval("$line.to_string()"), Some("key=a=b".into()));
+}
+
 #[test]
 fn exitcode_to_bool() {
     let mut runner = Runner::default();
@@ -238,6 +347,34 @@ fn exitcode_to_bool() {
     );
 }
 
+#[test]
+fn compare_exitcode() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "use std::assert::assert
+        val code = { /bin/false }
+        assert($code == 1)
+        assert($code != 0)
+        assert({ /bin/true } == 0)",
+    );
+}
+
+#[test]
+fn short_circuit() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "val calls = std::new_vec::[Int]()
+        fun touch() -> Bool = {
+            $calls.push(1)
+            true
+        }
+        val a = false && touch()
+        val b = true || touch()
+        val c = true && touch()",
+    );
+    assert_eq!(runner.eval("$calls.len()"), Some(VmValue::Int(1)));
+}
+
 #[test]
 fn working_directory_via_tilde() {
     let mut runner = Runner::default();
@@ -258,3 +395,72 @@ fn subprocess() {
         assert($code == 1)",
     );
 }
+
+#[test]
+fn status_substitution() {
+    let mut runner = Runner::default();
+    runner.eval("val status = $?(/bin/false)");
+    assert_eq!(runner.eval("$status.to_int()"), Some(VmValue::Int(1)));
+}
+
+#[test]
+fn high_exitcodes_are_unsigned() {
+    let mut runner = Runner::default();
+    runner.eval("val code = 200.to_exitcode()");
+    assert_eq!(runner.eval("$code == 200"), Some(VmValue::Byte(1)));
+    assert_eq!(runner.eval("$code != 200"), Some(VmValue::Byte(0)));
+    assert_eq!(runner.eval("$code.to_int()"), Some(VmValue::Int(200)));
+}
+
+#[test]
+fn membership_evaluates_element_first() {
+    let mut runner = Runner::default();
+    runner.eval(
+        r#"
+        val order = std::new_vec::[String]()
+        fun element(order: Vec[String]) -> Int = {
+            $order.push('element')
+            2
+        }
+        fun vector(order: Vec[String]) -> Vec[Int] = {
+            $order.push('vector')
+            [1, 2]
+        }
+    "#,
+    );
+    assert_eq!(
+        runner.eval("element($order) in vector($order)"),
+        Some(VmValue::Byte(1))
+    );
+    assert_eq!(
+        runner.eval("$order"),
+        Some(vec!["element", "vector"].into())
+    );
+}
+
+#[test]
+fn named_arguments_evaluate_in_source_order() {
+    let mut runner = Runner::default();
+    runner.eval(
+        r#"
+        val order = std::new_vec::[String]()
+        fun sub(a: Int, b: Int) -> Int = $a - $b
+        fun value(order: Vec[String], name: String, value: Int) -> Int = {
+            $order.push($name)
+            $value
+        }
+    "#,
+    );
+    assert_eq!(
+        runner.eval("sub(b = value($order, 'b', 1), a = value($order, 'a', 5))"),
+        Some(VmValue::Int(4))
+    );
+    assert_eq!(runner.eval("$order"), Some(vec!["b", "a"].into()));
+}
+
+#[test]
+fn by_name_argument() {
+    let mut runner = Runner::default();
+    runner.eval("fun twice(value: => Int) -> Int = $value * 2");
+    assert_eq!(runner.eval("twice(3 + 4)"), Some(VmValue::Int(14)));
+}
@@ -126,6 +126,44 @@ fn test_structure_field_subscript_assign() {
     )
 }
 
+#[test]
+fn tuple_instantiation() {
+    let mut runner = Runner::default();
+    let res = runner.try_eval("val t = (1, 'two', 3.0)\n$t");
+
+    assert_eq!(
+        res,
+        Ok(Some(VmValue::Struct(vec![
+            Some(VmValue::Int(1)),
+            Some("two".into()),
+            Some(VmValue::Double(3.0)),
+        ])))
+    )
+}
+
+#[test]
+fn tuple_index_access() {
+    let mut runner = Runner::default();
+    runner.eval("val t = ('a', (1, 'b'))");
+    assert_eq!(runner.eval("$t.1.0"), Some(VmValue::Int(1)));
+    assert_eq!(runner.eval("$t.1.1"), Some("b".into()));
+}
+
+#[test]
+fn tuple_destructuring() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "\
+        fun minmax(a: Int, b: Int) -> (Int, Int) = if $a < $b { ($a, $b) } else { ($b, $a) }
+        val (lo, _) = minmax(7, 3)
+        var (_, hi) = minmax(7, 3)
+        hi *= 10
+    ",
+    );
+    assert_eq!(runner.eval("$lo"), Some(VmValue::Int(3)));
+    assert_eq!(runner.eval("$hi"), Some(VmValue::Int(70)));
+}
+
 #[test]
 fn test_structure_gc() {
     let mut runner = Runner::default();
@@ -147,3 +185,53 @@ fn test_structure_gc() {
         vec![VmValue::Int(10), VmValue::Vec(vec![Some(VmValue::Int(10))])].into()
     );
 }
+
+#[test]
+fn call_user_methods() {
+    let mut runner = Runner::default();
+    runner.eval(
+        "\
+        struct Point { x: Int, y: Int }
+        impl Point {
+            fun scale(factor: Int) -> Point = Point($self.x * $factor, $self.y * $factor)
+            fun sum(self) -> Int = $self.x + $self.y
+        }
+        val p = Point(3, 4)
+    ",
+    );
+    assert_eq!(runner.eval("$p.scale(2).sum()"), Some(VmValue::Int(14)));
+    assert_eq!(runner.eval("$p.sum()"), Some(VmValue::Int(7)));
+}
+
+#[test]
+fn call_user_operator_method() {
+    let mut runner = Runner::default();
+    let res = runner.try_eval(
+        "\
+        struct Vec2 { x: Int, y: Int }
+        impl Vec2 {
+            fun add(other: Vec2) -> Vec2 = Vec2($self.x + $other.x, $self.y + $other.y)
+        }
+        val a = Vec2(1, 2)
+        val c = $a + Vec2(3, 4) + $a
+        $c.x * 10 + $c.y
+    ",
+    );
+    assert_eq!(res, Ok(Some(VmValue::Int(58))));
+}
+
+#[test]
+fn stringify_with_user_method() {
+    let mut runner = Runner::default();
+    let res = runner.try_eval(
+        "\
+        struct Point { x: Int, y: Int }
+        impl Point {
+            fun to_string() -> String = '(' + $self.x.to_string() + ', ' + $self.y.to_string() + ')'
+        }
+        val p = Point(3, 4)
+        \"p = $p\"
+    ",
+    );
+    assert_eq!(res, Ok(Some("p = (3, 4)".into())));
+}
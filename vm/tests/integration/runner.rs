@@ -9,7 +9,7 @@ use analyzer::types::ty::{Type, TypeRef};
 use analyzer::{analyze, types, Analyzer, Inject};
 use cli::pipeline::FileImporter;
 use compiler::externals::{CompiledReef, CompilerExternals};
-use compiler::{compile_reef, CompilerOptions};
+use compiler::{compile_reef, CaptureCache, CompilerOptions};
 use parser::parse_trusted;
 use vm::value::VmValue;
 use vm::{VmError, VmValueFFI, VM};
@@ -18,9 +18,11 @@ pub struct Runner<'a> {
     externals: Externals<'a>,
     compiler_externals: CompilerExternals,
     current_compiled_reef: CompiledReef,
+    captures: CaptureCache,
     vm: VM,
     analyzer: Analyzer<'a>,
     current_page: Option<SourceId>,
+    opt_level: u8,
 }
 
 impl Default for Runner<'_> {
@@ -43,6 +45,7 @@ impl Default for Runner<'_> {
             &compiler_externals,
             externals.current,
             SourceId(0),
+            &mut CaptureCache::default(),
             &mut buff,
             CompilerOptions::default(),
         )
@@ -61,13 +64,23 @@ impl Default for Runner<'_> {
             vm,
             compiler_externals,
             current_compiled_reef: CompiledReef::default(),
+            captures: CaptureCache::default(),
             analyzer: Analyzer::default(),
             current_page: None,
+            opt_level: 0,
         }
     }
 }
 
 impl<'a> Runner<'a> {
+    /// Creates a runner that compiles the evaluated sources with the given optimization level.
+    pub fn with_opt_level(opt_level: u8) -> Self {
+        Self {
+            opt_level,
+            ..Self::default()
+        }
+    }
+
     pub fn eval(&mut self, expr: &'a str) -> Option<VmValue> {
         match self.try_eval(expr) {
             Ok(v) => v,
@@ -120,11 +133,14 @@ impl<'a> Runner<'a> {
             &self.compiler_externals,
             reef,
             page,
+            &mut self.captures,
             &mut bytes,
             CompilerOptions {
                 line_provider: None,
                 last_page_storage_var: Some(VAR_EXPR_STORAGE.to_string())
                     .filter(|_| !expr_value_is_void),
+                opt_level: self.opt_level,
+                ..CompilerOptions::default()
             },
         )
         .expect("write failed");
@@ -162,7 +178,7 @@ impl<'a> Runner<'a> {
             match value_type {
                 types::BOOL | types::EXITCODE => Some(VmValue::Byte(value.get_as_u8())),
                 types::FLOAT => Some(VmValue::Double(value.get_as_double())),
-                types::INT => Some(VmValue::Int(value.get_as_i64())),
+                types::INT | types::DURATION => Some(VmValue::Int(value.get_as_i64())),
                 types::STRING => Some(VmValue::String(value.get_as_obj().get_as_string())),
                 types::UNIT | types::NOTHING => Some(VmValue::Void),
                 _ => match self.get_type(value_type) {
@@ -22,3 +22,27 @@ fn test_option_parse() {
         Err(VmError::Panic)
     )
 }
+
+#[test]
+fn read_env_variables() {
+    let mut runner = Runner::default();
+    std::env::set_var("MOSHELL_TEST_VARIABLE", "some value");
+    assert_eq!(
+        runner.eval("use @MOSHELL_TEST_VARIABLE; $MOSHELL_TEST_VARIABLE"),
+        Some("some value".into())
+    );
+    assert_eq!(
+        runner.try_eval("use @MOSHELL_UNSET_VARIABLE; $MOSHELL_UNSET_VARIABLE"),
+        Err(VmError::Panic)
+    );
+}
+
+#[test]
+fn run_with_timeout() {
+    let mut runner = Runner::default();
+    assert_eq!(runner.eval("timeout(5s, 'exit 3')"), Some(VmValue::Byte(3)));
+    assert_eq!(
+        runner.eval("timeout(50ms, 'sleep 5')"),
+        Some(VmValue::Byte(124))
+    );
+}